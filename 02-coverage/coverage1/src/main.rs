@@ -5,6 +5,8 @@
 
 // From https://www.fuzzingbook.org/html/Coverage.html
 
+mod cli;
+
 use std::collections::BTreeSet;
 use std::fs;
 use std::process;
@@ -19,13 +21,18 @@ use std::process;
 // difference   = [("cgi_decode", 42)]
 
 fn main() {
-    // let cov = run_and_get_coverage("Send+mail+to+me%40fuzzingbook.org");
-    let cov_standard = run_and_get_coverage("abc");
-    let cov_plus = run_and_get_coverage("a+b");
+    // Path of the C program, without the `.c` extension.
+    let target = cli::target("../cgi_decode");
+
+    // let cov = run_and_get_coverage(&target, "Send+mail+to+me%40fuzzingbook.org");
+    let cov_standard = run_and_get_coverage(&target, "abc");
+    let cov_plus = run_and_get_coverage(&target, "a+b");
 
-    println!("cov_standard = {:?}\n", cov_standard);
+    if !cli::quiet() {
+        println!("cov_standard = {:?}\n", cov_standard);
 
-    println!("cov_plus     = {:?}\n", cov_plus);
+        println!("cov_plus     = {:?}\n", cov_plus);
+    }
 
     println!(
         "difference   = {:?}\n",
@@ -37,44 +44,42 @@ type Location = (String, usize);
 
 type StatementCoverage = BTreeSet<Location>;
 
-/// Run the cgi_decode C program and trace coverage data.
-fn run_and_get_coverage(input: &str) -> StatementCoverage {
+/// Run the C program (cgi_decode by default) and trace coverage data.
+fn run_and_get_coverage(target: &str, input: &str) -> StatementCoverage {
+    let name = target.rsplit('/').next().unwrap();
+    let source = format!("{}.c", target);
+
     // Compile the C program.
     process::Command::new("gcc")
-        .args(["--coverage", "-o", "../cgi_decode", "../cgi_decode.c"])
+        .args(["--coverage", "-o", target, &source])
         .output()
         .unwrap();
 
     // Run the program.
-    process::Command::new("../cgi_decode")
-        .arg(input)
-        .output()
-        .unwrap();
+    process::Command::new(target).arg(input).output().unwrap();
 
     // Generate coverage data using gcov.
-    process::Command::new("gcov")
-        .arg("../cgi_decode.c")
-        .output()
-        .unwrap();
+    process::Command::new("gcov").arg(&source).output().unwrap();
 
     // "Parse" (process) gcov coverage file.
+    let gcov = format!("{}.c.gcov", name);
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string("cgi_decode.c.gcov").unwrap().lines() {
+    for line in fs::read_to_string(&gcov).unwrap().lines() {
         let elems = line.split(':').collect::<Vec<_>>();
         let covered = elems[0].trim();
         let line_number = elems[1].trim().parse::<usize>().unwrap();
         if covered.starts_with("-") || covered.starts_with("#") {
             continue;
         }
-        coverage.insert(("cgi_decode".to_string(), line_number));
+        coverage.insert((name.to_string(), line_number));
     }
 
     // Cleanup compiled and generated files.
     for file in [
-        "cgi_decode.c.gcov",
-        "../cgi_decode",
-        "../cgi_decode.gcda",
-        "../cgi_decode.gcno",
+        gcov,
+        target.to_string(),
+        format!("{}.gcda", target),
+        format!("{}.gcno", target),
     ] {
         let _ = fs::remove_file(file);
     }
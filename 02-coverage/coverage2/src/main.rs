@@ -5,6 +5,10 @@
 
 // From https://www.fuzzingbook.org/html/Coverage.html
 
+mod cli;
+mod json;
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod ptrace;
 mod rng;
 
 use std::collections::BTreeSet;
@@ -12,24 +16,41 @@ use std::fs;
 use std::io::Write;
 use std::process;
 
-/// Fuzz an external C program (cgi_decode) and gather/plot coverage.
+use json::Json;
+
+const USAGE: &str = "Usage: coverage1 [--backend gcov|llvm-cov|ptrace] [--blocks <file>] \
+                     [--seed N] [--iterations N] [--target PATH] [--out-dir DIR] [--quiet]";
 
+/// Fuzz an external C program (cgi_decode) and gather/plot coverage.
 fn main() {
-    let mut rng = rng::Rng::new();
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
+    let backend = CoverageBackend::from_cli().unwrap_or_else(|err| {
+        eprintln!("[!] {}", err);
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+    // Path of the C program, without the `.c` extension.
+    let target = cli::target("../cgi_decode");
 
     let mut population = Vec::new();
-    for _ in 0..100 {
+    for _ in 0..cli::iterations(100) {
         let len = rng.range(5, 10);
         let input = rng.ascii_printable(len);
-        println!("{}", input);
+        if !cli::quiet() {
+            println!("{}", input);
+        }
         population.push(input);
     }
 
-    let (_, cumulative_coverage) = population_coverage(population);
+    let (_, cumulative_coverage) = population_coverage(&backend, &target, population)
+        .unwrap_or_else(|err| {
+            eprintln!("[!] {}", err);
+            process::exit(1);
+        });
 
-    let mut file = fs::File::create("plot.data").unwrap();
-    for i in 0..cumulative_coverage.len() {
-        file.write_all(format!("{} {}\n", i, cumulative_coverage[i]).as_bytes())
+    let mut file = fs::File::create(cli::out_file("plot.data")).unwrap();
+    for (i, coverage) in cumulative_coverage.iter().enumerate() {
+        file.write_all(format!("{} {}\n", i, coverage).as_bytes())
             .unwrap();
     }
 }
@@ -37,63 +58,324 @@ fn main() {
 type Input = String;
 type Population = Vec<Input>;
 
-fn population_coverage(population: Population) -> (StatementCoverage, Vec<usize>) {
+fn population_coverage(
+    backend: &CoverageBackend,
+    target: &str,
+    population: Population,
+) -> Result<(StatementCoverage, Vec<usize>), String> {
     let mut all_coverage = BTreeSet::new();
     let mut cumulative_coverage = Vec::new();
 
     for s in population {
-        let cov = run_and_get_coverage(s);
+        let cov = run_and_get_coverage(backend, target, s)?;
         all_coverage.extend(cov);
         cumulative_coverage.push(all_coverage.len());
     }
 
-    (all_coverage, cumulative_coverage)
+    Ok((all_coverage, cumulative_coverage))
 }
 
 type Location = (String, usize);
 type StatementCoverage = BTreeSet<Location>;
 
-/// Run the cgi_decode C program and trace coverage data.
-fn run_and_get_coverage(input: Input) -> StatementCoverage {
-    // Compile the C program.
-    process::Command::new("gcc")
-        .args(["--coverage", "-o", "../cgi_decode", "../cgi_decode.c"])
+/// Tooling used for compiling the C program and gathering coverage.
+#[derive(Clone, Debug)]
+enum CoverageBackend {
+    /// gcc with `--coverage` and gcov.
+    Gcov,
+    /// clang with LLVM source-based coverage, llvm-profdata and llvm-cov.
+    LlvmCov,
+    /// Uninstrumented binary, breakpoints on basic block entries via ptrace.
+    /// The block addresses are read from the given file, or else the function
+    /// entries from the ELF symbol table are used. Only on Linux on x86_64.
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    Ptrace { blocks: Option<String> },
+}
+
+impl CoverageBackend {
+    /// Select the backend on the command line with `--backend gcov` (default),
+    /// `--backend llvm-cov` or `--backend ptrace [--blocks <file>]`.
+    fn from_cli() -> Result<Self, String> {
+        match cli::option("--backend").as_deref() {
+            None | Some("gcov") => Ok(CoverageBackend::Gcov),
+            Some("llvm-cov") => Ok(CoverageBackend::LlvmCov),
+            #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+            Some("ptrace") => Ok(CoverageBackend::Ptrace {
+                blocks: cli::option("--blocks"),
+            }),
+            #[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
+            Some("ptrace") => Err("The ptrace backend only supports Linux on x86_64".to_string()),
+            Some(other) => Err(format!("Unknown coverage backend {:?}", other)),
+        }
+    }
+}
+
+/// Run the C program (cgi_decode by default) and trace coverage data with the
+/// given backend. Errors are missing or failing tools (compiler, coverage
+/// tools), not failures of the program.
+fn run_and_get_coverage(
+    backend: &CoverageBackend,
+    target: &str,
+    input: Input,
+) -> Result<StatementCoverage, String> {
+    match backend {
+        CoverageBackend::Gcov => run_and_get_coverage_gcov(target, input),
+        CoverageBackend::LlvmCov => run_and_get_coverage_llvm(target, input),
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        CoverageBackend::Ptrace { blocks } => run_and_get_coverage_ptrace(blocks, target, input),
+    }
+}
+
+/// Name of the program (for the coverage locations and the generated files),
+/// and its source file.
+fn name_and_source(target: &str) -> (&str, String) {
+    (target.rsplit('/').next().unwrap(), format!("{}.c", target))
+}
+
+/// Run an external tool (compiler or coverage tool) to completion. The errors
+/// name the tool, since the usual cause is that it isn't installed.
+fn run_tool(tool: &str, args: &[&str]) -> Result<process::Output, String> {
+    let output = process::Command::new(tool)
+        .args(args)
         .output()
-        .unwrap();
+        .map_err(|err| format!("Running {} failed (is it installed?): {}", tool, err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed with {}: {}",
+            tool,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output)
+}
+
+/// Run the program under test. Its exit status doesn't matter (the coverage
+/// is written either way), only whether it could be started at all.
+fn run_target(cmd: &mut process::Command) -> Result<(), String> {
+    cmd.output()
+        .map(|_| ())
+        .map_err(|err| format!("Running {:?} failed: {}", cmd.get_program(), err))
+}
+
+/// Run the C program and trace coverage data using gcov.
+fn run_and_get_coverage_gcov(target: &str, input: Input) -> Result<StatementCoverage, String> {
+    let (name, source) = name_and_source(target);
+
+    // Compile the C program.
+    run_tool("gcc", &["--coverage", "-o", target, &source])?;
 
     // Run the program.
-    process::Command::new("../cgi_decode")
-        .arg(input)
-        .output()
-        .unwrap();
+    run_target(process::Command::new(target).arg(input))?;
 
     // Generate coverage data using gcov.
-    process::Command::new("gcov")
-        .arg("../cgi_decode.c")
-        .output()
-        .unwrap();
+    run_tool("gcov", &[&source])?;
 
     // "Parse" (process) gcov coverage file.
+    let gcov = format!("{}.c.gcov", name);
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string("cgi_decode.c.gcov").unwrap().lines() {
+    let content =
+        fs::read_to_string(&gcov).map_err(|err| format!("Reading {} failed: {}", gcov, err))?;
+    for line in content.lines() {
         let elems = line.split(':').collect::<Vec<_>>();
         let covered = elems[0].trim();
         let line_number = elems[1].trim().parse::<usize>().unwrap();
         if covered.starts_with("-") || covered.starts_with("#") {
             continue;
         }
-        coverage.insert(("cgi_decode".to_string(), line_number));
+        coverage.insert((name.to_string(), line_number));
     }
 
     // Cleanup compiled and generated files.
     for file in [
-        "cgi_decode.c.gcov",
-        "../cgi_decode",
-        "../cgi_decode.gcda",
-        "../cgi_decode.gcno",
+        gcov,
+        target.to_string(),
+        format!("{}.gcda", target),
+        format!("{}.gcno", target),
     ] {
         let _ = fs::remove_file(file);
     }
 
-    coverage
+    Ok(coverage)
+}
+
+/// Run the C program and trace coverage data using LLVM
+/// source-based coverage.
+/// See https://clang.llvm.org/docs/SourceBasedCodeCoverage.html
+fn run_and_get_coverage_llvm(target: &str, input: Input) -> Result<StatementCoverage, String> {
+    let (name, source) = name_and_source(target);
+    let profraw = format!("{}.profraw", name);
+    let profdata = format!("{}.profdata", name);
+
+    // Compile the C program.
+    run_tool(
+        "clang",
+        &[
+            "-fprofile-instr-generate",
+            "-fcoverage-mapping",
+            "-o",
+            target,
+            &source,
+        ],
+    )?;
+
+    // Run the program. The raw profile is written to the given file.
+    run_target(
+        process::Command::new(target)
+            .arg(input)
+            .env("LLVM_PROFILE_FILE", &profraw),
+    )?;
+
+    // Index/merge the raw profile (there is only one here).
+    run_tool(
+        "llvm-profdata",
+        &["merge", "-sparse", &profraw, "-o", &profdata],
+    )?;
+
+    // Export coverage data as JSON (the default format), without the
+    // per-function details.
+    let out = run_tool(
+        "llvm-cov",
+        &[
+            "export",
+            target,
+            &format!("-instr-profile={}", profdata),
+            "-skip-functions",
+        ],
+    )?;
+    let export = String::from_utf8_lossy(&out.stdout);
+    let lines = llvm_covered_lines(&Json::parse(&export)?)
+        .ok_or("Unexpected structure of the llvm-cov export")?;
+    let coverage = lines
+        .into_iter()
+        .map(|line| (name.to_string(), line))
+        .collect();
+
+    // Cleanup compiled and generated files.
+    for file in [&profraw, &profdata, target] {
+        let _ = fs::remove_file(file);
+    }
+
+    Ok(coverage)
+}
+
+/// Executed lines in the JSON export of llvm-cov (the program has a single
+/// source file), or `None` if the export doesn't have the expected structure.
+///
+/// The coverage of each file is a list of segments
+/// `[line, column, count, has_count, is_region_entry, is_gap_region]`, sorted
+/// by position; each segment holds from its position up to the next one.
+/// A line counts as executed if a segment with an execution count > 0 covers
+/// (part of) it; gap regions (e.g. the space between an `if` and its block)
+/// are skipped. This is how llvm-cov itself derives line coverage.
+fn llvm_covered_lines(export: &Json) -> Option<BTreeSet<usize>> {
+    let mut lines = BTreeSet::new();
+    for data in export.get("data")?.as_array()? {
+        for file in data.get("files")?.as_array()? {
+            let segments = file
+                .get("segments")?
+                .as_array()?
+                .iter()
+                .map(|segment| {
+                    let segment = segment.as_array()?;
+                    Some((
+                        segment.first()?.as_f64()? as usize,
+                        segment.get(1)?.as_f64()? as usize,
+                        segment.get(2)?.as_f64()?,
+                        segment.get(3)?.as_bool()?,
+                        // Older exports have no gap flag.
+                        segment.get(5).and_then(Json::as_bool).unwrap_or(false),
+                    ))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            for pair in segments.windows(2) {
+                let (line, _, count, has_count, is_gap) = pair[0];
+                let (next_line, next_column, ..) = pair[1];
+                if !has_count || is_gap || count <= 0.0 {
+                    continue;
+                }
+                // The next segment ends this one before its column.
+                let last = if next_column > 1 {
+                    next_line
+                } else {
+                    next_line.saturating_sub(1)
+                };
+                lines.extend(line..=last.max(line));
+            }
+        }
+    }
+    Some(lines)
+}
+
+/// Run the (uninstrumented) C program under ptrace and trace which
+/// basic blocks were reached. Locations are block addresses instead of line
+/// numbers here.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn run_and_get_coverage_ptrace(
+    blocks: &Option<String>,
+    target: &str,
+    input: Input,
+) -> Result<StatementCoverage, String> {
+    let (name, source) = name_and_source(target);
+
+    // Compile the C program without any instrumentation; this stands in for a
+    // closed-source binary.
+    run_tool("gcc", &["-o", target, &source])?;
+
+    let blocks = match blocks {
+        Some(path) => ptrace::read_blocks(path),
+        None => ptrace::function_entries(target),
+    };
+
+    let coverage = ptrace::run_and_get_blocks(target, &input, &blocks)
+        .into_iter()
+        .map(|addr| (name.to_string(), addr as usize))
+        .collect();
+
+    // Cleanup compiled file.
+    let _ = fs::remove_file(target);
+
+    Ok(coverage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Export (shortened) of a run without arguments of
+    ///
+    /// ```c
+    /// int main(int argc, char **argv) {
+    ///     if (argc > 1)
+    ///         return 1;
+    ///     return 0;
+    /// }
+    /// ```
+    const EXPORT: &str = r#"{"data":[{"files":[{"branches":[],"expansions":[],
+        "filename":"/tmp/main.c","segments":[
+            [1,33,1,true,true,false],[2,18,1,true,false,true],
+            [3,9,0,true,true,false],[3,17,1,true,false,true],
+            [4,5,1,true,true,false],[5,2,0,false,false,false]],
+        "summary":{}}],"totals":{}}],
+        "type":"llvm.coverage.json.export","version":"2.0.1"}"#;
+
+    #[test]
+    fn llvm_export_lines() {
+        let export = Json::parse(EXPORT).unwrap();
+        let lines = llvm_covered_lines(&export).unwrap();
+        assert_eq!(lines, BTreeSet::from([1, 2, 4, 5]));
+    }
+
+    #[test]
+    fn llvm_export_structure() {
+        for text in [r#"{}"#, r#"{"data":[{"files":[{"segments":[[1,2]]}]}]}"#] {
+            assert_eq!(llvm_covered_lines(&Json::parse(text).unwrap()), None);
+        }
+    }
+
+    #[test]
+    fn missing_tool() {
+        let err = run_tool("no-such-compiler", &["--version"]).unwrap_err();
+        assert!(err.contains("no-such-compiler"), "{}", err);
+    }
 }
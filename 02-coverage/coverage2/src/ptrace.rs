@@ -0,0 +1,254 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Coverage for uninstrumented (e.g. closed-source) binaries via ptrace.
+//
+// A breakpoint (`int3`, byte 0xcc) is written to the entry of every basic
+// block of interest before the program starts. When a breakpoint is hit, the
+// block is recorded as covered and the original code is restored, so each
+// breakpoint triggers at most once ("coverage breakpoints"). The overhead is
+// therefore only paid for newly covered blocks, not for every executed
+// instruction as with single-stepping.
+//
+// The block addresses come either from a user-supplied list, or from a very
+// simple analysis of the ELF file (entries of all functions in the symbol
+// table, so only function-level coverage).
+//
+// Only supports Linux on x86_64. libc is linked by std anyway, so the few
+// needed functions are declared directly instead of pulling in a crate.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::{c_int, c_long, c_void};
+use std::fs;
+use std::os::unix::process::CommandExt;
+use std::process;
+
+extern "C" {
+    fn ptrace(request: c_int, ...) -> c_long;
+    fn waitpid(pid: c_int, status: *mut c_int, options: c_int) -> c_int;
+}
+
+const PTRACE_TRACEME: c_int = 0;
+const PTRACE_PEEKTEXT: c_int = 1;
+const PTRACE_PEEKUSER: c_int = 3;
+const PTRACE_POKETEXT: c_int = 4;
+const PTRACE_POKEUSER: c_int = 6;
+const PTRACE_CONT: c_int = 7;
+const PTRACE_KILL: c_int = 8;
+
+const SIGTRAP: c_int = 5;
+
+/// Offset of the instruction pointer `rip` in `struct user_regs_struct`
+/// (see `sys/user.h`).
+const RIP_OFFSET: usize = 16 * 8;
+
+/// Virtual addresses of basic block entries (as shown by e.g. objdump).
+pub type Blocks = Vec<u64>;
+
+/// Read a list of basic block addresses: one hexadecimal address per line,
+/// empty lines and lines starting with `#` are ignored.
+pub fn read_blocks(path: &str) -> Blocks {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| u64::from_str_radix(line.trim_start_matches("0x"), 16).unwrap())
+        .collect()
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+/// Whether the ELF file is position independent (`ET_DYN`), i.e. the
+/// addresses are relative to the load address.
+fn is_pie(elf: &[u8]) -> bool {
+    u16_at(elf, 0x10) == 3
+}
+
+/// Entry addresses of all functions defined in the symbol table of a 64-bit
+/// little-endian ELF file. This needs a non-stripped binary.
+pub fn function_entries(program: &str) -> Blocks {
+    let elf = fs::read(program).unwrap();
+    assert!(&elf[..4] == b"\x7fELF" && elf[4] == 2 && elf[5] == 1);
+
+    let shoff = u64_at(&elf, 0x28) as usize;
+    let shentsize = u16_at(&elf, 0x3a) as usize;
+    let shnum = u16_at(&elf, 0x3c) as usize;
+    let section = |i: usize| shoff + i * shentsize;
+
+    let mut res = BTreeSet::new();
+    for i in 0..shnum {
+        let sh = section(i);
+        // SHT_SYMTAB
+        if u32_at(&elf, sh + 0x04) != 2 {
+            continue;
+        }
+        let offset = u64_at(&elf, sh + 0x18) as usize;
+        let size = u64_at(&elf, sh + 0x20) as usize;
+        let entsize = u64_at(&elf, sh + 0x38) as usize;
+        for sym in (offset..offset + size).step_by(entsize) {
+            let info = elf[sym + 4];
+            let shndx = u16_at(&elf, sym + 6);
+            let value = u64_at(&elf, sym + 8);
+            // STT_FUNC, defined in this file.
+            if info & 0xf == 2 && shndx != 0 && value != 0 {
+                res.insert(value);
+            }
+        }
+    }
+    res.into_iter().collect()
+}
+
+/// Address the program is loaded at, from `/proc/<pid>/maps`. The first
+/// mapping belongs to the executable itself.
+fn load_address(pid: c_int) -> u64 {
+    let maps = fs::read_to_string(format!("/proc/{}/maps", pid)).unwrap();
+    let first = maps.lines().next().unwrap();
+    let start = first.split('-').next().unwrap();
+    u64::from_str_radix(start, 16).unwrap()
+}
+
+fn peek(pid: c_int, addr: u64) -> u64 {
+    unsafe {
+        ptrace(
+            PTRACE_PEEKTEXT,
+            pid,
+            addr as *mut c_void,
+            std::ptr::null_mut::<c_void>(),
+        ) as u64
+    }
+}
+
+fn poke(pid: c_int, addr: u64, word: u64) {
+    unsafe {
+        ptrace(
+            PTRACE_POKETEXT,
+            pid,
+            addr as *mut c_void,
+            word as *mut c_void,
+        )
+    };
+}
+
+/// Run a program with the given argument and return the (virtual, not
+/// relocated) addresses of all blocks that were reached.
+pub fn run_and_get_blocks(program: &str, arg: &str, blocks: &[u64]) -> BTreeSet<u64> {
+    let bias = if is_pie(&fs::read(program).unwrap()) {
+        None
+    } else {
+        Some(0)
+    };
+
+    let mut cmd = process::Command::new(program);
+    cmd.arg(arg)
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null());
+    // The child stops with SIGTRAP right after exec, before executing any
+    // instruction of the program.
+    unsafe {
+        cmd.pre_exec(|| {
+            ptrace(
+                PTRACE_TRACEME,
+                0,
+                std::ptr::null_mut::<c_void>(),
+                std::ptr::null_mut::<c_void>(),
+            );
+            Ok(())
+        });
+    }
+    // The child is reaped by hand with waitpid below, so the handle is not
+    // needed any more.
+    let pid = cmd.spawn().unwrap().id() as c_int;
+
+    let mut status: c_int = 0;
+    unsafe { waitpid(pid, &mut status, 0) };
+
+    let bias = bias.unwrap_or_else(|| load_address(pid));
+
+    // Insert breakpoints, remembering the original code. Only single bytes
+    // are saved/restored, since several blocks may lie within the same word.
+    let mut original = BTreeMap::new();
+    for &block in blocks {
+        let addr = block + bias;
+        let word = peek(pid, addr);
+        original.insert(addr, word & 0xff);
+        poke(pid, addr, (word & !0xff) | 0xcc);
+    }
+
+    let mut covered = BTreeSet::new();
+    let mut signal: c_int = 0;
+    loop {
+        unsafe {
+            ptrace(
+                PTRACE_CONT,
+                pid,
+                std::ptr::null_mut::<c_void>(),
+                signal as usize as *mut c_void,
+            );
+            waitpid(pid, &mut status, 0);
+        }
+
+        // Exited or killed by a signal.
+        if status & 0x7f == 0 || (status & 0xff) != 0x7f {
+            break;
+        }
+
+        signal = (status >> 8) & 0xff;
+        if signal != SIGTRAP {
+            // Not ours, pass the signal on to the program.
+            continue;
+        }
+
+        // After the int3 the instruction pointer is one past the breakpoint.
+        let rip = unsafe {
+            ptrace(
+                PTRACE_PEEKUSER,
+                pid,
+                RIP_OFFSET as *mut c_void,
+                std::ptr::null_mut::<c_void>(),
+            )
+        } as u64
+            - 1;
+        match original.remove(&rip) {
+            Some(byte) => {
+                covered.insert(rip - bias);
+                poke(pid, rip, (peek(pid, rip) & !0xff) | byte);
+                unsafe {
+                    ptrace(
+                        PTRACE_POKEUSER,
+                        pid,
+                        RIP_OFFSET as *mut c_void,
+                        rip as *mut c_void,
+                    )
+                };
+                signal = 0;
+            }
+            None => {
+                // A trap we did not set (shouldn't happen); give up.
+                unsafe {
+                    ptrace(
+                        PTRACE_KILL,
+                        pid,
+                        std::ptr::null_mut::<c_void>(),
+                        std::ptr::null_mut::<c_void>(),
+                    );
+                    waitpid(pid, &mut status, 0);
+                }
+                break;
+            }
+        }
+    }
+
+    covered
+}
@@ -0,0 +1,245 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Statement and branch (edge) coverage of a C program from gcov,
+// and the difference of two coverages.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::process;
+
+/// A statement (`branch` is `None`) or one outgoing branch of a statement
+/// (numbered per line as by gcov) in the source code.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Location {
+    pub file: String,
+    pub function: String,
+    pub line: usize,
+    pub branch: Option<usize>,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.branch {
+            None => write!(f, "line {}", self.line),
+            Some(branch) => write!(f, "line {} branch {}", self.line, branch),
+        }
+    }
+}
+
+pub type Coverage = BTreeSet<Location>;
+
+/// Runs a C program (cgi_decode by default) compiled with gcov
+/// instrumentation, given by its path without the `.c` extension.
+/// The program is compiled once, and removed again when this is dropped.
+pub struct Gcov {
+    target: String,
+}
+
+impl Gcov {
+    pub fn new(target: &str) -> Self {
+        let gcov = Self {
+            target: target.to_string(),
+        };
+        let output = process::Command::new("gcc")
+            .args(["--coverage", "-o", target, &gcov.source()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "Compilation failed");
+        gcov
+    }
+
+    /// Path of the source file.
+    pub fn source(&self) -> String {
+        format!("{}.c", self.target)
+    }
+
+    /// Name of the source file, as in the locations.
+    pub fn file_name(&self) -> String {
+        format!("{}.c", self.target.rsplit('/').next().unwrap())
+    }
+
+    /// Run gcov on the data file of the program and parse its output.
+    fn gcov(&self) -> String {
+        // With branch counts (`-b -c`), and the function names in the
+        // `function ... called ...` lines.
+        process::Command::new("gcov")
+            .args(["-b", "-c", &self.source()])
+            .output()
+            .unwrap();
+        let path = format!("{}.gcov", self.file_name());
+        let gcov = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        gcov
+    }
+
+    /// Run the program with the input and get its coverage.
+    pub fn run(&self, input: &str) -> Coverage {
+        // The counters in the data file accumulate over runs.
+        let _ = fs::remove_file(format!("{}.gcda", self.target));
+
+        process::Command::new(&self.target)
+            .arg(input)
+            .output()
+            .unwrap();
+
+        parse_gcov(&self.file_name(), &self.gcov())
+    }
+
+    /// Union of the coverage of all inputs (one per file) in directory `dir`.
+    pub fn run_corpus(&self, dir: &Path) -> Coverage {
+        let mut coverage = Coverage::new();
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_file() {
+                let input = String::from_utf8_lossy(&fs::read(path).unwrap()).to_string();
+                coverage.extend(self.run(&input));
+            }
+        }
+        coverage
+    }
+}
+
+impl Drop for Gcov {
+    fn drop(&mut self) {
+        for file in [
+            self.target.clone(),
+            format!("{}.gcda", self.target),
+            format!("{}.gcno", self.target),
+        ] {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
+
+/// "Parse" (process) a gcov coverage file (of `gcov -b -c`). Relevant lines:
+///
+/// ```text
+/// function cgi_decode called 1 returned 100% blocks executed 69%
+///         4:   46:    while (*s != '\0') {
+/// branch  0 taken 3
+/// branch  1 taken 1 (fallthrough)
+///     #####:   50:            int digit_high = *++s;
+/// branch  0 never executed
+/// ```
+fn parse_gcov(file: &str, gcov: &str) -> Coverage {
+    let mut coverage = Coverage::new();
+    let mut function = String::new();
+    let mut line_number = 0;
+    for line in gcov.lines() {
+        if let Some(rest) = line.strip_prefix("function ") {
+            function = rest.split_whitespace().next().unwrap().to_string();
+        } else if let Some(rest) = line.strip_prefix("branch ") {
+            let mut words = rest.split_whitespace();
+            let branch = words.next().unwrap().parse().unwrap();
+            let taken = words.next() == Some("taken")
+                && words.next().unwrap().parse::<usize>().unwrap() > 0;
+            if taken {
+                coverage.insert(Location {
+                    file: file.to_string(),
+                    function: function.clone(),
+                    line: line_number,
+                    branch: Some(branch),
+                });
+            }
+        } else if let [count, number, ..] = line.splitn(3, ':').collect::<Vec<_>>()[..] {
+            let count = count.trim();
+            line_number = number.trim().parse().unwrap();
+            if !(count.starts_with('-') || count.starts_with('#')) {
+                coverage.insert(Location {
+                    file: file.to_string(),
+                    function: function.clone(),
+                    line: line_number,
+                    branch: None,
+                });
+            }
+        }
+    }
+    coverage
+}
+
+/// Locations covered only by a, and only by b.
+pub type Differences<'a> = (Vec<&'a Location>, Vec<&'a Location>);
+
+/// Locations covered by only one of two coverages a and b.
+pub struct CoverageDiff {
+    pub only_a: Coverage,
+    pub only_b: Coverage,
+}
+
+impl CoverageDiff {
+    pub fn new(a: &Coverage, b: &Coverage) -> Self {
+        Self {
+            only_a: a.difference(b).cloned().collect(),
+            only_b: b.difference(a).cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.only_a.is_empty() && self.only_b.is_empty()
+    }
+
+    /// The differences grouped by file and function.
+    pub fn grouped(&self) -> BTreeMap<(String, String), Differences<'_>> {
+        let mut res: BTreeMap<_, Differences> = BTreeMap::new();
+        for location in self.only_a.iter() {
+            let key = (location.file.clone(), location.function.clone());
+            res.entry(key).or_default().0.push(location);
+        }
+        for location in self.only_b.iter() {
+            let key = (location.file.clone(), location.function.clone());
+            res.entry(key).or_default().1.push(location);
+        }
+        res
+    }
+
+    /// The source code of the functions with differences, with each line
+    /// marked by `a` / `b` if it (or one of its branches) is covered only by
+    /// a / b, and the differing branches listed below the line.
+    pub fn annotate(&self, file: &str, source: &str) -> String {
+        let mut res = String::new();
+        for ((difffile, function), (only_a, only_b)) in self.grouped() {
+            if difffile != file {
+                continue;
+            }
+            // Lines of the function: from the first to the last line of the
+            // differences, extended to the surrounding empty lines.
+            let lines: Vec<_> = source.lines().collect();
+            let all = only_a.iter().chain(only_b.iter());
+            let first = all.clone().map(|l| l.line).min().unwrap();
+            let last = all.map(|l| l.line).max().unwrap();
+            let start = (1..first)
+                .rev()
+                .find(|&i| lines[i - 1].trim().is_empty())
+                .map_or(1, |i| i + 1);
+            let end = (last..=lines.len())
+                .find(|&i| lines[i - 1].trim().is_empty())
+                .map_or(lines.len(), |i| i - 1);
+
+            res += &format!("{} ({}):\n", function, file);
+            for i in start..=end {
+                let mark = match (
+                    only_a.iter().any(|l| l.line == i),
+                    only_b.iter().any(|l| l.line == i),
+                ) {
+                    (true, true) => "ab",
+                    (true, false) => "a ",
+                    (false, true) => " b",
+                    (false, false) => "  ",
+                };
+                res += &format!("{} {:4} | {}\n", mark, i, lines[i - 1]);
+                for (side, diff) in [("a", &only_a), ("b", &only_b)] {
+                    for location in diff.iter().filter(|l| l.line == i) {
+                        if let Some(branch) = location.branch {
+                            res += &format!("        | branch {} taken only by {}\n", branch, side);
+                        }
+                    }
+                }
+            }
+            res += "\n";
+        }
+        res
+    }
+}
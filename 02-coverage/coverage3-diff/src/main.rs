@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// From https://www.fuzzingbook.org/html/Coverage.html
+
+mod cli;
+mod coverage;
+
+use std::fs;
+
+use coverage::{CoverageDiff, Gcov};
+
+/// Compare the coverage of the cgi_decode C program (or the one given with
+/// `--target`, without the `.c` extension) for two inputs (or two
+/// corpus directories): which statements and branches are covered by one but
+/// not the other? E.g. to find out why a mutant didn't explore a branch.
+fn main() {
+    let annotate = cli::flag("--annotate");
+    let corpus = cli::flag("--corpus");
+    let positional = cli::positional();
+    let a = positional.first().map_or("abc", |arg| arg.as_str());
+    let b = positional.get(1).map_or("a+b", |arg| arg.as_str());
+
+    let gcov = Gcov::new(&cli::target("../cgi_decode"));
+    let (coverage_a, coverage_b) = if corpus {
+        (gcov.run_corpus(a.as_ref()), gcov.run_corpus(b.as_ref()))
+    } else {
+        (gcov.run(a), gcov.run(b))
+    };
+    println!("[+] a = {:?}: {} locations covered", a, coverage_a.len());
+    println!("[+] b = {:?}: {} locations covered", b, coverage_b.len());
+
+    let diff = CoverageDiff::new(&coverage_a, &coverage_b);
+    if diff.is_empty() {
+        println!("[+] Same coverage");
+        return;
+    }
+    if cli::quiet() {
+        return;
+    }
+
+    println!();
+    for ((file, function), (only_a, only_b)) in diff.grouped() {
+        println!("[+] {} ({}):", function, file);
+        for location in only_a.iter() {
+            println!("    only a: {}", location);
+        }
+        for location in only_b.iter() {
+            println!("    only b: {}", location);
+        }
+    }
+
+    if annotate {
+        let source = fs::read_to_string(gcov.source()).unwrap();
+        println!();
+        print!("{}", diff.annotate(&gcov.file_name(), &source));
+    }
+}
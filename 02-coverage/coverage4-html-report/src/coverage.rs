@@ -0,0 +1,157 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Statement and branch (edge) coverage of a C program from gcov.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::process;
+
+/// A statement (`branch` is `None`) or one outgoing branch of a statement
+/// (numbered per line as by gcov) in the source code.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Location {
+    pub file: String,
+    pub function: String,
+    pub line: usize,
+    pub branch: Option<usize>,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.branch {
+            None => write!(f, "line {}", self.line),
+            Some(branch) => write!(f, "line {} branch {}", self.line, branch),
+        }
+    }
+}
+
+pub type Coverage = BTreeSet<Location>;
+
+/// Runs a C program (cgi_decode by default) compiled with gcov
+/// instrumentation, given by its path without the `.c` extension.
+/// The program is compiled once, and removed again when this is dropped.
+pub struct Gcov {
+    target: String,
+}
+
+impl Gcov {
+    pub fn new(target: &str) -> Self {
+        let gcov = Self {
+            target: target.to_string(),
+        };
+        let output = process::Command::new("gcc")
+            .args(["--coverage", "-o", target, &gcov.source()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "Compilation failed");
+        gcov
+    }
+
+    /// Path of the source file.
+    pub fn source(&self) -> String {
+        format!("{}.c", self.target)
+    }
+
+    /// Name of the source file, as in the locations.
+    pub fn file_name(&self) -> String {
+        format!("{}.c", self.target.rsplit('/').next().unwrap())
+    }
+
+    /// Run gcov on the data file of the program and parse its output.
+    fn gcov(&self) -> String {
+        // With branch counts (`-b -c`), and the function names in the
+        // `function ... called ...` lines.
+        process::Command::new("gcov")
+            .args(["-b", "-c", &self.source()])
+            .output()
+            .unwrap();
+        let path = format!("{}.gcov", self.file_name());
+        let gcov = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        gcov
+    }
+
+    /// Run the program with the input and get its coverage.
+    pub fn run(&self, input: &str) -> Coverage {
+        // The counters in the data file accumulate over runs.
+        let _ = fs::remove_file(format!("{}.gcda", self.target));
+
+        process::Command::new(&self.target)
+            .arg(input)
+            .output()
+            .unwrap();
+
+        parse_gcov(&self.file_name(), &self.gcov(), false)
+    }
+
+    /// All instrumented locations (the ones that could be covered at all),
+    /// i.e. also those that are never executed.
+    pub fn instrumented(&self) -> Coverage {
+        // Without data file, gcov reports every location as not executed.
+        let _ = fs::remove_file(format!("{}.gcda", self.target));
+        parse_gcov(&self.file_name(), &self.gcov(), true)
+    }
+}
+
+impl Drop for Gcov {
+    fn drop(&mut self) {
+        for file in [
+            self.target.clone(),
+            format!("{}.gcda", self.target),
+            format!("{}.gcno", self.target),
+        ] {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
+
+/// "Parse" (process) a gcov coverage file (of `gcov -b -c`). Relevant lines:
+///
+/// ```text
+/// function cgi_decode called 1 returned 100% blocks executed 69%
+///         4:   46:    while (*s != '\0') {
+/// branch  0 taken 3
+/// branch  1 taken 1 (fallthrough)
+///     #####:   50:            int digit_high = *++s;
+/// branch  0 never executed
+/// ```
+///
+/// With `all`, the instrumented locations are returned whether they were
+/// executed or not.
+fn parse_gcov(file: &str, gcov: &str, all: bool) -> Coverage {
+    let mut coverage = Coverage::new();
+    let mut function = String::new();
+    let mut line_number = 0;
+    for line in gcov.lines() {
+        if let Some(rest) = line.strip_prefix("function ") {
+            function = rest.split_whitespace().next().unwrap().to_string();
+        } else if let Some(rest) = line.strip_prefix("branch ") {
+            let mut words = rest.split_whitespace();
+            let branch = words.next().unwrap().parse().unwrap();
+            let taken = words.next() == Some("taken")
+                && words.next().unwrap().parse::<usize>().unwrap() > 0;
+            if taken || all {
+                coverage.insert(Location {
+                    file: file.to_string(),
+                    function: function.clone(),
+                    line: line_number,
+                    branch: Some(branch),
+                });
+            }
+        } else if let [count, number, ..] = line.splitn(3, ':').collect::<Vec<_>>()[..] {
+            let count = count.trim();
+            line_number = number.trim().parse().unwrap();
+            if !count.starts_with('-') && (all || !count.starts_with('#')) {
+                coverage.insert(Location {
+                    file: file.to_string(),
+                    function: function.clone(),
+                    line: line_number,
+                    branch: None,
+                });
+            }
+        }
+    }
+    coverage
+}
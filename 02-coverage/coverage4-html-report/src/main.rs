@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// From https://www.fuzzingbook.org/html/Coverage.html
+
+mod cli;
+mod coverage;
+mod report;
+mod rng;
+
+use std::fs;
+
+use coverage::{Coverage, Gcov};
+
+/// Fuzz an external C program (cgi_decode) with random inputs, and write an
+/// HTML report of the cumulative coverage of the campaign.
+fn main() {
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
+    let out = cli::out_dir(".").join(
+        cli::positional()
+            .first()
+            .map_or("report.html", |arg| arg.as_str()),
+    );
+
+    let gcov = Gcov::new(&cli::target("../cgi_decode"));
+    let instrumented = gcov.instrumented();
+
+    let mut coverage = Coverage::new();
+    for i in 0..cli::iterations(100) {
+        let len = rng.range(5, 10);
+        let input = rng.ascii_printable(len);
+        coverage.extend(gcov.run(&input));
+        if (i + 1) % 25 == 0 && !cli::quiet() {
+            println!(
+                "[+] {} inputs: {:.1}% of the lines covered",
+                i + 1,
+                report::line_percentage(&coverage, &instrumented)
+            );
+        }
+    }
+
+    let source = fs::read_to_string(gcov.source()).unwrap();
+    let html = report::html(&gcov.file_name(), &source, &coverage, &instrumented);
+    fs::write(&out, html).unwrap();
+    println!("[+] Report written to {}", out.display());
+}
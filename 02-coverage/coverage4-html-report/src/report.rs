@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// HTML coverage report (similar to what gcovr/lcov generate): the source code
+// with covered and uncovered lines highlighted, and the percentage of covered
+// lines and branches per function.
+
+use std::collections::BTreeMap;
+
+use crate::coverage::Coverage;
+
+/// Covered and total number of lines and branches.
+#[derive(Default)]
+struct Counts {
+    lines: (usize, usize),
+    branches: (usize, usize),
+}
+
+/// Generate the report for the source file `file` with content `source`.
+/// `instrumented` are all locations that could be covered (see
+/// `Gcov::instrumented`), `coverage` the ones that were.
+pub fn html(file: &str, source: &str, coverage: &Coverage, instrumented: &Coverage) -> String {
+    let locations: Vec<_> = instrumented.iter().filter(|l| l.file == file).collect();
+
+    let mut functions: BTreeMap<&str, Counts> = BTreeMap::new();
+    let mut total = Counts::default();
+    for location in locations.iter() {
+        let covered = coverage.contains(location) as usize;
+        let counts = functions.entry(&location.function).or_default();
+        for counts in [counts, &mut total] {
+            let count = match location.branch {
+                None => &mut counts.lines,
+                Some(_) => &mut counts.branches,
+            };
+            count.0 += covered;
+            count.1 += 1;
+        }
+    }
+
+    let mut res = String::new();
+    res += "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n";
+    res += &format!("<title>Coverage of {}</title>\n", escape(file));
+    res += "<style>\n";
+    res += "body { font-family: sans-serif; }\n";
+    res += "table { border-collapse: collapse; }\n";
+    res += "td, th { padding: 0 0.5em; text-align: right; }\n";
+    res += ".source td { font-family: monospace; white-space: pre; text-align: left; }\n";
+    res += ".source td.line { text-align: right; color: grey; }\n";
+    res += ".covered { background-color: #c8f0c8; }\n";
+    res += ".uncovered { background-color: #f0c8c8; }\n";
+    res += ".partial { background-color: #f0f0b4; }\n";
+    res += "</style>\n</head>\n<body>\n";
+    res += &format!("<h1>Coverage of {}</h1>\n", escape(file));
+
+    res += "<table class=\"functions\">\n";
+    res += "<tr><th>Function</th><th>Lines</th><th></th><th>Branches</th><th></th></tr>\n";
+    for (function, counts) in functions.iter() {
+        res += &counts_row(&escape(function), counts);
+    }
+    res += &counts_row("<b>Total</b>", &total);
+    res += "</table>\n";
+
+    res += "<table class=\"source\">\n";
+    res += "<tr><th>Line</th><th>Branches</th><th>Source</th></tr>\n";
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let at_line = |covered_only: bool, branch: bool| {
+            locations
+                .iter()
+                .filter(|l| l.line == line_number && l.branch.is_some() == branch)
+                .filter(|l| !covered_only || coverage.contains(l))
+                .count()
+        };
+        let (lines, lines_covered) = (at_line(false, false), at_line(true, false));
+        let (branches, branches_covered) = (at_line(false, true), at_line(true, true));
+
+        // Partially covered: the line was executed, but not all of its
+        // branches were taken.
+        let class = if lines == 0 {
+            ""
+        } else if lines_covered == 0 {
+            " class=\"uncovered\""
+        } else if branches_covered < branches {
+            " class=\"partial\""
+        } else {
+            " class=\"covered\""
+        };
+        let branches = if branches > 0 {
+            format!("{}/{}", branches_covered, branches)
+        } else {
+            String::new()
+        };
+        res += &format!(
+            "<tr{}><td class=\"line\">{}</td><td class=\"line\">{}</td><td>{}</td></tr>\n",
+            class,
+            line_number,
+            branches,
+            escape(line)
+        );
+    }
+    res += "</table>\n</body>\n</html>\n";
+    res
+}
+
+fn counts_row(name: &str, counts: &Counts) -> String {
+    let [lines, branches] = [counts.lines, counts.branches].map(|(covered, all)| {
+        let percent = if all == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.1}%", 100.0 * covered as f64 / all as f64)
+        };
+        format!("<td>{}/{}</td><td>{}</td>", covered, all, percent)
+    });
+    format!(
+        "<tr><td style=\"text-align: left\">{}</td>{}{}</tr>\n",
+        name, lines, branches
+    )
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Percentage of the covered lines (for printing a summary).
+pub fn line_percentage(coverage: &Coverage, instrumented: &Coverage) -> f64 {
+    let lines: Vec<_> = instrumented.iter().filter(|l| l.branch.is_none()).collect();
+    let covered = lines.iter().filter(|l| coverage.contains(l)).count();
+    100.0 * covered as f64 / lines.len() as f64
+}
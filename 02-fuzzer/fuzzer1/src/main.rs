@@ -5,22 +5,34 @@
 
 // From https://www.fuzzingbook.org/html/Fuzzer.html
 
+mod cli;
 mod rng;
 
+use rng::Rng;
+
 fn main() {
-    let random_fuzzer = RandomFuzzer::default();
-    for _ in 0..10 {
-        println!("{}", String::from_utf8(random_fuzzer.fuzz()).unwrap());
-    }
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
 
-    let random_fuzzer = RandomFuzzer::new(10, 20, 65, 26);
-    for _ in 0..10 {
-        println!("{}", String::from_utf8(random_fuzzer.fuzz()).unwrap());
+    let n = cli::iterations(10);
+    let inputs = generate(&mut rng, n);
+    for input in inputs.iter() {
+        println!("{}", String::from_utf8(input.clone()).unwrap());
     }
 }
 
+/// `n` inputs of the default random fuzzer, followed by `n` inputs of one
+/// that generates uppercase letters.
+fn generate(rng: &mut Rng, n: usize) -> Vec<Vec<u8>> {
+    let default_fuzzer = RandomFuzzer::default();
+    let letter_fuzzer = RandomFuzzer::new(10, 20, 65, 26);
+    let mut inputs: Vec<Vec<u8>> = (0..n).map(|_| default_fuzzer.fuzz(rng)).collect();
+    inputs.extend((0..n).map(|_| letter_fuzzer.fuzz(rng)));
+    inputs
+}
+
 trait Fuzzer {
-    fn fuzz(&self) -> Vec<u8>;
+    fn fuzz(&self, rng: &mut Rng) -> Vec<u8>;
 }
 
 struct RandomFuzzer {
@@ -50,8 +62,7 @@ impl Default for RandomFuzzer {
 }
 
 impl Fuzzer for RandomFuzzer {
-    fn fuzz(&self) -> Vec<u8> {
-        let mut rng = rng::Rng::new();
+    fn fuzz(&self, rng: &mut Rng) -> Vec<u8> {
         let len = rng.range(self.min_length, self.max_length);
         let mut res = Vec::new();
         for _ in 0..len {
@@ -60,3 +71,17 @@ impl Fuzzer for RandomFuzzer {
         res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All random decisions are taken from the one generator that is passed
+    /// to the fuzzers, so the seed is enough to replay a run.
+    #[test]
+    fn seed_replays_run() {
+        let inputs = generate(&mut Rng::seeded(42), 10);
+        let replayed = generate(&mut Rng::seeded(42), 10);
+        assert_eq!(replayed, inputs);
+    }
+}
@@ -5,23 +5,22 @@
 
 // From https://www.fuzzingbook.org/html/Fuzzer.html
 
+mod bytes;
+mod cli;
 mod rng;
 
+use bytes::Bytes;
+use rng::Rng;
+
 fn main() {
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
+
     let random_fuzzer = RandomFuzzer::new(10, 20, 65, 26);
     let print_runner = PrintRunner {};
 
-    for _ in 0..10 {
-        random_fuzzer.run(&print_runner);
-    }
-}
-
-struct Bytes(Vec<u8>);
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Bytes {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", std::str::from_utf8(&self.0).unwrap())
+    for _ in 0..cli::iterations(10) {
+        random_fuzzer.run(&mut rng, &print_runner);
     }
 }
 
@@ -45,8 +44,8 @@ impl Runner for PrintRunner {
 }
 
 trait Fuzzer {
-    fn fuzz(&self) -> Bytes;
-    fn run<T: Runner>(&self, runner: &T) -> (Bytes, RunResult);
+    fn fuzz(&self, rng: &mut Rng) -> Bytes;
+    fn run<T: Runner>(&self, rng: &mut Rng, runner: &T) -> (Bytes, RunResult);
 }
 
 struct RandomFuzzer {
@@ -76,8 +75,7 @@ impl Default for RandomFuzzer {
 }
 
 impl Fuzzer for RandomFuzzer {
-    fn fuzz(&self) -> Bytes {
-        let mut rng = rng::Rng::new();
+    fn fuzz(&self, rng: &mut Rng) -> Bytes {
         let len = rng.range(self.min_length, self.max_length);
         let mut res = Vec::new();
         for _ in 0..len {
@@ -86,7 +84,7 @@ impl Fuzzer for RandomFuzzer {
         Bytes(res)
     }
 
-    fn run<T: Runner>(&self, runner: &T) -> (Bytes, RunResult) {
-        runner.run(self.fuzz())
+    fn run<T: Runner>(&self, rng: &mut Rng, runner: &T) -> (Bytes, RunResult) {
+        runner.run(self.fuzz(rng))
     }
 }
@@ -5,32 +5,34 @@
 
 // From https://www.fuzzingbook.org/html/Fuzzer.html
 
+mod bytes;
+mod cli;
 mod rng;
 
 use std::io::{Read, Write};
 use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
 
-fn main() {
-    let random_fuzzer = RandomFuzzer::new(10, 20, 65, 26);
-    let program_runner = ProgramRunner::new("cat".to_string());
+use bytes::Bytes;
+use rng::Rng;
 
-    for _ in 0..10 {
-        let res = random_fuzzer.run(&program_runner);
-        println!("{:?} {}", res.1, res.0);
-    }
-}
+fn main() {
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
 
-#[derive(Debug)]
-struct Bytes(Vec<u8>);
+    let random_fuzzer = RandomFuzzer::new(10, 20, 65, 26);
+    let program_runner = ProgramRunner::new(cli::target("cat"));
 
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Bytes {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", std::str::from_utf8(&self.0).unwrap())
+    for _ in 0..cli::iterations(10) {
+        let res = random_fuzzer.run(&mut rng, &program_runner);
+        if !cli::quiet() {
+            println!("{:?} {}", res.1, res.0);
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 enum RunResult {
     Pass,
     Fail,
@@ -41,6 +43,8 @@ trait Runner {
     fn run(&self, inp: Bytes) -> (Bytes, RunResult);
 }
 
+// Not used in main, kept as the simplest possible example of a runner.
+#[allow(dead_code)]
 struct PrintRunner {}
 
 impl Runner for PrintRunner {
@@ -50,13 +54,67 @@ impl Runner for PrintRunner {
     }
 }
 
+/// Runs an external program, feeding the input on stdin and capturing stdout.
 struct ProgramRunner {
     program: String,
+    /// Maximum number of bytes of stdout and stderr (each) that are captured.
+    /// Once this is reached the program's output pipes are closed, so a
+    /// program that doesn't stop printing (e.g. `yes`) gets killed by SIGPIPE.
+    max_output: usize,
+    /// A program that runs longer is killed, and the result is unresolved.
+    timeout: Duration,
 }
 
 impl ProgramRunner {
     fn new(program: String) -> Self {
-        Self { program }
+        Self::with_max_output(program, 1024 * 1024)
+    }
+
+    fn with_max_output(program: String, max_output: usize) -> Self {
+        Self {
+            program,
+            max_output,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    // Not used in main, where the default is fine.
+    #[allow(dead_code)]
+    fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Read at most `max` bytes from `r`. Returns the data and whether there would
+/// have been more.
+fn read_limited<R: Read>(r: R, max: usize) -> (Vec<u8>, bool) {
+    let mut res = Vec::new();
+    // Read one byte more than allowed to find out if the output was truncated.
+    r.take(max as u64 + 1).read_to_end(&mut res).unwrap();
+    let truncated = res.len() > max;
+    res.truncate(max);
+    (res, truncated)
+}
+
+/// Wait for the program to exit, and kill it once the timeout is over.
+/// Returns the exit status and whether the program was killed.
+fn wait_with_timeout(pgm: &mut process::Child, timeout: Duration) -> (process::ExitStatus, bool) {
+    let deadline = Instant::now() + timeout;
+    // Poll often at first (most runs are short), and less often the longer
+    // the program runs.
+    let mut poll = Duration::from_micros(50);
+    loop {
+        if let Some(status) = pgm.try_wait().unwrap() {
+            return (status, false);
+        }
+        if Instant::now() >= deadline {
+            // It may have exited in the meantime, then this fails.
+            let _ = pgm.kill();
+            return (pgm.wait().unwrap(), true);
+        }
+        thread::sleep(poll);
+        poll = (poll * 2).min(Duration::from_millis(10));
     }
 }
 
@@ -70,26 +128,39 @@ impl Runner for ProgramRunner {
             .unwrap();
 
         let mut stdin = pgm.stdin.take().unwrap();
-        let mut stdout = pgm.stdout.take().unwrap();
-        let mut stderr = pgm.stderr.take().unwrap();
-
-        stdin.write_all(&inp.0).unwrap();
-        drop(stdin);
-
-        let mut o: Vec<u8> = Vec::new();
-        stdout.read_to_end(&mut o).unwrap();
+        let stdout = pgm.stdout.take().unwrap();
+        let stderr = pgm.stderr.take().unwrap();
 
-        let mut e: Vec<u8> = Vec::new();
-        stderr.read_to_end(&mut e).unwrap();
+        // Writing stdin and reading stdout/stderr has to happen concurrently:
+        // If we first wrote all of stdin, a program that produces lots of
+        // output (e.g. `cat` on a large input) would block on writing to its
+        // full stdout pipe, not read its stdin any more, and we'd deadlock.
+        // Meanwhile this thread waits for the program, so that one that never
+        // exits can be killed (which also ends the other threads: the pipes
+        // are closed).
+        let (o, truncated, status, timed_out) = thread::scope(|s| {
+            s.spawn(move || {
+                // The program may exit without reading all of its input
+                // (broken pipe); that's fine.
+                let _ = stdin.write_all(&inp.0);
+            });
+            let e = s.spawn(|| read_limited(stderr, self.max_output));
+            let o = s.spawn(|| read_limited(stdout, self.max_output));
+            let (status, timed_out) = wait_with_timeout(&mut pgm, self.timeout);
+            let (o, o_truncated) = o.join().unwrap();
+            let (_e, e_truncated) = e.join().unwrap();
+            (o, o_truncated || e_truncated, status, timed_out)
+        });
 
-        let exitcode = pgm.wait().unwrap().code().unwrap();
-
-        let outcome = if exitcode == 0 {
-            RunResult::Pass
-        } else if exitcode < 0 {
-            RunResult::Fail
-        } else {
-            RunResult::Unresolved
+        let outcome = match status.code() {
+            // Killed because it took too long.
+            _ if timed_out => RunResult::Unresolved,
+            Some(0) => RunResult::Pass,
+            Some(_) => RunResult::Unresolved,
+            // Killed by SIGPIPE because we stopped reading its output.
+            None if truncated && signal(status) == Some(13) => RunResult::Unresolved,
+            // Killed by a signal, i.e. crashed.
+            None => RunResult::Fail,
         };
 
         (Bytes(o), outcome)
@@ -97,8 +168,8 @@ impl Runner for ProgramRunner {
 }
 
 trait Fuzzer {
-    fn fuzz(&self) -> Bytes;
-    fn run<T: Runner>(&self, runner: &T) -> (Bytes, RunResult);
+    fn fuzz(&self, rng: &mut Rng) -> Bytes;
+    fn run<T: Runner>(&self, rng: &mut Rng, runner: &T) -> (Bytes, RunResult);
 }
 
 struct RandomFuzzer {
@@ -128,8 +199,7 @@ impl Default for RandomFuzzer {
 }
 
 impl Fuzzer for RandomFuzzer {
-    fn fuzz(&self) -> Bytes {
-        let mut rng = rng::Rng::new();
+    fn fuzz(&self, rng: &mut Rng) -> Bytes {
         let len = rng.range(self.min_length, self.max_length);
         let mut res = Vec::new();
         for _ in 0..len {
@@ -138,7 +208,62 @@ impl Fuzzer for RandomFuzzer {
         Bytes(res)
     }
 
-    fn run<T: Runner>(&self, runner: &T) -> (Bytes, RunResult) {
-        runner.run(self.fuzz())
+    fn run<T: Runner>(&self, rng: &mut Rng, runner: &T) -> (Bytes, RunResult) {
+        runner.run(self.fuzz(rng))
+    }
+}
+
+/// Signal that terminated the process, if any (only Unix has signals).
+#[cfg(unix)]
+fn signal(status: process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn signal(_status: process::ExitStatus) -> Option<i32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An input that doesn't fit into the pipe buffer at once must not
+    /// deadlock with `cat` writing it back.
+    #[test]
+    fn large_input_and_output() {
+        let (out, result) = ProgramRunner::new("cat".to_string()).run(Bytes(vec![b'A'; 1 << 20]));
+        assert_eq!(result, RunResult::Pass);
+        assert_eq!(out.0.len(), 1 << 20);
+    }
+
+    /// A program that exits without reading its input closes stdin early.
+    #[test]
+    fn unread_input() {
+        let (out, result) = ProgramRunner::new("true".to_string()).run(Bytes(vec![b'A'; 1 << 20]));
+        assert_eq!(result, RunResult::Pass);
+        assert!(out.0.is_empty());
+    }
+
+    /// A program that doesn't stop producing output is cut off at the limit.
+    #[test]
+    fn output_limit() {
+        let (out, result) =
+            ProgramRunner::with_max_output("yes".to_string(), 4096).run(Bytes(vec![]));
+        assert_eq!(result, RunResult::Unresolved);
+        assert_eq!(out.0.len(), 4096);
+    }
+
+    /// A program that never exits is killed after the timeout (`exec`, so
+    /// that the shell doesn't leave `sleep` holding the pipes open).
+    #[test]
+    fn timeout() {
+        let start = Instant::now();
+        let (_, result) = ProgramRunner::new("sh".to_string())
+            .with_timeout(Duration::from_millis(100))
+            .run(Bytes::from("exec sleep 60"));
+        assert_eq!(result, RunResult::Unresolved);
+        assert!(start.elapsed() < Duration::from_secs(10));
     }
 }
@@ -5,69 +5,174 @@
 
 // From https://www.fuzzingbook.org/html/Fuzzer.html
 
+mod bytes;
+mod cli;
+mod oracle;
 mod rng;
 
 use std::fs;
 use std::process;
 
+use bytes::Bytes;
+use oracle::{Oracle, Rule, Statistics};
+use rng::Rng;
+
 fn main() {
-    let tmpdir = format!("/tmp/tmp-{}", unsafe { core::arch::x86_64::_rdtsc() });
-    let tmpfile = format!("{}/{}", tmpdir, "input.txt");
-    fs::create_dir(tmpdir).unwrap();
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    // Private directory for the input file in the temporary directory of the
+    // system (`/tmp`, `%TEMP%`, ...), named after the process.
+    let tmpdir = std::env::temp_dir().join(format!("fuzzer4-{}", process::id()));
+    let tmpfile = tmpdir.join("input.txt");
+    fs::create_dir(&tmpdir).unwrap();
+
+    // Show an example of each generation mode.
+    for mode in [
+        Mode::AsciiPrintable,
+        Mode::AnyBytes,
+        Mode::Utf8,
+        Mode::format_string(),
+        Mode::path(),
+    ] {
+        println!("{}", RandomFuzzer::with_mode(5, 20, mode).fuzz(&mut rng));
+    }
 
     let random_fuzzer = RandomFuzzer::new(20, 100, 32, 32);
 
-    let mut runs = Vec::new();
-    for _ in 0..100 {
-        let data = random_fuzzer.fuzz();
+    // The program gets the path of the input file as argument: `bc` by
+    // default, or any other with `--target <program>`.
+    let target = cli::target("bc");
+
+    // Additional rules for the output with `--pattern <name>=<pattern>`.
+    let mut oracle = Oracle::default();
+    for spec in options_from_cli("--pattern") {
+        oracle.rules.push(Rule::parse(&spec).unwrap());
+    }
+    println!("[+] Output rules:");
+    for rule in oracle.rules.iter() {
+        println!(
+            "    - {:20} {:?} {}",
+            rule.name, rule.stream, rule.pattern.source
+        );
+    }
+
+    let mut stats = Statistics::default();
+    for run in 0..cli::iterations(100) {
+        let data = random_fuzzer.fuzz(&mut rng);
         fs::write(&tmpfile, data.0).unwrap();
 
-        let out = process::Command::new("bc")
+        let out = process::Command::new(&target)
             .arg(&tmpfile)
             .stdin(process::Stdio::null())
             .output()
             .unwrap();
 
+        if !cli::quiet() {
+            println!(
+                "{} {} {}",
+                oracle::describe(out.status),
+                Bytes(out.stdout.clone()),
+                Bytes(out.stderr.clone())
+            );
+        }
+        stats.record(out.status, oracle.check(run, &out.stdout, &out.stderr));
+    }
+    fs::remove_dir_all(&tmpdir).unwrap();
+
+    println!();
+    println!("[+] {} runs of {}:", stats.runs, target);
+    println!("    - Exit statuses: {:?}", stats.exit_statuses);
+    println!("    - Findings:");
+    for (rule, (count, example)) in stats.by_rule() {
         println!(
-            "{} {} {}",
-            out.status.code().unwrap(),
-            Bytes(out.stdout.clone()),
-            Bytes(out.stderr.clone())
+            "        {:5}x {} (first in run {}: {})",
+            count, rule, example.run, example.line
         );
-        runs.push(out);
     }
 }
 
-#[derive(Debug)]
-struct Bytes(Vec<u8>);
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Bytes {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", std::str::from_utf8(&self.0).unwrap())
-    }
+/// Get the values of all occurrences of a command line option.
+fn options_from_cli(option: &str) -> Vec<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.windows(2)
+        .filter(|w| w[0] == option)
+        .map(|w| w[1].clone())
+        .collect()
 }
 
 trait Fuzzer {
-    fn fuzz(&self) -> Bytes;
+    fn fuzz(&self, rng: &mut Rng) -> Bytes;
+}
+
+/// What the random fuzzer generates. The length of the generated input is
+/// counted in units of the respective mode (bytes, code points, or tokens).
+enum Mode {
+    /// Single bytes from the contiguous range [char_start, char_start + char_range).
+    Range { char_start: u64, char_range: u64 },
+    /// Printable ascii characters.
+    AsciiPrintable,
+    /// Arbitrary bytes (also leading to invalid utf8).
+    AnyBytes,
+    /// Valid utf8 with code points of all encoding lengths (1 to 4 bytes).
+    Utf8,
+    /// Concatenation of tokens randomly chosen from a pool of special strings.
+    Pool(Vec<&'static str>),
+}
+
+impl Mode {
+    /// Pool of format string specifiers (printf and friends).
+    fn format_string() -> Self {
+        Mode::Pool(vec![
+            "%s",
+            "%n",
+            "%x",
+            "%p",
+            "%d",
+            "%c",
+            "%%",
+            "%999999999s",
+            "%.*s",
+            "%1$s",
+            "{}",
+            "{0}",
+        ])
+    }
+
+    /// Pool of path components and special characters in paths.
+    fn path() -> Self {
+        Mode::Pool(vec![
+            "/", "\\", ".", "..", "~", "*", "?", ":", " ", "\n", "\0", "%00", "aux", "etc",
+            "passwd",
+        ])
+    }
 }
 
 struct RandomFuzzer {
     min_length: u64,
     max_length: u64,
-    char_start: u64,
-    char_range: u64,
+    mode: Mode,
 }
 
 impl RandomFuzzer {
     fn new(min_length: u64, max_length: u64, char_start: u64, char_range: u64) -> Self {
         assert!(char_start <= 0x100);
         assert!(char_start + char_range <= 0x100);
+        Self::with_mode(
+            min_length,
+            max_length,
+            Mode::Range {
+                char_start,
+                char_range,
+            },
+        )
+    }
+
+    fn with_mode(min_length: u64, max_length: u64, mode: Mode) -> Self {
         Self {
             min_length,
             max_length,
-            char_start,
-            char_range,
+            mode,
         }
     }
 }
@@ -79,13 +184,45 @@ impl Default for RandomFuzzer {
 }
 
 impl Fuzzer for RandomFuzzer {
-    fn fuzz(&self) -> Bytes {
-        let mut rng = rng::Rng::new();
+    fn fuzz(&self, rng: &mut Rng) -> Bytes {
         let len = rng.range(self.min_length, self.max_length);
         let mut res = Vec::new();
         for _ in 0..len {
-            res.push(rng.range(self.char_start, self.char_start + self.char_range) as u8);
+            match &self.mode {
+                Mode::Range {
+                    char_start,
+                    char_range,
+                } => res.push(rng.range(*char_start, char_start + char_range) as u8),
+                Mode::AsciiPrintable => res.push(rng.u8_range(0x20..=0x7e)),
+                Mode::AnyBytes => res.push(rng.int(0x100) as u8),
+                Mode::Utf8 => {
+                    let mut buf = [0; 4];
+                    res.extend(random_char(rng).encode_utf8(&mut buf).as_bytes());
+                }
+                Mode::Pool(pool) => {
+                    res.extend(pool[rng.usize_below(pool.len())].as_bytes());
+                }
+            }
         }
         Bytes(res)
     }
 }
+
+/// Random unicode scalar value. First choose the length of the utf8
+/// encoding uniformly, since otherwise nearly all code points would be the
+/// 4-byte ones (there are way more of those).
+fn random_char(rng: &mut Rng) -> char {
+    loop {
+        let codepoint = match rng.int(4) {
+            0 => rng.range(0x00, 0x80),
+            1 => rng.range(0x80, 0x800),
+            2 => rng.range(0x800, 0x10000),
+            3 => rng.range(0x10000, 0x110000),
+            _ => panic!("Can't happen"),
+        };
+        // Surrogates are not valid scalar values; just try again.
+        if let Some(c) = char::from_u32(codepoint as u32) {
+            return c;
+        }
+    }
+}
@@ -0,0 +1,389 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Output-based oracle: instead of only printing what the program wrote, its
+// output is checked line by line against a set of rules (name, stream and
+// pattern), e.g. error messages of the program itself ("syntax error"), or
+// banners of the stack protector and the sanitizers. Each matching rule gives
+// a finding, and the findings of all runs are collected into the statistics.
+//
+// The patterns are a small subset of regular expressions (there are no
+// dependencies, so no regex crate):
+//   - `.` (any character), `[a-z0-9_]` and `[^...]` (character classes),
+//     `\d`, `\w`, `\s` and escaped special characters (`\.`, `\[`, ...)
+//   - `*`, `+` and `?` after a character or class
+//   - `^` and `$` (start and end of the line)
+//   - `|` (alternatives, only on the top level: there are no groups)
+
+use std::collections::BTreeMap;
+use std::process::ExitStatus;
+
+/* Patterns. *****************************************************************/
+
+#[derive(Clone, Debug)]
+enum ClassItem {
+    Range(char, char),
+    Digit,
+    Word,
+    Space,
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ClassItem::Range(lo, hi) => *lo <= c && c <= *hi,
+            ClassItem::Digit => c.is_ascii_digit(),
+            ClassItem::Word => c.is_alphanumeric() || c == '_',
+            ClassItem::Space => c.is_whitespace(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Atom {
+    Any,
+    Class {
+        negated: bool,
+        items: Vec<ClassItem>,
+    },
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Any => true,
+            Atom::Class { negated, items } => items.iter().any(|item| item.matches(c)) != *negated,
+        }
+    }
+}
+
+/// An atom with its number of repetitions.
+#[derive(Clone, Debug)]
+struct Piece {
+    atom: Atom,
+    min: usize,
+    max: usize,
+}
+
+/// One alternative of a pattern.
+#[derive(Clone, Debug)]
+struct Branch {
+    anchored_start: bool,
+    anchored_end: bool,
+    pieces: Vec<Piece>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    pub source: String,
+    branches: Vec<Branch>,
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self, String> {
+        let branches = split_branches(source)
+            .into_iter()
+            .map(parse_branch)
+            .collect::<Result<_, _>>()
+            .map_err(|err| format!("Invalid pattern {:?}: {}", source, err))?;
+        Ok(Self {
+            source: source.to_string(),
+            branches,
+        })
+    }
+
+    /// Whether the pattern matches somewhere in the line.
+    pub fn is_match(&self, line: &str) -> bool {
+        let chars: Vec<char> = line.chars().collect();
+        self.branches.iter().any(|branch| {
+            let starts = match branch.anchored_start {
+                true => 0..1,
+                false => 0..chars.len() + 1,
+            };
+            starts
+                .into_iter()
+                .any(|start| match_here(branch, 0, &chars, start))
+        })
+    }
+}
+
+/// Split the pattern at the top-level `|`: not at escaped ones (`\|`), and not
+/// inside character classes (`[|]`).
+fn split_branches(source: &str) -> Vec<&str> {
+    let mut branches = Vec::new();
+    let mut start = 0;
+    let mut in_class = false;
+    let mut chars = source.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' if !in_class => {
+                in_class = true;
+                // `]` right at the start of a class is a literal (as in
+                // `parse_branch`).
+                chars.next_if(|&(_, c)| c == '^');
+                chars.next_if(|&(_, c)| c == ']');
+            }
+            ']' if in_class => in_class = false,
+            '|' if !in_class => {
+                branches.push(&source[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    branches.push(&source[start..]);
+    branches
+}
+
+fn parse_branch(source: &str) -> Result<Branch, String> {
+    let mut chars = source.chars().peekable();
+    let anchored_start = chars.next_if_eq(&'^').is_some();
+    let mut anchored_end = false;
+    let mut pieces = Vec::new();
+    while let Some(c) = chars.next() {
+        let atom = match c {
+            '$' if chars.peek().is_none() => {
+                anchored_end = true;
+                break;
+            }
+            '.' => Atom::Any,
+            '\\' => escape(chars.next().ok_or("trailing \\")?),
+            '[' => {
+                let negated = chars.next_if_eq(&'^').is_some();
+                let mut items = Vec::new();
+                loop {
+                    let c = chars.next().ok_or("unterminated [")?;
+                    let lo = match c {
+                        ']' if !items.is_empty() => break,
+                        '\\' => match escape(chars.next().ok_or("trailing \\")?) {
+                            Atom::Class { items: escaped, .. } => {
+                                items.extend(escaped);
+                                continue;
+                            }
+                            Atom::Any => panic!("Can't happen"),
+                        },
+                        c => c,
+                    };
+                    if chars.peek() == Some(&'-') {
+                        chars.next();
+                        match chars.next() {
+                            Some(']') | None => {
+                                items.push(ClassItem::Range(lo, lo));
+                                items.push(ClassItem::Range('-', '-'));
+                                break;
+                            }
+                            Some(hi) => items.push(ClassItem::Range(lo, hi)),
+                        }
+                    } else {
+                        items.push(ClassItem::Range(lo, lo));
+                    }
+                }
+                Atom::Class { negated, items }
+            }
+            '*' | '+' | '?' => return Err(format!("nothing to repeat before {}", c)),
+            c => literal(c),
+        };
+        let (min, max) = match chars.next_if(|c| "*+?".contains(*c)) {
+            Some('*') => (0, usize::MAX),
+            Some('+') => (1, usize::MAX),
+            Some('?') => (0, 1),
+            _ => (1, 1),
+        };
+        pieces.push(Piece { atom, min, max });
+    }
+    Ok(Branch {
+        anchored_start,
+        anchored_end,
+        pieces,
+    })
+}
+
+fn literal(c: char) -> Atom {
+    Atom::Class {
+        negated: false,
+        items: vec![ClassItem::Range(c, c)],
+    }
+}
+
+fn escape(c: char) -> Atom {
+    let item = match c {
+        'd' => ClassItem::Digit,
+        'w' => ClassItem::Word,
+        's' => ClassItem::Space,
+        c => return literal(c),
+    };
+    Atom::Class {
+        negated: false,
+        items: vec![item],
+    }
+}
+
+/// Whether the pieces of the branch from index `i` on match the line from
+/// position `pos` on (greedy, with backtracking).
+fn match_here(branch: &Branch, i: usize, line: &[char], pos: usize) -> bool {
+    let Some(piece) = branch.pieces.get(i) else {
+        return !branch.anchored_end || pos == line.len();
+    };
+    let mut count = 0;
+    while count < piece.max && pos + count < line.len() && piece.atom.matches(line[pos + count]) {
+        count += 1;
+    }
+    (piece.min..=count)
+        .rev()
+        .any(|n| match_here(branch, i + 1, line, pos + n))
+}
+
+/* Oracle. *******************************************************************/
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+    Both,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rule {
+    pub name: String,
+    pub stream: Stream,
+    pub pattern: Pattern,
+}
+
+impl Rule {
+    pub fn new(name: &str, stream: Stream, pattern: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            stream,
+            pattern: Pattern::new(pattern).unwrap(),
+        }
+    }
+
+    /// Rule from the command line: `[stdout:|stderr:]<name>=<pattern>`
+    /// (without stream, both streams are checked).
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (stream, spec) = match spec.split_once(':') {
+            Some(("stdout", spec)) => (Stream::Stdout, spec),
+            Some(("stderr", spec)) => (Stream::Stderr, spec),
+            _ => (Stream::Both, spec),
+        };
+        let (name, pattern) = spec.split_once('=').ok_or_else(|| {
+            format!(
+                "Invalid rule {:?}, expected [stdout:|stderr:]<name>=<pattern>",
+                spec
+            )
+        })?;
+        Ok(Self {
+            name: name.to_string(),
+            stream,
+            pattern: Pattern::new(pattern)?,
+        })
+    }
+}
+
+/// One rule matching the output of one run.
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub rule: String,
+    /// Index of the run.
+    pub run: usize,
+    /// The (first) matching line.
+    pub line: String,
+}
+
+pub struct Oracle {
+    pub rules: Vec<Rule>,
+}
+
+impl Default for Oracle {
+    /// Error messages of bc (and of other interpreters), and the banners of
+    /// the stack protector and the sanitizers.
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                Rule::new("syntax-error", Stream::Stderr, "[Ss]yntax ?[Ee]rror"),
+                Rule::new("illegal-character", Stream::Stderr, "illegal character"),
+                Rule::new("stack-smashing", Stream::Stderr, "stack smashing detected"),
+                Rule::new("asan", Stream::Stderr, "^==\\d+==ERROR: AddressSanitizer"),
+                Rule::new("ubsan", Stream::Stderr, "runtime error: "),
+            ],
+        }
+    }
+}
+
+impl Oracle {
+    /// Check the output of a run against all rules. Each rule gives at most
+    /// one finding per run.
+    pub fn check(&self, run: usize, stdout: &[u8], stderr: &[u8]) -> Vec<Finding> {
+        let stdout = String::from_utf8_lossy(stdout);
+        let stderr = String::from_utf8_lossy(stderr);
+        let mut findings = Vec::new();
+        for rule in self.rules.iter() {
+            let lines: Vec<&str> = match rule.stream {
+                Stream::Stdout => stdout.lines().collect(),
+                Stream::Stderr => stderr.lines().collect(),
+                Stream::Both => stdout.lines().chain(stderr.lines()).collect(),
+            };
+            if let Some(line) = lines.into_iter().find(|line| rule.pattern.is_match(line)) {
+                findings.push(Finding {
+                    rule: rule.name.clone(),
+                    run,
+                    line: line.to_string(),
+                });
+            }
+        }
+        findings
+    }
+}
+
+/* Statistics. ***************************************************************/
+
+/// Exit code, or the signal that killed the program (`status.code()` is
+/// `None` then). There are no signals on Windows: a crash is an exit code
+/// there (the exception code, e.g. `0xC0000005` for an access violation).
+pub fn describe(status: ExitStatus) -> String {
+    match (status.code(), signal(status)) {
+        (Some(code), _) => format!("exit {}", code),
+        (None, Some(signal)) => format!("signal {}", signal),
+        (None, None) => panic!("Can't happen"),
+    }
+}
+
+#[cfg(unix)]
+fn signal(status: ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+#[derive(Default)]
+pub struct Statistics {
+    pub runs: usize,
+    /// Number of runs per exit code (or signal).
+    pub exit_statuses: BTreeMap<String, usize>,
+    pub findings: Vec<Finding>,
+}
+
+impl Statistics {
+    pub fn record(&mut self, status: ExitStatus, findings: Vec<Finding>) {
+        self.runs += 1;
+        *self.exit_statuses.entry(describe(status)).or_default() += 1;
+        self.findings.extend(findings);
+    }
+
+    /// Number of findings per rule, with the first one as example.
+    pub fn by_rule(&self) -> BTreeMap<&str, (usize, &Finding)> {
+        let mut res: BTreeMap<&str, (usize, &Finding)> = BTreeMap::new();
+        for finding in self.findings.iter() {
+            res.entry(&finding.rule).or_insert((0, finding)).0 += 1;
+        }
+        res
+    }
+}
@@ -0,0 +1,59 @@
+// Example program with a small commandline interface (options -h, -v, -n
+// <number>, -o <file>, at most one positional argument) that is also
+// configured via the environment variable MODE. Invalid invocations lead to
+// different non-zero exit codes; one specific combination crashes.
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+int main(int argc, char *argv[]) {
+    const char *mode = getenv("MODE");
+    if (mode != NULL && strcmp(mode, "fast") != 0 && strcmp(mode, "slow") != 0) {
+        fprintf(stderr, "invalid MODE\n");
+        return 3;
+    }
+
+    int opt;
+    int verbose = 0;
+    long n = 1;
+    while ((opt = getopt(argc, argv, "hvn:o:")) != -1) {
+        switch (opt) {
+        case 'h':
+            printf("usage: %s [-h] [-v] [-n number] [-o file] [input]\n", argv[0]);
+            return 0;
+        case 'v':
+            verbose++;
+            break;
+        case 'n': {
+            char *end;
+            n = strtol(optarg, &end, 10);
+            if (*optarg == '\0' || *end != '\0' || n < 0) {
+                fprintf(stderr, "invalid number\n");
+                return 2;
+            }
+            break;
+        }
+        case 'o':
+            if (strlen(optarg) == 0) {
+                fprintf(stderr, "empty filename\n");
+                return 2;
+            }
+            break;
+        default:
+            return 1;
+        }
+    }
+
+    if (argc - optind > 1) {
+        fprintf(stderr, "too many arguments\n");
+        return 1;
+    }
+
+    if (verbose > 1 && n > 100 && mode != NULL && strcmp(mode, "fast") == 0) {
+        abort(); // Crash.
+    }
+
+    return 0;
+}
@@ -0,0 +1,352 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// From https://www.fuzzingbook.org/html/Fuzzer.html
+// and https://www.fuzzingbook.org/html/ConfigurationFuzzer.html
+// Fuzz the commandline arguments and environment of a program instead of its
+// input data.
+
+mod cli;
+mod rng;
+
+use std::collections::BTreeMap;
+use std::process;
+
+use rng::Rng;
+
+fn main() {
+    let seed = cli::seed().unwrap_or(42);
+    println!("[+] Running with random seed {}", seed);
+
+    let n = cli::iterations(5000);
+
+    let runner = ArgvRunner::new(&cli::target("cmdline"));
+
+    let fuzzers = [
+        (
+            "Random",
+            ArgvFuzzer {
+                arg_generator: Box::new(RandomGenerator {
+                    min_length: 0,
+                    max_length: 5,
+                }),
+                env_generator: Box::new(RandomGenerator {
+                    min_length: 0,
+                    max_length: 5,
+                }),
+                ..ArgvFuzzer::default()
+            },
+        ),
+        (
+            "Grammar",
+            ArgvFuzzer {
+                arg_generator: Box::new(GrammarGenerator {
+                    grammar: arg_grammar(),
+                    max_nonterminals: 5,
+                }),
+                env_generator: Box::new(GrammarGenerator {
+                    grammar: env_grammar(),
+                    max_nonterminals: 5,
+                }),
+                ..ArgvFuzzer::default()
+            },
+        ),
+    ];
+
+    for (name, fuzzer) in fuzzers.iter() {
+        // Same seed for each fuzzer.
+        let mut rng = Rng::seeded(seed);
+
+        // Abstract argv/environment patterns with the outcomes they led to,
+        // together with how often they occurred.
+        let mut failures: BTreeMap<(Outcome, String), usize> = BTreeMap::new();
+        let mut passes = 0;
+
+        for _ in 0..n {
+            let invocation = fuzzer.fuzz(&mut rng);
+            match runner.run(&invocation) {
+                Outcome::Exit(0) => passes += 1,
+                outcome => *failures.entry((outcome, invocation.pattern())).or_default() += 1,
+            }
+        }
+
+        println!();
+        println!("[+] {} argv/environment fuzzer:", name);
+        println!("    - Successful runs: {}", passes);
+
+        let mut outcomes: BTreeMap<&Outcome, usize> = BTreeMap::new();
+        for ((outcome, _), count) in failures.iter() {
+            *outcomes.entry(outcome).or_default() += count;
+        }
+        println!("    - Non-zero exits:  {:?}", outcomes);
+
+        if cli::quiet() {
+            continue;
+        }
+
+        // Only show the most frequent patterns, and all that crashed.
+        let mut failures: Vec<_> = failures.into_iter().collect();
+        failures.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        println!("    - Most frequent patterns leading to non-zero exits:");
+        for (i, ((outcome, pattern), count)) in failures.iter().enumerate() {
+            if i < 10 || matches!(outcome, Outcome::Signal(_)) {
+                println!("      {:5} {:9} {}", count, outcome.to_string(), pattern);
+            }
+        }
+    }
+}
+
+/// One invocation of the program under test.
+#[derive(Debug)]
+struct Invocation {
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+}
+
+impl Invocation {
+    /// Abstract pattern of the invocation: the concrete values are replaced by
+    /// their class, so that similar invocations are grouped together.
+    fn pattern(&self) -> String {
+        let mut res = Vec::new();
+        for (name, value) in self.env.iter() {
+            res.push(format!("{}={}", name, classify(value)));
+        }
+        for arg in self.args.iter() {
+            res.push(classify(arg));
+        }
+        res.join(" ")
+    }
+}
+
+fn classify(s: &str) -> String {
+    if s.is_empty() {
+        "EMPTY".to_string()
+    } else if s.starts_with('-') && s.len() > 1 && !s[1..].chars().all(|c| c.is_ascii_digit()) {
+        // Options are kept as-is (at most the first two characters, so that
+        // e.g. `-n5` is grouped together with `-n10`).
+        s.chars().take(2).collect()
+    } else if s.parse::<i64>().is_ok() {
+        "NUM".to_string()
+    } else {
+        "STR".to_string()
+    }
+}
+
+/// Generates single commandline arguments or environment variable values.
+trait Generator {
+    fn generate(&self, rng: &mut Rng) -> String;
+}
+
+/// Random printable strings of random length.
+struct RandomGenerator {
+    min_length: u64,
+    max_length: u64,
+}
+
+impl Generator for RandomGenerator {
+    fn generate(&self, rng: &mut Rng) -> String {
+        let len = rng.range(self.min_length, self.max_length + 1);
+        rng.ascii_printable(len)
+    }
+}
+
+/// Grammar as mapping from nonterminals (written as `<name>`) to a list of
+/// alternative expansions, in the style of the fuzzingbook Grammars chapter.
+type Grammar = BTreeMap<&'static str, Vec<&'static str>>;
+
+/// Strings from a grammar, produced by `simple_grammar_fuzzer` from
+/// https://www.fuzzingbook.org/html/Grammars.html (see 03-grammarfuzzer for
+/// the proper implementation).
+struct GrammarGenerator {
+    grammar: Grammar,
+    max_nonterminals: usize,
+}
+
+/// Find all nonterminals `<...>` in a string as (start, end) byte ranges.
+fn nonterminals(term: &str) -> Vec<(usize, usize)> {
+    let mut res = Vec::new();
+    let mut start = None;
+    for (i, c) in term.char_indices() {
+        match c {
+            '<' => start = Some(i),
+            '>' if start.is_some() => {
+                res.push((start.unwrap(), i + 1));
+                start = None;
+            }
+            ' ' => start = None,
+            _ => (),
+        }
+    }
+    res
+}
+
+impl Generator for GrammarGenerator {
+    fn generate(&self, rng: &mut Rng) -> String {
+        let mut term = "<start>".to_string();
+        loop {
+            let symbols = nonterminals(&term);
+            if symbols.is_empty() {
+                return term;
+            }
+
+            let (start, end) = *rng.choice(&symbols);
+            let expansion = rng.choice(&self.grammar[&term[start..end]]);
+            let new_term = format!("{}{}{}", &term[..start], expansion, &term[end..]);
+
+            // Simply retry with another expansion when the term grows too large.
+            if nonterminals(&new_term).len() < self.max_nonterminals {
+                term = new_term;
+            }
+        }
+    }
+}
+
+fn arg_grammar() -> Grammar {
+    Grammar::from([
+        (
+            "<start>",
+            vec!["<option>", "<option>", "<number>", "<word>"],
+        ),
+        (
+            "<option>",
+            vec!["-h", "-v", "-vv", "-n", "-o", "-x", "--", "-n<number>"],
+        ),
+        ("<number>", vec!["<digits>", "-<digits>"]),
+        ("<digits>", vec!["<digit>", "<digit><digits>"]),
+        (
+            "<digit>",
+            vec!["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"],
+        ),
+        ("<word>", vec!["<letter>", "<letter><word>"]),
+        ("<letter>", vec!["a", "f", "l", "o", "s", "t", "w", "."]),
+    ])
+}
+
+fn env_grammar() -> Grammar {
+    Grammar::from([
+        ("<start>", vec!["fast", "slow", "<word>"]),
+        ("<word>", vec!["<letter>", "<letter><word>"]),
+        ("<letter>", vec!["a", "f", "l", "o", "s", "t", "w"]),
+    ])
+}
+
+/// Creates invocations with a random number of arguments and environment
+/// variables, whose contents come from the respective generators.
+struct ArgvFuzzer {
+    min_args: u64,
+    max_args: u64,
+    arg_generator: Box<dyn Generator>,
+
+    min_env: u64,
+    max_env: u64,
+    /// Names of the environment variables to choose from.
+    env_names: Vec<&'static str>,
+    env_generator: Box<dyn Generator>,
+}
+
+impl Default for ArgvFuzzer {
+    fn default() -> Self {
+        Self {
+            min_args: 0,
+            max_args: 5,
+            arg_generator: Box::new(RandomGenerator {
+                min_length: 0,
+                max_length: 10,
+            }),
+            min_env: 0,
+            max_env: 2,
+            env_names: vec!["MODE", "LANG"],
+            env_generator: Box::new(RandomGenerator {
+                min_length: 0,
+                max_length: 10,
+            }),
+        }
+    }
+}
+
+impl ArgvFuzzer {
+    fn fuzz(&self, rng: &mut Rng) -> Invocation {
+        let nargs = rng.range(self.min_args, self.max_args + 1);
+        let args = (0..nargs)
+            .map(|_| self.arg_generator.generate(rng))
+            .collect();
+
+        let nenv = rng.range(self.min_env, self.max_env + 1);
+        let env = (0..nenv)
+            .map(|_| {
+                let name = rng.choice(&self.env_names).to_string();
+                (name, self.env_generator.generate(rng))
+            })
+            .collect();
+
+        Invocation { args, env }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    /// Program exited normally with the given exit code.
+    Exit(i32),
+    /// Program was killed by the given signal.
+    Signal(i32),
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Outcome::Exit(code) => write!(f, "exit {}", code),
+            Outcome::Signal(signal) => write!(f, "signal {}", signal),
+        }
+    }
+}
+
+/// Runs a program with the commandline arguments and environment of an
+/// invocation (and nothing else from the environment of the fuzzer).
+struct ArgvRunner {
+    program: String,
+}
+
+impl ArgvRunner {
+    /// Compile the C program.
+    fn new(program: &str) -> Self {
+        process::Command::new("gcc")
+            .args(["-Wall", "-g", "-o", program])
+            .arg(format!("{}.c", program))
+            .output()
+            .unwrap();
+
+        Self {
+            program: program.to_string(),
+        }
+    }
+
+    fn run(&self, invocation: &Invocation) -> Outcome {
+        let status = process::Command::new(format!("./{}", self.program))
+            .args(&invocation.args)
+            .env_clear()
+            .envs(invocation.env.iter().map(|(k, v)| (k, v)))
+            .stdout(process::Stdio::null())
+            .stderr(process::Stdio::null())
+            .status()
+            .unwrap();
+
+        match status.code() {
+            Some(code) => Outcome::Exit(code),
+            None => Outcome::Signal(signal(status).unwrap()),
+        }
+    }
+}
+
+/// Signal that terminated the process, if any (only Unix has signals).
+#[cfg(unix)]
+fn signal(status: process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn signal(_status: process::ExitStatus) -> Option<i32> {
+    None
+}
@@ -0,0 +1,122 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Byte strings that the fuzzers operate on: inputs, mutants, and outputs of
+// the programs under test. They are not necessarily valid utf8 (random bytes
+// and bit flips produce anything), so printing them must not assume it.
+
+use std::fmt;
+
+/// Dedicated newtype instead of a plain `Vec<u8>` for being able to implement
+/// integrated printing routines.
+///
+/// `Display` is lossy: invalid utf8 is shown as replacement characters. The
+/// precision caps the length: `{:.40}` shows at most 40 characters, followed
+/// by the total size if anything was cut off. `Debug` shows valid utf8 as
+/// string and anything else as hex bytes.
+#[derive(Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    /// Convert a `&str` to `Bytes`. I choose to do it this way and not use
+    /// `FromStr` trait since that returns a Result which has to be unwrapped.
+    /// This is unnecessary since in this case the conversion can never fail
+    /// (Vec<u8> is a super-set of &str).
+    pub fn from_str(s: &str) -> Self {
+        Self(s.as_bytes().to_vec())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The bytes as string, if they are valid utf8.
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.0).ok()
+    }
+
+    /// Hexdump, 16 bytes per line (offset, bytes, and the printable ascii
+    /// characters), like `xxd`.
+    pub fn hexdump(&self) -> String {
+        let mut res = String::new();
+        for (i, line) in self.0.chunks(16).enumerate() {
+            let hex: Vec<String> = line.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = line
+                .iter()
+                .map(|&b| match b {
+                    b' ' => ' ',
+                    b if b.is_ascii_graphic() => b as char,
+                    _ => '.',
+                })
+                .collect();
+            res += &format!("{:08x}: {:<47}  {}\n", i * 16, hex.join(" "), ascii);
+        }
+        res
+    }
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = String::from_utf8_lossy(&self.0);
+        match f.precision() {
+            Some(max) if s.chars().count() > max => {
+                let prefix: String = s.chars().take(max).collect();
+                write!(f, "{}... ({} bytes)", prefix, self.0.len())
+            }
+            _ => write!(f, "{}", s),
+        }
+    }
+}
+
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.as_str() {
+            Some(_) => fmt::Display::fmt(self, f),
+            None => {
+                let hex: Vec<String> = self.0.iter().map(|b| format!("{:02x}", b)).collect();
+                write!(f, "<{}>", hex.join(" "))
+            }
+        }
+    }
+}
+
+impl From<&str> for Bytes {
+    fn from(s: &str) -> Self {
+        Self::from_str(s)
+    }
+}
+
+impl From<String> for Bytes {
+    fn from(s: String) -> Self {
+        Self(s.into_bytes())
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
@@ -0,0 +1,211 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+
+use crate::error::{Context, FuzzError};
+use crate::fuzzer::{Fuzzer, Input};
+use crate::rng::Rng;
+use crate::runner::{Coverage, CoverageBackend, RunResult};
+use crate::store::CoverageStore;
+use crate::timing::Timings;
+
+/// Statistics relevant during fuzzing. These are independent of the fuzzing
+/// technique, so that different campaigns can be compared.
+#[derive(Default)]
+pub struct Statistics {
+    /// Number of times an input was tested.
+    pub fuzz_cases: usize,
+
+    /// All inputs that crashed the program.
+    pub crashes: BTreeSet<Input>,
+
+    /// Fuzz case at which the first crash was found.
+    pub first_crash: Option<usize>,
+
+    /// Deduplicated crashes: the first crashing input for each crash
+    /// signature (see `SanitizerReport::signature`), together with the number
+    /// of crashes with that signature.
+    pub unique_crashes: BTreeMap<String, (Input, usize)>,
+
+    /// Union of all coverages that were achieved during execution.
+    pub coverage_all: Coverage,
+
+    /// Trace/history of coverage_all sizes.
+    pub coverage_cumul: Vec<usize>,
+
+    /// Number of calibrated inputs, and of the additional executions for
+    /// calibrating them (not counted as fuzz cases).
+    pub calibrations: usize,
+    pub calibration_runs: usize,
+
+    /// Number of calibrated inputs with unstable results.
+    pub unstable_inputs: usize,
+
+    /// Sum of the mean execution times of the calibrated inputs.
+    pub calibration_duration: Duration,
+
+    /// Execution time of each fuzz case, and the slowest inputs.
+    pub timings: Timings,
+
+    /// Number of errors (running inputs or writing to the store) that were
+    /// skipped, and the last one.
+    pub errors: usize,
+    pub last_error: Option<String>,
+}
+
+/// A campaign gives up after this many fuzz cases in a row that failed with an
+/// error: then the errors aren't transient anymore.
+pub const MAX_CONSECUTIVE_ERRORS: usize = 10;
+
+/// Number of times an input is run for calibration (including the original
+/// execution as fuzz case).
+pub const CALIBRATION_RUNS: usize = 4;
+
+/// Result of running an input several times (see `Fuzzer::calibration_request`).
+/// The coverage of an execution isn't necessarily stable: e.g. a timeout, or
+/// state left behind by earlier executions (shared files) can change it. Such
+/// inputs shouldn't be trusted too much.
+#[derive(Clone, Debug)]
+pub struct Calibration {
+    /// All runs gave the same result (the same coverage, or the same crash).
+    pub stable: bool,
+
+    /// Mean execution time over all runs.
+    pub mean_duration: Duration,
+}
+
+/// Pairs any fuzzer with any coverage backend: repeatedly let the fuzzer
+/// generate an input, run it, feed the result back to the fuzzer, and record
+/// statistics.
+///
+/// A campaign is deterministic given the random generator, as long as the
+/// backend is. `run` can be called several times to continue the campaign;
+/// the statistics accumulate.
+pub struct Campaign<'a> {
+    pub fuzzer: &'a mut dyn Fuzzer,
+    pub runner: &'a dyn CoverageBackend,
+    pub stats: Statistics,
+
+    /// Store on disk that every input with coverage is offered to, if any.
+    pub store: Option<&'a mut CoverageStore>,
+}
+
+impl<'a> Campaign<'a> {
+    pub fn new(fuzzer: &'a mut dyn Fuzzer, runner: &'a dyn CoverageBackend) -> Self {
+        Self {
+            fuzzer,
+            runner,
+            stats: Statistics::default(),
+            store: None,
+        }
+    }
+
+    /// Offer every input that doesn't crash to the store (see
+    /// `CoverageStore::add`).
+    pub fn with_store(mut self, store: &'a mut CoverageStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Run `n` fuzz cases. Calibration runs requested by the fuzzer are not
+    /// counted as fuzz cases and don't add to the coverage.
+    ///
+    /// Errors are logged (on stderr) and counted in the statistics, and the
+    /// fuzz case is skipped: they are usually transient, and a long campaign
+    /// shouldn't die of them. Only after `MAX_CONSECUTIVE_ERRORS` failed fuzz
+    /// cases in a row the campaign stops with the last error.
+    pub fn run(&mut self, rng: &mut Rng, n: usize) -> Result<(), FuzzError> {
+        let mut consecutive_errors = 0;
+        for _ in 0..n {
+            let input = self.fuzzer.fuzz(rng);
+            let start = Instant::now();
+            let result = match self.runner.run(&input) {
+                Ok(result) => result,
+                Err(err) => {
+                    consecutive_errors += 1;
+                    self.log_error(&err);
+                    if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+                        return Err(err);
+                    }
+                    continue;
+                }
+            };
+            consecutive_errors = 0;
+            let duration = start.elapsed();
+            self.stats.timings.record(&input, duration);
+
+            match &result {
+                RunResult::Crash(_) => {
+                    self.stats.first_crash.get_or_insert(self.stats.fuzz_cases);
+                    self.stats.crashes.insert(input.clone());
+                    self.stats
+                        .unique_crashes
+                        .entry(result.crash_signature().unwrap())
+                        .or_insert((input.clone(), 0))
+                        .1 += 1;
+                }
+                RunResult::Ok(coverage) => {
+                    self.stats.coverage_all.extend(coverage);
+                    if let Some(store) = self.store.as_mut() {
+                        let added = store.add(&input, coverage, duration);
+                        if let Err(err) = added.context("Adding an input to the store") {
+                            self.log_error(&err);
+                        }
+                    }
+                }
+            }
+            self.stats
+                .coverage_cumul
+                .push(self.stats.coverage_all.len());
+            self.stats.fuzz_cases += 1;
+
+            self.fuzzer.observe(&input, &result);
+            self.fuzzer.observe_duration(&input, duration);
+
+            if let Some(input) = self.fuzzer.calibration_request() {
+                let calibration = self.calibrate(&input, &result, duration);
+                self.fuzzer.calibrated(&input, &calibration);
+            }
+        }
+        Ok(())
+    }
+
+    fn log_error(&mut self, err: &FuzzError) {
+        eprintln!("[!] Fuzz case {}: {}", self.stats.fuzz_cases, err);
+        self.stats.errors += 1;
+        self.stats.last_error = Some(err.to_string());
+    }
+
+    /// Run the input `CALIBRATION_RUNS - 1` more times, and compare the
+    /// results with those of the first run. Runs that fail with an error
+    /// count as different.
+    fn calibrate(&mut self, input: &Input, result: &RunResult, duration: Duration) -> Calibration {
+        let mut stable = true;
+        let mut total_duration = duration;
+        for _ in 1..CALIBRATION_RUNS {
+            let start = Instant::now();
+            match self.runner.run(input) {
+                Ok(rerun) => stable &= rerun == *result,
+                Err(err) => {
+                    self.log_error(&err);
+                    stable = false;
+                }
+            }
+            total_duration += start.elapsed();
+        }
+        let calibration = Calibration {
+            stable,
+            mean_duration: total_duration / CALIBRATION_RUNS as u32,
+        };
+
+        self.stats.calibrations += 1;
+        self.stats.calibration_runs += CALIBRATION_RUNS - 1;
+        self.stats.unstable_inputs += !stable as usize;
+        self.stats.calibration_duration += calibration.mean_duration;
+        calibration
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Corpus distillation (similar to afl-cmin): reduce a set of inputs to a small
+// subset that still achieves the same coverage. Large seed corpora usually
+// contain many inputs that exercise the same code; fuzzing them is a waste of
+// time, since the greybox fuzzer spends its energy evenly among the seeds.
+//
+// Finding the smallest such subset is the set cover problem (NP-hard), so the
+// usual greedy approximation is used: repeatedly take the input that covers
+// the most locations not yet covered by the inputs taken so far.
+
+use crate::corpus::Corpus;
+use crate::error::{Context, FuzzError};
+use crate::fuzzer::Input;
+use crate::runner::{Coverage, CoverageBackend, RunResult};
+
+/// Compute a minimal (in the sense of the greedy approximation) subset of the
+/// inputs that covers the union of the coverage of all inputs.
+/// Inputs that crash the program are not part of the result.
+pub fn minset(runner: &dyn CoverageBackend, inputs: &[Input]) -> Result<Vec<Input>, FuzzError> {
+    let mut candidates: Vec<(&Input, Coverage)> = Vec::new();
+    for input in inputs {
+        if let RunResult::Ok(coverage) = runner.run(input)? {
+            candidates.push((input, coverage));
+        }
+    }
+
+    let mut covered = Coverage::new();
+    let mut res = Vec::new();
+    loop {
+        // Input that adds the most new coverage; among equally good inputs the
+        // shortest one (it's faster to execute and mutate).
+        let best = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, (input, coverage))| (i, coverage.difference(&covered).count(), input.0.len()))
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)));
+
+        match best {
+            Some((i, new, _)) if new > 0 => {
+                let (input, coverage) = candidates.swap_remove(i);
+                covered.extend(coverage);
+                res.push(input.clone());
+            }
+            _ => break,
+        }
+    }
+    Ok(res)
+}
+
+/// Read all inputs of corpus `input`, distill them and write the result to
+/// corpus `output`. Returns the number of inputs read and written.
+pub fn distill(
+    runner: &dyn CoverageBackend,
+    input: &Corpus,
+    output: &Corpus,
+) -> Result<(usize, usize), FuzzError> {
+    let inputs = (input.read()).context(format!("Reading corpus {}", input.dir.display()))?;
+    let distilled = minset(runner, &inputs)?;
+    (output.write(&distilled)).context(format!("Writing corpus {}", output.dir.display()))?;
+    Ok((inputs.len(), distilled.len()))
+}
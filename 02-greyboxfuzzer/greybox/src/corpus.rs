@@ -0,0 +1,231 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Corpora in the format of libFuzzer (and therefore cargo-fuzz, which keeps
+// them in fuzz/corpus/<target>/): a flat directory with one file per input,
+// named by the hex SHA-1 of its contents. This makes it possible to share
+// seeds with existing fuzzing setups in both directions: start a campaign from
+// a cargo-fuzz corpus, and add the population of a campaign to it.
+//
+// Seeds can also be imported from any directory tree (e.g. a collection of
+// sample files), see `Corpus::from_dir`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::fuzzer::Input;
+
+/// A corpus directory. It doesn't have to exist yet: it is created when
+/// inputs are first written to it.
+#[derive(Clone, Debug)]
+pub struct Corpus {
+    pub dir: PathBuf,
+}
+
+impl Corpus {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Read all inputs from the files in the directory (sorted and without
+    /// duplicates, since directory order is arbitrary). Subdirectories and
+    /// other non-files are ignored, the file names don't matter.
+    pub fn read(&self) -> io::Result<Vec<Input>> {
+        let mut inputs = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.is_file() {
+                inputs.push(Input(fs::read(path)?));
+            }
+        }
+        inputs.sort();
+        inputs.dedup();
+        Ok(inputs)
+    }
+
+    /// Import the seeds from all files in a directory tree (recursively, in
+    /// the order of their paths). Files larger than `max_size` bytes are
+    /// skipped (huge seeds make every execution and mutation slow), and so are
+    /// files with the same contents (SHA-1) as an earlier one. Symbolic links
+    /// to directories are not followed, so the traversal can't loop.
+    pub fn from_dir(dir: impl AsRef<Path>, max_size: u64) -> io::Result<Import> {
+        let mut files = Vec::new();
+        collect_files(dir.as_ref(), &mut files)?;
+        files.sort();
+
+        let mut import = Import {
+            max_size,
+            ..Default::default()
+        };
+        let mut seen: HashMap<[u8; 20], PathBuf> = HashMap::new();
+        for path in files {
+            let size = fs::metadata(&path)?.len();
+            if size > max_size {
+                import.oversized.push((path, size));
+                continue;
+            }
+            let input = Input(fs::read(&path)?);
+            let hash = sha1(&input.0);
+            match seen.get(&hash) {
+                Some(first) => import.duplicates.push((path, first.clone())),
+                None => {
+                    seen.insert(hash, path.clone());
+                    import.loaded.push(path);
+                    import.inputs.push(input);
+                }
+            }
+        }
+        Ok(import)
+    }
+
+    /// Write the inputs to the directory (created if necessary), each to a
+    /// file named by the SHA-1 of its contents. Inputs already present are
+    /// not written again, so writing is idempotent and several campaigns can
+    /// add to the same corpus. Returns the number of new files.
+    pub fn write(&self, inputs: &[Input]) -> io::Result<usize> {
+        self.write_prefixed("", inputs)
+    }
+
+    /// Like `write`, but with a prefix before the SHA-1 in the file names.
+    /// libFuzzer uses e.g. `crash-` for crashing inputs.
+    pub fn write_prefixed(&self, prefix: &str, inputs: &[Input]) -> io::Result<usize> {
+        fs::create_dir_all(&self.dir)?;
+        let mut written = 0;
+        for input in inputs.iter() {
+            let path = self.dir.join(format!("{}{}", prefix, file_name(input)));
+            if !path.exists() {
+                fs::write(path, &input.0)?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// Largest seed file that is imported by default (like `MAX_FILE` of AFL).
+pub const MAX_SEED_SIZE: u64 = 1024 * 1024;
+
+/// Seeds imported with `Corpus::from_dir`, and what was skipped.
+#[derive(Clone, Debug, Default)]
+pub struct Import {
+    /// The imported seeds, in the order of `loaded`.
+    pub inputs: Vec<Input>,
+    /// Paths of the imported files.
+    pub loaded: Vec<PathBuf>,
+    /// Skipped files with the same contents as an imported one (path of the
+    /// skipped file, path of the imported one).
+    pub duplicates: Vec<(PathBuf, PathBuf)>,
+    /// Skipped files larger than `max_size` (path, size in bytes).
+    pub oversized: Vec<(PathBuf, u64)>,
+    pub max_size: u64,
+}
+
+impl Import {
+    /// Lines describing what was imported and every skipped file.
+    pub fn report(&self) -> Vec<String> {
+        let bytes: usize = self.inputs.iter().map(|input| input.0.len()).sum();
+        let mut res = vec![format!(
+            "{} seeds ({} bytes) imported, {} duplicates and {} files larger than {} bytes skipped",
+            self.inputs.len(),
+            bytes,
+            self.duplicates.len(),
+            self.oversized.len(),
+            self.max_size
+        )];
+        for (path, first) in self.duplicates.iter() {
+            res.push(format!(
+                "Skipped {} (same as {})",
+                path.display(),
+                first.display()
+            ));
+        }
+        for (path, size) in self.oversized.iter() {
+            res.push(format!("Skipped {} ({} bytes)", path.display(), size));
+        }
+        res
+    }
+}
+
+/// Add all files below `dir` to `files`.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // `file_type` doesn't follow symbolic links, `is_file` does.
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// File name of an input in a libFuzzer corpus.
+pub fn file_name(input: &Input) -> String {
+    sha1(&input.0)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// SHA-1 (RFC 3174). Only used for naming files, so the fact that it's broken
+/// as a cryptographic hash doesn't matter here.
+pub fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    // Padding: a single 1 bit, zeros up to 56 mod 64 bytes, and the message
+    // length in bits as big-endian u64.
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend(((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for i in 0..16 {
+            w[i] = u32::from_be_bytes(block[4 * i..4 * i + 4].try_into().unwrap());
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*wi);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (hi, x) in h.iter_mut().zip([a, b, c, d, e]) {
+            *hi = hi.wrapping_add(x);
+        }
+    }
+
+    let mut res = [0; 20];
+    for (i, hi) in h.iter().enumerate() {
+        res[4 * i..4 * i + 4].copy_from_slice(&hi.to_be_bytes());
+    }
+    res
+}
@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Dictionaries of tokens (keywords, operators, magic strings) for the
+// mutation based fuzzers: the dictionary operators insert a token into the
+// input or overwrite part of the input with one, as in AFL. Byte-level
+// mutations practically never produce a keyword like `DELETE` by chance,
+// since the target gives no coverage feedback for the individual characters.
+//
+// If the format of the input is known as `Grammar`, the dictionary is derived
+// from its terminals: every terminal string longer than 2 bytes is a token
+// (shorter ones are easy enough to find byte by byte). Tokens that occur in
+// fewer expansions of the grammar are rarer, and are chosen more often: they
+// are the specific keywords, while frequent terminals are filler.
+
+use std::collections::BTreeMap;
+
+use crate::fuzzer::{Grammar, Input};
+use crate::rng::Rng;
+
+/// Terminals of at most this length don't become tokens.
+pub const MIN_TOKEN_LEN: usize = 3;
+
+#[derive(Clone, Debug, Default)]
+pub struct Dictionary {
+    /// Tokens, rarest first, with the number of expansions of the grammar
+    /// they occur in (1 for tokens not from a grammar).
+    tokens: Vec<(Vec<u8>, usize)>,
+}
+
+impl Dictionary {
+    /// Tokens given explicitly (all equally likely).
+    pub fn from_tokens(tokens: &[&[u8]]) -> Self {
+        let mut res = Self::default();
+        for token in tokens {
+            if !res.tokens.iter().any(|(t, _)| t == token) {
+                res.tokens.push((token.to_vec(), 1));
+            }
+        }
+        res
+    }
+
+    /// The terminals of the grammar longer than 2 bytes, deduplicated and
+    /// ranked by rarity (the number of expansions they occur in).
+    pub fn from_grammar(grammar: &Grammar) -> Self {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for expansion in grammar.values().flatten() {
+            for terminal in terminals(expansion) {
+                if terminal.len() >= MIN_TOKEN_LEN {
+                    *counts.entry(terminal).or_default() += 1;
+                }
+            }
+        }
+        let mut tokens: Vec<_> = counts
+            .into_iter()
+            .map(|(terminal, count)| (terminal.as_bytes().to_vec(), count))
+            .collect();
+        // Stable, so tokens of the same rarity stay sorted.
+        tokens.sort_by_key(|&(_, count)| count);
+        Self { tokens }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The tokens, rarest first.
+    pub fn tokens(&self) -> impl Iterator<Item = &[u8]> {
+        self.tokens.iter().map(|(token, _)| token.as_slice())
+    }
+
+    /// Choose a token, weighted by `1 / count`.
+    fn choose(&self, rng: &mut Rng) -> &[u8] {
+        let weights: Vec<_> = self
+            .tokens
+            .iter()
+            .map(|&(_, count)| 1.0 / count as f64)
+            .collect();
+        &rng.choice_w(&self.tokens, &weights).0
+    }
+
+    /// Apply a random dictionary mutation: insert a token at a random
+    /// position, or overwrite the bytes at a random position with a token
+    /// (extending the input if needed). Empty dictionaries leave the input
+    /// unchanged.
+    pub fn mutate(&self, rng: &mut Rng, mut s: Input) -> Input {
+        if self.is_empty() {
+            return s;
+        }
+        let token = self.choose(rng);
+        match rng.int(2) {
+            0 => {
+                let pos = rng.usize_below(s.0.len() + 1);
+                s.0.splice(pos..pos, token.iter().copied());
+            }
+            _ => {
+                let pos = rng.usize_below(s.0.len() + 1);
+                let end = std::cmp::min(pos + token.len(), s.0.len());
+                s.0.splice(pos..end, token.iter().copied());
+            }
+        }
+        s
+    }
+}
+
+/// The terminal strings of an expansion: everything between the
+/// nonterminals (`<name>`, without spaces or angle brackets in the name).
+fn terminals(expansion: &str) -> Vec<&str> {
+    let mut res = Vec::new();
+    let mut rest = expansion;
+    let mut start = 0;
+    while let Some(open) = rest[start..].find('<').map(|i| start + i) {
+        let close = rest[open + 1..]
+            .find(['<', '>', ' '])
+            .map(|i| open + 1 + i)
+            .filter(|&i| rest.as_bytes()[i] == b'>');
+        match close {
+            Some(close) => {
+                if open > 0 {
+                    res.push(&rest[..open]);
+                }
+                rest = &rest[close + 1..];
+                start = 0;
+            }
+            // A `<` that doesn't start a nonterminal is part of the terminal.
+            None => start = open + 1,
+        }
+    }
+    if !rest.is_empty() {
+        res.push(rest);
+    }
+    res
+}
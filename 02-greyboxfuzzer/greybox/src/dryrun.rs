@@ -0,0 +1,86 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Dry run before fuzzing (like the dry run of AFL): check that the backend can
+// run inputs at all, then run every seed once. Problems with the setup (a
+// missing program, gcov not installed, an unreachable server) show up here
+// with a clear message instead of as a panic somewhere in the middle of the
+// first campaign, and seeds that already crash or take very long are reported
+// before they waste the budget.
+
+use std::time::{Duration, Instant};
+
+use crate::error::FuzzError;
+use crate::fuzzer::Input;
+use crate::runner::{Coverage, CoverageBackend, RunResult};
+
+/// Result of running one seed.
+#[derive(Clone, Debug)]
+pub struct SeedRun {
+    pub input: Input,
+    /// Crash signature (see `RunResult::crash_signature`), if it crashed.
+    pub crash: Option<String>,
+    /// Number of covered locations (0 for crashes).
+    pub coverage: usize,
+    pub duration: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct DryRun {
+    pub seeds: Vec<SeedRun>,
+    /// Union of the coverage of all seeds that didn't crash: what the
+    /// campaigns start from.
+    pub baseline: Coverage,
+    /// Seeds that took longer than this are reported as slow.
+    pub slow: Duration,
+}
+
+impl DryRun {
+    pub fn crashing(&self) -> impl Iterator<Item = &SeedRun> {
+        self.seeds.iter().filter(|seed| seed.crash.is_some())
+    }
+
+    pub fn slow(&self) -> impl Iterator<Item = &SeedRun> {
+        self.seeds.iter().filter(|seed| seed.duration > self.slow)
+    }
+}
+
+/// Check the backend (`CoverageBackend::check`) and run each seed once. Seeds
+/// taking longer than `slow` are reported as slow (the backends have no
+/// general timeout, so a seed that hangs still blocks the dry run).
+pub fn run(
+    backend: &dyn CoverageBackend,
+    seeds: &[Input],
+    slow: Duration,
+) -> Result<DryRun, FuzzError> {
+    backend.check()?;
+
+    let mut dry_run = DryRun {
+        seeds: Vec::new(),
+        baseline: Coverage::new(),
+        slow,
+    };
+    for input in seeds {
+        let start = Instant::now();
+        let result = backend.run(input)?;
+        let duration = start.elapsed();
+
+        let crash = result.crash_signature();
+        let coverage = match result {
+            RunResult::Ok(coverage) => {
+                let len = coverage.len();
+                dry_run.baseline.extend(coverage);
+                len
+            }
+            RunResult::Crash(_) => 0,
+        };
+        dry_run.seeds.push(SeedRun {
+            input: input.clone(),
+            crash,
+            coverage,
+            duration,
+        });
+    }
+    Ok(dry_run)
+}
@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Errors of the coverage backends and the campaign machinery: what was being
+// done, and the underlying IO error (if any). Nothing in the backends panics on
+// errors of the environment (missing programs, failing processes, full disks,
+// unexpected gcov output); they return a `FuzzError` instead. Setup errors show
+// up in the dry run before fuzzing (see `dryrun`); errors during a campaign are
+// usually transient (e.g. too many open files), so campaigns log them and
+// continue with the next fuzz case (see `Campaign::run`).
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub struct FuzzError {
+    /// What failed, e.g. `Running gcov on crashme`.
+    pub context: String,
+    pub source: Option<io::Error>,
+}
+
+impl FuzzError {
+    /// Error without an underlying IO error.
+    pub fn new(context: impl Into<String>) -> Self {
+        Self {
+            context: context.into(),
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for FuzzError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{}: {}", self.context, source),
+            None => write!(f, "{}", self.context),
+        }
+    }
+}
+
+impl Error for FuzzError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+/// Add the context to IO errors, e.g.
+/// `fs::read(&path).context(format!("Reading {}", path.display()))?`.
+pub trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, FuzzError>;
+}
+
+impl<T> Context<T> for io::Result<T> {
+    fn context(self, context: impl Into<String>) -> Result<T, FuzzError> {
+        self.map_err(|source| FuzzError {
+            context: context.into(),
+            source: Some(source),
+        })
+    }
+}
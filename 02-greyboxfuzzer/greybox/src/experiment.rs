@@ -0,0 +1,277 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Comparing fuzzers based on one run each (and eyeballing the plot) is not
+// very meaningful: fuzzing is random, and the outcome of a single run depends
+// a lot on luck. Instead, run each fuzzer several times (with different seeds,
+// but the same seeds for each fuzzer) and compare the distributions of the
+// results, e.g. with a Mann-Whitney U test.
+// See e.g. Klees et al., "Evaluating Fuzz Testing" (CCS 2018) and
+// https://www.fuzzingbook.org/html/WhenToStopFuzzing.html
+
+use crate::campaign::Campaign;
+use crate::error::FuzzError;
+use crate::fuzzer::Fuzzer;
+use crate::rng::Rng;
+use crate::runner::CoverageBackend;
+
+/// Result of one campaign of one fuzzer.
+pub struct Trial {
+    /// Trace/history of the coverage sizes.
+    pub coverage_cumul: Vec<usize>,
+
+    /// Fuzz case at which the first crash was found.
+    pub first_crash: Option<usize>,
+}
+
+/// Results of all campaigns of all fuzzers.
+pub struct Results {
+    /// Number of fuzz cases per campaign.
+    pub n: usize,
+
+    /// Names of the fuzzers.
+    pub names: Vec<String>,
+
+    /// For each fuzzer the results of all repetitions.
+    pub trials: Vec<Vec<Trial>>,
+}
+
+/// Run `repetitions` campaigns of `n` fuzz cases for each of the fuzzers
+/// created by `make_fuzzers`. The fuzzers are created anew for each
+/// repetition, so that they don't carry over state (e.g. their population).
+/// The seeds of the repetitions are derived from `seed`; in each repetition
+/// all fuzzers use the same seed.
+pub fn run(
+    runner: &dyn CoverageBackend,
+    make_fuzzers: &dyn Fn() -> Vec<Box<dyn Fuzzer>>,
+    seed: u64,
+    n: usize,
+    repetitions: usize,
+) -> Result<Results, FuzzError> {
+    let mut master = Rng::seeded(seed);
+    let seeds: Vec<u64> = (0..repetitions).map(|_| master.u64()).collect();
+
+    let names: Vec<_> = make_fuzzers().iter().map(|fuzzer| fuzzer.name()).collect();
+    let mut trials: Vec<Vec<Trial>> = names.iter().map(|_| Vec::new()).collect();
+
+    for (repetition, seed) in seeds.iter().enumerate() {
+        println!(
+            "[+] Repetition {}/{} (seed {})",
+            repetition + 1,
+            repetitions,
+            seed
+        );
+        for (i, fuzzer) in make_fuzzers().iter_mut().enumerate() {
+            let mut rng = Rng::seeded(*seed);
+            let mut campaign = Campaign::new(fuzzer.as_mut(), runner);
+            campaign.run(&mut rng, n)?;
+            trials[i].push(Trial {
+                coverage_cumul: campaign.stats.coverage_cumul,
+                first_crash: campaign.stats.first_crash,
+            });
+        }
+    }
+
+    Ok(Results { n, names, trials })
+}
+
+impl Results {
+    /// Final coverage of each repetition, per fuzzer.
+    fn final_coverages(&self) -> Vec<Vec<f64>> {
+        self.trials
+            .iter()
+            .map(|trials| {
+                trials
+                    .iter()
+                    .map(|trial| *trial.coverage_cumul.last().unwrap_or(&0) as f64)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Fuzz cases until the first crash of each repetition, per fuzzer. If no
+    /// crash was found, `n` is used (i.e. as if it was found right after the
+    /// end of the campaign).
+    fn crash_times(&self) -> Vec<Vec<f64>> {
+        self.trials
+            .iter()
+            .map(|trials| {
+                trials
+                    .iter()
+                    .map(|trial| trial.first_crash.unwrap_or(self.n) as f64)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Median coverage over all repetitions after each fuzz case, per fuzzer.
+    pub fn median_curves(&self) -> Vec<Vec<f64>> {
+        self.trials
+            .iter()
+            .map(|trials| {
+                (0..self.n)
+                    .map(|i| {
+                        let values: Vec<_> = trials
+                            .iter()
+                            .map(|trial| trial.coverage_cumul[i] as f64)
+                            .collect();
+                        median(&values)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn print_report(&self) {
+        let coverages = self.final_coverages();
+        let crash_times = self.crash_times();
+
+        println!();
+        println!(
+            "[+] Summary ({} repetitions of {} fuzz cases each):",
+            self.trials[0].len(),
+            self.n
+        );
+        for (i, name) in self.names.iter().enumerate() {
+            let crashes = self.trials[i]
+                .iter()
+                .filter(|trial| trial.first_crash.is_some())
+                .count();
+            println!("    - {}:", name);
+            println!(
+                "        coverage:    median {:6.1} (min {}, max {})",
+                median(&coverages[i]),
+                coverages[i].iter().cloned().fold(f64::MAX, f64::min),
+                coverages[i].iter().cloned().fold(f64::MIN, f64::max),
+            );
+            println!(
+                "        first crash: median {:6.1} (found in {}/{} repetitions)",
+                median(&crash_times[i]),
+                crashes,
+                self.trials[i].len()
+            );
+        }
+
+        println!();
+        println!("[+] Mann-Whitney U test (two-sided p-values; p < 0.05: significant difference):");
+        for i in 0..self.names.len() {
+            for j in i + 1..self.names.len() {
+                let (_, p_coverage) = mann_whitney_u(&coverages[i], &coverages[j]);
+                let (_, p_crash) = mann_whitney_u(&crash_times[i], &crash_times[j]);
+                println!(
+                    "    - {} vs. {}: coverage p = {:.4}, first crash p = {:.4}",
+                    self.names[i], self.names[j], p_coverage, p_crash
+                );
+            }
+        }
+    }
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let len = values.len();
+    if len == 0 {
+        f64::NAN
+    } else if len % 2 == 1 {
+        values[len / 2]
+    } else {
+        (values[len / 2 - 1] + values[len / 2]) / 2.0
+    }
+}
+
+/// Mann-Whitney U test of whether two samples come from the same
+/// distribution. Returns the U statistic of the first sample and the
+/// two-sided p-value. The p-value uses the normal approximation (with
+/// correction for ties and continuity correction), which is reasonable from
+/// about 8 values per sample on. Without values in one of the samples there is
+/// nothing to compare, which gives U = 0 and p = 1.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> (f64, f64) {
+    if a.is_empty() || b.is_empty() {
+        return (0.0, 1.0);
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+
+    // Rank all values together; tied values get the average of their ranks.
+    let mut all: Vec<(f64, bool)> = a
+        .iter()
+        .map(|&x| (x, true))
+        .chain(b.iter().map(|&x| (x, false)))
+        .collect();
+    all.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+
+    let mut rank_sum_a = 0.0;
+    let mut tie_correction = 0.0;
+    let mut i = 0;
+    while i < all.len() {
+        let mut j = i;
+        while j < all.len() && all[j].0 == all[i].0 {
+            j += 1;
+        }
+        // Elements i..j are tied and get ranks i+1..=j.
+        let rank = (i + 1 + j) as f64 / 2.0;
+        rank_sum_a += rank * all[i..j].iter().filter(|x| x.1).count() as f64;
+        let t = (j - i) as f64;
+        tie_correction += t * t * t - t;
+        i = j;
+    }
+
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_correction / (n * (n - 1.0)));
+    // Negated, so that a NaN variance (0 / 0 in the tie correction for a
+    // single value) gives p = 1 as well.
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
+    if !(variance > 0.0) {
+        // All values are the same.
+        return (u, 1.0);
+    }
+    let z = ((u - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+    (u, 2.0 * (1.0 - normal_cdf(z)))
+}
+
+/// Cumulative distribution function of the standard normal distribution.
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Error function; approximation 7.1.26 from Abramowitz and Stegun (maximum
+/// error 1.5e-7), since std doesn't provide it.
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mann_whitney_u_small_samples() {
+        assert_eq!(mann_whitney_u(&[], &[]), (0.0, 1.0));
+        assert_eq!(mann_whitney_u(&[1.0, 2.0], &[]), (0.0, 1.0));
+        assert_eq!(mann_whitney_u(&[], &[1.0]), (0.0, 1.0));
+        assert_eq!(mann_whitney_u(&[3.0], &[3.0]), (0.5, 1.0));
+
+        let (u, p) = mann_whitney_u(&[1.0], &[2.0]);
+        assert_eq!(u, 0.0);
+        assert!(p.is_finite() && p > 0.05, "{}", p);
+    }
+
+    #[test]
+    fn mann_whitney_u_difference() {
+        let a: Vec<_> = (0..10).map(f64::from).collect();
+        let b: Vec<_> = (10..20).map(f64::from).collect();
+        let (u, p) = mann_whitney_u(&a, &b);
+        assert_eq!(u, 0.0);
+        assert!(p < 0.001, "{}", p);
+        assert!(mann_whitney_u(&a, &a).1 > 0.99);
+    }
+}
@@ -0,0 +1,98 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Inputs for targets that read several files per execution (e.g. a
+// configuration and a data file). The fuzzers work on one `Input` per fuzz
+// case, so the files ("slots") are stored in one input, separated by a line
+// `=====`. The backend writes each slot to its own file in the temporary
+// directory of the fuzz case (see `GcovRunner::with_files`).
+//
+// The slots can be mutated jointly (the mutations work on the whole input, as
+// for a single file; they can also move or destroy the separators) or
+// independently (each mutation is applied to one slot, and the separators
+// stay intact).
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Context, FuzzError};
+use crate::fuzzer::Input;
+use crate::rng::Rng;
+
+/// Separator of the slots in an input.
+pub const SEPARATOR: &[u8] = b"\n=====\n";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlotMutation {
+    Joint,
+    Independent,
+}
+
+#[derive(Clone, Debug)]
+pub struct Slots {
+    /// File name of each slot.
+    pub names: Vec<String>,
+    pub mutation: SlotMutation,
+}
+
+impl Slots {
+    pub fn new(names: Vec<String>, mutation: SlotMutation) -> Self {
+        assert!(!names.is_empty());
+        Self { names, mutation }
+    }
+
+    /// Split an input into exactly one part per slot: missing slots are
+    /// empty, and further separators belong to the last slot. So every input
+    /// can be delivered, even if the mutations destroyed some separators.
+    pub fn split(&self, input: &Input) -> Vec<Vec<u8>> {
+        let mut parts = Vec::new();
+        let mut rest = input.0.as_slice();
+        while parts.len() + 1 < self.names.len() {
+            let Some(pos) = rest
+                .windows(SEPARATOR.len())
+                .position(|window| window == SEPARATOR)
+            else {
+                break;
+            };
+            parts.push(rest[..pos].to_vec());
+            rest = &rest[pos + SEPARATOR.len()..];
+        }
+        parts.push(rest.to_vec());
+        parts.resize(self.names.len(), Vec::new());
+        parts
+    }
+
+    pub fn join(parts: &[Vec<u8>]) -> Input {
+        Input(parts.join(SEPARATOR))
+    }
+
+    /// Apply one mutation (e.g. `fuzzer::mutate`), to the whole input or to
+    /// one random slot.
+    pub fn mutate(
+        &self,
+        rng: &mut Rng,
+        input: Input,
+        mutate: impl FnOnce(&mut Rng, Input) -> Input,
+    ) -> Input {
+        match self.mutation {
+            SlotMutation::Joint => mutate(rng, input),
+            SlotMutation::Independent => {
+                let mut parts = self.split(&input);
+                let slot = rng.usize_below(parts.len());
+                let part = std::mem::take(&mut parts[slot]);
+                parts[slot] = mutate(rng, Input(part)).0;
+                Self::join(&parts)
+            }
+        }
+    }
+
+    /// Write the slots of the input to their files in the directory.
+    pub fn write(&self, input: &Input, dir: &Path) -> Result<(), FuzzError> {
+        for (name, part) in self.names.iter().zip(self.split(input)) {
+            let path = dir.join(name);
+            fs::write(&path, part).context(format!("Writing {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,939 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// The different fuzzers of the previous chapters, all behind one common
+// interface so that they can be swapped out and compared in the same harness.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use crate::campaign::Calibration;
+use crate::dictionary::Dictionary;
+use crate::files::Slots;
+use crate::rng::Rng;
+use crate::runner::{CoverageH, RunResult};
+use crate::schedule::Schedule;
+use crate::secondary::SecondaryQueue;
+use crate::snapshot::Snapshot;
+
+/// Represents the structure that the fuzzer operates on. Invalid utf8 (e.g.
+/// binary inputs from a libFuzzer corpus) is shown as replacement characters.
+pub use crate::bytes::Bytes as Input;
+
+/// Common interface of all fuzzers: produce a new input, and get feedback
+/// about how the execution of that input went.
+/// Pure generators (e.g. the random fuzzer) simply ignore the feedback.
+pub trait Fuzzer {
+    fn name(&self) -> String;
+
+    /// Get next input to fuzz with by whichever means suitable (e.g.
+    /// generation of input, choosing as-is from initial corpus, or mutating
+    /// from current population of inputs).
+    fn fuzz(&mut self, rng: &mut Rng) -> Input;
+
+    /// Feedback about the result of running the input returned by the last
+    /// call to `fuzz`.
+    fn observe(&mut self, _input: &Input, _result: &RunResult) {}
+
+    /// Execution time of the input passed to the last call to `observe`
+    /// (not given for calibration runs).
+    fn observe_duration(&mut self, _input: &Input, _duration: Duration) {}
+
+    /// Input that should be calibrated (run several more times, see
+    /// `Calibration`) before continuing, e.g. because it was just added to
+    /// the population. Asked after each call to `observe`.
+    fn calibration_request(&mut self) -> Option<Input> {
+        None
+    }
+
+    /// Result of calibrating the input returned by `calibration_request`.
+    fn calibrated(&mut self, _input: &Input, _calibration: &Calibration) {}
+
+    /// Current population of inputs that further inputs are derived from
+    /// (empty for pure generators), e.g. for saving it as a corpus.
+    fn population(&self) -> Vec<Input> {
+        Vec::new()
+    }
+
+    /// Lines for the campaign report about the state of the fuzzer (e.g. its
+    /// queues), if there is anything to report.
+    fn report(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// State to save for resuming the campaign later (see `snapshot`), if the
+    /// fuzzer supports it.
+    fn snapshot(&self) -> Option<Snapshot> {
+        None
+    }
+
+    /// Continue from the state of a snapshot returned by `snapshot`.
+    fn restore(&mut self, _snapshot: Snapshot) {}
+}
+
+/* Random fuzzer. ************************************************************/
+
+/// Generate random strings of random length (see 02-fuzzer).
+pub struct RandomFuzzer {
+    /// Length of the strings, both bounds inclusive.
+    pub min_length: u64,
+    pub max_length: u64,
+    /// Bytes of the strings: `char_range` bytes starting at `char_start`.
+    pub char_start: u8,
+    pub char_range: u8,
+}
+
+impl Default for RandomFuzzer {
+    fn default() -> Self {
+        Self {
+            min_length: 10,
+            max_length: 100,
+            char_start: 32,
+            char_range: 32,
+        }
+    }
+}
+
+impl Fuzzer for RandomFuzzer {
+    fn name(&self) -> String {
+        "Random fuzzer".to_string()
+    }
+
+    fn fuzz(&mut self, rng: &mut Rng) -> Input {
+        let len = rng.range(self.min_length, self.max_length + 1);
+        let chars = self.char_start..=self.char_start + (self.char_range - 1);
+        Input(rng.bytes_range(len, chars))
+    }
+}
+
+/* Mutation fuzzer. **********************************************************/
+
+/// Mutate a fixed population of seed inputs (see 02-mutationfuzzer).
+/// The seeds are first returned as-is.
+pub struct MutationFuzzer {
+    pub seed: Vec<Input>,
+    pub min_mutations: u64,
+    pub max_mutations: u64,
+
+    /// Choose the inputs to mutate weighted by how productive they were so
+    /// far (see `SeedScore`) instead of uniformly.
+    pub weighted: bool,
+
+    /// Mutation operators (by default detected from the seeds).
+    pub operators: Operators,
+
+    /// Slots of multi-file inputs, mutated jointly or independently.
+    pub slots: Option<Slots>,
+
+    /// Tokens for the dictionary mutations.
+    pub dictionary: Option<Dictionary>,
+
+    population: Vec<Input>,
+    seed_index: usize,
+
+    /// Score of each input in the population (same order).
+    scores: Vec<SeedScore>,
+
+    /// Index of the input in the population that the last returned input was
+    /// derived from (`None` for the initial seeds).
+    parent: Option<usize>,
+
+    fuzz_cases: usize,
+}
+
+/// How productive an input of the population has been as seed for mutation:
+/// the number of inputs derived from it that were added to the population
+/// (i.e. led to new coverage), and when the last of them was found. Similar
+/// to the power schedule of the boosted greybox fuzzer, but based on the
+/// finds of a seed instead of the frequency of its path.
+#[derive(Clone, Debug)]
+struct SeedScore {
+    descendants: usize,
+    last_find: usize,
+}
+
+impl SeedScore {
+    /// After this many fuzz cases without a find the weight of a seed is
+    /// halved (hyperbolic decay, so that weights never become 0).
+    const RECENCY: f64 = 100.0;
+
+    fn new(fuzz_cases: usize) -> Self {
+        Self {
+            descendants: 0,
+            last_find: fuzz_cases,
+        }
+    }
+
+    fn weight(&self, fuzz_cases: usize) -> f64 {
+        let age = (fuzz_cases - self.last_find) as f64;
+        (1 + self.descendants) as f64 / (1.0 + age / Self::RECENCY)
+    }
+}
+
+impl MutationFuzzer {
+    pub fn new(seed: Vec<Input>, min_mutations: u64, max_mutations: u64) -> Self {
+        assert!(!seed.is_empty());
+        let operators = Operators::detect(&seed);
+        Self {
+            population: seed.clone(),
+            scores: vec![SeedScore::new(0); seed.len()],
+            seed,
+            min_mutations,
+            max_mutations,
+            weighted: false,
+            operators,
+            slots: None,
+            dictionary: None,
+            seed_index: 0,
+            parent: None,
+            fuzz_cases: 0,
+        }
+    }
+
+    /// Use the given mutation operators instead of the detected ones.
+    pub fn with_operators(mut self, operators: Operators) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    /// Fuzz inputs made up of several files (see `files::Slots`).
+    pub fn with_slots(mut self, slots: Slots) -> Self {
+        self.slots = Some(slots);
+        self
+    }
+
+    /// Also insert and overwrite tokens of the dictionary (see
+    /// `dictionary`).
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.dictionary = Some(dictionary);
+        self
+    }
+
+    /// Add a new input to the population, and credit the input it was derived
+    /// from.
+    fn add_to_population(&mut self, input: Input) {
+        if let Some(parent) = self.parent {
+            self.scores[parent].descendants += 1;
+            self.scores[parent].last_find = self.fuzz_cases;
+        }
+        self.population.push(input);
+        self.scores.push(SeedScore::new(self.fuzz_cases));
+    }
+
+    /// Choose the index of the input in the population to mutate next.
+    fn choose(&self, rng: &mut Rng) -> usize {
+        let indices: Vec<_> = (0..self.population.len()).collect();
+        if self.weighted {
+            let weights: Vec<_> = self
+                .scores
+                .iter()
+                .map(|score| score.weight(self.fuzz_cases))
+                .collect();
+            *rng.choice_w(&indices, &weights)
+        } else {
+            *rng.choice(&indices)
+        }
+    }
+}
+
+impl Fuzzer for MutationFuzzer {
+    fn name(&self) -> String {
+        "Mutation fuzzer".to_string()
+    }
+
+    fn fuzz(&mut self, rng: &mut Rng) -> Input {
+        self.fuzz_cases += 1;
+        if self.seed_index < self.seed.len() {
+            // Choose input candidate from initial population as seed.
+            self.seed_index += 1;
+            self.parent = None;
+            self.seed[self.seed_index - 1].clone()
+        } else {
+            // Choose existing input from population and mutate it a random
+            // number of times.
+            let parent = self.choose(rng);
+            self.parent = Some(parent);
+            let mut candidate = self.population[parent].clone();
+            let trials = rng.range(self.min_mutations, self.max_mutations + 1);
+            for _ in 0..trials {
+                candidate = mutate_with(
+                    rng,
+                    candidate,
+                    self.operators,
+                    &self.slots,
+                    &self.dictionary,
+                );
+            }
+            candidate
+        }
+    }
+
+    fn population(&self) -> Vec<Input> {
+        self.population.clone()
+    }
+}
+
+/// Mutation fuzzer that adds inputs leading to new coverage to its population
+/// (see 02-mutationfuzzer/mutationfuzzer3 and later).
+pub struct MutationCoverageFuzzer {
+    fuzzer: MutationFuzzer,
+    coverages_seen: BTreeSet<CoverageH>,
+}
+
+impl MutationCoverageFuzzer {
+    pub fn new(seed: Vec<Input>, min_mutations: u64, max_mutations: u64) -> Self {
+        let mut fuzzer = MutationFuzzer::new(seed, min_mutations, max_mutations);
+        // The population is only made up of inputs that were actually run.
+        fuzzer.population.clear();
+        fuzzer.scores.clear();
+        Self {
+            fuzzer,
+            coverages_seen: BTreeSet::new(),
+        }
+    }
+
+    /// Like `new`, but productive inputs of the population are chosen more
+    /// often for mutation (see `SeedScore`).
+    pub fn weighted(seed: Vec<Input>, min_mutations: u64, max_mutations: u64) -> Self {
+        let mut res = Self::new(seed, min_mutations, max_mutations);
+        res.fuzzer.weighted = true;
+        res
+    }
+
+    /// Use the given mutation operators instead of the detected ones.
+    pub fn with_operators(mut self, operators: Operators) -> Self {
+        self.fuzzer.operators = operators;
+        self
+    }
+
+    /// Fuzz inputs made up of several files (see `files::Slots`).
+    pub fn with_slots(mut self, slots: Slots) -> Self {
+        self.fuzzer.slots = Some(slots);
+        self
+    }
+
+    /// Also insert and overwrite tokens of the dictionary (see
+    /// `dictionary`).
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.fuzzer.dictionary = Some(dictionary);
+        self
+    }
+}
+
+impl Fuzzer for MutationCoverageFuzzer {
+    fn name(&self) -> String {
+        if self.fuzzer.weighted {
+            "Weighted mutation coverage fuzzer".to_string()
+        } else {
+            "Mutation coverage fuzzer".to_string()
+        }
+    }
+
+    fn fuzz(&mut self, rng: &mut Rng) -> Input {
+        self.fuzzer.fuzz(rng)
+    }
+
+    fn observe(&mut self, input: &Input, result: &RunResult) {
+        if let RunResult::Ok(coverage) = result {
+            if self.coverages_seen.insert(CoverageH::new(coverage)) {
+                // We have some new coverage.
+                self.fuzzer.add_to_population(input.clone());
+            }
+        }
+    }
+
+    fn population(&self) -> Vec<Input> {
+        self.fuzzer.population()
+    }
+}
+
+/* Greybox fuzzer. ***********************************************************/
+
+/// Greybox fuzzer (see 02-greyboxfuzzer/greyboxfuzzer4 and greyboxfuzzer5):
+/// inputs with new coverage are added to the population, and chosen for
+/// mutation according to the power schedule. With the default (exponential)
+/// schedule, this is the boosted greybox fuzzer: inputs exercising rarely seen
+/// paths are chosen more often.
+pub struct GreyboxFuzzer {
+    pub seed: Vec<Input>,
+    pub schedule: Schedule,
+
+    /// Mutation operators (by default detected from the seeds).
+    pub operators: Operators,
+
+    /// Slots of multi-file inputs, mutated jointly or independently.
+    pub slots: Option<Slots>,
+
+    /// Tokens for the dictionary mutations.
+    pub dictionary: Option<Dictionary>,
+
+    seed_index: usize,
+
+    /// All inputs with unique coverage, together with a hash of the
+    /// associated coverage information.
+    population: BTreeMap<Input, CoverageH>,
+
+    /// Hashes of all coverages seen during fuzzing, together with the count /
+    /// frequency they occurred.
+    coverage_db: BTreeMap<CoverageH, usize>,
+
+    /// Calibration results of the inputs in the population. Inputs with
+    /// unstable coverage don't get boosted by the power schedule.
+    calibrations: BTreeMap<Input, Calibration>,
+
+    /// Input that was just added to the population and still has to be
+    /// calibrated.
+    uncalibrated: Option<Input>,
+
+    /// Near misses (rare paths, slow executions) that are chosen for mutation
+    /// at a low rate (see `secondary`).
+    secondary: Option<SecondaryQueue>,
+
+    /// How often the path of the last observed input was exercised (`None`
+    /// after a crash or new coverage).
+    last_path_count: Option<usize>,
+}
+
+impl GreyboxFuzzer {
+    pub fn new(seed: Vec<Input>) -> Self {
+        assert!(!seed.is_empty());
+        Self {
+            operators: Operators::detect(&seed),
+            seed,
+            schedule: Schedule::default(),
+            slots: None,
+            dictionary: None,
+            seed_index: 0,
+            population: BTreeMap::new(),
+            coverage_db: BTreeMap::new(),
+            calibrations: BTreeMap::new(),
+            uncalibrated: None,
+            secondary: None,
+            last_path_count: None,
+        }
+    }
+
+    /// Use the given mutation operators instead of the detected ones.
+    pub fn with_operators(mut self, operators: Operators) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    /// Fuzz inputs made up of several files (see `files::Slots`).
+    pub fn with_slots(mut self, slots: Slots) -> Self {
+        self.slots = Some(slots);
+        self
+    }
+
+    /// Also insert and overwrite tokens of the dictionary (see
+    /// `dictionary`).
+    pub fn with_dictionary(mut self, dictionary: Dictionary) -> Self {
+        self.dictionary = Some(dictionary);
+        self
+    }
+
+    /// Use the given power schedule.
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Keep near misses in a secondary queue (see `secondary`).
+    pub fn with_secondary(mut self, secondary: SecondaryQueue) -> Self {
+        self.secondary = Some(secondary);
+        self
+    }
+
+    /// Choose a value from the population according to the power schedule.
+    /// Inputs with unstable coverage get the lowest possible fitness (as if
+    /// their path were the most frequent one), since their path frequency
+    /// can't be trusted.
+    fn power_schedule_choose(&self, rng: &mut Rng) -> Input {
+        let max_count = *self.coverage_db.values().max().unwrap();
+        let fitness: Vec<_> = self
+            .population
+            .iter()
+            .map(|(input, coverageh)| {
+                let unstable = self.calibrations.get(input).is_some_and(|c| !c.stable);
+                let count = if unstable {
+                    max_count
+                } else {
+                    self.coverage_db[coverageh]
+                };
+                self.schedule.energy(count)
+            })
+            .collect();
+
+        let pop: Vec<_> = self.population.keys().collect();
+        (*rng.choice_w(&pop, &fitness)).clone()
+    }
+}
+
+impl Fuzzer for GreyboxFuzzer {
+    fn name(&self) -> String {
+        match self.schedule {
+            Schedule::Uniform => "Greybox fuzzer".to_string(),
+            Schedule::Exponential { .. } => "Boosted greybox fuzzer".to_string(),
+        }
+    }
+
+    fn fuzz(&mut self, rng: &mut Rng) -> Input {
+        if self.seed_index < self.seed.len() || self.population.is_empty() {
+            // Choose input candidate from initial population as seed.
+            self.seed_index += 1;
+            self.seed[(self.seed_index - 1) % self.seed.len()].clone()
+        } else {
+            let mut candidate = match self.secondary.as_mut().and_then(|q| q.choose(rng)) {
+                Some(input) => input,
+                None => self.power_schedule_choose(rng),
+            };
+            let trials = std::cmp::min(candidate.0.len() as u64, 1 << rng.range(1, 5));
+            for _ in 0..trials {
+                candidate = mutate_with(
+                    rng,
+                    candidate,
+                    self.operators,
+                    &self.slots,
+                    &self.dictionary,
+                );
+            }
+            candidate
+        }
+    }
+
+    fn observe(&mut self, input: &Input, result: &RunResult) {
+        self.last_path_count = None;
+        if let RunResult::Ok(coverage) = result {
+            let coverageh = CoverageH::new(coverage);
+            match self.coverage_db.get_mut(&coverageh) {
+                None => {
+                    // We have some new coverage.
+                    self.coverage_db.insert(coverageh.clone(), 1);
+                    self.population.insert(input.clone(), coverageh);
+                    self.uncalibrated = Some(input.clone());
+                }
+                Some(count) => {
+                    *count += 1;
+                    self.last_path_count = Some(*count);
+                }
+            }
+        }
+    }
+
+    fn observe_duration(&mut self, input: &Input, duration: Duration) {
+        let path_count = self.last_path_count.take();
+        if let Some(secondary) = self.secondary.as_mut() {
+            // Inputs of the population are mutated anyway.
+            let path_count = path_count.filter(|_| !self.population.contains_key(input));
+            secondary.offer(input, path_count, duration);
+        }
+    }
+
+    fn calibration_request(&mut self) -> Option<Input> {
+        self.uncalibrated.take()
+    }
+
+    fn calibrated(&mut self, input: &Input, calibration: &Calibration) {
+        self.calibrations.insert(input.clone(), calibration.clone());
+    }
+
+    fn population(&self) -> Vec<Input> {
+        self.population.keys().cloned().collect()
+    }
+
+    fn snapshot(&self) -> Option<Snapshot> {
+        Some(Snapshot {
+            population: self.population.clone(),
+            coverage_db: self.coverage_db.clone(),
+        })
+    }
+
+    /// The seeds are still run first (their paths are already known, so they
+    /// are not added again). Calibrations are not part of the snapshot:
+    /// restored inputs count as stable.
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.population = snapshot.population;
+        self.coverage_db = snapshot.coverage_db;
+        self.calibrations.clear();
+        self.uncalibrated = None;
+    }
+
+    fn report(&self) -> Vec<String> {
+        let Some(secondary) = &self.secondary else {
+            return Vec::new();
+        };
+        vec![format!(
+            "Secondary queue: {} inputs ({} rare paths and {} slow inputs added), chosen {} times",
+            secondary.len(),
+            secondary.stats.rare,
+            secondary.stats.slow,
+            secondary.stats.chosen
+        )]
+    }
+}
+
+/* Grammar fuzzer. ***********************************************************/
+
+/// Grammar as mapping from nonterminals (written as `<name>`) to a list of
+/// alternative expansions, in the style of the fuzzingbook Grammars chapter.
+pub type Grammar = BTreeMap<&'static str, Vec<&'static str>>;
+
+/// Simple string-replacement based grammar fuzzer (`simple_grammar_fuzzer`
+/// from https://www.fuzzingbook.org/html/Grammars.html); see 03-grammarfuzzer
+/// for the proper derivation tree based implementation.
+pub struct GrammarFuzzer {
+    pub grammar: Grammar,
+    pub start_symbol: &'static str,
+    pub max_nonterminals: usize,
+    pub max_expansion_trials: usize,
+}
+
+impl GrammarFuzzer {
+    pub fn new(grammar: Grammar) -> Self {
+        Self {
+            grammar,
+            start_symbol: "<start>",
+            max_nonterminals: 10,
+            max_expansion_trials: 100,
+        }
+    }
+}
+
+/// Find all nonterminals `<...>` in a string as (start, end) byte ranges.
+fn nonterminals(term: &str) -> Vec<(usize, usize)> {
+    let mut res = Vec::new();
+    let mut start = None;
+    for (i, c) in term.char_indices() {
+        match c {
+            '<' => start = Some(i),
+            '>' if start.is_some() => {
+                res.push((start.unwrap(), i + 1));
+                start = None;
+            }
+            ' ' => start = None,
+            _ => (),
+        }
+    }
+    res
+}
+
+impl Fuzzer for GrammarFuzzer {
+    fn name(&self) -> String {
+        "Grammar fuzzer".to_string()
+    }
+
+    fn fuzz(&mut self, rng: &mut Rng) -> Input {
+        let mut term = self.start_symbol.to_string();
+        let mut expansion_trials = 0;
+
+        loop {
+            let symbols = nonterminals(&term);
+            if symbols.is_empty() {
+                break;
+            }
+
+            let (start, end) = *rng.choice(&symbols);
+            let expansion = rng.choice(&self.grammar[&term[start..end]]);
+            let new_term = format!("{}{}{}", &term[..start], expansion, &term[end..]);
+
+            if nonterminals(&new_term).len() < self.max_nonterminals {
+                term = new_term;
+                expansion_trials = 0;
+            } else {
+                expansion_trials += 1;
+                if expansion_trials >= self.max_expansion_trials {
+                    panic!("Cannot expand {}", term);
+                }
+            }
+        }
+
+        Input::from_str(&term)
+    }
+}
+
+/* Mutations. ****************************************************************/
+
+/// Sets of mutation operators: for text inputs only those that keep printable
+/// text printable (more or less; a flipped bit can produce a control
+/// character), for binary inputs additionally the byte-level ones (which
+/// produce arbitrary bytes). For UTF-8 text, the operators work on whole code
+/// points instead of bytes, so that valid UTF-8 stays valid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operators {
+    Text,
+    Utf8,
+    Binary,
+}
+
+impl Operators {
+    /// Seeds count as text if they are valid utf8 and at least 90% of their
+    /// characters are printable (or whitespace); as UTF-8 text if some of
+    /// these characters are not ASCII.
+    pub fn detect(seeds: &[Input]) -> Self {
+        let mut chars = 0;
+        let mut printable = 0;
+        let mut ascii = true;
+        for seed in seeds.iter() {
+            let Ok(s) = std::str::from_utf8(&seed.0) else {
+                return Operators::Binary;
+            };
+            for c in s.chars() {
+                chars += 1;
+                if !c.is_control() || c.is_ascii_whitespace() {
+                    printable += 1;
+                }
+                ascii &= c.is_ascii();
+            }
+        }
+        if (printable as f64) < 0.9 * chars as f64 {
+            Operators::Binary
+        } else if ascii {
+            Operators::Text
+        } else {
+            Operators::Utf8
+        }
+    }
+}
+
+/// Apply one mutation, to a random slot if the input is made up of several
+/// files that are mutated independently. With a dictionary, a quarter of the
+/// mutations are dictionary mutations.
+fn mutate_with(
+    rng: &mut Rng,
+    s: Input,
+    operators: Operators,
+    slots: &Option<Slots>,
+    dictionary: &Option<Dictionary>,
+) -> Input {
+    let mutate_one = |rng: &mut Rng, s: Input| match dictionary {
+        Some(dictionary) if rng.int(4) == 0 => dictionary.mutate(rng, s),
+        _ => mutate(rng, s, operators),
+    };
+    match slots {
+        Some(slots) => slots.mutate(rng, s, mutate_one),
+        None => mutate_one(rng, s),
+    }
+}
+
+/// Choose a random mutation strategy from the set of operators and apply it
+/// to the input.
+pub fn mutate(rng: &mut Rng, s: Input, operators: Operators) -> Input {
+    let n = match operators {
+        Operators::Text => 3,
+        Operators::Utf8 => return mutate_utf8(rng, s),
+        Operators::Binary => 5,
+    };
+    match rng.int(n) {
+        0 => insert_random_character(rng, s),
+        1 => delete_random_character(rng, s),
+        2 => flip_random_bit(rng, s),
+        3 => set_interesting_value(rng, s),
+        4 => add_small_delta(rng, s),
+        _ => panic!("Can't happen"),
+    }
+}
+
+fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=126);
+    s.0.insert(pos, chr);
+    s
+}
+
+fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
+    if s.0.is_empty() {
+        s
+    } else {
+        let pos = rng.usize_below(s.0.len());
+        s.0.remove(pos);
+        s
+    }
+}
+
+fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
+    if s.0.is_empty() {
+        return s;
+    }
+
+    // Here we loop to make sure that the result does not contain a null-byte,
+    // which would be impossible to pass on the commandline to the external
+    // program.
+    loop {
+        let pos = rng.usize_below(s.0.len());
+        let bit = 1 << rng.int(7);
+        s.0[pos] ^= bit; // Flip bit.
+        if s.0[pos] != 0 {
+            return s;
+        }
+        s.0[pos] ^= bit; // Flip bit back and try next random mutation.
+    }
+}
+
+/* Code point mutators. *******************************************************/
+
+// Deleting or inserting single bytes, or flipping bits, in UTF-8 text mostly
+// gives invalid sequences (e.g. half of a two-byte character), which many
+// targets reject in their very first check. These operators work on whole
+// code points instead.
+
+/// Characters inserted by the code point mutators: printable ASCII, and some
+/// characters of two, three and four bytes in UTF-8 (including a combining
+/// character, a right-to-left letter, and characters that change length when
+/// changing case).
+const UTF8_CHARS: &[char] = &[
+    'é', 'ß', 'ﬁ', 'İ', 'Ω', 'ж', 'א', '\u{301}', '\u{200b}', '€', '中', '\u{fffd}', '😀', '𝔘',
+];
+
+/// Apply a random code point mutation. Inputs that aren't valid UTF-8 get a
+/// text (byte-level) mutation instead.
+fn mutate_utf8(rng: &mut Rng, s: Input) -> Input {
+    let Ok(text) = std::str::from_utf8(&s.0) else {
+        return mutate(rng, s, Operators::Text);
+    };
+    let mut chars: Vec<char> = text.chars().collect();
+    match rng.int(5) {
+        0 => insert_random_char(rng, &mut chars),
+        1 => delete_random_char(rng, &mut chars),
+        2 => replace_random_char(rng, &mut chars),
+        3 => swap_adjacent_chars(rng, &mut chars),
+        4 => change_case(rng, &mut chars),
+        _ => panic!("Can't happen"),
+    }
+    Input(chars.into_iter().collect::<String>().into_bytes())
+}
+
+/// Random printable ASCII character (half of the time) or one of
+/// `UTF8_CHARS`.
+fn random_char(rng: &mut Rng) -> char {
+    match rng.int(2) {
+        0 => rng.u8_range(32..=126) as char,
+        _ => *rng.choice(UTF8_CHARS),
+    }
+}
+
+fn insert_random_char(rng: &mut Rng, chars: &mut Vec<char>) {
+    let pos = rng.usize_below(chars.len() + 1);
+    chars.insert(pos, random_char(rng));
+}
+
+fn delete_random_char(rng: &mut Rng, chars: &mut Vec<char>) {
+    if !chars.is_empty() {
+        let pos = rng.usize_below(chars.len());
+        chars.remove(pos);
+    }
+}
+
+fn replace_random_char(rng: &mut Rng, chars: &mut [char]) {
+    if !chars.is_empty() {
+        let pos = rng.usize_below(chars.len());
+        chars[pos] = random_char(rng);
+    }
+}
+
+fn swap_adjacent_chars(rng: &mut Rng, chars: &mut [char]) {
+    if chars.len() >= 2 {
+        let pos = rng.usize_below(chars.len() - 1);
+        chars.swap(pos, pos + 1);
+    }
+}
+
+/// Change a character to upper or lower case (whichever changes it, if any).
+/// Some characters change their length (`ß` becomes `SS`).
+fn change_case(rng: &mut Rng, chars: &mut Vec<char>) {
+    if chars.is_empty() {
+        return;
+    }
+    let pos = rng.usize_below(chars.len());
+    let c = chars[pos];
+    let changed: Vec<char> = if c.is_uppercase() {
+        c.to_lowercase().collect()
+    } else {
+        c.to_uppercase().collect()
+    };
+    chars.splice(pos..pos + 1, changed);
+}
+
+/* Byte-level mutators (as in AFL). *******************************************/
+
+// Binary formats contain integers of 1, 2, 4 or 8 bytes (lengths, offsets,
+// counts, ...) in little or big endian. Replacing them with boundary values or
+// shifting them by a small amount triggers off-by-one errors and integer
+// overflows far more often than flipping random bits.
+
+/// Overwrite a window of 1, 2, 4 or 8 bytes with an interesting value.
+fn set_interesting_value(rng: &mut Rng, mut s: Input) -> Input {
+    let Some((pos, width)) = random_window(rng, &s) else {
+        return s;
+    };
+    let values = interesting_values(width);
+    let value = *rng.choice(&values);
+    let big_endian = rng.int(2) == 0;
+    write_window(&mut s.0[pos..pos + width], value as u64, big_endian);
+    s
+}
+
+/// Add or subtract a small delta (1 to 35, like AFL) to the integer in a
+/// window of 1, 2, 4 or 8 bytes (wrapping around).
+fn add_small_delta(rng: &mut Rng, mut s: Input) -> Input {
+    let Some((pos, width)) = random_window(rng, &s) else {
+        return s;
+    };
+    let big_endian = rng.int(2) == 0;
+    let window = &mut s.0[pos..pos + width];
+    let delta = rng.range(1, 35 + 1);
+    let value = if rng.int(2) == 0 {
+        read_window(window, big_endian).wrapping_add(delta)
+    } else {
+        read_window(window, big_endian).wrapping_sub(delta)
+    };
+    write_window(window, value, big_endian);
+    s
+}
+
+/// Values that are likely to trigger edge cases for an integer of `width`
+/// bytes: 0, 1, -1 (i.e. unsigned maximum), the powers of two, and the signed
+/// minimum and maximum.
+fn interesting_values(width: usize) -> Vec<i64> {
+    let bits = 8 * width as u32;
+    let mut res = vec![0, 1, -1];
+    res.extend((1..bits - 1).map(|k| 1i64 << k));
+    res.push(i64::MAX >> (64 - bits)); // Signed maximum.
+    res.push(i64::MIN >> (64 - bits)); // Signed minimum.
+    res
+}
+
+/// Random position and width (1, 2, 4 or 8 bytes) of a window that lies
+/// within the input; `None` if the input is empty.
+fn random_window(rng: &mut Rng, s: &Input) -> Option<(usize, usize)> {
+    let widths: Vec<_> = [1, 2, 4, 8]
+        .into_iter()
+        .filter(|&width| width <= s.0.len())
+        .collect();
+    if widths.is_empty() {
+        return None;
+    }
+    let width = *rng.choice(&widths);
+    let pos = rng.usize_below(s.0.len() - width + 1);
+    Some((pos, width))
+}
+
+/// Read the bytes of the window as unsigned integer.
+fn read_window(window: &[u8], big_endian: bool) -> u64 {
+    let mut bytes = window.to_vec();
+    if big_endian {
+        bytes.reverse();
+    }
+    bytes.resize(8, 0);
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+/// Write the lowest bytes of the value into the window.
+fn write_window(window: &mut [u8], value: u64, big_endian: bool) {
+    let width = window.len();
+    window.copy_from_slice(&value.to_le_bytes()[..width]);
+    if big_endian {
+        window.reverse();
+    }
+}
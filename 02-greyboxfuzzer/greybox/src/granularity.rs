@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Coverage at different granularities: lines (what the backends report),
+// functions, or files. A coarser granularity makes the coverage-guided fuzzers
+// (and their schedules) only count an input as new if it reaches a function
+// or file that wasn't reached before, and makes reports shorter for larger
+// programs.
+//
+// The backends only know lines; which function and file a line belongs to
+// comes from the backend as `Functions` (e.g. the gcov output lists the
+// functions with their first line). `GranularBackend` maps the coverage of
+// another backend to the coarser locations.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use crate::error::FuzzError;
+use crate::fuzzer::Input;
+use crate::runner::{Coverage, CoverageBackend, RunResult};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Granularity {
+    Line,
+    Function,
+    File,
+}
+
+impl Granularity {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "line" => Some(Self::Line),
+            "function" => Some(Self::Function),
+            "file" => Some(Self::File),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Granularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Line => write!(f, "line"),
+            Self::Function => write!(f, "function"),
+            Self::File => write!(f, "file"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub file: String,
+    /// Line of the function header; the function ends before the first line
+    /// of the next function (in the same file).
+    pub first_line: usize,
+}
+
+/// The functions of a program, by their first line. Locations are lines, so
+/// this only works for backends whose locations are the lines of a single
+/// source file (like `GcovRunner`).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Functions(BTreeMap<usize, Function>);
+
+impl Functions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, function: Function) {
+        self.0.insert(function.first_line, function);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The function containing the line (`None` for lines before the first
+    /// function, e.g. global variables).
+    pub fn function_of(&self, line: usize) -> Option<&Function> {
+        self.0
+            .range(..=line)
+            .next_back()
+            .map(|(_, function)| function)
+    }
+
+    /// Names of all files, sorted (the file locations are indices into this).
+    pub fn files(&self) -> Vec<&str> {
+        let mut files: Vec<&str> = self.0.values().map(|f| f.file.as_str()).collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// The location of the line at the given granularity: the line itself,
+    /// the first line of its function, or the index of its file. Lines
+    /// outside of all functions stay lines at function granularity (and
+    /// belong to no file).
+    pub fn location(&self, line: usize, granularity: Granularity) -> Option<usize> {
+        match granularity {
+            Granularity::Line => Some(line),
+            Granularity::Function => Some(self.function_of(line).map_or(line, |f| f.first_line)),
+            Granularity::File => {
+                let file = &self.function_of(line)?.file;
+                self.files().iter().position(|f| f == file)
+            }
+        }
+    }
+
+    /// Name of a location at the given granularity: the function (and line)
+    /// or the file.
+    pub fn name(&self, location: usize, granularity: Granularity) -> String {
+        match granularity {
+            Granularity::Line => match self.function_of(location) {
+                Some(function) => format!("{}:{}", function.name, location),
+                None => location.to_string(),
+            },
+            Granularity::Function => match self.0.get(&location) {
+                Some(function) => function.name.clone(),
+                None => location.to_string(),
+            },
+            Granularity::File => self
+                .files()
+                .get(location)
+                .map_or(location.to_string(), |f| f.to_string()),
+        }
+    }
+
+    /// The coverage at the given granularity.
+    pub fn coarsen(&self, coverage: &Coverage, granularity: Granularity) -> Coverage {
+        coverage
+            .iter()
+            .filter_map(|&line| self.location(line, granularity))
+            .collect()
+    }
+}
+
+/// Aggregation of line coverage, e.g. `coverage.by_function(&functions)`.
+pub trait Aggregate {
+    /// Number of covered lines per function (only functions with at least one
+    /// covered line).
+    fn by_function(&self, functions: &Functions) -> BTreeMap<String, usize>;
+
+    /// Number of covered lines per file.
+    fn by_file(&self, functions: &Functions) -> BTreeMap<String, usize>;
+}
+
+impl Aggregate for Coverage {
+    fn by_function(&self, functions: &Functions) -> BTreeMap<String, usize> {
+        let mut res = BTreeMap::new();
+        for function in self.iter().filter_map(|&line| functions.function_of(line)) {
+            *res.entry(function.name.clone()).or_default() += 1;
+        }
+        res
+    }
+
+    fn by_file(&self, functions: &Functions) -> BTreeMap<String, usize> {
+        let mut res = BTreeMap::new();
+        for function in self.iter().filter_map(|&line| functions.function_of(line)) {
+            *res.entry(function.file.clone()).or_default() += 1;
+        }
+        res
+    }
+}
+
+/// Backend that reports the coverage of another backend at a coarser
+/// granularity. The functions are taken from the backend after its first
+/// run (gcov only lists them once the program ran).
+pub struct GranularBackend {
+    pub backend: Box<dyn CoverageBackend>,
+    pub granularity: Granularity,
+    functions: RefCell<Option<Functions>>,
+}
+
+impl GranularBackend {
+    pub fn new(backend: Box<dyn CoverageBackend>, granularity: Granularity) -> Self {
+        Self {
+            backend,
+            granularity,
+            functions: RefCell::new(None),
+        }
+    }
+}
+
+impl CoverageBackend for GranularBackend {
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError> {
+        let result = self.backend.run(input)?;
+        if self.granularity == Granularity::Line {
+            return Ok(result);
+        }
+
+        let mut functions = self.functions.borrow_mut();
+        if functions.is_none() {
+            *functions = self.backend.functions();
+        }
+        let Some(functions) = functions.as_ref() else {
+            return Err(FuzzError::new(format!(
+                "Coverage by {} needs the functions of the target, but the backend has none",
+                self.granularity
+            )));
+        };
+        Ok(match result {
+            RunResult::Ok(coverage) => {
+                RunResult::Ok(functions.coarsen(&coverage, self.granularity))
+            }
+            crash => crash,
+        })
+    }
+
+    fn summary(&self) -> Option<String> {
+        self.backend.summary()
+    }
+
+    fn check(&self) -> Result<(), FuzzError> {
+        self.backend.check()
+    }
+
+    fn functions(&self) -> Option<Functions> {
+        self.backend.functions()
+    }
+}
@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Harness for fuzzing Rust functions in-process, like a libFuzzer fuzz target
+// (`LLVMFuzzerTestOneInput`) or a `cargo fuzz` target: the target gets the
+// input as bytes, and panics are caught and reported as crashes (with the
+// panic message and location), so that the campaign continues.
+
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use crate::error::FuzzError;
+use crate::fuzzer::Input;
+use crate::runner::{Coverage, CoverageBackend, RunResult};
+use crate::sanitizer::SanitizerReport;
+
+/// Result of running a fuzz target on one input (if it doesn't panic).
+pub enum TargetResult {
+    /// The input was processed. Contains the coverage, if the target is
+    /// instrumented (e.g. with a `Tracer`); empty otherwise.
+    Ok(Coverage),
+
+    /// The target detected a bug without panicking (e.g. an invariant doesn't
+    /// hold), with a description.
+    Crash(String),
+}
+
+/// A Rust function that is fuzzed in-process.
+pub trait FuzzTarget {
+    fn run(&mut self, data: &[u8]) -> TargetResult;
+}
+
+/// Closures can be used as fuzz targets directly.
+impl<F: FnMut(&[u8]) -> TargetResult> FuzzTarget for F {
+    fn run(&mut self, data: &[u8]) -> TargetResult {
+        self(data)
+    }
+}
+
+thread_local! {
+    /// Whether a fuzz target is running on this thread (panics are then
+    /// expected, and not printed).
+    static IN_TARGET: Cell<bool> = const { Cell::new(false) };
+
+    /// Location of the last panic in a fuzz target.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Install a panic hook that records the location of panics in fuzz targets
+/// instead of printing them. Panics outside of fuzz targets (bugs in the
+/// fuzzer itself) are still handled by the previous hook.
+fn install_panic_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if IN_TARGET.get() {
+                let location = info.location().map(|location| location.to_string());
+                PANIC_LOCATION.set(location);
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Runs a fuzz target (as `CoverageBackend`, so it can be used in any campaign).
+/// The target gets mutable access, but runners are shared, so it is kept in a
+/// `RefCell`.
+pub struct FuzzTargetRunner<T: FuzzTarget> {
+    target: RefCell<T>,
+}
+
+impl<T: FuzzTarget> FuzzTargetRunner<T> {
+    pub fn new(target: T) -> Self {
+        install_panic_hook();
+        Self {
+            target: RefCell::new(target),
+        }
+    }
+}
+
+impl<T: FuzzTarget> CoverageBackend for FuzzTargetRunner<T> {
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError> {
+        IN_TARGET.set(true);
+        // A target that panicked may have left its state inconsistent, but
+        // it is up to the target to cope with that (e.g. by not keeping state
+        // between runs).
+        let result =
+            panic::catch_unwind(AssertUnwindSafe(|| self.target.borrow_mut().run(&input.0)));
+        IN_TARGET.set(false);
+
+        Ok(match result {
+            Ok(TargetResult::Ok(coverage)) => RunResult::Ok(coverage),
+            Ok(TargetResult::Crash(message)) => RunResult::Crash(Some(report(message, None))),
+            Err(payload) => {
+                // The payload is the panic message for `panic!` (with and
+                // without format arguments), `unwrap`, indexing etc.
+                let message = match payload.downcast_ref::<&str>() {
+                    Some(message) => message.to_string(),
+                    None => match payload.downcast_ref::<String>() {
+                        Some(message) => message.clone(),
+                        None => "panic with non-string payload".to_string(),
+                    },
+                };
+                let location = PANIC_LOCATION.take();
+                RunResult::Crash(Some(report(message, location)))
+            }
+        })
+    }
+}
+
+/// Crash report of a fuzz target, in the same form as sanitizer reports (so
+/// that crashes are deduplicated by message and location).
+fn report(message: String, location: Option<String>) -> SanitizerReport {
+    SanitizerReport {
+        sanitizer: "panic".to_string(),
+        kind: message,
+        address: None,
+        frames: location.into_iter().collect(),
+    }
+}
@@ -0,0 +1,275 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Fuzzing network services: the input is sent as body of an HTTP request (or
+// as raw TCP payload) to a server that is already running. There is no
+// coverage from inside the server, so the only feedback is the response: the
+// HTTP status code is used as "coverage" (a new status code is new behaviour),
+// and server errors (5xx), connections that are reset or refused (the server
+// died), and timeouts count as crashes.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use crate::error::{Context, FuzzError};
+use crate::fuzzer::Input;
+use crate::harness::{FuzzTarget, FuzzTargetRunner};
+use crate::runner::{Coverage, CoverageBackend, RunResult};
+use crate::sanitizer::SanitizerReport;
+
+/// How the input is delivered to the server.
+pub enum Delivery {
+    /// As body of an HTTP/1.1 request with the given method and path.
+    Http { method: String, path: String },
+    /// As is, over a plain TCP connection.
+    Tcp,
+}
+
+/// Responses seen by an `HttpRunner`.
+#[derive(Default)]
+pub struct HttpStats {
+    pub requests: usize,
+    /// Count per HTTP status code.
+    pub statuses: BTreeMap<u16, usize>,
+    /// Connections that were refused or reset, and timeouts.
+    pub connection_errors: usize,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+pub struct HttpRunner {
+    /// Address of the server (`host:port`).
+    addr: String,
+    delivery: Delivery,
+    /// For connecting, and for each read and write.
+    timeout: Duration,
+    /// Runners are shared, so the statistics are kept in a `RefCell`.
+    stats: RefCell<HttpStats>,
+}
+
+impl HttpRunner {
+    pub fn new(addr: &str, delivery: Delivery) -> Self {
+        Self {
+            addr: addr.to_string(),
+            delivery,
+            timeout: Duration::from_secs(1),
+            stats: RefCell::new(HttpStats::default()),
+        }
+    }
+
+    /// Timeout for connecting, and for each read and write (default 1s).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runner for `http://host:port/path` (POST requests) or
+    /// `tcp://host:port` (raw payloads).
+    pub fn from_url(url: &str) -> Self {
+        if let Some(rest) = url.strip_prefix("http://") {
+            let (addr, path) = match rest.find('/') {
+                Some(i) => (&rest[..i], &rest[i..]),
+                None => (rest, "/"),
+            };
+            let delivery = Delivery::Http {
+                method: "POST".to_string(),
+                path: path.to_string(),
+            };
+            Self::new(addr, delivery)
+        } else if let Some(addr) = url.strip_prefix("tcp://") {
+            Self::new(addr, Delivery::Tcp)
+        } else {
+            panic!("Unsupported URL {}", url);
+        }
+    }
+
+    /// Send the input and read the whole response.
+    fn exchange(&self, input: &Input) -> io::Result<Vec<u8>> {
+        let addr = self
+            .addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or(ErrorKind::NotFound)?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        match &self.delivery {
+            Delivery::Http { method, path } => {
+                let header = format!(
+                    "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    method,
+                    path,
+                    self.addr,
+                    input.0.len()
+                );
+                stream.write_all(header.as_bytes())?;
+                stream.write_all(&input.0)?;
+            }
+            Delivery::Tcp => {
+                stream.write_all(&input.0)?;
+                stream.shutdown(Shutdown::Write)?;
+            }
+        }
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        Ok(response)
+    }
+
+    fn crash(&self, kind: String) -> RunResult {
+        RunResult::Crash(Some(SanitizerReport {
+            sanitizer: "http".to_string(),
+            kind,
+            address: None,
+            frames: vec![self.addr.clone()],
+        }))
+    }
+}
+
+impl CoverageBackend for HttpRunner {
+    /// Connection errors are crashes (the server died), not errors.
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError> {
+        let start = Instant::now();
+        let response = self.exchange(input);
+        let latency = start.elapsed();
+
+        let mut stats = self.stats.borrow_mut();
+        stats.requests += 1;
+        stats.total_latency += latency;
+        stats.max_latency = stats.max_latency.max(latency);
+
+        let response = match response {
+            Ok(response) => response,
+            Err(err) => {
+                stats.connection_errors += 1;
+                let kind = match err.kind() {
+                    ErrorKind::ConnectionRefused => "connection refused",
+                    ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => "connection reset",
+                    ErrorKind::BrokenPipe => "connection closed while sending",
+                    ErrorKind::WouldBlock | ErrorKind::TimedOut => "timeout",
+                    _ => "connection error",
+                };
+                return Ok(self.crash(kind.to_string()));
+            }
+        };
+
+        if let Delivery::Tcp = self.delivery {
+            return Ok(RunResult::Ok(Coverage::new()));
+        }
+        // Status line, e.g. `HTTP/1.1 500 Internal Server Error`.
+        let status = response
+            .split(|&b| b == b'\n')
+            .next()
+            .and_then(|line| std::str::from_utf8(line).ok())
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|status| status.parse::<u16>().ok());
+        Ok(match status {
+            None => self.crash("invalid response".to_string()),
+            Some(status) => {
+                *stats.statuses.entry(status).or_default() += 1;
+                if status >= 500 {
+                    self.crash(format!("status {}", status))
+                } else {
+                    RunResult::Ok(Coverage::from([status as usize]))
+                }
+            }
+        })
+    }
+
+    /// The server accepts connections.
+    fn check(&self) -> Result<(), FuzzError> {
+        let addr = (self.addr.to_socket_addrs().ok())
+            .and_then(|mut addrs| addrs.next())
+            .ok_or_else(|| FuzzError::new(format!("Can't resolve address {}", self.addr)))?;
+        TcpStream::connect_timeout(&addr, self.timeout)
+            .context(format!("Connecting to {}", self.addr))?;
+        Ok(())
+    }
+
+    fn summary(&self) -> Option<String> {
+        let stats = self.stats.borrow();
+        if stats.requests == 0 {
+            return None;
+        }
+        let statuses: Vec<_> = stats
+            .statuses
+            .iter()
+            .map(|(status, count)| format!("{}: {}", status, count))
+            .collect();
+        Some(format!(
+            "{} requests to {}, status codes {{{}}}, {} connection errors, latency mean {:0.3}ms max {:0.3}ms",
+            stats.requests,
+            self.addr,
+            statuses.join(", "),
+            stats.connection_errors,
+            stats.total_latency.as_secs_f64() * 1000.0 / stats.requests as f64,
+            stats.max_latency.as_secs_f64() * 1000.0
+        ))
+    }
+}
+
+/// Start a small HTTP server on a free local port, for trying out the HTTP
+/// runner: it runs the fuzz target on the request body and responds with 200
+/// (echoing the body), or with 500 if the target crashes.
+pub fn serve<T: FuzzTarget + Send + 'static>(target: T) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    std::thread::spawn(move || {
+        let runner = FuzzTargetRunner::new(target);
+        for stream in listener.incoming() {
+            // Errors of single connections don't stop the server.
+            let _ = stream.and_then(|stream| handle_demo_request(&runner, stream));
+        }
+    });
+    Ok(addr)
+}
+
+fn handle_demo_request<T: FuzzTarget>(
+    runner: &FuzzTargetRunner<T>,
+    mut stream: TcpStream,
+) -> io::Result<()> {
+    // Read up to the end of the header, then the body (Content-Length).
+    let mut request = Vec::new();
+    let mut buf = [0; 4096];
+    let header_end = loop {
+        if let Some(i) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            return Ok(());
+        }
+        request.extend(&buf[..n]);
+    };
+    let header = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+    let content_length = header
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0);
+    while request.len() < header_end + content_length {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        request.extend(&buf[..n]);
+    }
+    let body = Input(request[header_end..].to_vec());
+
+    let (status, response) = match runner.run(&body).map_err(io::Error::other)? {
+        RunResult::Ok(_) => ("200 OK", body.0),
+        RunResult::Crash(_) => ("500 Internal Server Error", Vec::new()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        response.len()
+    )?;
+    stream.write_all(&response)
+}
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+//! Greybox fuzzing library: the fuzzers, coverage backends and campaign
+//! machinery of 02-greyboxfuzzer, as reusable crate.
+//!
+//! The main types:
+//!   - [`Campaign`] pairs a [`fuzzer::Fuzzer`] with a [`CoverageBackend`],
+//!     runs fuzz cases, and records [`campaign::Statistics`] that are the same
+//!     for every fuzzer, so that techniques can be compared.
+//!   - [`CoverageBackend`] executes an input and reports its coverage or
+//!     crash: C programs with gcov ([`runner::GcovRunner`]), in-process
+//!     targets ([`runner::TargetRunner`], [`harness::FuzzTargetRunner`]), and
+//!     network services ([`http::HttpRunner`]). In-process targets can be
+//!     looked up by name in a [`target::Registry`].
+//!   - [`Schedule`] is the power schedule of the
+//!     [`fuzzer::GreyboxFuzzer`]: how inputs of the population are chosen for
+//!     mutation.
+//!   - [`dictionary::Dictionary`] holds tokens for the dictionary
+//!     mutations, e.g. derived from the terminals of a [`fuzzer::Grammar`].
+//!   - [`secondary::SecondaryQueue`] keeps near misses of the greybox fuzzer
+//!     (rare paths, slow inputs) that are mutated at a low rate.
+//!   - [`Corpus`] is a libFuzzer/cargo-fuzz corpus directory to read seeds
+//!     from and write populations and crashes to.
+//!   - [`timing::Timings`] are the execution times of the fuzz cases
+//!     (percentiles, slowest inputs and outliers) in the statistics.
+//!   - [`store::CoverageStore`] keeps the best input per coverage signature
+//!     on disk, and prunes dominated entries, for long runs.
+//!   - [`snapshot::Snapshot`] saves the population of the greybox fuzzer with
+//!     its coverage hashes, to resume a campaign.
+//!   - [`error::FuzzError`] is the error of the backends and campaigns:
+//!     nothing panics on errors of the environment.
+//!   - [`dryrun::run`] checks the backend and runs every seed once before
+//!     fuzzing, to report setup errors, crashing and slow seeds.
+//!   - [`granularity::GranularBackend`] reports coverage by function or file
+//!     instead of by line.
+//!   - [`files::Slots`] fuzz several files per execution, delivered by
+//!     [`runner::GcovRunner::with_files`].
+//!   - [`postprocess::PostprocessBackend`] encodes each input (base64, gzip,
+//!     checksums, ...) before the target gets it.
+//!   - [`stability::check`] finds locations whose coverage is not
+//!     deterministic, and [`stability::MaskedBackend`] ignores them.
+//!   - [`bytes::Bytes`] is the byte string of the inputs ([`fuzzer::Input`]):
+//!     printed lossily or as hexdump, as invalid utf8 is common.
+//!   - [`cli`] parses the command-line options shared by the example
+//!     binaries (`--seed`, `--iterations`, `--target`, `--out-dir`,
+//!     `--quiet`).
+//!
+//! Everything is deterministic given the seed of the [`rng::Rng`] (and a
+//! deterministic backend).
+//!
+//! ```no_run
+//! use greybox::fuzzer::{GreyboxFuzzer, Input};
+//! use greybox::oracle::ExitStatusOracle;
+//! use greybox::rng::Rng;
+//! use greybox::runner::GcovRunner;
+//! use greybox::{Campaign, Schedule};
+//!
+//! let backend = GcovRunner::new("crashme", Box::new(ExitStatusOracle)).unwrap();
+//! let mut fuzzer = GreyboxFuzzer::new(vec![Input::from_str("good")])
+//!     .with_schedule(Schedule::Exponential { exponent: 5.0 });
+//! let mut campaign = Campaign::new(&mut fuzzer, &backend);
+//! campaign.run(&mut Rng::seeded(42), 10000).unwrap();
+//! println!("{} crashes", campaign.stats.crashes.len());
+//! ```
+
+// `Rng` and `Bytes` are shared with the other crates of the repository, where
+// these methods were never public API.
+#![allow(clippy::should_implement_trait, clippy::new_without_default)]
+
+pub mod bytes;
+pub mod campaign;
+pub mod cli;
+pub mod cmin;
+pub mod corpus;
+pub mod dictionary;
+pub mod dryrun;
+pub mod error;
+pub mod experiment;
+pub mod files;
+pub mod fuzzer;
+pub mod granularity;
+pub mod harness;
+pub mod http;
+pub mod oracle;
+pub mod postprocess;
+pub mod replay;
+pub mod rng;
+pub mod runner;
+pub mod sanitizer;
+pub mod schedule;
+pub mod secondary;
+pub mod snapshot;
+pub mod stability;
+pub mod store;
+pub mod target;
+pub mod timing;
+
+pub use campaign::Campaign;
+pub use corpus::Corpus;
+pub use runner::CoverageBackend;
+pub use schedule::Schedule;
@@ -0,0 +1,106 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Oracles decide whether an execution of the program under test failed.
+// Looking only at the exit code is often not enough (or even wrong): A crash
+// shows up as a signal and not as an exit code, sanitizers report errors on
+// stderr, and some bugs only manifest as wrong output.
+
+use crate::fuzzer::Input;
+
+/// Everything that was observed about one execution of the program.
+pub struct Execution<'a> {
+    /// Exit code, if the program exited normally.
+    pub exit_code: Option<i32>,
+
+    /// Number of the signal that killed the program, if any (on Windows: the
+    /// exception code of a crash, see `GcovRunner`).
+    pub signal: Option<i32>,
+
+    pub stdout: &'a [u8],
+    pub stderr: &'a [u8],
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Crash,
+}
+
+/// Classifies the outcome of an execution.
+pub trait Oracle {
+    fn classify(&self, input: &Input, execution: &Execution) -> Verdict;
+}
+
+fn verdict(crash: bool) -> Verdict {
+    if crash {
+        Verdict::Crash
+    } else {
+        Verdict::Pass
+    }
+}
+
+/// Any exit code other than 0 and any signal count as crash (default).
+pub struct ExitStatusOracle;
+
+impl Oracle for ExitStatusOracle {
+    fn classify(&self, _input: &Input, execution: &Execution) -> Verdict {
+        verdict(execution.exit_code != Some(0))
+    }
+}
+
+/// Only getting killed by a signal (e.g. SIGSEGV, or SIGABRT from a failed
+/// assertion) counts as crash; exit codes are regular program behaviour.
+pub struct SignalOracle;
+
+impl Oracle for SignalOracle {
+    fn classify(&self, _input: &Input, execution: &Execution) -> Verdict {
+        verdict(execution.signal.is_some())
+    }
+}
+
+/// Crash if stderr contains the given text, e.g. `AddressSanitizer` for
+/// programs compiled with `-fsanitize=address`.
+pub struct StderrOracle {
+    pub pattern: String,
+}
+
+impl Oracle for StderrOracle {
+    fn classify(&self, _input: &Input, execution: &Execution) -> Verdict {
+        let pattern = self.pattern.as_bytes();
+        verdict(
+            !pattern.is_empty()
+                && execution
+                    .stderr
+                    .windows(pattern.len())
+                    .any(|window| window == pattern),
+        )
+    }
+}
+
+/// Crash if an invariant about input and output doesn't hold, e.g. that the
+/// output of cgi_decode is never longer than its input.
+pub struct InvariantOracle<F: Fn(&Input, &Execution) -> bool> {
+    pub invariant: F,
+}
+
+impl<F: Fn(&Input, &Execution) -> bool> Oracle for InvariantOracle<F> {
+    fn classify(&self, input: &Input, execution: &Execution) -> Verdict {
+        verdict(!(self.invariant)(input, execution))
+    }
+}
+
+/// Crash if any of the given oracles says so.
+pub struct AnyOracle(pub Vec<Box<dyn Oracle>>);
+
+impl Oracle for AnyOracle {
+    fn classify(&self, input: &Input, execution: &Execution) -> Verdict {
+        verdict(
+            self.0
+                .iter()
+                .any(|oracle| oracle.classify(input, execution) == Verdict::Crash),
+        )
+    }
+}
@@ -0,0 +1,192 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Postprocessors transform each generated input right before it is run,
+// e.g. because the target decodes its input first (base64, url-encoding,
+// gzip), or rejects inputs without the right magic bytes or checksum. Without
+// them, nearly all inputs of the fuzzers die in the decoding layer, and the
+// interesting code behind it is never reached.
+//
+// The fuzzers keep working on the raw inputs (their population, crashes and
+// corpus files are not encoded); only the target sees the postprocessed ones.
+// So mutations still happen on the structure that matters (and a checksum is
+// always correct, no matter what the mutations did).
+
+use crate::error::FuzzError;
+use crate::fuzzer::Input;
+use crate::granularity::Functions;
+use crate::runner::{CoverageBackend, RunResult};
+
+/// Transforms an input before it is run.
+pub trait Postprocessor {
+    fn process(&self, data: Vec<u8>) -> Vec<u8>;
+}
+
+/// Base64 (RFC 4648, standard alphabet with padding).
+pub struct Base64;
+
+impl Postprocessor for Base64 {
+    fn process(&self, data: Vec<u8>) -> Vec<u8> {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut res = Vec::with_capacity(data.len().div_ceil(3) * 4);
+        for chunk in data.chunks(3) {
+            let b = [
+                chunk[0],
+                *chunk.get(1).unwrap_or(&0),
+                *chunk.get(2).unwrap_or(&0),
+            ];
+            let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    res.push(ALPHABET[(n >> (18 - 6 * i)) & 0x3f]);
+                } else {
+                    res.push(b'=');
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Percent-encoding of everything except the unreserved characters of URLs
+/// (`A-Z a-z 0-9 - . _ ~`, RFC 3986).
+pub struct UrlEncode;
+
+impl Postprocessor for UrlEncode {
+    fn process(&self, data: Vec<u8>) -> Vec<u8> {
+        let mut res = Vec::with_capacity(data.len());
+        for b in data {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                res.push(b);
+            } else {
+                res.extend(format!("%{:02X}", b).bytes());
+            }
+        }
+        res
+    }
+}
+
+/// gzip (RFC 1952). The data is not actually compressed, but stored in
+/// uncompressed deflate blocks (RFC 1951): any gzip decoder accepts it, and
+/// it's fast and simple.
+pub struct Gzip;
+
+impl Postprocessor for Gzip {
+    fn process(&self, data: Vec<u8>) -> Vec<u8> {
+        // Header: magic, deflate, no flags, no modification time, no extra
+        // flags, unknown OS.
+        let mut res = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 255];
+        let mut blocks = data.chunks(0xffff).peekable();
+        if blocks.peek().is_none() {
+            // An empty final stored block.
+            res.extend([1, 0, 0, 0xff, 0xff]);
+        }
+        while let Some(block) = blocks.next() {
+            let last = blocks.peek().is_none();
+            let len = block.len() as u16;
+            res.push(last as u8);
+            res.extend(len.to_le_bytes());
+            res.extend((!len).to_le_bytes());
+            res.extend(block);
+        }
+        res.extend(crc32(&data).to_le_bytes());
+        res.extend((data.len() as u32).to_le_bytes());
+        res
+    }
+}
+
+/// Prepend fixed bytes, e.g. the magic number of a file format.
+pub struct Prepend(pub Vec<u8>);
+
+impl Postprocessor for Prepend {
+    fn process(&self, data: Vec<u8>) -> Vec<u8> {
+        let mut res = self.0.clone();
+        res.extend(data);
+        res
+    }
+}
+
+/// Write the CRC-32 of everything after the 4 bytes at `offset` to these 4
+/// bytes (overwriting them; shorter inputs are padded with null bytes).
+pub struct Crc32 {
+    pub offset: usize,
+    pub big_endian: bool,
+}
+
+impl Postprocessor for Crc32 {
+    fn process(&self, mut data: Vec<u8>) -> Vec<u8> {
+        let end = self.offset + 4;
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        let crc = crc32(&data[end..]);
+        data[self.offset..end].copy_from_slice(&if self.big_endian {
+            crc.to_be_bytes()
+        } else {
+            crc.to_le_bytes()
+        });
+        data
+    }
+}
+
+/// Apply the postprocessors one after the other.
+pub struct Chain(pub Vec<Box<dyn Postprocessor>>);
+
+impl Postprocessor for Chain {
+    fn process(&self, data: Vec<u8>) -> Vec<u8> {
+        self.0
+            .iter()
+            .fold(data, |data, postprocessor| postprocessor.process(data))
+    }
+}
+
+/// CRC-32 (IEEE 802.3, as in gzip, zip and PNG).
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Backend that postprocesses each input before another backend runs it.
+pub struct PostprocessBackend {
+    pub backend: Box<dyn CoverageBackend>,
+    pub postprocessor: Box<dyn Postprocessor>,
+}
+
+impl PostprocessBackend {
+    pub fn new(backend: Box<dyn CoverageBackend>, postprocessor: Box<dyn Postprocessor>) -> Self {
+        Self {
+            backend,
+            postprocessor,
+        }
+    }
+}
+
+impl CoverageBackend for PostprocessBackend {
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError> {
+        self.backend
+            .run(&Input(self.postprocessor.process(input.0.clone())))
+    }
+
+    fn summary(&self) -> Option<String> {
+        self.backend.summary()
+    }
+
+    fn check(&self) -> Result<(), FuzzError> {
+        self.backend.check()
+    }
+
+    fn functions(&self) -> Option<Functions> {
+        self.backend.functions()
+    }
+}
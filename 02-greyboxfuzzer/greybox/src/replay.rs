@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Re-run the inputs of a corpus or crash directory, e.g. after the target was
+// rebuilt or when getting findings from someone else: which inputs still
+// crash, and with which coverage do the others run?
+//
+// Files named `crash-*` (as written by libFuzzer, and by `--crashes-out`) are
+// recorded crashes, so they are expected to crash again. Each input is run
+// twice, to also detect results that are not deterministic (e.g. depending on
+// timing or on state left behind by earlier runs); such findings can't be
+// reproduced reliably.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{Context, FuzzError};
+use crate::fuzzer::Input;
+use crate::runner::{CoverageBackend, RunResult};
+
+/// Result of replaying a single input.
+pub struct Replayed {
+    /// File name of the input.
+    pub name: String,
+    pub result: RunResult,
+
+    /// The input is a recorded crash (file name starts with `crash-`).
+    pub expect_crash: bool,
+
+    /// The second run gave a different result.
+    pub flaky: bool,
+}
+
+impl Replayed {
+    /// The input behaves as recorded: it's deterministic, and crashes if it's
+    /// a recorded crash.
+    pub fn reproduces(&self) -> bool {
+        !self.flaky && (!self.expect_crash || matches!(self.result, RunResult::Crash(_)))
+    }
+}
+
+/// Run all inputs in directory `dir` (in order of the file names).
+pub fn replay(runner: &dyn CoverageBackend, dir: &Path) -> Result<Vec<Replayed>, FuzzError> {
+    let context = format!("Reading {}", dir.display());
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).context(&context)? {
+        let path = entry.context(&context)?.path();
+        if path.is_file() {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            let input = Input(fs::read(&path).context(format!("Reading {}", path.display()))?);
+            files.push((name, input));
+        }
+    }
+    files.sort();
+
+    let mut res = Vec::new();
+    for (name, input) in files {
+        let result = runner.run(&input)?;
+        let flaky = runner.run(&input)? != result;
+        res.push(Replayed {
+            expect_crash: name.starts_with("crash-"),
+            name,
+            result,
+            flaky,
+        });
+    }
+    Ok(res)
+}
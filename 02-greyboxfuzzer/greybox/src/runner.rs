@@ -0,0 +1,501 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::corpus;
+use crate::error::{Context, FuzzError};
+use crate::files::Slots;
+use crate::fuzzer::Input;
+use crate::granularity::{Function, Functions};
+use crate::oracle::{Execution, Oracle, Verdict};
+use crate::sanitizer::{self, SanitizerReport};
+use crate::target::{TestTarget, Tracer};
+
+/// Location is a linenumber
+type Location = usize;
+
+/// Statement coverage.
+pub type Coverage = BTreeSet<Location>;
+
+/// Newtype wrapper for hash of coverage information (for strong typing).
+///
+/// The hash is 128 bits of the SHA-1 of a canonical encoding of the coverage
+/// (the number of locations, then the locations in ascending order, each as
+/// little-endian u64), prefixed with a fixed seed. Unlike `DefaultHasher`,
+/// whose algorithm and keys are unspecified and may change between Rust
+/// releases, this gives the same hash in every run and on every machine, so
+/// coverage hashes can be stored and compared across campaigns.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
+pub struct CoverageH(u128);
+impl CoverageH {
+    /// Seed of the hash. Changing it invalidates all stored hashes.
+    const SEED: &'static [u8] = b"fuzzingbook-notes coverage v1\0";
+
+    pub fn new(coverage: &Coverage) -> Self {
+        let mut data = Self::SEED.to_vec();
+        data.extend((coverage.len() as u64).to_le_bytes());
+        // A BTreeSet iterates in ascending order, independent of the order in
+        // which the locations were inserted.
+        for location in coverage.iter() {
+            data.extend((*location as u64).to_le_bytes());
+        }
+        let digest = corpus::sha1(&data);
+        Self(u128::from_be_bytes(digest[..16].try_into().unwrap()))
+    }
+
+    /// Parse the hex form written by `Display` (e.g. read back from a
+    /// snapshot or a coverage store).
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != 32 {
+            return None;
+        }
+        u128::from_str_radix(hex, 16).ok().map(Self)
+    }
+}
+
+impl std::fmt::Display for CoverageH {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+/// Results of the execution of an external program (essentially an Option).
+pub enum RunResult {
+    /// Program exits in any other way not due to crash/signal.
+    Ok(Coverage),
+    /// Program crashes. Contains the sanitizer report, if there is one.
+    Crash(Option<SanitizerReport>),
+}
+
+impl RunResult {
+    /// Signature for deduplicating crashes (see `SanitizerReport::signature`),
+    /// `None` if the program didn't crash. Without a sanitizer report, all
+    /// crashes end up in the same bucket.
+    pub fn crash_signature(&self) -> Option<String> {
+        match self {
+            RunResult::Ok(_) => None,
+            RunResult::Crash(Some(report)) => Some(report.signature()),
+            RunResult::Crash(None) => Some("crash without sanitizer report".to_string()),
+        }
+    }
+}
+
+/// Common interface of everything that can execute an input and observe its
+/// coverage (a C program with gcov, an in-process target, a network
+/// service, ...).
+///
+/// `run` takes `&self`, so that one backend can be shared by several
+/// campaigns; backends that keep state (counters, statistics) use interior
+/// mutability. Running the same input twice should give the same result;
+/// campaigns check this during calibration, but don't rely on it.
+/// Locations only have to be consistent within one backend: they are
+/// compared with each other, never interpreted.
+pub trait CoverageBackend {
+    /// Execute the input: its coverage, or whether (and how) it crashed.
+    /// Errors are failures of the environment (e.g. a process that couldn't
+    /// be started), not of the target.
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError>;
+
+    /// Summary of what the runner observed over all runs (printed at the end
+    /// of the campaigns), if it keeps any statistics.
+    fn summary(&self) -> Option<String> {
+        None
+    }
+
+    /// Check that inputs can be run at all (the program and tools exist, the
+    /// server is reachable, ...). Done once before fuzzing (see `dryrun`), so
+    /// that setup errors are reported before the campaigns start.
+    fn check(&self) -> Result<(), FuzzError> {
+        Ok(())
+    }
+
+    /// The functions of the target, if the backend knows them, for coverage
+    /// by function or file (see `granularity`).
+    fn functions(&self) -> Option<Functions> {
+        None
+    }
+}
+
+/// Runs a C program compiled with gcc/gcov instrumentation, passing the input
+/// as its single commandline argument (or as files, see `with_files`), and
+/// traces the statement coverage.
+pub struct GcovRunner {
+    /// Name of the program; the source code is expected at `<program>.c`.
+    program: String,
+
+    /// Decides whether an execution crashed.
+    oracle: Box<dyn Oracle>,
+
+    /// Whether the program is compiled with ASAN and UBSAN.
+    sanitizers: bool,
+
+    /// Functions of the program, from the gcov output of the last run.
+    functions: RefCell<Functions>,
+
+    /// Files to write the slots of the input to (instead of passing it as
+    /// argument).
+    files: Option<Slots>,
+
+    /// Executions that take longer are killed (see `with_timeout`).
+    timeout: Option<Duration>,
+}
+
+/// Number of test runs so far; used for naming temporary directories.
+static TESTRUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl GcovRunner {
+    /// Compile the C program. This is done once here and not on each run,
+    /// since the source code doesn't change between fuzz cases.
+    /// The oracle decides whether an execution crashed.
+    pub fn new(program: &str, oracle: Box<dyn Oracle>) -> Result<Self, FuzzError> {
+        Self::build(program, oracle, false)
+    }
+
+    /// Like `new`, but additionally compile the program with ASAN and UBSAN,
+    /// so that memory errors and undefined behaviour lead to a crash (with a
+    /// report on stderr).
+    pub fn with_sanitizers(program: &str, oracle: Box<dyn Oracle>) -> Result<Self, FuzzError> {
+        Self::build(program, oracle, true)
+    }
+
+    fn build(program: &str, oracle: Box<dyn Oracle>, sanitizers: bool) -> Result<Self, FuzzError> {
+        let source = format!("{}.c", program);
+        if !Path::new(&source).is_file() {
+            return Err(FuzzError::new(format!("Source file {} not found", source)));
+        }
+
+        let mut cmd = process::Command::new("gcc");
+        cmd.args(["-Wall", "-g", "--coverage", "-o", program]);
+        if sanitizers {
+            cmd.args(sanitizer::CFLAGS);
+        }
+        let output = cmd
+            .arg(&source)
+            .output()
+            .context(format!("Running gcc to compile {}", source))?;
+        if !output.status.success() {
+            return Err(FuzzError::new(format!(
+                "Compiling {} failed: {}",
+                source,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(Self {
+            program: program.to_string(),
+            oracle,
+            sanitizers,
+            functions: RefCell::new(Functions::new()),
+            files: None,
+            timeout: None,
+        })
+    }
+
+    /// Deliver the input as files: each slot is written to its file in the
+    /// temporary directory of the fuzz case, and the program gets the file
+    /// names as arguments (in the order of the slots). Files can contain null
+    /// bytes, arguments can't.
+    pub fn with_files(mut self, slots: Slots) -> Self {
+        self.files = Some(slots);
+        self
+    }
+
+    /// Kill the program once it runs longer than the timeout. The oracles see
+    /// the kill (SIGKILL on Unix) like a crash, so hangs are reported as
+    /// crashes, like the timeouts of the network runner (see `http`).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Check whether gcc and gcov can be executed on this system at all.
+    pub fn is_available() -> bool {
+        ["gcc", "gcov"].iter().all(|tool| {
+            process::Command::new(tool)
+                .arg("--version")
+                .output()
+                .is_ok_and(|out| out.status.success())
+        })
+    }
+}
+
+impl GcovRunner {
+    /// Run the program and gcov in the temporary directory `root`.
+    fn run_in(&self, root: &str, input: &Input) -> Result<RunResult, FuzzError> {
+        let program = &self.program;
+
+        // Copy over the relevant data resulting from the initial program compilation.
+        // This is needed for later gathering code coverage with `gcov`.
+        for file in [
+            format!("{}.c", program),
+            executable(program),
+            format!("{}.gcno", program),
+        ] {
+            fs::copy(&file, format!("{}/{}", root, file))
+                .context(format!("Copying {} to {}", file, root))?;
+        }
+
+        let args = match &self.files {
+            Some(slots) => {
+                slots.write(input, Path::new(root))?;
+                slots.names.iter().map(OsString::from).collect()
+            }
+            // C strings end at the first null byte, so that's all the program
+            // gets to see of the input anyway (and a commandline argument can't
+            // contain null bytes).
+            None => vec![argument(input.0.split(|&b| b == 0).next().unwrap())],
+        };
+
+        // Run the program.
+        let path = fs::canonicalize(executable(program)).context(format!("Finding {}", program))?;
+        let mut cmd = process::Command::new(path);
+        cmd.current_dir(root)
+            .args(args)
+            // Instruct gcov to write the collected information into the
+            // temporary root of this fuzz case (see greyboxfuzzer5).
+            .env("GCOV_PREFIX", ".")
+            .env("GCOV_PREFIX_STRIP", "20")
+            .envs(if self.sanitizers {
+                sanitizer::ENV.to_vec()
+            } else {
+                Vec::new()
+            });
+        let output =
+            output_with_timeout(&mut cmd, self.timeout).context(format!("Running {}", program))?;
+
+        // Generate coverage data using gcov. `-b` adds the branch
+        // probabilities, and with them a line for each function (before its
+        // first line).
+        process::Command::new("gcov")
+            .current_dir(root)
+            .arg("-b")
+            .arg(format!("{}.c", program))
+            .output()
+            .context(format!("Running gcov on {}", program))?;
+
+        // "Parse" (process) gcov coverage file.
+        let mut coverage = BTreeSet::new();
+        let mut functions = Functions::new();
+        let mut function = None;
+        let gcov = fs::read_to_string(format!("{}/{}.c.gcov", root, program))
+            .context(format!("Reading the gcov output of {}", program))?;
+        for line in gcov.lines() {
+            // `function cgi_decode called 1 returned 100% blocks executed 92%`,
+            // followed by the first line of the function.
+            if let Some(rest) = line.strip_prefix("function ") {
+                function = rest.split(' ').next().map(|name| name.to_string());
+                continue;
+            }
+            // `branch  0 taken 80%`, `call    0 returned 100%`.
+            if line.starts_with("branch ") || line.starts_with("call ") {
+                continue;
+            }
+            let elems = line.split(':').collect::<Vec<_>>();
+            let (Some(covered), Some(Ok(line_number))) = (
+                elems.first().map(|s| s.trim()),
+                elems.get(1).map(|s| s.trim().parse::<usize>()),
+            ) else {
+                return Err(FuzzError::new(format!(
+                    "Invalid gcov output for {}: {}",
+                    program, line
+                )));
+            };
+            if let Some(name) = function.take() {
+                functions.insert(Function {
+                    name,
+                    file: format!("{}.c", program),
+                    first_line: line_number,
+                });
+            }
+            if covered.starts_with("-") || covered.starts_with("#") {
+                continue;
+            }
+            coverage.insert(line_number);
+        }
+
+        *self.functions.borrow_mut() = functions;
+
+        let (exit_code, signal) = exit_status(output.status);
+        let execution = Execution {
+            exit_code,
+            signal,
+            stdout: &output.stdout,
+            stderr: &output.stderr,
+        };
+        Ok(match self.oracle.classify(input, &execution) {
+            Verdict::Pass => RunResult::Ok(coverage),
+            Verdict::Crash => RunResult::Crash(SanitizerReport::parse(&output.stderr)),
+        })
+    }
+}
+
+/// Run the command and collect its output like `Command::output`, but kill
+/// the process once it runs longer than the timeout (if there is one).
+fn output_with_timeout(
+    cmd: &mut process::Command,
+    timeout: Option<Duration>,
+) -> io::Result<process::Output> {
+    let Some(timeout) = timeout else {
+        return cmd.output();
+    };
+    let mut child = cmd
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::piped())
+        .stderr(process::Stdio::piped())
+        .spawn()?;
+
+    // Read both pipes while waiting, so that a program with a lot of output
+    // doesn't block on a full pipe.
+    let read_all = |mut pipe: Box<dyn Read + Send>| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            pipe.read_to_end(&mut buf).map(|_| buf)
+        })
+    };
+    let stdout = read_all(Box::new(child.stdout.take().unwrap()));
+    let stderr = read_all(Box::new(child.stderr.take().unwrap()));
+
+    // Poll often at first (most runs take a few milliseconds), and less often
+    // the longer the program runs.
+    let deadline = Instant::now() + timeout;
+    let mut poll = Duration::from_micros(20);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            break child.wait()?;
+        }
+        thread::sleep(poll);
+        poll = (poll * 2).min(Duration::from_millis(10));
+    };
+    Ok(process::Output {
+        status,
+        stdout: stdout.join().unwrap()?,
+        stderr: stderr.join().unwrap()?,
+    })
+}
+
+/// File name of the compiled program (gcc appends `.exe` on Windows).
+fn executable(program: &str) -> String {
+    format!("{}{}", program, std::env::consts::EXE_SUFFIX)
+}
+
+/// The input as commandline argument. Unix passes arbitrary bytes.
+#[cfg(unix)]
+fn argument(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::OsStr::from_bytes(bytes).to_owned()
+}
+
+/// The input as commandline argument. Elsewhere (Windows) arguments are
+/// strings, so invalid UTF-8 is replaced by U+FFFD.
+#[cfg(not(unix))]
+fn argument(bytes: &[u8]) -> OsString {
+    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Exit code and signal (see `Execution`).
+#[cfg(unix)]
+fn exit_status(status: process::ExitStatus) -> (Option<i32>, Option<i32>) {
+    use std::os::unix::process::ExitStatusExt;
+    (status.code(), status.signal())
+}
+
+/// Exit code and signal (see `Execution`). There are no signals on Windows: a
+/// crashing program exits with the code of the exception instead, an NTSTATUS
+/// with severity "error" (the two highest bits set), e.g. 0xC0000005 for an
+/// access violation. That code is reported as signal, so that the oracles
+/// treat it like a crash on Unix.
+#[cfg(not(unix))]
+fn exit_status(status: process::ExitStatus) -> (Option<i32>, Option<i32>) {
+    match status.code() {
+        Some(code) if cfg!(windows) && (code as u32) >> 30 == 0b11 => (None, Some(code)),
+        code => (code, None),
+    }
+}
+
+impl CoverageBackend for GcovRunner {
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError> {
+        // Create new temporary directory for running without conflicts. The
+        // name is derived from a global counter (and not from the PRNG), so
+        // that running the target doesn't influence the random choices of the
+        // fuzzer.
+        let root = format!(
+            "testrun-{}-{}",
+            process::id(),
+            TESTRUN_COUNTER.fetch_add(1, Ordering::Relaxed),
+        );
+
+        // Create a temporary directory that acts as root for this fuzz case execution.
+        fs::create_dir(&root).context(format!("Creating {}", root))?;
+        let result = self.run_in(&root, input);
+        // Cleanup compiled and generated files (also after errors).
+        fs::remove_dir_all(&root).context(format!("Removing {}", root))?;
+        result
+    }
+
+    /// gcov can be run, and running the program on an empty input gives
+    /// coverage.
+    fn check(&self) -> Result<(), FuzzError> {
+        process::Command::new("gcov")
+            .arg("--version")
+            .output()
+            .context("Running gcov (needed for the coverage)")?;
+        self.run(&Input(Vec::new())).map(|_| ())
+    }
+
+    /// The functions listed by gcov in the last run (`None` before the first
+    /// run).
+    fn functions(&self) -> Option<Functions> {
+        let functions = self.functions.borrow();
+        (!functions.is_empty()).then(|| functions.clone())
+    }
+}
+
+/// Runs a built-in test target in-process (no compiler or external program
+/// needed) and traces the coverage of its manual instrumentation points.
+pub struct TargetRunner<T: TestTarget> {
+    target: T,
+    oracle: Box<dyn Oracle>,
+}
+
+impl<T: TestTarget> TargetRunner<T> {
+    pub fn new(target: T, oracle: Box<dyn Oracle>) -> Self {
+        Self { target, oracle }
+    }
+}
+
+impl<T: TestTarget> CoverageBackend for TargetRunner<T> {
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError> {
+        let mut tracer = Tracer::default();
+        let success = self.target.execute(&input.0, &mut tracer);
+
+        // The built-in targets behave like a C program that exits with 0 on
+        // success and 1 otherwise, and produces no output.
+        let execution = Execution {
+            exit_code: Some(if success { 0 } else { 1 }),
+            signal: None,
+            stdout: &[],
+            stderr: &[],
+        };
+        Ok(match self.oracle.classify(input, &execution) {
+            Verdict::Pass => RunResult::Ok(tracer.coverage),
+            Verdict::Crash => RunResult::Crash(None),
+        })
+    }
+}
@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Support for C programs compiled with AddressSanitizer (ASAN) and
+// UndefinedBehaviorSanitizer (UBSAN): these detect memory errors and undefined
+// behaviour that would otherwise go unnoticed (e.g. reading slightly out of
+// bounds usually doesn't crash), and print a report to stderr.
+// https://github.com/google/sanitizers/wiki/AddressSanitizer
+
+/// Compiler flags for building a program with sanitizers. Errors abort the
+/// program instead of only printing a report (`-fno-sanitize-recover`).
+pub const CFLAGS: [&str; 2] = ["-fsanitize=address,undefined", "-fno-sanitize-recover=all"];
+
+/// Runtime options (environment variables) of the sanitizers.
+pub const ENV: [(&str, &str); 2] = [
+    // Leak detection would make every program that doesn't free its memory
+    // before exit "crash" (e.g. cgi_decode).
+    ("ASAN_OPTIONS", "detect_leaks=0:abort_on_error=1"),
+    ("UBSAN_OPTIONS", "print_stacktrace=1:abort_on_error=1"),
+];
+
+/// Number of stack frames that are kept in the report.
+const MAX_FRAMES: usize = 3;
+
+/// The relevant parts of a sanitizer report.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SanitizerReport {
+    /// E.g. `AddressSanitizer` or `UndefinedBehaviorSanitizer` (or `panic`
+    /// for Rust fuzz targets, see `harness`).
+    pub sanitizer: String,
+
+    /// Type of the error, e.g. `heap-buffer-overflow` or
+    /// `signed integer overflow`.
+    pub kind: String,
+
+    /// Accessed address (only for ASAN memory errors).
+    pub address: Option<u64>,
+
+    /// Top stack frames (function and source location), innermost first.
+    pub frames: Vec<String>,
+}
+
+impl SanitizerReport {
+    /// Extract the report from the stderr output of a program. Returns `None`
+    /// if there is no sanitizer report.
+    ///
+    /// ASAN reports look like this:
+    ///
+    /// ```text
+    /// ==9013==ERROR: AddressSanitizer: heap-buffer-overflow on address 0x602000000020 at pc ...
+    /// WRITE of size 4 at 0x602000000020 thread T0
+    ///     #0 0x55d18823c284 in main /tmp/t.c:2
+    ///     #1 0x7fad66045249  (/lib/x86_64-linux-gnu/libc.so.6+0x27249)
+    /// ```
+    ///
+    /// UBSAN reports (with `print_stacktrace=1`) like this:
+    ///
+    /// ```text
+    /// u.c:1:49: runtime error: signed integer overflow: 2147483647 + 1 cannot be represented in type 'int'
+    ///     #0 0x5643647081b5 in main /tmp/u.c:1
+    /// ```
+    pub fn parse(stderr: &[u8]) -> Option<Self> {
+        let stderr = String::from_utf8_lossy(stderr);
+        let mut lines = stderr.lines();
+
+        let mut report = None;
+        for line in lines.by_ref() {
+            if let Some((_, rest)) = line.split_once("ERROR: AddressSanitizer: ") {
+                let (kind, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                let address = rest
+                    .strip_prefix("on address 0x")
+                    .and_then(|rest| rest.split(' ').next())
+                    .and_then(|address| u64::from_str_radix(address, 16).ok());
+                report = Some(Self {
+                    sanitizer: "AddressSanitizer".to_string(),
+                    kind: kind.to_string(),
+                    address,
+                    frames: Vec::new(),
+                });
+                break;
+            }
+            if let Some((_, rest)) = line.split_once(": runtime error: ") {
+                let kind = rest.split(':').next().unwrap();
+                report = Some(Self {
+                    sanitizer: "UndefinedBehaviorSanitizer".to_string(),
+                    kind: kind.to_string(),
+                    address: None,
+                    frames: Vec::new(),
+                });
+                break;
+            }
+        }
+        let mut report = report?;
+
+        // The first stack trace after the error belongs to it (ASAN may print
+        // further ones, e.g. where the memory was allocated).
+        for line in lines {
+            let line = line.trim();
+            if !line.starts_with('#') {
+                if report.frames.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            // `#0 0x55d18823c284 in main /tmp/t.c:2`; frames without symbol
+            // information (no ` in `) are skipped, as are frames inside the
+            // sanitizer runtime (e.g. the interceptor of `puts`), which only
+            // tell what kind of access happened and not where.
+            if let Some((_, frame)) = line.split_once(" in ") {
+                let runtime =
+                    frame.starts_with("__interceptor_") || frame.contains("/libsanitizer/");
+                if !runtime && report.frames.len() < MAX_FRAMES {
+                    report.frames.push(frame.to_string());
+                }
+            }
+        }
+
+        Some(report)
+    }
+
+    /// Signature for deduplicating crashes: crashes with the same kind of
+    /// error at the same location are most likely the same bug.
+    pub fn signature(&self) -> String {
+        format!(
+            "{}: {} at {}",
+            self.sanitizer,
+            self.kind,
+            self.frames.join(" < ")
+        )
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Power schedules: how much energy (the probability of being chosen for
+// mutation) an input of the population gets, depending on how often its path
+// was exercised (see 02-greyboxfuzzer/greyboxfuzzer4 and greyboxfuzzer5).
+
+/// Power schedule of the greybox fuzzer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Schedule {
+    /// All inputs get the same energy.
+    Uniform,
+
+    /// Inputs exercising rarely seen paths get more energy: the energy is
+    /// `(1 / count) ^ exponent`, where `count` is the number of times the
+    /// path was exercised (as implemented in AFL).
+    Exponential { exponent: f64 },
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule::Exponential { exponent: 5.0 }
+    }
+}
+
+impl Schedule {
+    /// Energy of an input whose path was exercised `count` (at least 1)
+    /// times. Only the ratios between the energies of the inputs matter.
+    pub fn energy(&self, count: usize) -> f64 {
+        match self {
+            Schedule::Uniform => 1.0,
+            Schedule::Exponential { exponent } => (1.0 / (count as f64)).powf(*exponent),
+        }
+    }
+}
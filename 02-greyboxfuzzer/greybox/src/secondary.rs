@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Secondary queue for inputs that are interesting, but don't have new
+// coverage: inputs exercising a path that is still rare (seen only once or
+// twice before), and inputs that run much longer than usual. They don't get
+// into the population of the greybox fuzzer (the population only keeps one
+// input per path), but are near misses worth mutating now and then: a rare
+// path is barely explored yet, and a slow execution often means a loop or
+// some other work that depends on the input. Modern fuzzers keep such inputs
+// in a separate queue with a lower priority, too.
+//
+// The queue is bounded (the oldest inputs are dropped first), and inputs are
+// chosen from it only at a low rate, uniformly. Which inputs are slow depends
+// on the measured execution times, so with slow inputs enabled a campaign is
+// no longer deterministic given the seed.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::fuzzer::Input;
+use crate::rng::Rng;
+
+/// Why an input was added to the secondary queue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reason {
+    RarePath,
+    Slow,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SecondaryStats {
+    /// Inputs added because of a rare path or a slow execution.
+    pub rare: usize,
+    pub slow: usize,
+    /// Number of times an input of the queue was chosen for mutation.
+    pub chosen: usize,
+}
+
+pub struct SecondaryQueue {
+    /// Maximum number of inputs in the queue.
+    pub capacity: usize,
+    /// Probability of choosing an input from the queue instead of the
+    /// population (if the queue isn't empty).
+    pub rate: f64,
+    /// Paths exercised at most this many times (including the input itself)
+    /// are rare.
+    pub rare_count: usize,
+    /// Executions taking longer than this factor times the mean execution
+    /// time are slow (`f64::INFINITY` disables this, for deterministic
+    /// campaigns).
+    pub slow_factor: f64,
+
+    pub stats: SecondaryStats,
+
+    inputs: VecDeque<(Input, Reason)>,
+    total_duration: Duration,
+    executions: u32,
+}
+
+impl SecondaryQueue {
+    /// Executions needed before inputs are considered slow (a mean over fewer
+    /// executions is too noisy).
+    const MIN_EXECUTIONS: u32 = 20;
+
+    pub fn new() -> Self {
+        Self {
+            capacity: 100,
+            rate: 0.1,
+            rare_count: 2,
+            slow_factor: 3.0,
+            stats: SecondaryStats::default(),
+            inputs: VecDeque::new(),
+            total_duration: Duration::ZERO,
+            executions: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// The inputs in the queue, oldest first.
+    pub fn inputs(&self) -> impl Iterator<Item = &(Input, Reason)> {
+        self.inputs.iter()
+    }
+
+    /// Offer an executed input, with the number of times its path was
+    /// exercised so far (`None` for inputs that crashed or had new coverage,
+    /// which are only used for the mean execution time). Returns why the
+    /// input was added, if it was.
+    pub fn offer(
+        &mut self,
+        input: &Input,
+        path_count: Option<usize>,
+        duration: Duration,
+    ) -> Option<Reason> {
+        let slow = self.executions >= Self::MIN_EXECUTIONS
+            && duration.as_secs_f64()
+                > self.slow_factor * self.total_duration.as_secs_f64() / self.executions as f64;
+        self.total_duration += duration;
+        self.executions += 1;
+
+        let reason = match path_count? {
+            count if count <= self.rare_count => Reason::RarePath,
+            _ if slow => Reason::Slow,
+            _ => return None,
+        };
+        if self.inputs.iter().any(|(i, _)| i == input) {
+            return None;
+        }
+        match reason {
+            Reason::RarePath => self.stats.rare += 1,
+            Reason::Slow => self.stats.slow += 1,
+        }
+        if self.inputs.len() >= self.capacity {
+            self.inputs.pop_front();
+        }
+        self.inputs.push_back((input.clone(), reason));
+        Some(reason)
+    }
+
+    /// With probability `rate`, choose an input of the queue to mutate next.
+    pub fn choose(&mut self, rng: &mut Rng) -> Option<Input> {
+        if self.inputs.is_empty() || rng.f64() >= self.rate {
+            return None;
+        }
+        self.stats.chosen += 1;
+        let i = rng.usize_below(self.inputs.len());
+        Some(self.inputs[i].0.clone())
+    }
+}
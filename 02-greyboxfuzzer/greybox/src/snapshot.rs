@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Snapshot of the state of the greybox fuzzer, to resume a campaign later
+// (e.g. after it was stopped, or with a larger budget): the population with
+// the coverage hash of each input, and how often each path was exercised
+// (which the power schedule is based on). The coverage hashes are stable
+// across runs (see `CoverageH`), so inputs of the resumed campaign with the
+// coverage of a saved path count as known paths.
+//
+// The file is plain text, one entry per line:
+//   - `path <signature> <count>` for each path (hex `CoverageH`);
+//   - `input <signature> <hex bytes>` for each input of the population.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::fuzzer::Input;
+use crate::runner::CoverageH;
+
+const HEADER: &str = "# greybox snapshot v1";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// All inputs with unique coverage, with the hash of their coverage.
+    pub population: BTreeMap<Input, CoverageH>,
+    /// Hashes of all coverages seen, with how often they occurred.
+    pub coverage_db: BTreeMap<CoverageH, usize>,
+}
+
+impl Snapshot {
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "{}", HEADER)?;
+        for (coverageh, count) in self.coverage_db.iter() {
+            writeln!(file, "path {} {}", coverageh, count)?;
+        }
+        for (input, coverageh) in self.population.iter() {
+            let hex: String = input.0.iter().map(|b| format!("{:02x}", b)).collect();
+            writeln!(file, "input {} {}", coverageh, hex)?;
+        }
+        file.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let content = fs::read_to_string(path)?;
+        let mut lines = content.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(HEADER) {
+            return Err(invalid("Not a greybox snapshot".to_string()));
+        }
+
+        let mut snapshot = Self::default();
+        for (i, line) in lines {
+            let fields: Vec<_> = line.split(' ').collect();
+            let entry =
+                match fields[..] {
+                    ["path", coverageh, count] => CoverageH::from_hex(coverageh)
+                        .zip(count.parse().ok())
+                        .map(|(coverageh, count)| {
+                            snapshot.coverage_db.insert(coverageh, count);
+                        }),
+                    ["input", coverageh, hex] => CoverageH::from_hex(coverageh)
+                        .zip(from_hex(hex))
+                        .map(|(coverageh, input)| {
+                            snapshot.population.insert(Input(input), coverageh);
+                        }),
+                    _ => None,
+                };
+            if entry.is_none() {
+                return Err(invalid(format!(
+                    "Invalid snapshot line {}: {}",
+                    i + 1,
+                    line
+                )));
+            }
+        }
+
+        // The power schedule looks up the path of each input.
+        if let Some(coverageh) = snapshot
+            .population
+            .values()
+            .find(|coverageh| !snapshot.coverage_db.contains_key(coverageh))
+        {
+            return Err(invalid(format!("No path count for {}", coverageh)));
+        }
+        Ok(snapshot)
+    }
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Stability check of a target (like the "stability" of AFL): run each seed
+// several times and compare the coverage. Targets whose coverage depends on
+// the time, the process id, randomized hash seeds etc. report different
+// coverage for the same input; the coverage-guided fuzzers then take every
+// variation for new coverage, and their population and coverage database
+// fill up with noise.
+//
+// The locations that differ between runs can be masked: `MaskedBackend`
+// removes them from the coverage of every run, so that only the deterministic
+// part of the coverage counts for novelty.
+
+use crate::error::FuzzError;
+use crate::fuzzer::Input;
+use crate::granularity::Functions;
+use crate::runner::{Coverage, CoverageBackend, RunResult};
+
+/// Result of running one seed several times.
+#[derive(Clone, Debug)]
+pub struct SeedStability {
+    pub input: Input,
+    /// Some runs crashed and others didn't (or with different reports).
+    pub crash_differs: bool,
+    /// Locations covered by some, but not all runs without crash.
+    pub unstable_locations: Coverage,
+}
+
+impl SeedStability {
+    pub fn is_stable(&self) -> bool {
+        !self.crash_differs && self.unstable_locations.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StabilityReport {
+    /// Number of runs per seed.
+    pub runs: usize,
+    pub seeds: Vec<SeedStability>,
+    /// Union of the unstable locations of all seeds.
+    pub unstable_locations: Coverage,
+}
+
+impl StabilityReport {
+    /// Fraction of the seeds with stable results (1 if there are none).
+    pub fn stability(&self) -> f64 {
+        match self.seeds.len() {
+            0 => 1.0,
+            n => self.seeds.iter().filter(|seed| seed.is_stable()).count() as f64 / n as f64,
+        }
+    }
+}
+
+/// Run each seed `runs` times (at least 2) and compare the results.
+pub fn check(
+    backend: &dyn CoverageBackend,
+    seeds: &[Input],
+    runs: usize,
+) -> Result<StabilityReport, FuzzError> {
+    assert!(runs >= 2, "Need at least 2 runs to compare");
+    let mut report = StabilityReport {
+        runs,
+        seeds: Vec::new(),
+        unstable_locations: Coverage::new(),
+    };
+    for input in seeds.iter() {
+        let results = (0..runs)
+            .map(|_| backend.run(input))
+            .collect::<Result<Vec<_>, _>>()?;
+        let crash_differs = results.iter().any(|result| *result != results[0])
+            && results
+                .iter()
+                .any(|result| matches!(result, RunResult::Crash(_)));
+
+        let coverages: Vec<&Coverage> = results
+            .iter()
+            .filter_map(|result| match result {
+                RunResult::Ok(coverage) => Some(coverage),
+                RunResult::Crash(_) => None,
+            })
+            .collect();
+        let unstable_locations = match coverages.split_first() {
+            None => Coverage::new(),
+            Some((first, rest)) => {
+                let mut union = (*first).clone();
+                let mut intersection = (*first).clone();
+                for coverage in rest.iter() {
+                    union.extend(coverage.iter());
+                    intersection.retain(|location| coverage.contains(location));
+                }
+                union.difference(&intersection).cloned().collect()
+            }
+        };
+
+        report.unstable_locations.extend(unstable_locations.iter());
+        report.seeds.push(SeedStability {
+            input: input.clone(),
+            crash_differs,
+            unstable_locations,
+        });
+    }
+    Ok(report)
+}
+
+/// Backend that removes the masked locations from the coverage of another
+/// backend.
+pub struct MaskedBackend {
+    pub backend: Box<dyn CoverageBackend>,
+    pub mask: Coverage,
+}
+
+impl MaskedBackend {
+    pub fn new(backend: Box<dyn CoverageBackend>, mask: Coverage) -> Self {
+        Self { backend, mask }
+    }
+}
+
+impl CoverageBackend for MaskedBackend {
+    fn run(&self, input: &Input) -> Result<RunResult, FuzzError> {
+        Ok(match self.backend.run(input)? {
+            RunResult::Ok(coverage) => {
+                RunResult::Ok(coverage.difference(&self.mask).cloned().collect())
+            }
+            crash => crash,
+        })
+    }
+
+    fn summary(&self) -> Option<String> {
+        self.backend.summary()
+    }
+
+    fn check(&self) -> Result<(), FuzzError> {
+        self.backend.check()
+    }
+
+    fn functions(&self) -> Option<Functions> {
+        self.backend.functions()
+    }
+}
@@ -0,0 +1,242 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// On-disk store of the population, organized by coverage signature: one
+// folder per signature (the hex `CoverageH` of the coverage), holding the best
+// input with that coverage. Unlike a flat corpus, which only ever grows, this
+// keeps the corpus small in long runs:
+//   - per signature only the smallest input is kept (the fastest one among
+//     inputs of the same size);
+//   - periodically, entries whose coverage is a strict subset of the coverage
+//     of another entry (dominated entries) are pruned, since the other entry
+//     exercises everything they do.
+//
+// Layout of a folder `<dir>/<signature>/`:
+//   - the input, named by its SHA-1 (as in a libFuzzer corpus);
+//   - `meta`: `duration <ns>` and `coverage <location>...` on one line each.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::corpus;
+use crate::fuzzer::Input;
+use crate::runner::{Coverage, CoverageH};
+
+/// Best input for one coverage signature.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub input: Input,
+    pub coverage: Coverage,
+    pub duration: Duration,
+}
+
+impl Entry {
+    /// Smaller inputs are better, and faster ones among those of the same
+    /// size.
+    fn is_better_than(&self, other: &Entry) -> bool {
+        (self.input.0.len(), self.duration) < (other.input.0.len(), other.duration)
+    }
+}
+
+pub struct CoverageStore {
+    pub dir: PathBuf,
+
+    /// Dominated entries are pruned every this many added inputs (0: only
+    /// when calling `prune`).
+    pub prune_interval: usize,
+
+    entries: BTreeMap<CoverageH, Entry>,
+
+    /// Signatures of pruned entries, which are not stored again (until the
+    /// store is opened anew).
+    pruned: BTreeSet<CoverageH>,
+
+    /// Number of inputs passed to `add` since the last pruning.
+    since_prune: usize,
+
+    pub stats: StoreStatistics,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StoreStatistics {
+    /// Entries for new signatures.
+    pub added: usize,
+    /// Entries replaced by a smaller or faster input.
+    pub replaced: usize,
+    /// Dominated entries that were pruned.
+    pub pruned: usize,
+}
+
+impl CoverageStore {
+    /// Open the store in directory `dir` (created if necessary), with the
+    /// entries that are already there.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let mut entries = BTreeMap::new();
+        for folder in fs::read_dir(&dir)? {
+            let folder = folder?.path();
+            if folder.is_dir() {
+                let entry = read_entry(&folder)?;
+                entries.insert(CoverageH::new(&entry.coverage), entry);
+            }
+        }
+        Ok(Self {
+            dir,
+            prune_interval: 1000,
+            entries,
+            pruned: BTreeSet::new(),
+            since_prune: 0,
+            stats: StoreStatistics::default(),
+        })
+    }
+
+    pub fn with_prune_interval(mut self, prune_interval: usize) -> Self {
+        self.prune_interval = prune_interval;
+        self
+    }
+
+    /// Offer an input with the coverage and duration of its execution. It is
+    /// stored if its signature is new, or if it is better than the stored
+    /// input. Returns whether it was stored.
+    pub fn add(
+        &mut self,
+        input: &Input,
+        coverage: &Coverage,
+        duration: Duration,
+    ) -> io::Result<bool> {
+        self.since_prune += 1;
+        if self.prune_interval > 0 && self.since_prune >= self.prune_interval {
+            self.prune()?;
+        }
+
+        let signature = CoverageH::new(coverage);
+        if self.pruned.contains(&signature) {
+            return Ok(false);
+        }
+        let entry = Entry {
+            input: input.clone(),
+            coverage: coverage.clone(),
+            duration,
+        };
+        match self.entries.get(&signature) {
+            Some(old) if !entry.is_better_than(old) => return Ok(false),
+            Some(_) => {
+                self.stats.replaced += 1;
+                fs::remove_dir_all(self.folder(&signature))?;
+            }
+            None => self.stats.added += 1,
+        }
+        write_entry(&self.folder(&signature), &entry)?;
+        self.entries.insert(signature, entry);
+        Ok(true)
+    }
+
+    /// Remove all entries whose coverage is a strict subset of the coverage
+    /// of another entry. Returns the number of removed entries.
+    pub fn prune(&mut self) -> io::Result<usize> {
+        self.since_prune = 0;
+        let dominated: Vec<CoverageH> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| {
+                self.entries.values().any(|other| {
+                    other.coverage.len() > entry.coverage.len()
+                        && other.coverage.is_superset(&entry.coverage)
+                })
+            })
+            .map(|(signature, _)| signature.clone())
+            .collect();
+        for signature in dominated.iter() {
+            fs::remove_dir_all(self.folder(signature))?;
+            self.entries.remove(signature);
+            self.pruned.insert(signature.clone());
+        }
+        self.stats.pruned += dominated.len();
+        Ok(dominated.len())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.values()
+    }
+
+    /// The stored inputs (e.g. as seeds for the next campaign).
+    pub fn inputs(&self) -> Vec<Input> {
+        self.entries
+            .values()
+            .map(|entry| entry.input.clone())
+            .collect()
+    }
+
+    fn folder(&self, signature: &CoverageH) -> PathBuf {
+        self.dir.join(signature.to_string())
+    }
+}
+
+fn write_entry(folder: &Path, entry: &Entry) -> io::Result<()> {
+    fs::create_dir_all(folder)?;
+    fs::write(folder.join(corpus::file_name(&entry.input)), &entry.input.0)?;
+    let locations: Vec<String> = entry.coverage.iter().map(|l| l.to_string()).collect();
+    fs::write(
+        folder.join("meta"),
+        format!(
+            "duration {}\ncoverage {}\n",
+            entry.duration.as_nanos(),
+            locations.join(" ")
+        ),
+    )
+}
+
+fn read_entry(folder: &Path) -> io::Result<Entry> {
+    let invalid = |what: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} in {}", what, folder.display()),
+        )
+    };
+    let meta = fs::read_to_string(folder.join("meta"))?;
+    let mut duration = None;
+    let mut coverage = None;
+    for line in meta.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("duration") => {
+                let ns = fields.next().and_then(|ns| ns.parse().ok());
+                duration = Some(Duration::from_nanos(
+                    ns.ok_or_else(|| invalid("Invalid duration"))?,
+                ));
+            }
+            Some("coverage") => {
+                let locations: Result<Coverage, _> = fields.map(|l| l.parse()).collect();
+                coverage = Some(locations.map_err(|_| invalid("Invalid coverage"))?);
+            }
+            _ => return Err(invalid("Invalid meta line")),
+        }
+    }
+
+    let mut inputs = Vec::new();
+    for file in fs::read_dir(folder)? {
+        let path = file?.path();
+        if path.is_file() && path.file_name() != Some("meta".as_ref()) {
+            inputs.push(Input(fs::read(path)?));
+        }
+    }
+    let [input] = <[Input; 1]>::try_from(inputs).map_err(|_| invalid("Not exactly one input"))?;
+    Ok(Entry {
+        input,
+        coverage: coverage.ok_or_else(|| invalid("Missing coverage"))?,
+        duration: duration.ok_or_else(|| invalid("Missing duration"))?,
+    })
+}
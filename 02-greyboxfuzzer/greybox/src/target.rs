@@ -0,0 +1,92 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// In-process test targets, e.g. Rust ports of C programs, so that a campaign
+// also runs on systems without gcc/gcov.
+// Instead of compiler instrumentation, the targets are instrumented manually:
+// each interesting point in the code reports its source line to a tracer.
+// The `Registry` makes them selectable by name.
+
+use std::collections::BTreeMap;
+
+use crate::runner::Coverage;
+
+/// Records which instrumentation points were hit during one execution.
+#[derive(Default)]
+pub struct Tracer {
+    pub coverage: Coverage,
+}
+
+impl Tracer {
+    pub fn hit(&mut self, line: u32) {
+        self.coverage.insert(line as usize);
+    }
+}
+
+/// A program under test that is executed in-process.
+pub trait TestTarget {
+    /// Execute the program on an input. Returns whether it succeeded (`false`
+    /// corresponds to a crash / non-zero exit code of the C program).
+    fn execute(&self, input: &[u8], tracer: &mut Tracer) -> bool;
+}
+
+/// A test target as plain function, e.g. a non-capturing closure around a
+/// `TestTarget` or a function of a Rust port.
+pub type TargetFn = fn(&[u8], &mut Tracer) -> bool;
+
+impl TestTarget for TargetFn {
+    fn execute(&self, input: &[u8], tracer: &mut Tracer) -> bool {
+        self(input, tracer)
+    }
+}
+
+/// In-process test targets by name, so that a campaign can choose one on the
+/// command line (`--target <name>`). They run without starting a process
+/// (and without writing files), so a campaign gets through millions of fuzz
+/// cases per second instead of a few hundred with gcov, which is enough to
+/// compare fuzzers over many repetitions in seconds.
+#[derive(Default)]
+pub struct Registry {
+    targets: BTreeMap<&'static str, Entry>,
+}
+
+struct Entry {
+    function: TargetFn,
+    description: &'static str,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a target (replacing a previous one with the same name).
+    pub fn with(
+        mut self,
+        name: &'static str,
+        description: &'static str,
+        function: TargetFn,
+    ) -> Self {
+        self.targets.insert(
+            name,
+            Entry {
+                function,
+                description,
+            },
+        );
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<TargetFn> {
+        self.targets.get(name).map(|entry| entry.function)
+    }
+
+    /// Names and descriptions of all targets, sorted by name.
+    pub fn list(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.targets
+            .iter()
+            .map(|(name, entry)| (*name, entry.description))
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Execution times of the fuzz cases of a campaign. Most inputs of a target
+// take about the same time; inputs that take many times longer than the
+// median point to algorithmic complexity issues (quadratic loops, excessive
+// backtracking, hash collisions), which are bugs of their own (denial of
+// service). Reporting the slowest inputs is a cheap form of what SlowFuzz
+// does with a dedicated fitness function.
+//
+// The times are wall-clock times, so they include noise of the system (and,
+// for external programs, of starting the process): single outliers only
+// become meaningful if they reproduce, so `reproducible_outliers` runs them
+// again.
+
+use std::time::{Duration, Instant};
+
+use crate::error::FuzzError;
+use crate::fuzzer::Input;
+use crate::runner::CoverageBackend;
+
+/// Number of slowest inputs that are kept.
+pub const SLOWEST: usize = 10;
+
+/// Inputs taking more than this factor times the median are outliers.
+pub const OUTLIER_FACTOR: u32 = 10;
+
+#[derive(Default)]
+pub struct Timings {
+    /// Execution time of each fuzz case.
+    pub durations: Vec<Duration>,
+
+    /// The `SLOWEST` slowest inputs, slowest first (each input once).
+    slowest: Vec<(Duration, Input)>,
+}
+
+impl Timings {
+    pub fn record(&mut self, input: &Input, duration: Duration) {
+        self.durations.push(duration);
+
+        if self.slowest.len() == SLOWEST && duration <= self.slowest[SLOWEST - 1].0 {
+            return;
+        }
+        if let Some(i) = self.slowest.iter().position(|(_, i)| i == input) {
+            if self.slowest[i].0 >= duration {
+                return;
+            }
+            self.slowest.remove(i);
+        }
+        let pos = self.slowest.partition_point(|&(d, _)| d >= duration);
+        self.slowest.insert(pos, (duration, input.clone()));
+        self.slowest.truncate(SLOWEST);
+    }
+
+    /// The `p`-th percentile (0 to 100, nearest rank) of the execution times
+    /// (`None` without fuzz cases).
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        let mut sorted = self.durations.clone();
+        sorted.sort();
+        percentile_of_sorted(&sorted, p)
+    }
+
+    /// Several percentiles at once (sorting only once).
+    pub fn percentiles(&self, ps: &[f64]) -> Vec<Option<Duration>> {
+        let mut sorted = self.durations.clone();
+        sorted.sort();
+        ps.iter()
+            .map(|&p| percentile_of_sorted(&sorted, p))
+            .collect()
+    }
+
+    /// The slowest inputs, slowest first.
+    pub fn slowest(&self) -> &[(Duration, Input)] {
+        &self.slowest
+    }
+
+    /// The slowest inputs that take more than `OUTLIER_FACTOR` times the
+    /// median.
+    pub fn outliers(&self) -> Vec<&(Duration, Input)> {
+        let Some(median) = self.percentile(50.0) else {
+            return Vec::new();
+        };
+        self.slowest
+            .iter()
+            .filter(|(duration, _)| *duration > median * OUTLIER_FACTOR)
+            .collect()
+    }
+
+    /// The outliers that are still outliers when run `runs` more times: the
+    /// fastest of the runs takes more than `OUTLIER_FACTOR` times the median.
+    /// Returns them with the fastest of their times.
+    pub fn reproducible_outliers(
+        &self,
+        backend: &dyn CoverageBackend,
+        runs: usize,
+    ) -> Result<Vec<(Duration, Input)>, FuzzError> {
+        let Some(median) = self.percentile(50.0) else {
+            return Ok(Vec::new());
+        };
+        let mut res = Vec::new();
+        for (_, input) in self.outliers() {
+            let mut fastest = Duration::MAX;
+            for _ in 0..runs {
+                let start = Instant::now();
+                backend.run(input)?;
+                fastest = fastest.min(start.elapsed());
+            }
+            if fastest > median * OUTLIER_FACTOR {
+                res.push((fastest, input.clone()));
+            }
+        }
+        Ok(res)
+    }
+}
+
+fn percentile_of_sorted(sorted: &[Duration], p: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
@@ -5,28 +5,8 @@
 
 use crate::rng::Rng;
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Input(pub Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(self.0.clone()).unwrap())
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 pub fn power_schedule_uniform_choose<'a>(rng: &mut Rng, pop: &'a [Input]) -> &'a Input {
     rng.choice(pop)
@@ -3,6 +3,8 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
@@ -11,7 +13,7 @@ use std::collections::BTreeMap;
 use fuzzer::{power_schedule_uniform_choose, Input};
 
 fn main() {
-    let mut rng = rng::Rng::new();
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
 
     let population = vec![
         Input::from_str("A"),
@@ -24,7 +26,7 @@ fn main() {
     hits.insert("B".as_bytes(), 0);
     hits.insert("C".as_bytes(), 0);
 
-    for _ in 0..10000 {
+    for _ in 0..cli::iterations(10000) {
         let val = power_schedule_uniform_choose(&mut rng, &population);
         *hits.get_mut(&val.0[..]).unwrap() += 1;
     }
@@ -6,31 +6,10 @@
 use std::collections::BTreeSet;
 use std::fs;
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::rng::Rng;
-
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Input(pub Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(self.0.clone()).unwrap())
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 /// Location is a tuple (filename, linenumber).
 type Location = (String, usize);
@@ -38,12 +17,19 @@ type Location = (String, usize);
 /// Statement coverage.
 pub type Coverage = BTreeSet<Location>;
 
-/// Compile the crashme C program. This is done in a separate function and
-/// not in run_and_get_coverage, since it only has to be done once and not on
-/// each fuzz case (the source code doesn't change between fuzz cases).
-pub fn compile_program() {
+/// Compile the C program (crashme by default). This is done in a separate
+/// function and not in run_and_get_coverage, since it only has to be done once
+/// and not on each fuzz case (the source code doesn't change between fuzz cases).
+pub fn compile_program(program: &str) {
     process::Command::new("gcc")
-        .args(["-Wall", "-g", "--coverage", "-o", "crashme", "crashme.c"])
+        .args([
+            "-Wall",
+            "-g",
+            "--coverage",
+            "-o",
+            program,
+            &format!("{}.c", program),
+        ])
         .output()
         .unwrap();
 }
@@ -57,23 +43,32 @@ pub enum RunResult {
     Crash,
 }
 
-/// Run the crashme C program and trace coverage data.
-pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
+/// Number of test runs so far; used for naming temporary directories.
+static TESTRUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Run the C program and trace coverage data.
+pub fn run_and_get_coverage(input: &Input, program: &str) -> RunResult {
     // Create new temporary directory for multi-threaded running without
-    // conflicts.
+    // conflicts. The name is derived from a global counter (and not from the
+    // PRNG), so that running the target doesn't influence the random choices
+    // of the fuzzer.
     let root = format!(
         "testrun-{}-{}",
-        unsafe { core::arch::x86_64::_rdtsc() },
-        rng.next(),
+        process::id(),
+        TESTRUN_COUNTER.fetch_add(1, Ordering::Relaxed),
     );
 
     // Create a temporary directory that acts as root for this fuzz case execution.
     fs::create_dir(&root).unwrap();
     // Copy over the relevant data resulting from the initial program compilation.
     // This is needed for later gathering code coverage with `gcov`.
-    fs::copy("crashme.c", format!("{}/{}", root, "crashme.c")).unwrap();
-    fs::copy("crashme", format!("{}/{}", root, "crashme")).unwrap();
-    fs::copy("crashme.gcno", format!("{}/{}", root, "crashme.gcno")).unwrap();
+    fs::copy(format!("{}.c", program), format!("{}/{}.c", root, program)).unwrap();
+    fs::copy(program, format!("{}/{}", root, program)).unwrap();
+    fs::copy(
+        format!("{}.gcno", program),
+        format!("{}/{}.gcno", root, program),
+    )
+    .unwrap();
 
     // Run the program.
     let exitstatus = process::Command::new(
@@ -83,7 +78,7 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
         // > directory or relative to current_dir. The behavior in this case is
         // > platform specific and unstable, and it’s recommended to use
         // > canonicalize to get an absolute program path instead.
-        fs::canonicalize("./crashme").unwrap(),
+        fs::canonicalize(program).unwrap(),
     )
     .current_dir(&root)
     .arg(format!("{}", input))
@@ -107,13 +102,13 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
     // Generate coverage data using gcov.
     process::Command::new("gcov")
         .current_dir(&root)
-        .arg("crashme.c")
+        .arg(format!("{}.c", program))
         .output()
         .unwrap();
 
     // "Parse" (process) gcov coverage file.
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string(format!("{}/{}", root, "crashme.c.gcov"))
+    for line in fs::read_to_string(format!("{}/{}.c.gcov", root, program))
         .unwrap()
         .lines()
     {
@@ -123,16 +118,16 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
         if covered.starts_with("-") || covered.starts_with("#") {
             continue;
         }
-        coverage.insert(("crashme".to_string(), line_number));
+        coverage.insert((program.to_string(), line_number));
     }
 
     // Cleanup compiled and generated files.
     for file in [
-        format!("{}/{}", root, "crashme"),
-        format!("{}/{}", root, "crashme.c"),
-        format!("{}/{}", root, "crashme.c.gcov"),
-        format!("{}/{}", root, "crashme.gcda"),
-        format!("{}/{}", root, "crashme.gcno"),
+        format!("{}/{}", root, program),
+        format!("{}/{}.c", root, program),
+        format!("{}/{}.c.gcov", root, program),
+        format!("{}/{}.gcda", root, program),
+        format!("{}/{}.gcno", root, program),
     ] {
         let _ = fs::remove_file(file);
     }
@@ -3,22 +3,24 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
 use fuzzer::Input;
 
 fn main() {
-    let mut rng = rng::Rng::new();
+    let target = cli::target("crashme");
 
     // let initial_input = Input::from_str("good");
     // let n = 30000;
 
-    fuzzer::compile_program();
+    fuzzer::compile_program(&target);
 
     for input in ["good", "bad", "bad!"] {
         let input = Input::from_str(input);
-        let coverage = fuzzer::run_and_get_coverage(&mut rng, &input);
+        let coverage = fuzzer::run_and_get_coverage(&input, &target);
         println!("{:?}", coverage);
     }
 
@@ -3,6 +3,8 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
@@ -12,19 +14,21 @@ use std::time::Instant;
 use fuzzer::Input;
 
 fn main() {
-    let mut rng = rng::Rng::new();
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
     println!("[+] Running with random seed {}", rng.initialseed);
 
-    let n = 3000;
+    let n = cli::iterations(3000);
+    let target = cli::target("crashme");
+    let quiet = cli::quiet();
 
-    fuzzer::compile_program();
+    fuzzer::compile_program(&target);
 
     let start = Instant::now();
 
     let mut stats = fuzzer::Statistics::default();
 
     for i in 0..n {
-        if i % 200 == 0 {
+        if i % 200 == 0 && !quiet {
             println!("Fuzz case {}", i);
         }
 
@@ -32,7 +36,7 @@ fn main() {
 
         let input = fuzzer::fuzz(&mut rng, &stats, &initial_population);
 
-        match fuzzer::run_and_get_coverage(&mut rng, &input) {
+        match fuzzer::run_and_get_coverage(&input, &target) {
             fuzzer::RunResult::Crash => println!("Found crash!"),
             fuzzer::RunResult::Ok(coverage) => {
                 stats.fuzz_cases += 1;
@@ -71,7 +75,7 @@ fn main() {
         stats.coverage_all
     );
 
-    let mut logfile = std::fs::File::create("plot.data").unwrap();
+    let mut logfile = std::fs::File::create(cli::out_file("plot.data")).unwrap();
     for (i, el) in stats.coverage_cumul.iter().enumerate() {
         writeln!(logfile, "{} {}", i, el).unwrap();
     }
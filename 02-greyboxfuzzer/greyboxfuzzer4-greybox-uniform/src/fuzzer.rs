@@ -5,38 +5,15 @@
 
 use std::collections::BTreeSet;
 use std::fs;
+use std::io;
+use std::path::Path;
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::rng::Rng;
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Input(pub Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(self.0.clone()).unwrap())
-    }
-}
-
-impl std::fmt::Debug for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self)
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 /// Location is a linenumber
 type Location = usize;
@@ -53,6 +30,10 @@ pub struct Statistics {
     /// List of all inputs with unique coverage.
     pub population: Vec<Input>,
 
+    /// Number of locations that each input of the population added to
+    /// coverage_all when it was found (same order as population).
+    pub contribution: Vec<usize>,
+
     /// Union of all coverages that were achieved during execution.
     pub coverage_all: Coverage,
 
@@ -63,6 +44,62 @@ pub struct Statistics {
     pub coverage_cumul: Vec<usize>,
 }
 
+impl Statistics {
+    /// Write the population to `dir` (created if missing), one file per
+    /// input, named by its index in the population.
+    pub fn export(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        for (i, input) in self.population.iter().enumerate() {
+            fs::write(dir.join(format!("{:06}", i)), &input.0)?;
+        }
+        Ok(())
+    }
+
+    /// Overview of the population: the number of inputs, their size
+    /// distribution, and for each input its size and the number of new
+    /// locations it contributed.
+    pub fn summary(&self) -> String {
+        let inputs: Vec<&Input> = self.population.iter().collect();
+        let mut res = format!("{} inputs, {}\n", inputs.len(), size_distribution(&inputs));
+        for (i, (input, new)) in self.population.iter().zip(&self.contribution).enumerate() {
+            let description = format!("{:4}: {:4} bytes, +{:2} locations:", i, input.0.len(), new);
+            res += &summary_entry(description, input);
+        }
+        res
+    }
+}
+
+/// Size distribution of the inputs: minimum, median, mean and maximum.
+fn size_distribution(inputs: &[&Input]) -> String {
+    let mut sizes: Vec<usize> = inputs.iter().map(|input| input.0.len()).collect();
+    sizes.sort();
+    match (sizes.first(), sizes.last()) {
+        (Some(min), Some(max)) => format!(
+            "sizes {} / {} / {:.1} / {} bytes (min / median / mean / max)",
+            min,
+            sizes[sizes.len() / 2],
+            sizes.iter().sum::<usize>() as f64 / sizes.len() as f64,
+            max
+        ),
+        _ => "no inputs".to_string(),
+    }
+}
+
+/// One line of the population summary; inputs that aren't valid utf8 are
+/// followed by their hexdump.
+fn summary_entry(description: String, input: &Input) -> String {
+    match std::str::from_utf8(&input.0) {
+        Ok(s) => format!("    {} {:?}\n", description, s),
+        Err(_) => {
+            let mut res = format!("    {} (binary)\n", description);
+            for line in input.hexdump().lines() {
+                res += &format!("        {}\n", line);
+            }
+            res
+        }
+    }
+}
+
 /// Get next random input to fuzz with by whichever means suitable
 /// (e.g. generation of input, choosing as-is from initial corpus,
 /// or mutating from current population of inputs).
@@ -87,12 +124,19 @@ pub fn fuzz(rng: &mut Rng, stats: &Statistics, seed: &[Input]) -> Input {
     }
 }
 
-/// Compile the crashme C program. This is done in a separate function and
-/// not in run_and_get_coverage, since it only has to be done once and not on
-/// each fuzz case (the source code doesn't change between fuzz cases).
-pub fn compile_program() {
+/// Compile the C program (crashme by default). This is done in a separate
+/// function and not in run_and_get_coverage, since it only has to be done once
+/// and not on each fuzz case (the source code doesn't change between fuzz cases).
+pub fn compile_program(program: &str) {
     process::Command::new("gcc")
-        .args(["-Wall", "-g", "--coverage", "-o", "crashme", "crashme.c"])
+        .args([
+            "-Wall",
+            "-g",
+            "--coverage",
+            "-o",
+            program,
+            &format!("{}.c", program),
+        ])
         .output()
         .unwrap();
 }
@@ -106,23 +150,32 @@ pub enum RunResult {
     Crash,
 }
 
-/// Run the crashme C program and trace coverage data.
-pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
+/// Number of test runs so far; used for naming temporary directories.
+static TESTRUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Run the C program and trace coverage data.
+pub fn run_and_get_coverage(input: &Input, program: &str) -> RunResult {
     // Create new temporary directory for multi-threaded running without
-    // conflicts.
+    // conflicts. The name is derived from a global counter (and not from the
+    // PRNG), so that running the target doesn't influence the random choices
+    // of the fuzzer.
     let root = format!(
         "testrun-{}-{}",
-        unsafe { core::arch::x86_64::_rdtsc() },
-        rng.next(),
+        process::id(),
+        TESTRUN_COUNTER.fetch_add(1, Ordering::Relaxed),
     );
 
     // Create a temporary directory that acts as root for this fuzz case execution.
     fs::create_dir(&root).unwrap();
     // Copy over the relevant data resulting from the initial program compilation.
     // This is needed for later gathering code coverage with `gcov`.
-    fs::copy("crashme.c", format!("{}/{}", root, "crashme.c")).unwrap();
-    fs::copy("crashme", format!("{}/{}", root, "crashme")).unwrap();
-    fs::copy("crashme.gcno", format!("{}/{}", root, "crashme.gcno")).unwrap();
+    fs::copy(format!("{}.c", program), format!("{}/{}.c", root, program)).unwrap();
+    fs::copy(program, format!("{}/{}", root, program)).unwrap();
+    fs::copy(
+        format!("{}.gcno", program),
+        format!("{}/{}.gcno", root, program),
+    )
+    .unwrap();
 
     // Run the program.
     let exitstatus = process::Command::new(
@@ -132,7 +185,7 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
         // > directory or relative to current_dir. The behavior in this case is
         // > platform specific and unstable, and it’s recommended to use
         // > canonicalize to get an absolute program path instead.
-        fs::canonicalize("./crashme").unwrap(),
+        fs::canonicalize(program).unwrap(),
     )
     .current_dir(&root)
     .arg(format!("{}", input))
@@ -156,13 +209,13 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
     // Generate coverage data using gcov.
     process::Command::new("gcov")
         .current_dir(&root)
-        .arg("crashme.c")
+        .arg(format!("{}.c", program))
         .output()
         .unwrap();
 
     // "Parse" (process) gcov coverage file.
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string(format!("{}/{}", root, "crashme.c.gcov"))
+    for line in fs::read_to_string(format!("{}/{}.c.gcov", root, program))
         .unwrap()
         .lines()
     {
@@ -177,11 +230,11 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
 
     // Cleanup compiled and generated files.
     for file in [
-        format!("{}/{}", root, "crashme"),
-        format!("{}/{}", root, "crashme.c"),
-        format!("{}/{}", root, "crashme.c.gcov"),
-        format!("{}/{}", root, "crashme.gcda"),
-        format!("{}/{}", root, "crashme.gcno"),
+        format!("{}/{}", root, program),
+        format!("{}/{}.c", root, program),
+        format!("{}/{}.c.gcov", root, program),
+        format!("{}/{}.gcda", root, program),
+        format!("{}/{}.gcno", root, program),
     ] {
         let _ = fs::remove_file(file);
     }
@@ -212,8 +265,8 @@ pub fn mutate(rng: &mut Rng, s: Input) -> Input {
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
@@ -222,7 +275,7 @@ fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
@@ -233,7 +286,7 @@ fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
     // which would be impossible to pass on the commandline to the external
     // program.
     loop {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         let bit = 1 << rng.int(7);
         s.0[pos] ^= bit; // Flip bit.
         if s.0[pos] != 0 {
@@ -3,29 +3,34 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io::Write;
+use std::path::Path;
 use std::time::Instant;
 
 use fuzzer::Input;
 
 fn main() {
-    let mut rng = rng::Rng::new();
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
     println!("[+] Running with random seed {}", rng.initialseed);
 
-    let n = 3000;
+    let n = cli::iterations(3000);
+    let target = cli::target("crashme");
+    let quiet = cli::quiet();
 
-    fuzzer::compile_program();
+    fuzzer::compile_program(&target);
 
     let start = Instant::now();
 
     let mut stats = fuzzer::Statistics::default();
 
     for i in 0..n {
-        if i % 200 == 0 {
+        if i % 200 == 0 && !quiet {
             println!("Fuzz case {}", i);
         }
 
@@ -33,7 +38,7 @@ fn main() {
 
         let input = fuzzer::fuzz(&mut rng, &stats, &initial_population);
 
-        match fuzzer::run_and_get_coverage(&mut rng, &input) {
+        match fuzzer::run_and_get_coverage(&input, &target) {
             fuzzer::RunResult::Crash => println!("Found crash!"),
             fuzzer::RunResult::Ok(coverage) => {
                 let coveragehash = {
@@ -45,6 +50,9 @@ fn main() {
                 if !stats.coverage_db.contains(&coveragehash) {
                     // We have some new coverage.
                     stats.coverage_db.insert(coveragehash);
+                    stats
+                        .contribution
+                        .push(coverage.difference(&stats.coverage_all).count());
                     stats.population.push(input);
                 }
                 stats.coverage_all.extend(coverage);
@@ -64,8 +72,8 @@ fn main() {
         (end - start).as_secs_f64()
     );
     println!(
-        "    - Inputs leading to new coverage: {:?}",
-        stats.population
+        "    - Inputs leading to new coverage: {}",
+        stats.population.len()
     );
     println!(
         "    - All coverage:                   {:0.4} {:?}",
@@ -73,9 +81,18 @@ fn main() {
         stats.coverage_all
     );
 
-    let mut logfile = std::fs::File::create("plot.data").unwrap();
+    let mut logfile = std::fs::File::create(cli::out_file("plot.data")).unwrap();
     for (i, el) in stats.coverage_cumul.iter().enumerate() {
         writeln!(logfile, "{} {}", i, el).unwrap();
     }
     logfile.flush().unwrap();
+
+    if !quiet {
+        println!();
+        println!("[+] Population: {}", stats.summary());
+    }
+    if let Some(dir) = cli::option("--export") {
+        stats.export(Path::new(&dir)).unwrap();
+        println!("[+] Wrote the population to {}", dir);
+    }
 }
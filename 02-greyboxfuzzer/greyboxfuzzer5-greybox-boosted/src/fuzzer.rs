@@ -6,38 +6,15 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::Path;
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::rng::Rng;
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Input(pub Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(self.0.clone()).unwrap())
-    }
-}
-
-impl std::fmt::Debug for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", self)
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 /// Location is a linenumber
 type Location = usize;
@@ -55,7 +32,7 @@ impl CoverageH {
 }
 
 /// Statistics relevant during fuzzing.
-#[derive(Default)]
+#[derive(Default, Debug)]
 pub struct Statistics {
     /// Number of times a random input was tested.
     pub fuzz_cases: usize,
@@ -64,6 +41,10 @@ pub struct Statistics {
     /// associated coverage information.
     pub population: BTreeMap<Input, CoverageH>,
 
+    /// Number of locations that each input of the population added to
+    /// coverage_all when it was found.
+    pub contribution: BTreeMap<Input, usize>,
+
     /// Union of all coverages that were achieved during execution.
     pub coverage_all: Coverage,
 
@@ -73,6 +54,112 @@ pub struct Statistics {
 
     /// Trace/history of coverage_all sizes.
     pub coverage_cumul: Vec<usize>,
+
+    /// The full coverage information of the inputs in the population, by
+    /// coverage hash (needed for the rare-location schedules).
+    pub coverages: BTreeMap<CoverageH, Coverage>,
+
+    /// All individual locations seen during fuzzing, together with the number
+    /// of fuzz cases that covered them.
+    pub location_freq: BTreeMap<Location, usize>,
+}
+
+impl Statistics {
+    /// Write the population to `dir` (created if missing), one file per
+    /// input, named by its index in the population (which is sorted by
+    /// input).
+    pub fn export(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        for (i, input) in self.population.keys().enumerate() {
+            fs::write(dir.join(format!("{:06}", i)), &input.0)?;
+        }
+        Ok(())
+    }
+
+    /// Overview of the population: the number of inputs, their size
+    /// distribution, and for each input its size, the number of locations it
+    /// covers, the number of new locations it contributed, and how often its
+    /// path was seen.
+    pub fn summary(&self) -> String {
+        let inputs: Vec<&Input> = self.population.keys().collect();
+        let mut res = format!("{} inputs, {}\n", inputs.len(), size_distribution(&inputs));
+        for (i, (input, coverageh)) in self.population.iter().enumerate() {
+            let description = format!(
+                "{:4}: {:4} bytes, {:2} locations (+{:2}), path seen {:4} times:",
+                i,
+                input.0.len(),
+                self.coverages
+                    .get(coverageh)
+                    .map_or(0, |coverage| coverage.len()),
+                self.contribution.get(input).copied().unwrap_or(0),
+                self.coverage_db.get(coverageh).copied().unwrap_or(0)
+            );
+            res += &summary_entry(description, input);
+        }
+        res
+    }
+}
+
+/// Size distribution of the inputs: minimum, median, mean and maximum.
+fn size_distribution(inputs: &[&Input]) -> String {
+    let mut sizes: Vec<usize> = inputs.iter().map(|input| input.0.len()).collect();
+    sizes.sort();
+    match (sizes.first(), sizes.last()) {
+        (Some(min), Some(max)) => format!(
+            "sizes {} / {} / {:.1} / {} bytes (min / median / mean / max)",
+            min,
+            sizes[sizes.len() / 2],
+            sizes.iter().sum::<usize>() as f64 / sizes.len() as f64,
+            max
+        ),
+        _ => "no inputs".to_string(),
+    }
+}
+
+/// One line of the population summary; inputs that aren't valid utf8 are
+/// followed by their hexdump.
+fn summary_entry(description: String, input: &Input) -> String {
+    match std::str::from_utf8(&input.0) {
+        Ok(s) => format!("    {} {:?}\n", description, s),
+        Err(_) => {
+            let mut res = format!("    {} (binary)\n", description);
+            for line in input.hexdump().lines() {
+                res += &format!("        {}\n", line);
+            }
+            res
+        }
+    }
+}
+
+/// Strategy for assigning energy to the inputs of the population.
+#[derive(Clone, Copy, Debug)]
+pub enum Schedule {
+    /// Based on how often the full coverage (path) of an input was seen.
+    Path,
+    /// Based on the rarest individual location an input covers.
+    RareMin,
+    /// Based on the product of the frequencies of all locations an input
+    /// covers.
+    RareProduct,
+}
+
+impl Schedule {
+    /// Select the schedule on the command line with `--schedule path`
+    /// (default), `--schedule rare-min` or `--schedule rare-product`.
+    pub fn from_cli() -> Self {
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--schedule" {
+                return match args.next().as_deref() {
+                    Some("path") => Schedule::Path,
+                    Some("rare-min") => Schedule::RareMin,
+                    Some("rare-product") => Schedule::RareProduct,
+                    other => panic!("Unknown schedule {:?}", other),
+                };
+            }
+        }
+        Schedule::Path
+    }
 }
 
 pub fn hash<T: Hash>(val: &T) -> u64 {
@@ -84,7 +171,7 @@ pub fn hash<T: Hash>(val: &T) -> u64 {
 /// Get next random input to fuzz with by whichever means suitable
 /// (e.g. generation of input, choosing as-is from initial corpus,
 /// or mutating from current population of inputs).
-pub fn fuzz(rng: &mut Rng, stats: &mut Statistics, seed: &[Input]) -> Input {
+pub fn fuzz(rng: &mut Rng, stats: &mut Statistics, schedule: Schedule, seed: &[Input]) -> Input {
     if stats.fuzz_cases < seed.len() {
         // Choose input candidate from initial population as seed.
         seed[stats.fuzz_cases].clone()
@@ -92,7 +179,7 @@ pub fn fuzz(rng: &mut Rng, stats: &mut Statistics, seed: &[Input]) -> Input {
         // Create new a input candidate through mutating existing population.
 
         // Choose random existing input from population.
-        let mut candidate = power_schedule_choose(rng, stats);
+        let mut candidate = power_schedule_choose(rng, stats, schedule);
 
         // Then mutate that input a random number of times.
 
@@ -105,12 +192,19 @@ pub fn fuzz(rng: &mut Rng, stats: &mut Statistics, seed: &[Input]) -> Input {
     }
 }
 
-/// Compile the crashme C program. This is done in a separate function and
-/// not in run_and_get_coverage, since it only has to be done once and not on
-/// each fuzz case (the source code doesn't change between fuzz cases).
-pub fn compile_program() {
+/// Compile the C program (crashme by default). This is done in a separate
+/// function and not in run_and_get_coverage, since it only has to be done once
+/// and not on each fuzz case (the source code doesn't change between fuzz cases).
+pub fn compile_program(program: &str) {
     process::Command::new("gcc")
-        .args(["-Wall", "-g", "--coverage", "-o", "crashme", "crashme.c"])
+        .args([
+            "-Wall",
+            "-g",
+            "--coverage",
+            "-o",
+            program,
+            &format!("{}.c", program),
+        ])
         .output()
         .unwrap();
 }
@@ -124,23 +218,32 @@ pub enum RunResult {
     Crash,
 }
 
-/// Run the crashme C program and trace coverage data.
-pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
+/// Number of test runs so far; used for naming temporary directories.
+static TESTRUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Run the C program and trace coverage data.
+pub fn run_and_get_coverage(input: &Input, program: &str) -> RunResult {
     // Create new temporary directory for multi-threaded running without
-    // conflicts.
+    // conflicts. The name is derived from a global counter (and not from the
+    // PRNG), so that running the target doesn't influence the random choices
+    // of the fuzzer.
     let root = format!(
         "testrun-{}-{}",
-        unsafe { core::arch::x86_64::_rdtsc() },
-        rng.next(),
+        process::id(),
+        TESTRUN_COUNTER.fetch_add(1, Ordering::Relaxed),
     );
 
     // Create a temporary directory that acts as root for this fuzz case execution.
     fs::create_dir(&root).unwrap();
     // Copy over the relevant data resulting from the initial program compilation.
     // This is needed for later gathering code coverage with `gcov`.
-    fs::copy("crashme.c", format!("{}/{}", root, "crashme.c")).unwrap();
-    fs::copy("crashme", format!("{}/{}", root, "crashme")).unwrap();
-    fs::copy("crashme.gcno", format!("{}/{}", root, "crashme.gcno")).unwrap();
+    fs::copy(format!("{}.c", program), format!("{}/{}.c", root, program)).unwrap();
+    fs::copy(program, format!("{}/{}", root, program)).unwrap();
+    fs::copy(
+        format!("{}.gcno", program),
+        format!("{}/{}.gcno", root, program),
+    )
+    .unwrap();
 
     // Run the program.
     let exitstatus = process::Command::new(
@@ -150,7 +253,7 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
         // > directory or relative to current_dir. The behavior in this case is
         // > platform specific and unstable, and it’s recommended to use
         // > canonicalize to get an absolute program path instead.
-        fs::canonicalize("./crashme").unwrap(),
+        fs::canonicalize(program).unwrap(),
     )
     .current_dir(&root)
     .arg(format!("{}", input))
@@ -174,13 +277,13 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
     // Generate coverage data using gcov.
     process::Command::new("gcov")
         .current_dir(&root)
-        .arg("crashme.c")
+        .arg(format!("{}.c", program))
         .output()
         .unwrap();
 
     // "Parse" (process) gcov coverage file.
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string(format!("{}/{}", root, "crashme.c.gcov"))
+    for line in fs::read_to_string(format!("{}/{}.c.gcov", root, program))
         .unwrap()
         .lines()
     {
@@ -195,11 +298,11 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
 
     // Cleanup compiled and generated files.
     for file in [
-        format!("{}/{}", root, "crashme"),
-        format!("{}/{}", root, "crashme.c"),
-        format!("{}/{}", root, "crashme.c.gcov"),
-        format!("{}/{}", root, "crashme.gcda"),
-        format!("{}/{}", root, "crashme.gcno"),
+        format!("{}/{}", root, program),
+        format!("{}/{}.c", root, program),
+        format!("{}/{}.c.gcov", root, program),
+        format!("{}/{}.gcda", root, program),
+        format!("{}/{}.gcno", root, program),
     ] {
         let _ = fs::remove_file(file);
     }
@@ -208,7 +311,8 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
     if exitstatus.success() {
         RunResult::Ok(coverage)
     } else {
-        assert!(coverage.is_empty());
+        // Note that there may still be coverage information, since crashme
+        // only exits with an error code instead of really crashing.
         RunResult::Crash
     }
 }
@@ -216,14 +320,44 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> RunResult {
 /// Choose a value from a given population of inputs for the SUT.
 /// This implementation chooses according to an exponential power schedule as
 /// implemented in AFL.
-pub fn power_schedule_choose(rng: &mut Rng, stats: &mut Statistics) -> Input {
+pub fn power_schedule_choose(rng: &mut Rng, stats: &mut Statistics, schedule: Schedule) -> Input {
+    let exponent = 5.0;
     let mut fitness = Vec::new();
     for (_, coverageh) in stats.population.iter() {
-        let exponent = 5.0;
-        let f = (1.0 / (*stats.coverage_db.get(coverageh).unwrap() as f64)).powf(exponent);
+        let f = match schedule {
+            Schedule::Path => {
+                (1.0 / (*stats.coverage_db.get(coverageh).unwrap() as f64)).powf(exponent)
+            }
+            // Rare-branch boosting (see FairFuzz): Inputs that share the path
+            // frequency of a common path may still be the only ones reaching a
+            // rarely covered location; these get the most energy.
+            Schedule::RareMin => {
+                let rarest = rare_locations(stats, coverageh).fold(f64::MAX, f64::min);
+                (1.0 / rarest).powf(exponent)
+            }
+            // Product of the rarities (inverse relative frequencies) of all
+            // locations. Each factor is at least 1, so inputs covering more
+            // (and rarer) locations get more energy. The product is computed
+            // as sum of logarithms, since it gets too large for floating point
+            // numbers quickly. It is exponentiated (and normalized) below.
+            Schedule::RareProduct => {
+                let total = stats.fuzz_cases as f64;
+                rare_locations(stats, coverageh)
+                    .map(|freq| (total / freq).ln())
+                    .sum::<f64>()
+            }
+        };
         fitness.push(f);
     }
 
+    if let Schedule::RareProduct = schedule {
+        // Relative to the best input, to stay in the range of f64.
+        let max = fitness.iter().cloned().fold(f64::MIN, f64::max);
+        for f in fitness.iter_mut() {
+            *f = (*f - max).exp();
+        }
+    }
+
     let mut fitnessnorm = Vec::new();
     let total_fitness: f64 = fitness.iter().sum();
     assert!(total_fitness > 0.0);
@@ -235,6 +369,16 @@ pub fn power_schedule_choose(rng: &mut Rng, stats: &mut Statistics) -> Input {
     rng.choice_w(&pop, &fitnessnorm).clone()
 }
 
+/// Frequencies of all locations in the coverage with the given hash.
+fn rare_locations<'a>(
+    stats: &'a Statistics,
+    coverageh: &CoverageH,
+) -> impl Iterator<Item = f64> + 'a {
+    stats.coverages[coverageh]
+        .iter()
+        .map(|location| stats.location_freq[location] as f64)
+}
+
 /// Choose a random mutation strategy and apply it to the input.
 pub fn mutate(rng: &mut Rng, s: Input) -> Input {
     match rng.int(3) {
@@ -246,8 +390,8 @@ pub fn mutate(rng: &mut Rng, s: Input) -> Input {
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
@@ -256,7 +400,7 @@ fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
@@ -267,7 +411,7 @@ fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
     // which would be impossible to pass on the commandline to the external
     // program.
     loop {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         let bit = 1 << rng.int(7);
         s.0[pos] ^= bit; // Flip bit.
         if s.0[pos] != 0 {
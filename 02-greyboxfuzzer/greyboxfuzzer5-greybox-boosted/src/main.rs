@@ -3,42 +3,107 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
 use std::io::Write;
+use std::path::Path;
 use std::time::Instant;
 
 fn main() {
-    let mut rng = rng::Rng::new();
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
     println!("[+] Running with random seed {}", rng.initialseed);
 
-    let n = 4000;
+    let schedule = fuzzer::Schedule::from_cli();
+    println!("[+] Running with schedule {:?}", schedule);
 
-    fuzzer::compile_program();
+    let n = cli::iterations(4000);
+    let target = cli::target("crashme");
+    let quiet = cli::quiet();
+
+    fuzzer::compile_program(&target);
 
     let start = Instant::now();
+    let stats = campaign(&mut rng, schedule, n, &target, quiet);
+    let end = Instant::now();
+
+    println!();
+    println!("[+] Boosted greybox mutation-based fuzzer:");
+    println!(
+        "    - Runtime:                        {:0.4}s",
+        (end - start).as_secs_f64()
+    );
+    println!(
+        "    - Inputs leading to new coverage: {}",
+        stats.population.len()
+    );
+    println!(
+        "    - All coverage:                   {:0.4} {:?}",
+        stats.coverage_all.len(),
+        stats.coverage_all
+    );
+    if !quiet {
+        println!("    - Coverage frequencies: {:#?}", stats.coverage_db);
+        println!("    - Location frequencies: {:?}", stats.location_freq);
+    }
+
+    let mut logfile = std::fs::File::create(cli::out_file("plot.data")).unwrap();
+    for (i, el) in stats.coverage_cumul.iter().enumerate() {
+        writeln!(logfile, "{} {}", i, el).unwrap();
+    }
+    logfile.flush().unwrap();
+
+    if !quiet {
+        println!();
+        println!("[+] Population: {}", stats.summary());
+    }
+    if let Some(dir) = cli::option("--export") {
+        stats.export(Path::new(&dir)).unwrap();
+        println!("[+] Wrote the population to {}", dir);
+    }
+}
 
+/// Run `n` fuzz cases against the (compiled) target program.
+fn campaign(
+    rng: &mut rng::Rng,
+    schedule: fuzzer::Schedule,
+    n: usize,
+    target: &str,
+    quiet: bool,
+) -> fuzzer::Statistics {
     let mut stats = fuzzer::Statistics::default();
 
     for i in 0..n {
-        if i % 200 == 0 {
+        if i % 200 == 0 && !quiet {
             println!("Fuzz case {}", i);
         }
 
         let initial_population = vec![fuzzer::Input::from_str("good")];
 
-        let input = fuzzer::fuzz(&mut rng, &mut stats, &initial_population);
+        let input = fuzzer::fuzz(rng, &mut stats, schedule, &initial_population);
 
-        match fuzzer::run_and_get_coverage(&mut rng, &input) {
-            fuzzer::RunResult::Crash => println!("Found crash!"),
+        match fuzzer::run_and_get_coverage(&input, target) {
+            fuzzer::RunResult::Crash => println!("Found crash at fuzz case {}!", i),
             fuzzer::RunResult::Ok(coverage) => {
                 let coveragehash = fuzzer::CoverageH::new(&coverage);
 
+                for location in coverage.iter() {
+                    *stats.location_freq.entry(*location).or_default() += 1;
+                }
+
                 match stats.coverage_db.get_mut(&coveragehash) {
                     None => {
                         // We have some new coverage.
                         stats.coverage_db.insert(coveragehash.clone(), 1);
+                        stats
+                            .coverages
+                            .insert(coveragehash.clone(), coverage.clone());
+                        stats.contribution.insert(
+                            input.clone(),
+                            coverage.difference(&stats.coverage_all).count(),
+                        );
                         stats.population.insert(input, coveragehash);
                     }
                     Some(count) => *count += 1,
@@ -51,29 +116,29 @@ fn main() {
         stats.fuzz_cases += 1;
     }
 
-    let end = Instant::now();
-
-    println!();
-    println!("[+] Boosted greybox mutation-based fuzzer:");
-    println!(
-        "    - Runtime:                        {:0.4}s",
-        (end - start).as_secs_f64()
-    );
-    println!(
-        "    - Inputs leading to new coverage: {:?}",
-        stats.population
-    );
-    println!(
-        "    - All coverage:                   {:0.4} {:?}",
-        stats.coverage_all.len(),
-        stats.coverage_all
-    );
-    println!("    - Coverage frequencies: {:#?}", stats.coverage_db);
-    println!("{:#?}", stats.population);
+    stats
+}
 
-    let mut logfile = std::fs::File::create("plot.data").unwrap();
-    for (i, el) in stats.coverage_cumul.iter().enumerate() {
-        writeln!(logfile, "{} {}", i, el).unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All random decisions are derived from the seed, and the temporary
+    /// directories of the runs don't depend on it, so two campaigns with the
+    /// same seed end with identical statistics (population, coverage, path
+    /// and location frequencies, cumulative coverage).
+    #[test]
+    fn seed_replays_campaign() {
+        fuzzer::compile_program("crashme");
+        for schedule in [
+            fuzzer::Schedule::Path,
+            fuzzer::Schedule::RareMin,
+            fuzzer::Schedule::RareProduct,
+        ] {
+            let first = campaign(&mut rng::Rng::seeded(42), schedule, 100, "crashme", true);
+            let second = campaign(&mut rng::Rng::seeded(42), schedule, 100, "crashme", true);
+            assert_eq!(format!("{:?}", first), format!("{:?}", second));
+            assert_eq!(first.summary(), second.summary());
+        }
     }
-    logfile.flush().unwrap();
 }
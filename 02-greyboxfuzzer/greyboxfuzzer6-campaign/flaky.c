@@ -0,0 +1,40 @@
+// Example program with nondeterministic coverage: besides the comparisons of
+// crashme, it takes a branch depending on the time (like code that depends on
+// the process id, temporary file names or randomized hash seeds). The same
+// input therefore doesn't always give the same coverage.
+
+#include <stdio.h>
+#include <string.h>
+#include <time.h>
+
+int main(int argc, char *argv[]) {
+    if (argc != 2) {
+        return 0;
+    }
+
+    struct timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    if (now.tv_nsec / 1000 % 2 == 0) {
+        printf("even\n");
+    } else {
+        printf("odd\n");
+    }
+
+    char *input = argv[1];
+    int len = strlen(input);
+
+    if (!(len > 0 && input[0] == 'b')) {
+        return 0;
+    }
+    if (!(len > 1 && input[1] == 'a')) {
+        return 0;
+    }
+    if (!(len > 2 && input[2] == 'd')) {
+        return 0;
+    }
+    if (!(len > 3 && input[3] == '!')) {
+        return 0;
+    }
+
+    return 1; // Crash.
+}
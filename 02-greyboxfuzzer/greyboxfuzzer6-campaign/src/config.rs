@@ -0,0 +1,301 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Campaign configuration, loaded from a file in (a small subset of) TOML, so
+// that a campaign can be described in one place instead of a long command
+// line. Options given on the command line override the ones from the file.
+//
+// ```toml
+// seed = 42
+// budget = 2000
+// quiet = false
+//
+// [target]
+// name = "cgi_decode"
+// builtin = false
+// sanitize = false
+// oracle = "exit"
+// granularity = "line"
+// postprocess = "magic:89504e47,crc32:4"
+// files = "config,data"
+// slot_mutation = "independent"
+// timeout_ms = 1000
+//
+// [schedule]
+// exponent = 5.0
+// min_mutations = 1
+// max_mutations = 5
+// operators = "text"
+// secondary = false
+// dictionary = false
+//
+// [dirs]
+// seeds = "corpus"
+// max_seed_size = 1048576
+// corpus_out = "corpus"
+// crashes_out = "crashes"
+// slowest_out = "slowest"
+// store = "store"
+// snapshot = "campaign.snapshot"
+// out_dir = "results"
+// ```
+//
+// Supported TOML: comments, `[table]` headers, and `key = value` with strings,
+// integers, floats and booleans as values. Only greyboxfuzzer6-campaign reads
+// this file, the other examples are configured on the command line. The
+// greybox fuzzer always uses the exponential power schedule, the mutation
+// operators are chosen with fixed probabilities (there are no weights to
+// configure), and the budget is a number of fuzz cases, not a wall-clock time.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use greybox::corpus::MAX_SEED_SIZE;
+
+#[derive(Debug, Clone)]
+pub struct CampaignConfig {
+    /// Random seed (default 42).
+    pub seed: u64,
+    /// Number of fuzz cases per fuzzer.
+    pub budget: usize,
+    /// Only print the summary of each fuzzer, without the crashing and the
+    /// slowest inputs.
+    pub quiet: bool,
+
+    /// Target program: `crashme`, `cgi_decode`, the Rust fuzz target
+    /// `cgi_decode_rust`, or a network service (`http://host:port/path`,
+    /// `tcp://host:port` or `http-demo`).
+    pub target: String,
+    /// Use the built-in Rust port instead of the C program.
+    pub builtin: bool,
+    /// Compile the C program with sanitizers.
+    pub sanitize: bool,
+    /// Crash oracle (see `oracle_from_str` in main.rs).
+    pub oracle: String,
+    /// Coverage by `line`, `function` or `file` (see `greybox::granularity`).
+    pub granularity: String,
+    /// Postprocessors applied to each input before it is run (see
+    /// `postprocessor_from_str` in main.rs).
+    pub postprocess: Option<String>,
+    /// Deliver the input as several files with these names (comma-separated,
+    /// see `greybox::files`) instead of as argument (C programs only).
+    pub files: Option<String>,
+    /// Mutate the files `joint`ly or `independent`ly.
+    pub slot_mutation: String,
+    /// Kill C programs that run longer, and time out network requests after
+    /// this many milliseconds (in-process targets can't be interrupted).
+    pub timeout_ms: Option<u64>,
+
+    /// Exponent of the power schedule of the boosted greybox fuzzer.
+    pub exponent: f64,
+    /// Number of mutations per fuzz case of the mutation fuzzers.
+    pub min_mutations: u64,
+    pub max_mutations: u64,
+    /// Mutation operators (`text`, `utf8` or `binary`), detected from the
+    /// seeds if not given.
+    pub operators: Option<String>,
+    /// Keep near misses of the greybox fuzzer in a secondary queue (see
+    /// `greybox::secondary`).
+    pub secondary: bool,
+    /// Dictionary mutations with the tokens of the grammar of the target (see
+    /// `greybox::dictionary`).
+    pub dictionary: bool,
+
+    /// Directory with the seeds (a libFuzzer/cargo-fuzz corpus or any other
+    /// directory tree, see `Corpus::from_dir`).
+    pub seeds: Option<String>,
+    /// Larger seed files are skipped.
+    pub max_seed_size: u64,
+    /// Directory to add the population to after the campaign.
+    pub corpus_out: Option<String>,
+    /// Directory to write one input per unique crash to.
+    pub crashes_out: Option<String>,
+    /// Directory to write the slowest inputs to (see `greybox::timing`).
+    pub slowest_out: Option<String>,
+    /// Directory of the coverage store (one folder per coverage signature,
+    /// see `greybox::store`), kept up to date during the campaign.
+    pub store: Option<String>,
+    /// File to resume the greybox fuzzer from (if it exists) and to save its
+    /// state to after the campaign (see `greybox::snapshot`).
+    pub snapshot: Option<String>,
+    /// Directory for `plot.data` (default the current directory).
+    pub out_dir: Option<String>,
+}
+
+impl Default for CampaignConfig {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            budget: 2000,
+            quiet: false,
+            target: "crashme".to_string(),
+            builtin: false,
+            sanitize: false,
+            oracle: "exit".to_string(),
+            granularity: "line".to_string(),
+            postprocess: None,
+            files: None,
+            slot_mutation: "independent".to_string(),
+            timeout_ms: None,
+            exponent: 5.0,
+            min_mutations: 1,
+            max_mutations: 5,
+            operators: None,
+            secondary: false,
+            dictionary: false,
+            seeds: None,
+            max_seed_size: MAX_SEED_SIZE,
+            corpus_out: None,
+            crashes_out: None,
+            slowest_out: None,
+            store: None,
+            snapshot: None,
+            out_dir: None,
+        }
+    }
+}
+
+/// A parsed TOML value.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl CampaignConfig {
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parse the configuration. Fails on syntax errors and unknown keys (to
+    /// catch typos, which would otherwise silently fall back to the default).
+    pub fn parse(toml: &str) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut config = Self::default();
+        let mut table = String::new();
+        for (i, line) in toml.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                table = name.trim().to_string();
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(invalid(format!("Invalid config line {}: {}", i + 1, line)));
+            };
+            let key = match table.as_str() {
+                "" => key.trim().to_string(),
+                _ => format!("{}.{}", table, key.trim()),
+            };
+            let value = parse_value(value.trim()).ok_or_else(|| {
+                invalid(format!("Invalid value in config line {}: {}", i + 1, line))
+            })?;
+            config
+                .set(&key, value)
+                .map_err(|msg| invalid(format!("{} in config line {}", msg, i + 1)))?;
+        }
+        if config.min_mutations > config.max_mutations {
+            return Err(invalid("min_mutations > max_mutations".to_string()));
+        }
+        Ok(config)
+    }
+
+    fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        // Negative numbers are rejected instead of wrapping around.
+        let unsigned = |v: i64| u64::try_from(v).map_err(|_| format!("Negative {} = {}", key, v));
+        match (key, value) {
+            ("seed", Value::Integer(v)) => self.seed = unsigned(v)?,
+            ("budget", Value::Integer(v)) => self.budget = unsigned(v)? as usize,
+            ("quiet", Value::Boolean(v)) => self.quiet = v,
+            ("target.name", Value::String(v)) => self.target = v,
+            ("target.builtin", Value::Boolean(v)) => self.builtin = v,
+            ("target.sanitize", Value::Boolean(v)) => self.sanitize = v,
+            ("target.oracle", Value::String(v)) => self.oracle = v,
+            ("target.granularity", Value::String(v)) => self.granularity = v,
+            ("target.postprocess", Value::String(v)) => self.postprocess = Some(v),
+            ("target.files", Value::String(v)) => self.files = Some(v),
+            ("target.slot_mutation", Value::String(v)) => self.slot_mutation = v,
+            ("target.timeout_ms", Value::Integer(v)) => self.timeout_ms = Some(unsigned(v)?),
+            ("schedule.exponent", Value::Float(v)) => self.exponent = v,
+            ("schedule.exponent", Value::Integer(v)) => self.exponent = v as f64,
+            ("schedule.min_mutations", Value::Integer(v)) => self.min_mutations = unsigned(v)?,
+            ("schedule.max_mutations", Value::Integer(v)) => self.max_mutations = unsigned(v)?,
+            ("schedule.operators", Value::String(v)) => self.operators = Some(v),
+            ("schedule.secondary", Value::Boolean(v)) => self.secondary = v,
+            ("schedule.dictionary", Value::Boolean(v)) => self.dictionary = v,
+            ("dirs.seeds", Value::String(v)) => self.seeds = Some(v),
+            ("dirs.max_seed_size", Value::Integer(v)) => self.max_seed_size = unsigned(v)?,
+            ("dirs.corpus_out", Value::String(v)) => self.corpus_out = Some(v),
+            ("dirs.crashes_out", Value::String(v)) => self.crashes_out = Some(v),
+            ("dirs.slowest_out", Value::String(v)) => self.slowest_out = Some(v),
+            ("dirs.store", Value::String(v)) => self.store = Some(v),
+            ("dirs.snapshot", Value::String(v)) => self.snapshot = Some(v),
+            ("dirs.out_dir", Value::String(v)) => self.out_dir = Some(v),
+            (key, value) => {
+                return Err(format!(
+                    "Unknown config key {} or wrong type of {:?}",
+                    key, value
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remove a trailing comment (a `#` outside of a string).
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(s: &str) -> Option<Value> {
+    if let Some(s) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        let mut res = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            // An unescaped quote would end the string early (`"a"b"`).
+            if c == '"' {
+                return None;
+            }
+            if c != '\\' {
+                res.push(c);
+                continue;
+            }
+            res.push(match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                '"' => '"',
+                '\\' => '\\',
+                _ => return None,
+            });
+        }
+        return Some(Value::String(res));
+    }
+    match s {
+        "true" => return Some(Value::Boolean(true)),
+        "false" => return Some(Value::Boolean(false)),
+        _ => {}
+    }
+    // TOML allows underscores between digits.
+    let number = s.replace('_', "");
+    if let Ok(v) = number.parse() {
+        return Some(Value::Integer(v));
+    }
+    number.parse().ok().map(Value::Float)
+}
@@ -0,0 +1,895 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Compare the fuzzers of the different chapters in the same harness. The
+// fuzzers and the campaign machinery are in the `greybox` library crate
+// (02-greyboxfuzzer/greybox); this is only the command line on top, with the
+// example targets.
+
+mod config;
+mod target;
+
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use config::CampaignConfig;
+use greybox::dictionary::Dictionary;
+use greybox::files::{SlotMutation, Slots};
+use greybox::fuzzer::{
+    Fuzzer, GrammarFuzzer, GreyboxFuzzer, Input, MutationCoverageFuzzer, MutationFuzzer, Operators,
+    RandomFuzzer,
+};
+use greybox::granularity::{Aggregate, GranularBackend, Granularity};
+use greybox::harness::FuzzTargetRunner;
+use greybox::postprocess::{self, PostprocessBackend, Postprocessor};
+use greybox::runner::{GcovRunner, RunResult, TargetRunner};
+use greybox::secondary::SecondaryQueue;
+use greybox::snapshot::Snapshot;
+use greybox::stability::{self, MaskedBackend};
+use greybox::store::CoverageStore;
+use greybox::timing;
+use greybox::{cli, cmin, dryrun, experiment, http, oracle, replay, rng};
+use greybox::{Campaign, Corpus, CoverageBackend, Schedule};
+
+/// Seeds that take longer than this in the dry run are reported.
+const SLOW_SEED: Duration = Duration::from_secs(1);
+
+/// Outliers of the execution time are run this many more times, to check
+/// whether they are really slow.
+const OUTLIER_RUNS: usize = 3;
+
+fn main() {
+    if cli::flag("--check-rng") {
+        check_rng();
+        return;
+    }
+
+    let config = config_from_cli();
+    let seed = config.seed;
+    println!("[+] Running with random seed {}", seed);
+
+    let n = config.budget;
+    let quiet = config.quiet;
+    let plot_data = cli::out_dir(config.out_dir.as_deref().unwrap_or(".")).join("plot.data");
+
+    // Target program: `crashme` (default) or `cgi_decode`, the Rust fuzz
+    // target `cgi_decode_rust`, or a network service (`http://host:port/path`,
+    // `tcp://host:port`, or `http-demo` for a local demo server).
+    let target = config.target.clone();
+
+    // Several files per execution instead of a single argument.
+    let slots = config.files.as_ref().map(|names| {
+        Slots::new(
+            names.split(',').map(str::to_string).collect(),
+            slot_mutation_from_str(&config.slot_mutation),
+        )
+    });
+
+    // Use the C program if gcc/gcov are available, and otherwise (or when
+    // explicitly requested) the built-in Rust port.
+    let oracle = oracle_from_str(&config.oracle);
+    let timeout = config.timeout_ms.map(Duration::from_millis);
+    if let Some(timeout) = timeout {
+        println!("[+] Timeout {:?} per fuzz case", timeout);
+    }
+    let http_runner = |url: &str| {
+        let runner = http::HttpRunner::from_url(url);
+        match timeout {
+            Some(timeout) => runner.with_timeout(timeout),
+            None => runner,
+        }
+    };
+    let runner: Box<dyn CoverageBackend> =
+        if target.starts_with("http://") || target.starts_with("tcp://") {
+            println!("[+] Sending inputs to {}", target);
+            Box::new(http_runner(&target))
+        } else if target == "http-demo" {
+            let addr = http::serve(target::CgiDecodeRust).unwrap();
+            println!("[+] Sending inputs to demo server at {}", addr);
+            Box::new(http_runner(&format!("http://{}/", addr)))
+        } else if target == "cgi_decode_rust" {
+            println!("[+] Running Rust fuzz target {}", target);
+            Box::new(FuzzTargetRunner::new(target::CgiDecodeRust))
+        } else if !config.builtin && GcovRunner::is_available() {
+            let runner = if config.sanitize {
+                println!(
+                    "[+] Running C program {} with gcov coverage and sanitizers",
+                    target
+                );
+                GcovRunner::with_sanitizers(&target, oracle).unwrap_or_else(abort)
+            } else {
+                println!("[+] Running C program {} with gcov coverage", target);
+                GcovRunner::new(&target, oracle).unwrap_or_else(abort)
+            };
+            let runner = match timeout {
+                Some(timeout) => runner.with_timeout(timeout),
+                None => runner,
+            };
+            match &slots {
+                Some(slots) => {
+                    println!(
+                        "[+] Passing the input as files {} (mutated {:?})",
+                        slots.names.join(", "),
+                        slots.mutation
+                    );
+                    Box::new(runner.with_files(slots.clone()))
+                }
+                None => Box::new(runner),
+            }
+        } else {
+            let registry = target::registry();
+            let Some(function) = registry.get(&target) else {
+                println!("[!] Unknown built-in target {}, available are:", target);
+                for (name, description) in registry.list() {
+                    println!("    - {:12} {}", name, description);
+                }
+                std::process::exit(1);
+            };
+            println!("[+] Running built-in target {}", target);
+            Box::new(TargetRunner::new(function, oracle))
+        };
+
+    // Encode the inputs before the target gets them (the fuzzers, corpora
+    // and crash files keep the raw inputs).
+    let runner: Box<dyn CoverageBackend> = match &config.postprocess {
+        Some(spec) => {
+            println!("[+] Postprocessing inputs with {}", spec);
+            Box::new(PostprocessBackend::new(
+                runner,
+                postprocessor_from_str(spec),
+            ))
+        }
+        None => runner,
+    };
+
+    // Only distill the corpus in the given directory instead of fuzzing.
+    if let Some(in_dir) = cli::option("--cmin") {
+        let out_dir = cli::option("--cmin-out").unwrap_or(format!("{}.min", in_dir));
+        let (n_in, n_out) = cmin::distill(
+            runner.as_ref(),
+            &Corpus::new(&in_dir),
+            &Corpus::new(&out_dir),
+        )
+        .unwrap_or_else(abort);
+        println!(
+            "[+] Distilled {} inputs from {} to {} inputs in {}",
+            n_in, in_dir, n_out, out_dir
+        );
+        return;
+    }
+
+    // Only re-run the inputs in the given directory instead of fuzzing.
+    if let Some(dir) = cli::option("--replay") {
+        let replayed = replay::replay(runner.as_ref(), dir.as_ref()).unwrap_or_else(abort);
+        println!("[+] Replaying {} inputs from {}", replayed.len(), dir);
+        for r in replayed.iter() {
+            let result = match &r.result {
+                RunResult::Ok(coverage) => {
+                    format!("ok, coverage {} {:?}", coverage.len(), coverage)
+                }
+                RunResult::Crash(_) => format!("crash, {}", r.result.crash_signature().unwrap()),
+            };
+            println!("    {}: {}", r.name, result);
+            if r.flaky {
+                println!("    [!] {}: result differs when run again", r.name);
+            } else if !r.reproduces() {
+                println!("    [!] {}: recorded crash does not reproduce", r.name);
+            }
+        }
+        let failed = replayed.iter().filter(|r| !r.reproduces()).count();
+        println!(
+            "[+] {} of {} recorded crashes reproduce, {} inputs don't behave as recorded",
+            replayed
+                .iter()
+                .filter(|r| r.expect_crash && r.reproduces())
+                .count(),
+            replayed.iter().filter(|r| r.expect_crash).count(),
+            failed
+        );
+        if failed > 0 {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Seeds from a libFuzzer/cargo-fuzz corpus or any other directory tree,
+    // if given.
+    let initial_population = match &config.seeds {
+        Some(dir) => {
+            let import = Corpus::from_dir(dir, config.max_seed_size).unwrap();
+            println!("[+] Imported seeds from {}:", dir);
+            for line in import.report() {
+                println!("    - {}", line);
+            }
+            assert!(!import.inputs.is_empty(), "No seeds in {}", dir);
+            import.inputs
+        }
+        None => match target.as_str() {
+            "cgi_decode" | "cgi_decode_rust" | "http-demo" => {
+                vec![Input::from_str("Hello+World%21")]
+            }
+            "twofiles" => vec![Slots::join(&[b"v1".to_vec(), b"good".to_vec()])],
+            "transactions" => vec![Input::from_str("SELECT * FROM users")],
+            _ => vec![Input::from_str("good")],
+        },
+    };
+    let corpus_out = &config.corpus_out;
+
+    // Check whether the target gives the same coverage for the same input,
+    // and optionally ignore the locations that don't.
+    let runner = match cli::option("--stability") {
+        Some(runs) => check_stability(
+            runner,
+            &initial_population,
+            runs.parse().unwrap(),
+            cli::flag("--mask-unstable"),
+        ),
+        None => runner,
+    };
+
+    // Coverage by function or file instead of by line, for the fuzzers and
+    // the report.
+    let granularity = Granularity::from_str(&config.granularity)
+        .unwrap_or_else(|| panic!("Unknown granularity {}", config.granularity));
+    let runner: Box<dyn CoverageBackend> = match granularity {
+        Granularity::Line => runner,
+        _ => {
+            println!("[+] Coverage by {}", granularity);
+            Box::new(GranularBackend::new(runner, granularity))
+        }
+    };
+
+    // Run every seed once before fuzzing, to find problems with the setup and
+    // the seeds early.
+    let dry_run =
+        dryrun::run(runner.as_ref(), &initial_population, SLOW_SEED).unwrap_or_else(abort);
+    println!(
+        "[+] Dry run: {} seeds, baseline coverage {} {:?}",
+        dry_run.seeds.len(),
+        dry_run.baseline.len(),
+        dry_run.baseline
+    );
+    for seed in dry_run.crashing() {
+        println!(
+            "    [!] Seed {:?} crashes already: {}",
+            seed.input,
+            seed.crash.as_ref().unwrap()
+        );
+    }
+    for seed in dry_run.slow() {
+        println!(
+            "    [!] Seed {:?} is slow: {:.3}s",
+            seed.input,
+            seed.duration.as_secs_f64()
+        );
+    }
+
+    // Text-safe or binary mutation operators, depending on the seeds (unless
+    // explicitly chosen).
+    let operators = match &config.operators {
+        Some(operators) => operators_from_str(operators),
+        None => Operators::detect(&initial_population),
+    };
+    println!("[+] Using {:?} mutation operators", operators);
+    if config.dictionary {
+        let dictionary = Dictionary::from_grammar(&target::grammar(&target));
+        println!(
+            "[+] Dictionary from the grammar: {} tokens (rarest first) {:?}",
+            dictionary.len(),
+            dictionary
+                .tokens()
+                .map(String::from_utf8_lossy)
+                .collect::<Vec<_>>()
+        );
+    }
+    let crashes_out = &config.crashes_out;
+
+    // Compare the fuzzers over several repetitions of the campaign instead of
+    // a single one, and write the median coverage to plot.data.
+    if let Some(repetitions) = cli::option("--experiment") {
+        let repetitions = repetitions.parse().unwrap();
+        let results = experiment::run(
+            runner.as_ref(),
+            &|| make_fuzzers(&initial_population, operators, &slots, &config),
+            seed,
+            n,
+            repetitions,
+        )
+        .unwrap_or_else(abort);
+        results.print_report();
+
+        let mut logfile = BufWriter::new(std::fs::File::create(&plot_data).unwrap());
+        let median_curves = results.median_curves();
+        for i in 0..n {
+            write!(logfile, "{}", i).unwrap();
+            for median_curve in median_curves.iter() {
+                write!(logfile, " {}", median_curve[i]).unwrap();
+            }
+            writeln!(logfile).unwrap();
+        }
+        logfile.flush().unwrap();
+        return;
+    }
+
+    let mut fuzzers = make_fuzzers(&initial_population, operators, &slots, &config);
+
+    // Keep the best input per coverage signature on disk during the
+    // campaigns (shared by all fuzzers).
+    let mut store = config
+        .store
+        .as_ref()
+        .map(|dir| CoverageStore::open(dir).unwrap());
+
+    // Resume from the snapshot of an earlier campaign, if there is one (only
+    // the greybox fuzzer has state to snapshot).
+    let mut resume = config
+        .snapshot
+        .as_ref()
+        .filter(|path| Path::new(path).exists())
+        .map(|path| {
+            println!("[+] Reading snapshot {}", path);
+            Snapshot::load(path).unwrap_or_else(|err| abort(format!("{}: {}", path, err)))
+        });
+
+    let mut coverage_cumuls = Vec::new();
+
+    for fuzzer in fuzzers.iter_mut() {
+        let name = fuzzer.name();
+
+        // Same seed for each fuzzer.
+        let mut rng = rng::Rng::seeded(seed);
+
+        if fuzzer.snapshot().is_some() {
+            if let Some(snapshot) = resume.take() {
+                println!();
+                println!(
+                    "[+] Resuming {} ({} inputs, {} paths)",
+                    name,
+                    snapshot.population.len(),
+                    snapshot.coverage_db.len()
+                );
+                fuzzer.restore(snapshot);
+            }
+        }
+
+        let start = Instant::now();
+        let mut campaign = Campaign::new(fuzzer.as_mut(), runner.as_ref());
+        if let Some(store) = store.as_mut() {
+            campaign = campaign.with_store(store);
+        }
+        campaign.run(&mut rng, n).unwrap_or_else(abort);
+        let end = Instant::now();
+
+        let stats = campaign.stats;
+        println!();
+        println!("[+] {}:", name);
+        println!("    - Runtime:      {:0.4}s", (end - start).as_secs_f64());
+        println!(
+            "    - All coverage: {} {:?}",
+            stats.coverage_all.len(),
+            stats.coverage_all
+        );
+        if quiet {
+            println!(
+                "    - Crashes:      {} (first at fuzz case {:?})",
+                stats.crashes.len(),
+                stats.first_crash
+            );
+        } else {
+            println!(
+                "    - Crashes:      {} (first at fuzz case {:?}) {:?}",
+                stats.crashes.len(),
+                stats.first_crash,
+                stats.crashes
+            );
+        }
+        // Which functions (or files) the coverage is in, if the backend
+        // knows them: covered lines per function, or the names of the
+        // covered functions or files.
+        if let Some(functions) = runner.functions() {
+            let (label, report): (&str, Vec<String>) = match granularity {
+                Granularity::Line => (
+                    "By function: ",
+                    stats
+                        .coverage_all
+                        .by_function(&functions)
+                        .iter()
+                        .map(|(function, lines)| format!("{} {}", function, lines))
+                        .collect(),
+                ),
+                _ => (
+                    if granularity == Granularity::Function {
+                        "Functions:   "
+                    } else {
+                        "Files:       "
+                    },
+                    stats
+                        .coverage_all
+                        .iter()
+                        .map(|&location| functions.name(location, granularity))
+                        .collect(),
+                ),
+            };
+            println!("    - {} {}", label, report.join(", "));
+        }
+        for line in fuzzer.report() {
+            println!("    - {}", line);
+        }
+        println!("    - Unique crashes: {}", stats.unique_crashes.len());
+        if !quiet {
+            for (signature, (input, count)) in stats.unique_crashes.iter() {
+                println!("        {:5}x {} (e.g. {:?})", count, signature, input);
+            }
+        }
+        if stats.errors > 0 {
+            println!(
+                "    - Errors:       {} (last: {})",
+                stats.errors,
+                stats.last_error.as_ref().unwrap()
+            );
+        }
+        if stats.calibrations > 0 {
+            println!(
+                "    - Calibration:  {} inputs ({} extra runs), {} unstable, mean execution time {:0.3}ms",
+                stats.calibrations,
+                stats.calibration_runs,
+                stats.unstable_inputs,
+                stats.calibration_duration.as_secs_f64() * 1000.0 / stats.calibrations as f64
+            );
+        }
+
+        // Execution times: unusually slow inputs point to algorithmic
+        // complexity issues (if they are still slow when run again).
+        if let [Some(p50), Some(p90), Some(p99), Some(max)] =
+            stats.timings.percentiles(&[50.0, 90.0, 99.0, 100.0])[..]
+        {
+            let reproducible = stats
+                .timings
+                .reproducible_outliers(runner.as_ref(), OUTLIER_RUNS)
+                .unwrap_or_else(abort);
+            println!(
+                "    - Timing:       median {}, p90 {}, p99 {}, max {}",
+                format_duration(p50),
+                format_duration(p90),
+                format_duration(p99),
+                format_duration(max)
+            );
+            println!(
+                "    - Outliers:     {} slowest inputs > {}x median, {} still when run {} more times",
+                stats.timings.outliers().len(),
+                timing::OUTLIER_FACTOR,
+                reproducible.len(),
+                OUTLIER_RUNS
+            );
+            if !quiet {
+                println!("    - Slowest inputs:");
+                for (duration, input) in stats.timings.slowest().iter().take(5) {
+                    let rerun = reproducible.iter().find(|(_, i)| i == input);
+                    println!(
+                        "        {:>9} {:?}{}",
+                        format_duration(*duration),
+                        input,
+                        match rerun {
+                            Some((fastest, _)) => format!(
+                                " [!] outlier (fastest rerun {})",
+                                format_duration(*fastest)
+                            ),
+                            None => String::new(),
+                        }
+                    );
+                }
+            }
+        }
+
+        // Add the population to a libFuzzer/cargo-fuzz corpus directory.
+        if let Some(dir) = corpus_out {
+            let population = fuzzer.population();
+            let written = Corpus::new(dir).write(&population).unwrap();
+            println!(
+                "    - Corpus:       {} new of {} inputs written to {}",
+                written,
+                population.len(),
+                dir
+            );
+        }
+
+        // Save one input per unique crash, named like libFuzzer crash files,
+        // for later replay.
+        if let Some(dir) = crashes_out {
+            let crashes: Vec<_> = stats
+                .unique_crashes
+                .values()
+                .map(|(input, _)| input.clone())
+                .collect();
+            let written = Corpus::new(dir).write_prefixed("crash-", &crashes).unwrap();
+            println!(
+                "    - Crash files:  {} new of {} written to {}",
+                written,
+                crashes.len(),
+                dir
+            );
+        }
+
+        // Save the slowest inputs, named like the slow units of libFuzzer.
+        if let Some(dir) = &config.slowest_out {
+            let slowest: Vec<_> = stats
+                .timings
+                .slowest()
+                .iter()
+                .map(|(_, input)| input.clone())
+                .collect();
+            let written = Corpus::new(dir)
+                .write_prefixed("slow-unit-", &slowest)
+                .unwrap();
+            println!(
+                "    - Slow units:   {} new of {} written to {}",
+                written,
+                slowest.len(),
+                dir
+            );
+        }
+
+        // Save the state of the fuzzer for resuming the campaign.
+        if let (Some(path), Some(snapshot)) = (&config.snapshot, fuzzer.snapshot()) {
+            snapshot
+                .save(path)
+                .unwrap_or_else(|err| abort(format!("{}: {}", path, err)));
+            println!(
+                "    - Snapshot:     {} inputs, {} paths saved to {}",
+                snapshot.population.len(),
+                snapshot.coverage_db.len(),
+                path
+            );
+        }
+
+        if let Some(store) = store.as_mut() {
+            store.prune().unwrap();
+            println!(
+                "    - Store:        {} entries in {} ({} added, {} replaced, {} pruned so far)",
+                store.len(),
+                store.dir.display(),
+                store.stats.added,
+                store.stats.replaced,
+                store.stats.pruned
+            );
+        }
+
+        coverage_cumuls.push(stats.coverage_cumul);
+    }
+
+    if let Some(summary) = runner.summary() {
+        println!();
+        println!("[+] Runner: {}", summary);
+    }
+
+    // One column per fuzzer. Buffered: with the built-in targets, there are
+    // millions of lines.
+    let mut logfile = BufWriter::new(std::fs::File::create(&plot_data).unwrap());
+    for i in 0..n {
+        write!(logfile, "{}", i).unwrap();
+        for coverage_cumul in coverage_cumuls.iter() {
+            write!(logfile, " {}", coverage_cumul[i]).unwrap();
+        }
+        writeln!(logfile).unwrap();
+    }
+    logfile.flush().unwrap();
+}
+
+/// Run each seed `runs` times and report the seeds and locations with
+/// nondeterministic results. With `mask`, the unstable locations are removed
+/// from the coverage of all later runs.
+fn check_stability(
+    runner: Box<dyn CoverageBackend>,
+    seeds: &[Input],
+    runs: usize,
+    mask: bool,
+) -> Box<dyn CoverageBackend> {
+    let report = stability::check(runner.as_ref(), seeds, runs).unwrap_or_else(abort);
+    println!(
+        "[+] Stability: {:.0}% of {} seeds give the same result in {} runs",
+        report.stability() * 100.0,
+        seeds.len(),
+        runs
+    );
+    for seed in report.seeds.iter().filter(|seed| !seed.is_stable()) {
+        println!(
+            "    [!] {:?}: {}unstable locations {:?}",
+            seed.input,
+            if seed.crash_differs {
+                "crashes only sometimes, "
+            } else {
+                ""
+            },
+            seed.unstable_locations
+        );
+    }
+    if !mask || report.unstable_locations.is_empty() {
+        return runner;
+    }
+    println!(
+        "[+] Masking {} unstable locations {:?}",
+        report.unstable_locations.len(),
+        report.unstable_locations
+    );
+    Box::new(MaskedBackend::new(runner, report.unstable_locations))
+}
+
+/// Regression check for the bounded random numbers of `greybox::rng`, with
+/// 10^6 samples: `u8_range(32..=126)` gives exactly the 95 printable
+/// characters, each about equally often (chi² below 150 for 94 degrees of
+/// freedom; the 0.1% quantile is 138), and `int(3 * 2^62)` is below 2^62 in
+/// a third of the cases (the plain modulo would give one half). Exits with
+/// status 1 if any of these doesn't hold.
+fn check_rng() {
+    const SAMPLES: usize = 1_000_000;
+    let mut rng = rng::Rng::seeded(cli::seed().unwrap_or(42));
+    let mut failures = 0;
+    let mut check = |ok: bool, description: String| {
+        if ok {
+            println!("    - {}", description);
+        } else {
+            println!("[!] {}", description);
+            failures += 1;
+        }
+    };
+
+    let mut counts = [0usize; 0x100];
+    for _ in 0..SAMPLES {
+        counts[rng.u8_range(32..=126) as usize] += 1;
+    }
+    let outside: usize = (0..0x100)
+        .filter(|b| !(32..=126).contains(b))
+        .map(|b| counts[b])
+        .sum();
+    let missing = counts[32..=126].iter().filter(|&&c| c == 0).count();
+    check(
+        outside == 0 && missing == 0,
+        format!(
+            "u8_range(32..=126): {} values outside, {} of 95 values missing",
+            outside, missing
+        ),
+    );
+    let expected = SAMPLES as f64 / 95.0;
+    let chi2: f64 = counts[32..=126]
+        .iter()
+        .map(|&c| (c as f64 - expected).powi(2) / expected)
+        .sum();
+    check(
+        chi2 < 150.0,
+        format!(
+            "u8_range(32..=126): chi² {:.1} for 94 degrees of freedom",
+            chi2
+        ),
+    );
+
+    let below = (0..SAMPLES).filter(|_| rng.int(3 << 62) < 1 << 62).count();
+    let ratio = below as f64 / SAMPLES as f64;
+    check(
+        (ratio - 1.0 / 3.0).abs() < 0.01,
+        format!(
+            "int(3 * 2^62) below 2^62: {:.1}% (expected 33.3%)",
+            ratio * 100.0
+        ),
+    );
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// The fuzzers that are compared. With slots, the mutation based fuzzers
+/// mutate the files jointly or independently (the random and grammar fuzzers
+/// know nothing about files; all of their output goes to the first one). The
+/// grammar fuzzer, and the dictionary of the mutation based fuzzers, use the
+/// grammar of the target.
+fn make_fuzzers(
+    initial_population: &[Input],
+    operators: Operators,
+    slots: &Option<Slots>,
+    config: &CampaignConfig,
+) -> Vec<Box<dyn Fuzzer>> {
+    let grammar = target::grammar(&config.target);
+
+    let (min, max) = (config.min_mutations, config.max_mutations);
+    let mut mutation =
+        MutationFuzzer::new(initial_population.to_vec(), min, max).with_operators(operators);
+    let mut coverage = MutationCoverageFuzzer::new(initial_population.to_vec(), min, max)
+        .with_operators(operators);
+    let mut weighted = MutationCoverageFuzzer::weighted(initial_population.to_vec(), min, max)
+        .with_operators(operators);
+    let mut greybox = GreyboxFuzzer::new(initial_population.to_vec())
+        .with_operators(operators)
+        .with_schedule(Schedule::Exponential {
+            exponent: config.exponent,
+        });
+    if config.secondary {
+        greybox = greybox.with_secondary(SecondaryQueue::new());
+    }
+    if config.dictionary {
+        let dictionary = Dictionary::from_grammar(&grammar);
+        mutation = mutation.with_dictionary(dictionary.clone());
+        coverage = coverage.with_dictionary(dictionary.clone());
+        weighted = weighted.with_dictionary(dictionary.clone());
+        greybox = greybox.with_dictionary(dictionary);
+    }
+    if let Some(slots) = slots {
+        mutation = mutation.with_slots(slots.clone());
+        coverage = coverage.with_slots(slots.clone());
+        weighted = weighted.with_slots(slots.clone());
+        greybox = greybox.with_slots(slots.clone());
+    }
+
+    vec![
+        Box::new(RandomFuzzer::default()),
+        Box::new(GrammarFuzzer::new(grammar)),
+        Box::new(mutation),
+        Box::new(coverage),
+        Box::new(weighted),
+        Box::new(greybox),
+    ]
+}
+
+/// Read the campaign configuration from the file given with `--config <file>`
+/// (or use the defaults), and override it with the command line options
+/// `--seed`, `--iterations` (or `--budget`), `--target`, `--quiet` (also as
+/// environment variables, see `greybox::cli`), `--builtin`,
+/// `--sanitize`, `--oracle`, `--granularity`, `--postprocess`, `--files`, `--slot-mutation`,
+/// `--timeout-ms`, `--operators`, `--dictionary`, `--secondary`, `--corpus`, `--max-seed-size`,
+/// `--corpus-out`, `--crashes-out`, `--slowest-out`, `--store` and `--snapshot`.
+fn config_from_cli() -> CampaignConfig {
+    let mut config = match cli::option("--config") {
+        Some(path) => {
+            println!("[+] Reading campaign configuration from {}", path);
+            CampaignConfig::from_file(path.as_ref())
+                .unwrap_or_else(|err| abort(format!("{}: {}", path, err)))
+        }
+        None => CampaignConfig::default(),
+    };
+    if let Some(seed) = cli::seed() {
+        config.seed = seed;
+    }
+    config.budget = cli::iterations(cli::parsed("--budget", config.budget));
+    config.target = cli::target(&config.target);
+    config.quiet |= cli::quiet();
+    config.builtin |= cli::flag("--builtin");
+    config.sanitize |= cli::flag("--sanitize");
+    config.oracle = cli::option("--oracle").unwrap_or(config.oracle);
+    config.granularity = cli::option("--granularity").unwrap_or(config.granularity);
+    config.postprocess = cli::option("--postprocess").or(config.postprocess);
+    config.files = cli::option("--files").or(config.files);
+    config.slot_mutation = cli::option("--slot-mutation").unwrap_or(config.slot_mutation);
+    if cli::option("--timeout-ms").is_some() {
+        config.timeout_ms = Some(cli::parsed("--timeout-ms", 0));
+    }
+    config.operators = cli::option("--operators").or(config.operators);
+    config.dictionary |= cli::flag("--dictionary");
+    config.secondary |= cli::flag("--secondary");
+    config.seeds = cli::option("--corpus").or(config.seeds);
+    config.max_seed_size = cli::parsed("--max-seed-size", config.max_seed_size);
+    config.corpus_out = cli::option("--corpus-out").or(config.corpus_out);
+    config.crashes_out = cli::option("--crashes-out").or(config.crashes_out);
+    config.slowest_out = cli::option("--slowest-out").or(config.slowest_out);
+    config.store = cli::option("--store").or(config.store);
+    config.snapshot = cli::option("--snapshot").or(config.snapshot);
+    config
+}
+
+/// Select how crashes are detected with `--oracle <oracle>`:
+///   - `exit`: non-zero exit code or signal (default)
+///   - `signal`: only signals
+///   - `stderr:<text>`: stderr contains the text (e.g. `stderr:AddressSanitizer`)
+///   - `output-length`: the output is longer than the input (an invariant of
+///     cgi_decode, which can only make its input shorter), or any exit code
+///     other than 0
+fn oracle_from_str(value: &str) -> Box<dyn oracle::Oracle> {
+    match value {
+        "exit" => Box::new(oracle::ExitStatusOracle),
+        "signal" => Box::new(oracle::SignalOracle),
+        "output-length" => Box::new(oracle::AnyOracle(vec![
+            Box::new(oracle::ExitStatusOracle),
+            Box::new(oracle::InvariantOracle {
+                // The program prints a trailing newline.
+                invariant: |input: &Input, execution: &oracle::Execution| {
+                    execution.stdout.len() <= input.0.len() + 1
+                },
+            }),
+        ])),
+        _ => match value.strip_prefix("stderr:") {
+            Some(pattern) => Box::new(oracle::StderrOracle {
+                pattern: pattern.to_string(),
+            }),
+            None => panic!("Unknown oracle {}", value),
+        },
+    }
+}
+
+/// Select the postprocessors with `--postprocess <name>,<name>,...` (applied
+/// in this order):
+///   - `base64`: base64 encoding
+///   - `url`: percent-encoding of everything but `A-Z a-z 0-9 - . _ ~`
+///   - `gzip`: gzip (with uncompressed blocks)
+///   - `magic:<hex>`: prepend the bytes, e.g. `magic:89504e47`
+///   - `crc32:<offset>`, `crc32be:<offset>`: write the CRC-32 of the rest of
+///     the input after the 4 bytes at the offset to these bytes (little or
+///     big endian)
+fn postprocessor_from_str(value: &str) -> Box<dyn Postprocessor> {
+    let parse_offset = |offset: &str| {
+        offset
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid offset {}", offset))
+    };
+    let chain = value
+        .split(',')
+        .map(|name| -> Box<dyn Postprocessor> {
+            match name.split_once(':') {
+                None if name == "base64" => Box::new(postprocess::Base64),
+                None if name == "url" => Box::new(postprocess::UrlEncode),
+                None if name == "gzip" => Box::new(postprocess::Gzip),
+                Some(("magic", hex)) => Box::new(postprocess::Prepend(
+                    from_hex(hex).unwrap_or_else(|| panic!("Invalid hex {}", hex)),
+                )),
+                Some(("crc32", offset)) => Box::new(postprocess::Crc32 {
+                    offset: parse_offset(offset),
+                    big_endian: false,
+                }),
+                Some(("crc32be", offset)) => Box::new(postprocess::Crc32 {
+                    offset: parse_offset(offset),
+                    big_endian: true,
+                }),
+                _ => panic!("Unknown postprocessor {}", name),
+            }
+        })
+        .collect();
+    Box::new(postprocess::Chain(chain))
+}
+
+/// Bytes from a hex string like `89504e47`.
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Select how the files of multi-file inputs are mutated with
+/// `--slot-mutation joint|independent`: all mutations on the whole input
+/// (including the separators), or each mutation on one of the files.
+fn slot_mutation_from_str(value: &str) -> SlotMutation {
+    match value {
+        "joint" => SlotMutation::Joint,
+        "independent" => SlotMutation::Independent,
+        _ => panic!("Unknown slot mutation {}", value),
+    }
+}
+
+/// Override the mutation operators with `--operators text|utf8|binary`.
+fn operators_from_str(value: &str) -> Operators {
+    match value {
+        "text" => Operators::Text,
+        "utf8" => Operators::Utf8,
+        "binary" => Operators::Binary,
+        _ => panic!("Unknown operators {}", value),
+    }
+}
+
+/// Duration in milliseconds, or microseconds below 1ms, for the report.
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_millis(1) {
+        format!("{:.1}µs", duration.as_secs_f64() * 1e6)
+    } else {
+        format!("{:.3}ms", duration.as_secs_f64() * 1e3)
+    }
+}
+
+/// Print the error and exit (for setup errors, which are not bugs of the
+/// fuzzer and don't need a backtrace).
+fn abort<T>(err: impl std::fmt::Display) -> T {
+    eprintln!("[!] Error: {}", err);
+    std::process::exit(1);
+}
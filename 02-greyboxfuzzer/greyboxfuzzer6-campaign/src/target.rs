@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Built-in test targets: Rust ports of the C example programs, so that the
+// examples also run on systems without gcc/gcov.
+
+use greybox::fuzzer::Grammar;
+use greybox::harness::{FuzzTarget, TargetResult};
+use greybox::target::{Registry, TestTarget, Tracer};
+
+/// Port of `crashme.c`: performs incremental comparisons and then finally
+/// "crashes" on the input `bad!`.
+pub struct Crashme;
+
+impl TestTarget for Crashme {
+    fn execute(&self, input: &[u8], tracer: &mut Tracer) -> bool {
+        tracer.hit(line!());
+        if input.first() != Some(&b'b') {
+            tracer.hit(line!());
+            return true;
+        }
+        tracer.hit(line!());
+        if input.get(1) != Some(&b'a') {
+            tracer.hit(line!());
+            return true;
+        }
+        tracer.hit(line!());
+        if input.get(2) != Some(&b'd') {
+            tracer.hit(line!());
+            return true;
+        }
+        tracer.hit(line!());
+        if input.get(3) != Some(&b'!') {
+            tracer.hit(line!());
+            return true;
+        }
+        tracer.hit(line!());
+        false // Crash.
+    }
+}
+
+/// Port of `transactions.c`: looks for the keywords `BEGIN`, `DELETE users`
+/// and `COMMIT` (in this order, anywhere in the input) and then "crashes".
+pub struct Transactions;
+
+/// Position of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+impl TestTarget for Transactions {
+    fn execute(&self, input: &[u8], tracer: &mut Tracer) -> bool {
+        tracer.hit(line!());
+        let Some(begin) = find(input, b"BEGIN") else {
+            tracer.hit(line!());
+            return true;
+        };
+        tracer.hit(line!());
+        let Some(delete) = find(&input[begin..], b"DELETE users").map(|i| begin + i) else {
+            tracer.hit(line!());
+            return true;
+        };
+        tracer.hit(line!());
+        if find(&input[delete..], b"COMMIT").is_none() {
+            tracer.hit(line!());
+            return true;
+        }
+        tracer.hit(line!());
+        false // Crash.
+    }
+}
+
+/// Port of `cgi_decode.c` (see 02-coverage): decodes `+` to space and `%xx`
+/// to the byte with hex value `xx`. Fails on invalid hex digits.
+pub struct CgiDecode;
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+impl TestTarget for CgiDecode {
+    fn execute(&self, input: &[u8], tracer: &mut Tracer) -> bool {
+        tracer.hit(line!());
+        let mut res = Vec::new();
+        let mut i = 0;
+        while i < input.len() {
+            tracer.hit(line!());
+            if input[i] == b'+' {
+                tracer.hit(line!());
+                res.push(b' ');
+            } else if input[i] == b'%' {
+                tracer.hit(line!());
+                // Unlike the C program, we don't read past the end of the input.
+                let digit_high = input.get(i + 1).and_then(|&c| hex_value(c));
+                let digit_low = input.get(i + 2).and_then(|&c| hex_value(c));
+                match (digit_high, digit_low) {
+                    (Some(high), Some(low)) => {
+                        tracer.hit(line!());
+                        res.push(high * 16 + low);
+                    }
+                    _ => {
+                        tracer.hit(line!());
+                        return false;
+                    }
+                }
+                i += 2;
+            } else {
+                tracer.hit(line!());
+                res.push(input[i]);
+            }
+            i += 1;
+        }
+        tracer.hit(line!());
+        true
+    }
+}
+
+/// Port of `cgi_decode.c` as fuzz target for the Rust harness, written like a
+/// Rust library function would be (without the checks of `CgiDecode`): a
+/// `%` at the end of the input, or an invalid hex digit, panic.
+pub struct CgiDecodeRust;
+
+impl FuzzTarget for CgiDecodeRust {
+    fn run(&mut self, data: &[u8]) -> TargetResult {
+        let mut tracer = Tracer::default();
+        tracer.hit(line!());
+        let mut res = Vec::new();
+        let mut i = 0;
+        while i < data.len() {
+            tracer.hit(line!());
+            if data[i] == b'+' {
+                tracer.hit(line!());
+                res.push(b' ');
+            } else if data[i] == b'%' {
+                tracer.hit(line!());
+                let digit_high = hex_value(*data.get(i + 1).unwrap()).expect("invalid hex digit");
+                let digit_low = hex_value(*data.get(i + 2).unwrap()).expect("invalid hex digit");
+                tracer.hit(line!());
+                res.push(digit_high * 16 + digit_low);
+                i += 2;
+            } else {
+                tracer.hit(line!());
+                res.push(data[i]);
+            }
+            i += 1;
+        }
+        tracer.hit(line!());
+
+        // Decoding never makes the input longer.
+        if res.len() > data.len() {
+            return TargetResult::Crash("output longer than input".to_string());
+        }
+        TargetResult::Ok(tracer.coverage)
+    }
+}
+
+/// The built-in targets that are run with `--builtin` (or when gcc/gcov are
+/// not available).
+pub fn registry() -> Registry {
+    Registry::new()
+        .with(
+            "crashme",
+            "Port of crashme.c, crashes on `bad!`",
+            |input, tracer| Crashme.execute(input, tracer),
+        )
+        .with(
+            "cgi_decode",
+            "Port of cgi_decode.c, fails on invalid `%xx`",
+            |input, tracer| CgiDecode.execute(input, tracer),
+        )
+        .with(
+            "transactions",
+            "Port of transactions.c, crashes on `BEGIN`, `DELETE users`, `COMMIT`",
+            |input, tracer| Transactions.execute(input, tracer),
+        )
+}
+
+/// Grammar of the inputs of the target, for the grammar fuzzer and the
+/// dictionary: the statements of transactions, and otherwise words of the
+/// characters of crashme.
+pub fn grammar(target: &str) -> Grammar {
+    match target {
+        "transactions" => Grammar::from([
+            ("<start>", vec!["<statements>"]),
+            (
+                "<statements>",
+                vec!["<statement>", "<statement>; <statements>"],
+            ),
+            (
+                "<statement>",
+                vec![
+                    "BEGIN",
+                    "COMMIT",
+                    "ROLLBACK",
+                    "SELECT <columns> FROM <table>",
+                    "INSERT INTO <table> VALUES (<values>)",
+                    "DELETE <table>",
+                ],
+            ),
+            ("<columns>", vec!["*", "<column>", "<column>, <columns>"]),
+            ("<column>", vec!["id", "name"]),
+            ("<values>", vec!["<value>", "<value>, <values>"]),
+            ("<value>", vec!["1", "2", "NULL", "'name'"]),
+            ("<table>", vec!["users", "orders"]),
+        ]),
+        _ => Grammar::from([
+            ("<start>", vec!["<word>"]),
+            ("<word>", vec!["<char>", "<char><word>"]),
+            ("<char>", vec!["a", "b", "d", "g", "o", "!"]),
+        ]),
+    }
+}
@@ -0,0 +1,31 @@
+// Example program that looks for keywords anywhere in its input (given on the
+// commandline), like a simple command language, and finally crashes on
+// `BEGIN`, followed by `DELETE users`, followed by `COMMIT`. Unlike crashme,
+// matching a keyword character by character gives no new coverage, so
+// byte-level mutations practically never find them (see the dictionary
+// mutations).
+
+#include <string.h>
+
+int main(int argc, char *argv[]) {
+    if (argc != 2) {
+        return 0;
+    }
+
+    char *input = argv[1];
+
+    char *begin = strstr(input, "BEGIN");
+    if (begin == NULL) {
+        return 0;
+    }
+    char *delete = strstr(begin, "DELETE users");
+    if (delete == NULL) {
+        return 0;
+    }
+    char *commit = strstr(delete, "COMMIT");
+    if (commit == NULL) {
+        return 0;
+    }
+
+    return 1; // assert(0); // Crash.
+}
@@ -0,0 +1,64 @@
+// Example program that reads two files given on the commandline: a
+// configuration file and a data file. Like crashme, it performs incremental
+// comparisons, and finally crashes if the configuration starts with 'v2' and
+// the data with 'bad!'. Neither file alone is enough.
+
+#include <stdio.h>
+
+// Read up to `size` bytes of the file into `buf`; returns the number of bytes
+// read (0 if the file can't be opened).
+static size_t read_file(const char *path, char *buf, size_t size) {
+    FILE *f = fopen(path, "rb");
+    if (f == NULL) {
+        return 0;
+    }
+    size_t len = fread(buf, 1, size, f);
+    fclose(f);
+    return len;
+}
+
+static int check_config(const char *config, size_t len) {
+    if (!(len > 0 && config[0] == 'v')) {
+        return 0;
+    }
+    if (!(len > 1 && config[1] == '2')) {
+        return 0;
+    }
+    return 1;
+}
+
+static int check_data(const char *data, size_t len) {
+    if (!(len > 0 && data[0] == 'b')) {
+        return 0;
+    }
+    if (!(len > 1 && data[1] == 'a')) {
+        return 0;
+    }
+    if (!(len > 2 && data[2] == 'd')) {
+        return 0;
+    }
+    if (!(len > 3 && data[3] == '!')) {
+        return 0;
+    }
+    return 1;
+}
+
+int main(int argc, char *argv[]) {
+    if (argc != 3) {
+        return 0;
+    }
+
+    char config[64];
+    char data[64];
+    size_t config_len = read_file(argv[1], config, sizeof(config));
+    size_t data_len = read_file(argv[2], data, sizeof(data));
+
+    if (!check_config(config, config_len)) {
+        return 0;
+    }
+    if (!check_data(data, data_len)) {
+        return 0;
+    }
+
+    return 1; // assert(0); // Crash.
+}
@@ -5,18 +5,38 @@
 
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html
 
+mod bytes;
+mod cli;
 mod rng;
 
+use bytes::Bytes;
 use rng::Rng;
 
 fn main() {
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
+
     let seed_input = Bytes("A quick brown fox".as_bytes().to_vec());
+    let n = cli::iterations(10);
 
-    for _ in 0..10 {
-        let inp = delete_random_character(seed_input.clone());
-        println!("{}", inp);
+    let inputs = generate(&mut rng, &seed_input, n);
+    // One block per mutation operator.
+    for block in inputs.chunks(n.max(1)) {
+        for inp in block {
+            println!("{}", inp);
+        }
+        println!();
+    }
+}
+
+/// Mutate `seed_input` `n` times with each of the operators (delete, insert,
+/// flip, and a random one of these three).
+fn generate(rng: &mut Rng, seed_input: &Bytes, n: usize) -> Vec<Bytes> {
+    let mut res = Vec::new();
+
+    for _ in 0..n {
+        res.push(delete_random_character(rng, seed_input.clone()));
     }
-    println!();
     // A quick brown fx
     // A quickbrown fox
     // A quick brow fox
@@ -28,11 +48,9 @@ fn main() {
     // A quck brown fox
     // A quick brow fox
 
-    for _ in 0..10 {
-        let inp = insert_random_character(seed_input.clone());
-        println!("{}", inp);
+    for _ in 0..n {
+        res.push(insert_random_character(rng, seed_input.clone()));
     }
-    println!();
     // Ak quick brown fox
     // A quick brownW fox
     // A quick %brown fox
@@ -44,11 +62,9 @@ fn main() {
     // A quick brow(n fox
     // A qui/ck brown fox
 
-    for _ in 0..10 {
-        let inp = flip_random_bit(seed_input.clone());
-        println!("{}", inp);
+    for _ in 0..n {
+        res.push(flip_random_bit(rng, seed_input.clone()));
     }
-    println!();
     // A quick bsown fox
     // A quick$brown fox
     // A uuick brown fox
@@ -60,10 +76,9 @@ fn main() {
     // A quick brown fx
     // A$quick brown fox
 
-    for _ in 0..10 {
-        println!("{}", mutate(seed_input.clone()));
+    for _ in 0..n {
+        res.push(mutate(rng, seed_input.clone()));
     }
-    println!();
     // a quick brown fox
     // Aquick brown fox
     // A quick brownfox
@@ -75,54 +90,53 @@ fn main() {
     // A quick bown fox
     // A quick rown fox
 
-    println!("Hello, world!");
+    res
 }
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug)]
-struct Bytes(Vec<u8>);
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Bytes {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", std::str::from_utf8(&self.0).unwrap())
-    }
-}
-
-fn mutate(s: Bytes) -> Bytes {
-    match Rng::new().int(3) {
-        0 => delete_random_character(s),
-        1 => insert_random_character(s),
-        2 => flip_random_bit(s),
+fn mutate(rng: &mut Rng, s: Bytes) -> Bytes {
+    match rng.int(3) {
+        0 => delete_random_character(rng, s),
+        1 => insert_random_character(rng, s),
+        2 => flip_random_bit(rng, s),
         _ => panic!("Can't happen"),
     }
 }
 
-fn delete_random_character(mut s: Bytes) -> Bytes {
+fn delete_random_character(rng: &mut Rng, mut s: Bytes) -> Bytes {
     if s.0.is_empty() {
         s
     } else {
-        let mut rng = Rng::new();
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
 }
 
-fn insert_random_character(mut s: Bytes) -> Bytes {
-    let mut rng = Rng::new();
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+fn insert_random_character(rng: &mut Rng, mut s: Bytes) -> Bytes {
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
 
-fn flip_random_bit(mut s: Bytes) -> Bytes {
-    let mut rng = Rng::new();
-    let pos = rng.int(s.0.len() as u64) as usize;
+fn flip_random_bit(rng: &mut Rng, mut s: Bytes) -> Bytes {
+    let pos = rng.usize_below(s.0.len());
     let bit = 1 << rng.int(7);
     s.0[pos] ^= bit;
     s
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All random decisions are taken from the one generator that is passed
+    /// to the mutators, so the seed is enough to replay a run.
+    #[test]
+    fn seed_replays_run() {
+        let seed_input = Bytes("A quick brown fox".as_bytes().to_vec());
+        let inputs = generate(&mut Rng::seeded(42), &seed_input, 10);
+        let replayed = generate(&mut Rng::seeded(42), &seed_input, 10);
+        assert_eq!(replayed, inputs);
+    }
+}
@@ -3,33 +3,40 @@
 //
 // SPDX-License-Identifier: MIT
 
+use crate::bytes::Bytes;
 use crate::rng::Rng;
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug)]
-pub struct Bytes(Vec<u8>);
-
-impl Bytes {
-    /// Convert a `&str` to `Bytes`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
+pub trait Fuzzer {
+    fn fuzz(&self, rng: &mut Rng) -> Bytes;
 }
 
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Bytes {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", std::str::from_utf8(&self.0).unwrap())
-    }
+/// Distribution of the number of mutations that are stacked on top of each
+/// other to create one fuzz case. Few mutations stay close to the seed
+/// (exploitation), many wander further off from it (exploration).
+#[derive(Clone, Debug)]
+pub enum MutationCount {
+    /// Always the same number of mutations.
+    Fixed(usize),
+
+    /// Uniformly distributed in the range [min, max).
+    Uniform(usize, usize),
+
+    /// 1 + a geometrically distributed number (mean 1/p), capped at `max`:
+    /// mostly few mutations, but every now and then many.
+    Geometric { p: f64, max: usize },
 }
 
-pub trait Fuzzer {
-    fn fuzz(&self, rng: &mut Rng) -> Bytes;
+impl MutationCount {
+    /// Sample the number of mutations for the next fuzz case.
+    pub fn sample(&self, rng: &mut Rng) -> usize {
+        match *self {
+            MutationCount::Fixed(n) => n,
+            MutationCount::Uniform(min, max) => rng.range(min as u64, max as u64) as usize,
+            MutationCount::Geometric { p, max } => {
+                std::cmp::min(1 + rng.geometric(p) as usize, max)
+            }
+        }
+    }
 }
 
 pub struct MutationFuzzer {
@@ -37,6 +44,9 @@ pub struct MutationFuzzer {
     /// candidates are drawn from here, before we start mutating.
     initial_population: Vec<Bytes>,
 
+    /// Number of mutations applied to create a candidate.
+    pub mutations: MutationCount,
+
     /// Number of times this fuzzer has produced output. We use interior
     /// mutability here in order to not violate the trait definition and keep
     /// the `self` passed to `fuzz` immutable.
@@ -44,26 +54,22 @@ pub struct MutationFuzzer {
 }
 
 impl MutationFuzzer {
+    /// Fuzzer with 2 to 10 mutations per candidate (uniformly distributed).
     pub fn new(seed: Vec<Bytes>) -> Self {
+        Self::with_mutations(seed, MutationCount::Uniform(2, 10 + 1))
+    }
+
+    pub fn with_mutations(seed: Vec<Bytes>, mutations: MutationCount) -> Self {
         Self {
             initial_population: seed,
+            mutations,
             nfuzzed: std::cell::RefCell::new(0),
         }
     }
 
-    pub fn fuzz_(&self, rng: &mut Rng, min_mutations: usize, max_mutations: usize) -> Bytes {
-        let nfuzzed = *self.nfuzzed.borrow();
-        *self.nfuzzed.borrow_mut() = nfuzzed + 1;
-        if nfuzzed < self.initial_population.len() {
-            self.initial_population[nfuzzed].clone()
-        } else {
-            self.create_candidate(rng, min_mutations, max_mutations)
-        }
-    }
-
-    fn create_candidate(&self, rng: &mut Rng, min_mutations: usize, max_mutations: usize) -> Bytes {
+    fn create_candidate(&self, rng: &mut Rng) -> Bytes {
         let mut candidate = rng.choice(&self.initial_population).clone();
-        let trials = rng.range(min_mutations as u64, max_mutations as u64);
+        let trials = self.mutations.sample(rng);
         for _ in 0..trials {
             candidate = mutate(rng, candidate);
         }
@@ -73,7 +79,13 @@ impl MutationFuzzer {
 
 impl Fuzzer for MutationFuzzer {
     fn fuzz(&self, rng: &mut Rng) -> Bytes {
-        self.fuzz_(rng, 2, 10 + 1)
+        let nfuzzed = *self.nfuzzed.borrow();
+        *self.nfuzzed.borrow_mut() = nfuzzed + 1;
+        if nfuzzed < self.initial_population.len() {
+            self.initial_population[nfuzzed].clone()
+        } else {
+            self.create_candidate(rng)
+        }
     }
 }
 
@@ -91,21 +103,21 @@ fn delete_random_character(rng: &mut Rng, mut s: Bytes) -> Bytes {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Bytes) -> Bytes {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
 
 fn flip_random_bit(rng: &mut Rng, mut s: Bytes) -> Bytes {
-    let pos = rng.int(s.0.len() as u64) as usize;
+    let pos = rng.usize_below(s.0.len());
     let bit = 1 << rng.int(7);
     s.0[pos] ^= bit;
     s
@@ -5,18 +5,21 @@
 
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html Multiple Mutations
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
-use fuzzer::{mutate, Bytes, Fuzzer, MutationFuzzer};
+use bytes::Bytes;
+use fuzzer::{mutate, Fuzzer, MutationCount, MutationFuzzer};
 use rng::Rng;
 
 fn main() {
-    let mut rng = Rng::new();
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
 
     let seed_input = Bytes::from_str("http://www.google.com/search?q=fuzzing");
 
-    let mutations = 50;
+    let mutations = cli::iterations(50);
     let mut input = seed_input.clone();
     for i in 0..mutations {
         if i % 5 == 0 {
@@ -36,7 +39,7 @@ fn main() {
     // 40 htt/ovsw.g/*la.bksejauchfI<u[xzgF
     // 45 htt/ovwg/*la.bksgyejuchfI<u[xzgF
 
-    let mutation_fuzzer = MutationFuzzer::new(vec![seed_input]);
+    let mutation_fuzzer = MutationFuzzer::new(vec![seed_input.clone()]);
     let mutations = 10;
     for _ in 0..mutations {
         println!("{}", mutation_fuzzer.fuzz(&mut rng));
@@ -51,4 +54,41 @@ fn main() {
     // htup//www.google.com/search?q=fuzzbg
     // http~://fww.googl.com/search?q=fuzziDng
     // http:/Qwww.goo'{le.com/srhq=fuzz4kng
+
+    // The number of stacked mutations per candidate is sampled from a
+    // configurable distribution.
+    for mutations in [
+        MutationCount::Fixed(1),
+        MutationCount::Uniform(2, 10 + 1),
+        MutationCount::Geometric { p: 0.2, max: 50 },
+    ] {
+        let samples: Vec<_> = (0..1000).map(|_| mutations.sample(&mut rng)).collect();
+        println!();
+        println!(
+            "{:?}: mean {:.1}, max {} mutations",
+            mutations,
+            samples.iter().sum::<usize>() as f64 / samples.len() as f64,
+            samples.iter().max().unwrap()
+        );
+
+        let mutation_fuzzer = MutationFuzzer::with_mutations(vec![seed_input.clone()], mutations);
+        mutation_fuzzer.fuzz(&mut rng); // The seed itself.
+        for _ in 0..3 {
+            println!("{}", mutation_fuzzer.fuzz(&mut rng));
+        }
+    }
+    // Fixed(1): mean 1.0, max 1 mutations
+    // Uhttp://www.google.com/search?q=fuzzing
+    // http://www.goog2le.com/search?q=fuzzing
+    // http://www.google.com/qearch?q=fuzzing
+    //
+    // Uniform(2, 11): mean 6.0, max 10 mutations
+    // http//w7w.googh>com/sNearch?q=,f<uzzing
+    // htp//^www.ogle.comf/searchc?Upq=fzzing
+    // http://www{.google.com/seavch?q=fuzzig
+    //
+    // Geometric { p: 0.2, max: 50 }: mean 5.1, max 35 mutations
+    // http://www.ooogle.cOm/se2arch?q=fuzzing
+    // htp://uww7.gooele.co/search?q=bujziog
+    // http:/'Www.googlfE&com/searZcx?q=efuzziNg
 }
@@ -6,6 +6,8 @@
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html Guiding by Coverage
 
 /// Wrappers around easily gathering code coverage.
+mod bytes;
+mod cli;
 mod coverage;
 mod fuzzer;
 
@@ -14,7 +16,7 @@ use coverage::run_and_get_coverage;
 fn main() {
     let input = fuzzer::Input::from_str("http://www.google.com/search?q=fuzzing");
 
-    let coverage = run_and_get_coverage(input);
+    let coverage = run_and_get_coverage(input, &cli::target("cgi_decode"));
     println!("{:#?}", coverage);
 
     // re-formatted output:
@@ -8,28 +8,8 @@ use crate::rng::Rng;
 
 use std::collections::BTreeSet;
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
-pub struct Input(Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", str::from_utf8(&self.0).unwrap())
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 pub struct MutationCoverageFuzzer {
     /// The size of the initial population. This is need for distinguishing
@@ -80,7 +60,12 @@ impl MutationCoverageFuzzer {
         self.population.clone()
     }
 
-    pub fn runs(&mut self, rng: &mut Rng, n: usize) -> (Coverage, CumulativeCoverage) {
+    pub fn runs(
+        &mut self,
+        rng: &mut Rng,
+        program: &str,
+        n: usize,
+    ) -> (Coverage, CumulativeCoverage) {
         // Current coverage (union of all coverages during execution; set of
         // unique locations).
         let mut coverage: Coverage = BTreeSet::new();
@@ -91,7 +76,7 @@ impl MutationCoverageFuzzer {
         for _ in 0..n {
             let input = self.fuzz(rng);
 
-            let (runcoverage, runoutcome) = run_and_get_coverage(&input);
+            let (runcoverage, runoutcome) = run_and_get_coverage(&input, program);
 
             if runoutcome == RunResult::Pass
                 && !runcoverage
@@ -135,21 +120,21 @@ fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
 
 fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int(s.0.len() as u64) as usize;
+    let pos = rng.usize_below(s.0.len());
     let bit = 1 << rng.int(7);
     s.0[pos] ^= bit;
     s
@@ -6,6 +6,8 @@
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html Guiding by Coverage
 
 /// Wrappers around easily gathering code coverage.
+mod bytes;
+mod cli;
 mod coverage;
 mod fuzzer;
 mod rng;
@@ -23,22 +25,25 @@ use rng::Rng;
 // [+] Final coverage: 43
 
 fn main() {
-    let mut rng = Rng::new();
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
     println!("[+] Running with random seed {}", rng.initialseed);
     println!();
 
     let input = fuzzer::Input::from_str("http://www.google.com/search?q=fuzzing");
 
     let mut mutation_fuzzer = MutationCoverageFuzzer::new(vec![input]);
-    let (cov_all, cov_cumul) = mutation_fuzzer.runs(&mut rng, 30);
+    let (cov_all, cov_cumul) =
+        mutation_fuzzer.runs(&mut rng, &cli::target("cgi_decode"), cli::iterations(30));
 
     let pop = mutation_fuzzer.population();
 
-    println!("[+] Final population");
-    for el in pop {
-        println!("{}", el);
+    if !cli::quiet() {
+        println!("[+] Final population");
+        for el in pop {
+            println!("{}", el);
+        }
+        println!();
     }
-    println!();
 
     println!("[+] Final coverage: {}", cov_all.len());
 
@@ -47,5 +52,5 @@ fn main() {
     // fuzz cases => the code coverage doesn't improve significantly
     // => the diagram is relatively useless.
     // => write multithreaded implementation.
-    plot_cumulative_coverage(&cov_cumul);
+    plot_cumulative_coverage(&cov_cumul, &cli::out_file("plot.data"));
 }
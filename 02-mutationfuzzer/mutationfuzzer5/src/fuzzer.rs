@@ -9,30 +9,11 @@ use crate::rng::Rng;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::io::Write;
+use std::path::Path;
 use std::process;
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
-pub struct Input(Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", str::from_utf8(&self.0).unwrap())
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 /// Statistics relevant during fuzzing.
 #[derive(Default)]
@@ -71,7 +52,7 @@ impl Statistics {
 }
 
 /// Create and run `n` random fuzz cases and record statistics during execution.
-pub fn run(rng: &mut Rng, stats: &mut Statistics, n: usize) {
+pub fn run(rng: &mut Rng, stats: &mut Statistics, program: &str, n: usize) {
     for _ in 0..n {
         let input = fuzz(rng, stats);
 
@@ -81,7 +62,7 @@ pub fn run(rng: &mut Rng, stats: &mut Statistics, n: usize) {
             continue;
         }
 
-        let (runcoverage, runoutcome) = run_and_get_coverage(&input);
+        let (runcoverage, runoutcome) = run_and_get_coverage(&input, program);
 
         // Check if the obtained coverage contains new entries / is interesting.
         if runoutcome == RunResult::Pass && !stats.coverage_db.contains_key(&runcoverage) {
@@ -149,21 +130,21 @@ fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
 
 fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int(s.0.len() as u64) as usize;
+    let pos = rng.usize_below(s.0.len());
     let bit = 1 << rng.int(7);
     s.0[pos] ^= bit;
     s
@@ -178,20 +159,52 @@ pub type Coverage = BTreeSet<Location>;
 #[derive(Debug, Eq, PartialEq)]
 pub enum RunResult {
     Pass,
-    Fail,
+    /// Crashed: killed by the given signal.
+    Fail(i32),
     Unresolved,
 }
 
-/// Run the cgi_decode C program and trace coverage data.
-pub fn run_and_get_coverage(input: &Input) -> (Coverage, RunResult) {
+/// Classify the exit status of the program. Exit codes are never negative
+/// (the Python original gets negative return codes for signals): a program
+/// killed by a signal (SIGSEGV, SIGABRT, ...) has no exit code at all, but
+/// the signal number instead.
+pub fn run_result(status: process::ExitStatus) -> RunResult {
+    match (status.code(), crash_signal(status)) {
+        (Some(0), _) => RunResult::Pass,
+        (_, Some(signal)) => RunResult::Fail(signal),
+        _ => RunResult::Unresolved,
+    }
+}
+
+#[cfg(unix)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+/// There are no signals on Windows: a crashing program exits with the code of
+/// the exception instead, an NTSTATUS with severity "error" (the two highest
+/// bits set), e.g. 0xC0000005 for an access violation.
+#[cfg(windows)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    status.code().filter(|&code| (code as u32) >> 30 == 0b11)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn crash_signal(_status: process::ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Run the C program (cgi_decode by default) and trace coverage data.
+pub fn run_and_get_coverage(input: &Input, program: &str) -> (Coverage, RunResult) {
     // Compile the C program.
     process::Command::new("gcc")
-        .args(["--coverage", "-o", "cgi_decode", "cgi_decode.c"])
+        .args(["--coverage", "-o", program, &format!("{}.c", program)])
         .output()
         .unwrap();
 
     // Run the program.
-    let cres = process::Command::new("./cgi_decode")
+    let cres = process::Command::new(Path::new(".").join(program))
         .arg(format!("{}", input))
         .stdout(process::Stdio::null())
         .spawn()
@@ -201,34 +214,33 @@ pub fn run_and_get_coverage(input: &Input) -> (Coverage, RunResult) {
 
     // Generate coverage data using gcov.
     process::Command::new("gcov")
-        .arg("cgi_decode.c")
+        .arg(format!("{}.c", program))
         .output()
         .unwrap();
 
     // "Parse" (process) gcov coverage file.
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string("cgi_decode.c.gcov").unwrap().lines() {
+    for line in fs::read_to_string(format!("{}.c.gcov", program))
+        .unwrap()
+        .lines()
+    {
         let elems = line.split(':').collect::<Vec<_>>();
         let covered = elems[0].trim();
         let line_number = elems[1].trim().parse::<usize>().unwrap();
         if covered.starts_with("-") || covered.starts_with("#") {
             continue;
         }
-        coverage.insert(("cgi_decode".to_string(), line_number));
+        coverage.insert((program.to_string(), line_number));
     }
 
-    let res = match cres.code() {
-        Some(0) => RunResult::Pass,
-        Some(n) if n < 0 => RunResult::Fail,
-        _ => RunResult::Unresolved,
-    };
+    let res = run_result(cres);
 
     // Cleanup compiled and generated files.
     for file in [
-        "cgi_decode.c.gcov",
-        "cgi_decode",
-        "cgi_decode.gcda",
-        "cgi_decode.gcno",
+        format!("{}.c.gcov", program),
+        program.to_string(),
+        format!("{}.gcda", program),
+        format!("{}.gcno", program),
     ] {
         let _ = fs::remove_file(file);
     }
@@ -238,8 +250,8 @@ pub fn run_and_get_coverage(input: &Input) -> (Coverage, RunResult) {
 
 /// Output the cumulative coverage over time into a file that can then be
 /// plotted in a diagram with gnuplot.
-pub fn plot_cumulative_coverage(cov_cumul: &Vec<usize>) {
-    let mut file = fs::File::create("plot.data").unwrap();
+pub fn plot_cumulative_coverage(cov_cumul: &Vec<usize>, path: &Path) {
+    let mut file = fs::File::create(path).unwrap();
     for i in 0..cov_cumul.len() {
         file.write_all(format!("{} {}\n", i, cov_cumul[i]).as_bytes())
             .unwrap();
@@ -7,6 +7,8 @@
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html Guiding by Coverage
 // But refactored adapted from https://github.com/gamozolabs/guifuzz.
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
@@ -60,23 +62,30 @@ use rng::Rng;
 // [+] Final coverage: 43
 
 fn main() {
-    let mut rng = Rng::new();
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
     println!("[+] Running with random seed {}", rng.initialseed);
     println!();
 
     let input = fuzzer::Input::from_str("http://www.google.com/search?q=fuzzing");
 
     let mut stats = fuzzer::Statistics::new(vec![input]);
-    fuzzer::run(&mut rng, &mut stats, 40);
+    fuzzer::run(
+        &mut rng,
+        &mut stats,
+        &cli::target("cgi_decode"),
+        cli::iterations(40),
+    );
 
-    println!("[+] Final population");
-    for el in stats.population_list {
-        println!("{}", el);
+    if !cli::quiet() {
+        println!("[+] Final population");
+        for el in stats.population_list {
+            println!("{}", el);
+        }
+        println!();
     }
-    println!();
 
     println!("[+] Final coverage: {}", stats.coverage_all.len());
 
     // Output gnuplot file; generate plot: ./plot.plt
-    fuzzer::plot_cumulative_coverage(&stats.cumulative_coverage);
+    fuzzer::plot_cumulative_coverage(&stats.cumulative_coverage, &cli::out_file("plot.data"));
 }
@@ -9,31 +9,13 @@ use crate::rng::Rng;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Input(Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(self.0.clone()).unwrap())
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 /// Statistics relevant during fuzzing.
 #[derive(Default)]
@@ -56,10 +38,8 @@ pub struct Statistics {
 }
 
 /// Create and run `n` random fuzz cases and record statistics during execution.
-pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
-    let mut rng = Rng::new();
-    println!("[+] Running with random seed {}", rng.initialseed);
-
+/// Each worker thread gets its own PRNG split off from a common master PRNG.
+pub fn run(mut rng: Rng, stats: Arc<Mutex<Statistics>>, seed: &[Input], program: &str) {
     loop {
         let input = fuzz(&mut rng, Arc::clone(&stats), seed);
 
@@ -69,7 +49,7 @@ pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
             continue;
         }
 
-        let (runcoverage, runoutcome) = run_and_get_coverage(&mut rng, &input);
+        let (runcoverage, runoutcome) = run_and_get_coverage(&input, program);
 
         let mut stats = stats.lock().unwrap();
 
@@ -168,21 +148,21 @@ fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
 
 fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int(s.0.len() as u64) as usize;
+    let pos = rng.usize_below(s.0.len());
     let bit = 1 << rng.int(7);
     s.0[pos] ^= bit;
     s
@@ -197,32 +177,69 @@ pub type Coverage = BTreeSet<Location>;
 #[derive(Debug, Eq, PartialEq)]
 pub enum RunResult {
     Pass,
-    Fail,
+    /// Crashed: killed by the given signal.
+    Fail(i32),
     Unresolved,
 }
 
-/// Run the cgi_decode C program and trace coverage data.
-pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResult) {
+/// Classify the exit status of the program. Exit codes are never negative
+/// (the Python original gets negative return codes for signals): a program
+/// killed by a signal (SIGSEGV, SIGABRT, ...) has no exit code at all, but
+/// the signal number instead.
+pub fn run_result(status: process::ExitStatus) -> RunResult {
+    match (status.code(), crash_signal(status)) {
+        (Some(0), _) => RunResult::Pass,
+        (_, Some(signal)) => RunResult::Fail(signal),
+        _ => RunResult::Unresolved,
+    }
+}
+
+#[cfg(unix)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+/// There are no signals on Windows: a crashing program exits with the code of
+/// the exception instead, an NTSTATUS with severity "error" (the two highest
+/// bits set), e.g. 0xC0000005 for an access violation.
+#[cfg(windows)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    status.code().filter(|&code| (code as u32) >> 30 == 0b11)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn crash_signal(_status: process::ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Number of test runs so far; used for naming temporary directories.
+static TESTRUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Run the C program (cgi_decode by default) and trace coverage data.
+pub fn run_and_get_coverage(input: &Input, program: &str) -> (Coverage, RunResult) {
     // Create new temporary directory for multi-threaded running without
-    // conflicts.
+    // conflicts. The name is derived from a global counter (and not from the
+    // PRNG), so that running the target doesn't influence the random choices
+    // of the fuzzer.
     let root = format!(
         "testrun-{}-{}",
-        unsafe { core::arch::x86_64::_rdtsc() },
-        rng.next(),
+        process::id(),
+        TESTRUN_COUNTER.fetch_add(1, Ordering::Relaxed),
     );
 
     std::fs::create_dir(&root).unwrap();
-    std::fs::copy("cgi_decode.c", format!("{}/{}", root, "cgi_decode.c")).unwrap();
+    std::fs::copy(format!("{}.c", program), format!("{}/{}.c", root, program)).unwrap();
 
     // Compile the C program.
     process::Command::new("gcc")
         .current_dir(&root)
-        .args(["--coverage", "-o", "cgi_decode", "cgi_decode.c"])
+        .args(["--coverage", "-o", program, &format!("{}.c", program)])
         .output()
         .unwrap();
 
     // Run the program.
-    let cres = process::Command::new("./cgi_decode")
+    let cres = process::Command::new(Path::new(".").join(program))
         .current_dir(&root)
         .arg(format!("{}", input))
         .stdout(process::Stdio::null())
@@ -234,13 +251,13 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResul
     // Generate coverage data using gcov.
     process::Command::new("gcov")
         .current_dir(&root)
-        .arg("cgi_decode.c")
+        .arg(format!("{}.c", program))
         .output()
         .unwrap();
 
     // "Parse" (process) gcov coverage file.
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string(format!("{}/{}", root, "cgi_decode.c.gcov"))
+    for line in fs::read_to_string(format!("{}/{}.c.gcov", root, program))
         .unwrap()
         .lines()
     {
@@ -250,22 +267,18 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResul
         if covered.starts_with("-") || covered.starts_with("#") {
             continue;
         }
-        coverage.insert(("cgi_decode".to_string(), line_number));
+        coverage.insert((program.to_string(), line_number));
     }
 
-    let res = match cres.code() {
-        Some(0) => RunResult::Pass,
-        Some(n) if n < 0 => RunResult::Fail,
-        _ => RunResult::Unresolved,
-    };
+    let res = run_result(cres);
 
     // Cleanup compiled and generated files.
     for file in [
-        format!("{}/{}", root, "cgi_decode.c"),
-        format!("{}/{}", root, "cgi_decode.c.gcov"),
-        format!("{}/{}", root, "cgi_decode"),
-        format!("{}/{}", root, "cgi_decode.gcda"),
-        format!("{}/{}", root, "cgi_decode.gcno"),
+        format!("{}/{}.c", root, program),
+        format!("{}/{}.c.gcov", root, program),
+        format!("{}/{}", root, program),
+        format!("{}/{}.gcda", root, program),
+        format!("{}/{}.gcno", root, program),
     ] {
         let _ = fs::remove_file(file);
     }
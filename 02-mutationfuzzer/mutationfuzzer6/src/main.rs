@@ -7,6 +7,8 @@
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html Guiding by Coverage
 // But refactored to multi-threaded runner adapted from https://github.com/gamozolabs/guifuzz.
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
@@ -16,19 +18,30 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 fn main() {
+    // Master PRNG from which the PRNGs of all worker threads are derived.
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
+
     let stats = Arc::new(Mutex::new(fuzzer::Statistics::default()));
 
-    let mut logfile = fs::File::create("plot.data").unwrap();
+    // Without --iterations, fuzz until interrupted.
+    let n = cli::iterations(usize::MAX);
+    let target = cli::target("cgi_decode");
+    let quiet = cli::quiet();
+
+    let mut logfile = fs::File::create(cli::out_file("plot.data")).unwrap();
 
     let start_time = Instant::now();
 
     let nthreads = 6;
     for _ in 0..nthreads {
         let stats = Arc::clone(&stats);
+        let rng = rng.split();
         let input = fuzzer::Input::from_str("http://www.google.com/search?q=fuzzing");
+        let target = target.clone();
 
         std::thread::spawn(move || {
-            fuzzer::run(stats, vec![input].as_slice());
+            fuzzer::run(rng, stats, vec![input].as_slice(), &target);
         });
     }
 
@@ -45,10 +58,12 @@ fn main() {
         );
         drop(stats);
 
-        println!(
-            "{:12.2} uptime | {:7} fuzz cases | {:8} coverage | {:5} inputs",
-            uptime, curstats.0, curstats.1, curstats.2,
-        );
+        if !quiet {
+            println!(
+                "{:12.2} uptime | {:7} fuzz cases | {:8} coverage | {:5} inputs",
+                uptime, curstats.0, curstats.1, curstats.2,
+            );
+        }
 
         writeln!(
             logfile,
@@ -57,5 +72,13 @@ fn main() {
         )
         .unwrap();
         logfile.flush().unwrap();
+
+        if curstats.0 >= n {
+            println!(
+                "{:12.2} uptime | {:7} fuzz cases | {:8} coverage | {:5} inputs",
+                uptime, curstats.0, curstats.1, curstats.2,
+            );
+            break;
+        }
     }
 }
@@ -20,4 +20,22 @@ set key bottom
 
 plot "plot.data" using 2:3 with lines linewidth 2 title "Fuzzer"
 
+# Further pages: speed, stability and plateau over time.
+set xlabel "Uptime (s)"
+set key top
+
+set title "Executions per second (sliding window)"
+set ylabel "exec/s"
+plot "plot.data" using 1:5 with lines linewidth 2 title "Fuzzer"
+
+set title "Stability"
+set ylabel "Stable executions (%)"
+set yrange [0:100]
+plot "plot.data" using 1:6 with lines linewidth 2 title "Fuzzer"
+
+set title "Time since last find"
+set ylabel "Seconds"
+set yrange [0:*]
+plot "plot.data" using 1:7 with lines linewidth 2 title "Fuzzer"
+
 # pause -1
@@ -6,34 +6,16 @@
 
 use crate::rng::Rng;
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::fs;
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Input(Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(self.0.clone()).unwrap())
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 /// Statistics relevant during fuzzing.
 #[derive(Default)]
@@ -53,13 +35,105 @@ pub struct Statistics {
 
     /// Union of all coverages that were achieved during execution.
     pub coverage_all: Coverage,
+
+    /// Points in time of the most recent executions (at most `EXEC_WINDOW`
+    /// old), for computing the current execution speed.
+    pub recent_execs: VecDeque<Instant>,
+
+    /// Number of stability checks, i.e. of inputs that were executed twice.
+    pub stability_checks: usize,
+
+    /// Number of stability checks where both executions yielded the same
+    /// coverage.
+    pub stability_stable: usize,
+
+    /// Point in time when the last input with new coverage was found.
+    pub last_new_coverage: Option<Instant>,
+
+    /// Number of executions where the program crashed.
+    pub crashes: usize,
+
+    /// Status of each worker thread, by worker number.
+    pub workers: BTreeMap<usize, WorkerStatus>,
 }
 
-/// Create and run `n` random fuzz cases and record statistics during execution.
-pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
-    let mut rng = Rng::new();
-    println!("[+] Running with random seed {}", rng.initialseed);
+/// Statistics of a single worker thread.
+#[derive(Default)]
+pub struct WorkerStatus {
+    /// Number of inputs tested by this worker.
+    pub fuzz_cases: usize,
+
+    /// Point in time of the last execution by this worker. A worker that
+    /// hasn't executed anything for a long time probably hangs.
+    pub last_exec: Option<Instant>,
+}
+
+/// Length of the sliding window over which executions per second are measured.
+const EXEC_WINDOW: Duration = Duration::from_secs(5);
+
+/// Each worker executes every n-th input a second time to check whether the
+/// target behaves deterministically.
+const STABILITY_CHECK_INTERVAL: usize = 100;
+
+impl Statistics {
+    /// Record that an execution has just happened.
+    fn record_exec(&mut self, now: Instant) {
+        self.recent_execs.push_back(now);
+        while let Some(&first) = self.recent_execs.front() {
+            if now - first <= EXEC_WINDOW {
+                break;
+            }
+            self.recent_execs.pop_front();
+        }
+    }
+
+    /// Executions per second over the last `EXEC_WINDOW` (or since the start
+    /// of the campaign, if that was more recently).
+    pub fn execs_per_sec(&self, start_time: Instant) -> f64 {
+        let now = Instant::now();
+        let window = EXEC_WINDOW.min(now - start_time).as_secs_f64();
+        let execs = self
+            .recent_execs
+            .iter()
+            .filter(|&&t| now - t <= EXEC_WINDOW)
+            .count();
+        if window > 0.0 {
+            execs as f64 / window
+        } else {
+            0.0
+        }
+    }
+
+    /// Percentage of stability checks where executing the same input again led
+    /// to the same coverage. AFL calls this "stability"; values below 100%
+    /// indicate non-determinism in the target (or the coverage measurement),
+    /// which makes coverage feedback less reliable.
+    pub fn stability(&self) -> f64 {
+        if self.stability_checks == 0 {
+            100.0
+        } else {
+            100.0 * self.stability_stable as f64 / self.stability_checks as f64
+        }
+    }
+
+    /// Time since the last input with new coverage was found (or since the
+    /// start of the campaign, if nothing was found yet). A long time indicates
+    /// that the fuzzer has reached a plateau.
+    pub fn time_since_last_find(&self, start_time: Instant) -> Duration {
+        Instant::now() - self.last_new_coverage.unwrap_or(start_time)
+    }
+}
 
+/// Create and run `n` random fuzz cases and record statistics during execution.
+/// Each worker thread gets its own PRNG split off from a common master PRNG.
+pub fn run(
+    worker: usize,
+    mut rng: Rng,
+    stats: Arc<Mutex<Statistics>>,
+    seed: &[Input],
+    program: &str,
+) {
+    let mut worker_execs = 0;
     loop {
         let input = fuzz(&mut rng, Arc::clone(&stats), seed);
 
@@ -69,7 +143,16 @@ pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
             continue;
         }
 
-        let (runcoverage, runoutcome) = run_and_get_coverage(&mut rng, &input);
+        let (runcoverage, runoutcome) = run_and_get_coverage(&input, program);
+        worker_execs += 1;
+
+        // Occasionally run the same input again and compare the coverage.
+        let stable = if worker_execs % STABILITY_CHECK_INTERVAL == 0 {
+            let (recoverage, reoutcome) = run_and_get_coverage(&input, program);
+            Some(recoverage == runcoverage && reoutcome == runoutcome)
+        } else {
+            None
+        };
 
         let mut stats = stats.lock().unwrap();
 
@@ -77,6 +160,22 @@ pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
 
         // Fuzz cases are always increased, regardless of the coverage information.
         stats.fuzz_cases += 1;
+        stats.record_exec(Instant::now());
+
+        let status = stats.workers.entry(worker).or_default();
+        status.fuzz_cases += 1;
+        status.last_exec = Some(Instant::now());
+
+        if matches!(runoutcome, RunResult::Fail(_)) {
+            stats.crashes += 1;
+        }
+
+        if let Some(stable) = stable {
+            stats.stability_checks += 1;
+            if stable {
+                stats.stability_stable += 1;
+            }
+        }
 
         // Check if the obtained coverage contains new entries / is interesting.
         if runoutcome == RunResult::Pass && !stats.coverage_db.contains_key(&runcoverage) {
@@ -85,6 +184,7 @@ pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
 
             stats.coverage_db.insert(runcoverage.clone(), input.clone());
             stats.coverage_all.extend(runcoverage);
+            stats.last_new_coverage = Some(Instant::now());
 
             drop(stats);
 
@@ -168,21 +268,21 @@ fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
 
 fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int(s.0.len() as u64) as usize;
+    let pos = rng.usize_below(s.0.len());
     let bit = 1 << rng.int(7);
     s.0[pos] ^= bit;
     s
@@ -197,38 +297,79 @@ pub type Coverage = BTreeSet<Location>;
 #[derive(Debug, Eq, PartialEq)]
 pub enum RunResult {
     Pass,
-    Fail,
+    /// Crashed: killed by the given signal.
+    Fail(i32),
     Unresolved,
 }
 
-/// Compile the cgi_decode C program. This is done in a separate function and
-/// not in run_and_get_coverage, since it only has to be done once and not on
-/// each fuzz case (the source code doesn't change between fuzz cases).
-pub fn compile_program() {
+/// Classify the exit status of the program. Exit codes are never negative
+/// (the Python original gets negative return codes for signals): a program
+/// killed by a signal (SIGSEGV, SIGABRT, ...) has no exit code at all, but
+/// the signal number instead.
+pub fn run_result(status: process::ExitStatus) -> RunResult {
+    match (status.code(), crash_signal(status)) {
+        (Some(0), _) => RunResult::Pass,
+        (_, Some(signal)) => RunResult::Fail(signal),
+        _ => RunResult::Unresolved,
+    }
+}
+
+#[cfg(unix)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+/// There are no signals on Windows: a crashing program exits with the code of
+/// the exception instead, an NTSTATUS with severity "error" (the two highest
+/// bits set), e.g. 0xC0000005 for an access violation.
+#[cfg(windows)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    status.code().filter(|&code| (code as u32) >> 30 == 0b11)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn crash_signal(_status: process::ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Compile the C program (cgi_decode by default). This is done in a separate
+/// function and not in run_and_get_coverage, since it only has to be done once
+/// and not on each fuzz case (the source code doesn't change between fuzz cases).
+pub fn compile_program(program: &str) {
     // Compile the C program.
     process::Command::new("gcc")
-        .args(["--coverage", "-o", "cgi_decode", "cgi_decode.c"])
+        .args(["--coverage", "-o", program, &format!("{}.c", program)])
         .output()
         .unwrap();
 }
 
-/// Run the cgi_decode C program and trace coverage data.
-pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResult) {
+/// Number of test runs so far; used for naming temporary directories.
+static TESTRUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Run the C program and trace coverage data.
+pub fn run_and_get_coverage(input: &Input, program: &str) -> (Coverage, RunResult) {
     // Create new temporary directory for multi-threaded running without
-    // conflicts.
+    // conflicts. The name is derived from a global counter (and not from the
+    // PRNG), so that running the target doesn't influence the random choices
+    // of the fuzzer.
     let root = format!(
         "testrun-{}-{}",
-        unsafe { core::arch::x86_64::_rdtsc() },
-        rng.next(),
+        process::id(),
+        TESTRUN_COUNTER.fetch_add(1, Ordering::Relaxed),
     );
 
     // Create a temporary directory that acts as root for this fuzz case execution.
     std::fs::create_dir(&root).unwrap();
     // Copy over the relevant data resulting from the initial program compilation.
     // This is needed for later gathering code coverage with `gcov`.
-    std::fs::copy("cgi_decode.c", format!("{}/{}", root, "cgi_decode.c")).unwrap();
-    std::fs::copy("cgi_decode", format!("{}/{}", root, "cgi_decode")).unwrap();
-    std::fs::copy("cgi_decode.gcno", format!("{}/{}", root, "cgi_decode.gcno")).unwrap();
+    std::fs::copy(format!("{}.c", program), format!("{}/{}.c", root, program)).unwrap();
+    std::fs::copy(program, format!("{}/{}", root, program)).unwrap();
+    std::fs::copy(
+        format!("{}.gcno", program),
+        format!("{}/{}.gcno", root, program),
+    )
+    .unwrap();
 
     // Run the program.
     let cres = process::Command::new(
@@ -238,7 +379,7 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResul
         // > directory or relative to current_dir. The behavior in this case is
         // > platform specific and unstable, and it’s recommended to use
         // > canonicalize to get an absolute program path instead.
-        fs::canonicalize("./cgi_decode").unwrap(),
+        fs::canonicalize(program).unwrap(),
     )
     .current_dir(&root)
     .arg(format!("{}", input))
@@ -262,13 +403,13 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResul
     // Generate coverage data using gcov.
     process::Command::new("gcov")
         .current_dir(&root)
-        .arg("cgi_decode.c")
+        .arg(format!("{}.c", program))
         .output()
         .unwrap();
 
     // "Parse" (process) gcov coverage file.
     let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string(format!("{}/{}", root, "cgi_decode.c.gcov"))
+    for line in fs::read_to_string(format!("{}/{}.c.gcov", root, program))
         .unwrap()
         .lines()
     {
@@ -278,22 +419,18 @@ pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResul
         if covered.starts_with("-") || covered.starts_with("#") {
             continue;
         }
-        coverage.insert(("cgi_decode".to_string(), line_number));
+        coverage.insert((program.to_string(), line_number));
     }
 
-    let res = match cres.code() {
-        Some(0) => RunResult::Pass,
-        Some(n) if n < 0 => RunResult::Fail,
-        _ => RunResult::Unresolved,
-    };
+    let res = run_result(cres);
 
     // Cleanup compiled and generated files.
     for file in [
-        format!("{}/{}", root, "cgi_decode"),
-        format!("{}/{}", root, "cgi_decode.c"),
-        format!("{}/{}", root, "cgi_decode.c.gcov"),
-        format!("{}/{}", root, "cgi_decode.gcda"),
-        format!("{}/{}", root, "cgi_decode.gcno"),
+        format!("{}/{}", root, program),
+        format!("{}/{}.c", root, program),
+        format!("{}/{}.c.gcov", root, program),
+        format!("{}/{}.gcda", root, program),
+        format!("{}/{}.gcno", root, program),
     ] {
         let _ = fs::remove_file(file);
     }
@@ -7,30 +7,53 @@
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html Guiding by Coverage
 // But refactored to multi-threaded runner adapted from https://github.com/gamozolabs/guifuzz.
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
 use std::fs;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 fn main() {
+    // Master PRNG from which the PRNGs of all worker threads are derived.
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
+
     let stats = Arc::new(Mutex::new(fuzzer::Statistics::default()));
 
-    let mut logfile = fs::File::create("plot.data").unwrap();
+    // Without --iterations, fuzz until interrupted.
+    let n = cli::iterations(usize::MAX);
+    let target = cli::target("cgi_decode");
+    let quiet = cli::quiet();
+
+    let mut logfile = fs::File::create(cli::out_file("plot.data")).unwrap();
 
     let start_time = Instant::now();
 
-    fuzzer::compile_program();
+    fuzzer::compile_program(&target);
+
+    // Show the status as a screen that is refreshed in place, unless disabled
+    // with `--no-tui` or when the output isn't a terminal (e.g. redirected to
+    // a file). With `--quiet`, only the final status is printed.
+    let tui = !cli::flag("--no-tui") && !quiet && std::io::stdout().is_terminal();
+    if tui {
+        // Clear the screen once; afterwards only the cursor is moved back to
+        // the top left corner and the content overwritten (less flickering).
+        print!("\x1b[2J");
+    }
 
     let nthreads = 6;
-    for _ in 0..nthreads {
+    for worker in 0..nthreads {
         let stats = Arc::clone(&stats);
+        let rng = rng.split();
         let input = fuzzer::Input::from_str("http://www.google.com/search?q=fuzzing");
+        let target = target.clone();
 
         std::thread::spawn(move || {
-            fuzzer::run(stats, vec![input].as_slice());
+            fuzzer::run(worker, rng, stats, vec![input].as_slice(), &target);
         });
     }
 
@@ -44,20 +67,83 @@ fn main() {
             stats.fuzz_cases,
             stats.coverage_all.len(),
             stats.population_list.len(),
+            stats.execs_per_sec(start_time),
+            stats.stability(),
+            stats.time_since_last_find(start_time).as_secs_f64(),
         );
+        if tui {
+            print!("{}", tui_screen(&stats, start_time));
+            std::io::stdout().flush().unwrap();
+        }
         drop(stats);
 
-        println!(
-            "{:12.2} uptime | {:7} fuzz cases | {:8} coverage | {:5} inputs",
-            uptime, curstats.0, curstats.1, curstats.2,
-        );
+        let finished = curstats.0 >= n;
+        if !tui && (!quiet || finished) {
+            println!(
+                "{:12.2} uptime | {:7} fuzz cases | {:8} coverage | {:5} inputs | {:8.1} exec/s | {:6.2}% stable | {:8.2}s since last find",
+                uptime, curstats.0, curstats.1, curstats.2, curstats.3, curstats.4, curstats.5,
+            );
+        }
 
         writeln!(
             logfile,
-            "{:12.2} {:7} {:8} {:5}",
-            uptime, curstats.0, curstats.1, curstats.2
+            "{:12.2} {:7} {:8} {:5} {:8.1} {:6.2} {:8.2}",
+            uptime, curstats.0, curstats.1, curstats.2, curstats.3, curstats.4, curstats.5,
         )
         .unwrap();
         logfile.flush().unwrap();
+
+        if finished {
+            break;
+        }
+    }
+}
+
+/// Render the status screen for the terminal UI. Uses plain ANSI escape
+/// sequences: move the cursor to the top left corner, and clear the rest of
+/// each line (and of the screen) so that no remains of longer previous content
+/// are left over.
+fn tui_screen(stats: &fuzzer::Statistics, start_time: Instant) -> String {
+    let uptime = (Instant::now() - start_time).as_secs_f64();
+
+    let mut lines = vec![
+        "mutationfuzzer7 -- coverage-guided mutation fuzzing of cgi_decode".to_string(),
+        String::new(),
+        format!("  uptime            {:12.2}s", uptime),
+        format!("  fuzz cases        {:12}", stats.fuzz_cases),
+        format!(
+            "  exec/s            {:12.1}",
+            stats.execs_per_sec(start_time)
+        ),
+        format!("  coverage          {:12}", stats.coverage_all.len()),
+        format!("  corpus size       {:12}", stats.population_list.len()),
+        format!("  crashes           {:12}", stats.crashes),
+        format!("  stability         {:11.2}%", stats.stability()),
+        format!(
+            "  last new find     {:12.2}s ago",
+            stats.time_since_last_find(start_time).as_secs_f64()
+        ),
+        String::new(),
+        "  worker   fuzz cases   last exec".to_string(),
+    ];
+    for (worker, status) in stats.workers.iter() {
+        let last_exec = match status.last_exec {
+            Some(t) => format!("{:8.2}s ago", (Instant::now() - t).as_secs_f64()),
+            None => "never".to_string(),
+        };
+        lines.push(format!(
+            "  {:6}   {:10}   {}",
+            worker, status.fuzz_cases, last_exec
+        ));
+    }
+    lines.push(String::new());
+    lines.push("(Ctrl-C to stop, --no-tui for line-based output)".to_string());
+
+    let mut screen = "\x1b[H".to_string();
+    for line in lines {
+        screen.push_str(&line);
+        screen.push_str("\x1b[K\n");
     }
+    screen.push_str("\x1b[J");
+    screen
 }
@@ -9,31 +9,14 @@ use crate::rng::Rng;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
 use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-/// Represents the structure that the fuzzer operates on. Here we use a
-/// dedicated newtype instead of a type alias for being able to implement
-/// integrated printing routines.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct Input(Vec<u8>);
-
-impl Input {
-    /// Convert a `&str` to `Input`. I choose to do it this way and not use
-    /// `FromStr` trait since that returns a Result which has to be unwrapped.
-    /// This is unnecessary since in this case the conversion can never fail
-    /// (Vec<u8> is a super-set of &str).
-    pub fn from_str(s: &str) -> Self {
-        Self(s.as_bytes().to_vec())
-    }
-}
-
-// This assumes that the string is valid utf8.
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(self.0.clone()).unwrap())
-    }
-}
+/// Represents the structure that the fuzzer operates on (see `bytes.rs`).
+pub use crate::bytes::Bytes as Input;
 
 /// Statistics relevant during fuzzing.
 #[derive(Default)]
@@ -56,14 +39,87 @@ pub struct Statistics {
 
     /// Union of all coverages that were achieved during execution.
     pub coverage_all: Coverage,
+
+    /// Point in time when the last input with new coverage was found.
+    pub last_new_coverage: Option<Instant>,
 }
 
-/// Create and run `n` random fuzz cases and record statistics during execution.
-pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
-    let mut rng = Rng::new();
-    println!("[+] Running with random seed {}", rng.initialseed);
+/// Limits for how long a fuzzing campaign runs. The campaign stops as soon as
+/// any of the given limits is reached; limits that are `None` are ignored.
+/// E.g. "run for 10 minutes or until 60s without new coverage".
+pub struct Budget {
+    /// Point in time when the campaign was started.
+    pub start_time: Instant,
+
+    /// Maximum number of executions of the program under test.
+    pub max_executions: Option<usize>,
+
+    /// Maximum wall-clock time of the whole campaign.
+    pub max_duration: Option<Duration>,
+
+    /// Maximum wall-clock time without finding new coverage (plateau length).
+    pub max_plateau: Option<Duration>,
+}
+
+impl Budget {
+    /// Budget that never runs out.
+    pub fn unlimited() -> Self {
+        Self {
+            start_time: Instant::now(),
+            max_executions: None,
+            max_duration: None,
+            max_plateau: None,
+        }
+    }
+
+    /// Check whether any of the limits has been reached.
+    pub fn is_exhausted(&self, stats: &Statistics) -> bool {
+        let now = Instant::now();
+
+        if let Some(max_executions) = self.max_executions {
+            if stats.fuzz_cases >= max_executions {
+                return true;
+            }
+        }
+
+        if let Some(max_duration) = self.max_duration {
+            if now - self.start_time >= max_duration {
+                return true;
+            }
+        }
+
+        if let Some(max_plateau) = self.max_plateau {
+            // Before the first find the plateau starts with the campaign.
+            let last_find = stats.last_new_coverage.unwrap_or(self.start_time);
+            if now - last_find >= max_plateau {
+                return true;
+            }
+        }
+
+        false
+    }
+}
 
+/// Create and run random fuzz cases until the budget is exhausted and record
+/// statistics during execution.
+/// Each worker thread gets its own PRNG split off from a common master PRNG,
+/// and its own runner.
+/// Without `coverage` (gcov not available), all inputs have the same (empty)
+/// coverage, i.e. this is plain mutation fuzzing of the seed.
+pub fn run(
+    mut rng: Rng,
+    stats: Arc<Mutex<Statistics>>,
+    budget: Arc<Budget>,
+    seed: &[Input],
+    program: &str,
+    coverage: bool,
+) {
+    let runner = Runner::new(program, coverage);
     loop {
+        if budget.is_exhausted(&stats.lock().unwrap()) {
+            return;
+        }
+
         let input = fuzz(&mut rng, Arc::clone(&stats), seed);
 
         // Ignore input (and don't perform unnecessary expensive re-evaluation)
@@ -72,7 +128,7 @@ pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
             continue;
         }
 
-        let (runcoverage, runoutcome) = run_and_get_coverage(&mut rng, &input);
+        let (runcoverage, runoutcome) = runner.run(&input);
 
         let runcoveragehash = {
             let mut hasher = DefaultHasher::new();
@@ -94,6 +150,7 @@ pub fn run(stats: Arc<Mutex<Statistics>>, seed: &[Input]) {
 
             stats.coverage_db.insert(runcoveragehash, input.clone());
             stats.coverage_all.extend(runcoverage);
+            stats.last_new_coverage = Some(Instant::now());
 
             drop(stats);
 
@@ -177,21 +234,21 @@ fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
     if s.0.is_empty() {
         s
     } else {
-        let pos = rng.int(s.0.len() as u64) as usize;
+        let pos = rng.usize_below(s.0.len());
         s.0.remove(pos);
         s
     }
 }
 
 fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int((s.0.len() + 1) as u64) as usize;
-    let chr = rng.range(32, 127 + 1) as u8;
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
     s.0.insert(pos, chr);
     s
 }
 
 fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
-    let pos = rng.int(s.0.len() as u64) as usize;
+    let pos = rng.usize_below(s.0.len());
     let bit = 1 << rng.int(7);
     s.0[pos] ^= bit;
     s
@@ -206,107 +263,215 @@ pub type Coverage = BTreeSet<Location>;
 #[derive(Debug, Eq, PartialEq)]
 pub enum RunResult {
     Pass,
-    Fail,
+    /// Crashed: killed by the given signal (on Windows: the exception code).
+    Fail(i32),
     Unresolved,
 }
 
-/// Compile the cgi_decode C program. This is done in a separate function and
-/// not in run_and_get_coverage, since it only has to be done once and not on
-/// each fuzz case (the source code doesn't change between fuzz cases).
-pub fn compile_program() {
+/// Classify the exit status of the program. Exit codes are never negative
+/// (the Python original gets negative return codes for signals): a program
+/// killed by a signal (SIGSEGV, SIGABRT, ...) has no exit code at all, but
+/// the signal number instead.
+pub fn run_result(status: process::ExitStatus) -> RunResult {
+    match (status.code(), crash_signal(status)) {
+        (Some(0), _) => RunResult::Pass,
+        (_, Some(signal)) => RunResult::Fail(signal),
+        _ => RunResult::Unresolved,
+    }
+}
+
+#[cfg(unix)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+/// There are no signals on Windows: a crashing program exits with the code of
+/// the exception instead, an NTSTATUS with severity "error" (the two highest
+/// bits set), e.g. 0xC0000005 for an access violation.
+#[cfg(windows)]
+fn crash_signal(status: process::ExitStatus) -> Option<i32> {
+    status.code().filter(|&code| (code as u32) >> 30 == 0b11)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn crash_signal(_status: process::ExitStatus) -> Option<i32> {
+    None
+}
+
+/// File name of the compiled program (gcc appends `.exe` on Windows).
+fn program_name(program: &str) -> String {
+    format!("{}{}", program, std::env::consts::EXE_SUFFIX)
+}
+
+/// Check whether gcov can be executed on this system (it's not installed
+/// everywhere, e.g. not with the Xcode command line tools on macOS).
+pub fn gcov_available() -> bool {
+    process::Command::new("gcov")
+        .arg("--version")
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+/// Compile the C program (cgi_decode by default). This is done in a separate
+/// function and not in run_and_get_coverage, since it only has to be done once
+/// and not on each fuzz case (the source code doesn't change between fuzz cases).
+pub fn compile_program(program: &str) {
     // Compile the C program.
     process::Command::new("gcc")
-        .args(["--coverage", "-o", "cgi_decode", "cgi_decode.c"])
+        .args(["--coverage", "-o", program, &format!("{}.c", program)])
         .output()
         .unwrap();
 }
 
-/// Run the cgi_decode C program and trace coverage data.
-pub fn run_and_get_coverage(rng: &mut Rng, input: &Input) -> (Coverage, RunResult) {
-    // Create new temporary directory for multi-threaded running without
-    // conflicts.
-    let root = format!(
-        "testrun-{}-{}",
-        unsafe { core::arch::x86_64::_rdtsc() },
-        rng.next(),
-    );
-
-    // Create a temporary directory that acts as root for this fuzz case execution.
-    std::fs::create_dir(&root).unwrap();
-    // Copy over the relevant data resulting from the initial program compilation.
-    // This is needed for later gathering code coverage with `gcov`.
-    std::fs::copy("cgi_decode.c", format!("{}/{}", root, "cgi_decode.c")).unwrap();
-    std::fs::copy("cgi_decode", format!("{}/{}", root, "cgi_decode")).unwrap();
-    std::fs::copy("cgi_decode.gcno", format!("{}/{}", root, "cgi_decode.gcno")).unwrap();
-
-    // Run the program.
-    let cres = process::Command::new(
-        // https://doc.rust-lang.org/std/process/struct.Command.html#method.current_dir
-        // > If the program path is relative (e.g., "./script.sh"), it’s ambiguous
-        // > whether it should be interpreted relative to the parent’s working
-        // > directory or relative to current_dir. The behavior in this case is
-        // > platform specific and unstable, and it’s recommended to use
-        // > canonicalize to get an absolute program path instead.
-        fs::canonicalize("./cgi_decode").unwrap(),
-    )
-    .current_dir(&root)
-    .arg(format!("{}", input))
-    // https://gcc.gnu.org/onlinedocs/gcc/Cross-profiling.html
-    // The following two environment variables are needed in order to
-    // instruct gcov to write the collected information into the current
-    // directory (inside the temporary root of this fuzz case) and not into
-    // the directory/absolute path were the program was initially compiled
-    // (which is global to all fuzz cases and would thus lead to conflicts).
-    .env("GCOV_PREFIX", ".")
-    // Strip leading directory names from the initial absolute path. This
-    // value should be enough, although I'd prefer an explicit option to
-    // strip all leading directory names (idk if there is such an option).
-    .env("GCOV_PREFIX_STRIP", "20")
-    .stdout(process::Stdio::null())
-    .spawn()
-    .unwrap()
-    .wait()
-    .unwrap();
+/// Number of runners so far; used for naming their working directories.
+static RUNNER_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Executes the C program (cgi_decode by default) and gathers the coverage via gcov.
+///
+/// gcov transfers the coverage from the program to the fuzzer via files (the
+/// .gcda file written on exit, and the .gcov file produced by gcov). In order
+/// for worker threads not to clobber each other's files, each worker owns a
+/// runner with its own private working directory. The directory lives in the
+/// system's temporary directory (usually a tmpfs, i.e. in memory), is set up
+/// once when the runner is created, and removed again when it is dropped.
+/// Previously a fresh directory was created (and the program copied into it)
+/// for each single execution.
+/// Without `coverage`, the program is only run, and each execution has the
+/// empty coverage.
+pub struct Runner {
+    root: PathBuf,
+    program: String,
+    coverage: bool,
+}
 
-    // Generate coverage data using gcov.
-    process::Command::new("gcov")
-        .current_dir(&root)
-        .arg("cgi_decode.c")
-        .output()
-        .unwrap();
+impl Runner {
+    pub fn new(program: &str, coverage: bool) -> Self {
+        // The name is derived from a global counter (and not from the PRNG),
+        // so that running the target doesn't influence the random choices of
+        // the fuzzer.
+        let root = std::env::temp_dir().join(format!(
+            "{}-{}-{}",
+            program,
+            process::id(),
+            RUNNER_COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+
+        fs::create_dir(&root).unwrap();
+        // Copy over the relevant data resulting from the initial program
+        // compilation. This is needed for later gathering code coverage with
+        // `gcov`.
+        for file in [
+            format!("{}.c", program),
+            program_name(program),
+            format!("{}.gcno", program),
+        ] {
+            fs::copy(&file, root.join(&file)).unwrap();
+        }
 
-    // "Parse" (process) gcov coverage file.
-    let mut coverage = BTreeSet::new();
-    for line in fs::read_to_string(format!("{}/{}", root, "cgi_decode.c.gcov"))
-        .unwrap()
-        .lines()
-    {
-        let elems = line.split(':').collect::<Vec<_>>();
-        let covered = elems[0].trim();
-        let line_number = elems[1].trim().parse::<usize>().unwrap();
-        if covered.starts_with("-") || covered.starts_with("#") {
-            continue;
+        Self {
+            root,
+            program: program.to_string(),
+            coverage,
         }
-        coverage.insert(("cgi_decode".to_string(), line_number));
     }
 
-    let res = match cres.code() {
-        Some(0) => RunResult::Pass,
-        Some(n) if n < 0 => RunResult::Fail,
-        _ => RunResult::Unresolved,
-    };
-
-    // Cleanup compiled and generated files.
-    for file in [
-        format!("{}/{}", root, "cgi_decode"),
-        format!("{}/{}", root, "cgi_decode.c"),
-        format!("{}/{}", root, "cgi_decode.c.gcov"),
-        format!("{}/{}", root, "cgi_decode.gcda"),
-        format!("{}/{}", root, "cgi_decode.gcno"),
-    ] {
-        let _ = fs::remove_file(file);
+    /// Run the C program and trace coverage data.
+    pub fn run(&self, input: &Input) -> (Coverage, RunResult) {
+        // gcov accumulates the counts of all executions in the .gcda file, so
+        // remove the one from the previous execution.
+        let _ = fs::remove_file(self.root.join(format!("{}.gcda", self.program)));
+
+        // Run the program.
+        let cres = process::Command::new(self.root.join(program_name(&self.program)))
+            .current_dir(&self.root)
+            .arg(format!("{}", input))
+            // https://gcc.gnu.org/onlinedocs/gcc/Cross-profiling.html
+            // The following two environment variables are needed in order to
+            // instruct gcov to write the collected information into the
+            // working directory of this runner and not into the directory/
+            // absolute path were the program was initially compiled (which is
+            // global to all workers and would thus lead to conflicts).
+            .env("GCOV_PREFIX", &self.root)
+            // Strip leading directory names from the initial absolute path.
+            // This value should be enough, although I'd prefer an explicit
+            // option to strip all leading directory names (idk if there is
+            // such an option).
+            .env("GCOV_PREFIX_STRIP", "20")
+            .stdout(process::Stdio::null())
+            .spawn()
+            .unwrap()
+            .wait()
+            .unwrap();
+
+        let res = run_result(cres);
+        if !self.coverage {
+            return (BTreeSet::new(), res);
+        }
+
+        // Generate coverage data using gcov.
+        process::Command::new("gcov")
+            .current_dir(&self.root)
+            .arg(format!("{}.c", self.program))
+            .output()
+            .unwrap();
+
+        // "Parse" (process) gcov coverage file.
+        let mut coverage = BTreeSet::new();
+        for line in fs::read_to_string(self.root.join(format!("{}.c.gcov", self.program)))
+            .unwrap()
+            .lines()
+        {
+            let elems = line.split(':').collect::<Vec<_>>();
+            let covered = elems[0].trim();
+            let line_number = elems[1].trim().parse::<usize>().unwrap();
+            if covered.starts_with("-") || covered.starts_with("#") {
+                continue;
+            }
+            coverage.insert((self.program.clone(), line_number));
+        }
+
+        (coverage, res)
+    }
+}
+
+impl Drop for Runner {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn run_result_by_signal() {
+        use std::os::unix::process::ExitStatusExt;
+        // Raw wait statuses: the exit code is in the second byte, a signal
+        // (as on Linux: SIGABRT 6, SIGKILL 9, SIGSEGV 11) in the first.
+        let status = process::ExitStatus::from_raw;
+        assert_eq!(run_result(status(0)), RunResult::Pass);
+        assert_eq!(run_result(status(1 << 8)), RunResult::Unresolved);
+        assert_eq!(run_result(status(255 << 8)), RunResult::Unresolved);
+        assert_eq!(run_result(status(6)), RunResult::Fail(6));
+        assert_eq!(run_result(status(9)), RunResult::Fail(9));
+        assert_eq!(run_result(status(11)), RunResult::Fail(11));
     }
-    fs::remove_dir(root).unwrap();
 
-    (coverage, res)
+    #[cfg(windows)]
+    #[test]
+    fn run_result_by_exception_code() {
+        use std::os::windows::process::ExitStatusExt;
+        let status = process::ExitStatus::from_raw;
+        assert_eq!(run_result(status(0)), RunResult::Pass);
+        assert_eq!(run_result(status(1)), RunResult::Unresolved);
+        assert_eq!(run_result(status(255)), RunResult::Unresolved);
+        // Access violation.
+        assert_eq!(
+            run_result(status(0xC0000005)),
+            RunResult::Fail(0xC0000005_u32 as i32)
+        );
+    }
 }
@@ -7,6 +7,8 @@
 // From https://www.fuzzingbook.org/html/MutationFuzzer.html Guiding by Coverage
 // But refactored to multi-threaded runner adapted from https://github.com/gamozolabs/guifuzz.
 
+mod bytes;
+mod cli;
 mod fuzzer;
 mod rng;
 
@@ -16,26 +18,60 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 fn main() {
+    // Master PRNG from which the PRNGs of all worker threads are derived.
+    let mut rng = cli::seed().map_or_else(rng::Rng::new, rng::Rng::seeded);
+    println!("[+] Running with random seed {}", rng.initialseed);
+
     let stats = Arc::new(Mutex::new(fuzzer::Statistics::default()));
 
-    let mut logfile = fs::File::create("plot.data").unwrap();
+    let target = cli::target("cgi_decode");
+    let quiet = cli::quiet();
+
+    let mut logfile = fs::File::create(cli::out_file("plot.data")).unwrap();
 
     let start_time = Instant::now();
 
-    fuzzer::compile_program();
+    fuzzer::compile_program(&target);
+    let coverage = fuzzer::gcov_available();
+    if !coverage {
+        println!("[!] gcov not found, fuzzing without coverage feedback");
+    }
+
+    // Run for 10 minutes or until there was no new coverage for 60 seconds
+    // (or until the number of fuzz cases given with --iterations).
+    let budget = Arc::new(fuzzer::Budget {
+        max_executions: Some(cli::iterations(usize::MAX)),
+        max_duration: Some(Duration::from_secs(10 * 60)),
+        max_plateau: Some(Duration::from_secs(60)),
+        ..fuzzer::Budget::unlimited()
+    });
 
     let nthreads = 6;
+    let mut threads = Vec::new();
     for _ in 0..nthreads {
         let stats = Arc::clone(&stats);
+        let budget = Arc::clone(&budget);
+        let rng = rng.split();
         let input = fuzzer::Input::from_str("http://www.google.com/search?q=fuzzing");
+        let target = target.clone();
 
-        std::thread::spawn(move || {
-            fuzzer::run(stats, vec![input].as_slice());
-        });
+        threads.push(std::thread::spawn(move || {
+            fuzzer::run(
+                rng,
+                stats,
+                budget,
+                vec![input].as_slice(),
+                &target,
+                coverage,
+            );
+        }));
     }
 
-    loop {
+    // Print status until all worker threads have used up the budget.
+    let mut done = false;
+    while !done {
         std::thread::sleep(Duration::from_millis(1000));
+        done = threads.iter().all(|thread| thread.is_finished());
 
         let uptime = (Instant::now() - start_time).as_secs_f64();
 
@@ -48,10 +84,12 @@ fn main() {
             )
         };
 
-        println!(
-            "{:12.2} uptime | {:7} fuzz cases | {:8} coverage | {:5} inputs",
-            uptime, curstats.0, curstats.1, curstats.2,
-        );
+        if !quiet || done {
+            println!(
+                "{:12.2} uptime | {:7} fuzz cases | {:8} coverage | {:5} inputs",
+                uptime, curstats.0, curstats.1, curstats.2,
+            );
+        }
 
         writeln!(
             logfile,
@@ -0,0 +1,281 @@
+// SPDX-FileCopyrightText: 2025 Original python code: Distributed Evolutionary Algorithms in Python (DEAP) https://github.com/DEAP/deap
+// SPDX-FileCopyrightText: 2025 Rust translation and adaptation: stfnw
+//
+// SPDX-License-Identifier: LGPL-3.0-only
+
+// The genetic algorithm from genetic-algorithm-onemax5, but evolving inputs of
+// a real program instead of bitstrings: the genome is a byte string (the
+// commandline argument of the program), the fitness is the number of lines it
+// covers (executed and traced with gcov by the `GcovRunner` of the greybox
+// library), mutation uses the mutation operators of the mutation fuzzers, and
+// crossover splices two inputs.
+
+use greybox::cli;
+use greybox::fuzzer::{self, Input, Operators};
+use greybox::oracle::{ExitStatusOracle, Oracle, SignalOracle};
+use greybox::rng::Rng;
+use greybox::runner::{Coverage, CoverageBackend, GcovRunner, RunResult};
+
+fn main() {
+    let seed = cli::seed().unwrap_or(42);
+    println!("[+] Running with random seed {}", seed);
+
+    if !GcovRunner::is_available() {
+        println!("[!] gcc and gcov are needed for running the target programs");
+        return;
+    }
+
+    let max_evaluations = cli::iterations(cli::parsed("--evaluations", 2000));
+
+    // crashme "crashes" (exits with 1) on its magic input, and every matching
+    // character of it covers one more line; cgi_decode exits with -1 on
+    // invalid inputs, so only signals count as crashes there.
+    let targets: Vec<(&str, Box<dyn Oracle>)> = vec![
+        ("crashme", Box::new(ExitStatusOracle)),
+        ("cgi_decode", Box::new(SignalOracle)),
+    ];
+    let selected = cli::target("all");
+
+    for (program, oracle) in targets {
+        if selected != "all" && selected != program {
+            continue;
+        }
+        let backend = match GcovRunner::new(program, oracle) {
+            Ok(backend) => backend,
+            Err(err) => {
+                println!("[!] {}", err);
+                continue;
+            }
+        };
+
+        println!();
+        println!(
+            "[+] Target {} with a budget of {} executions:",
+            program, max_evaluations
+        );
+
+        // Same seed and budget for the genetic algorithm and the baseline.
+        let mut rng = Rng::seeded(seed);
+        let mut evaluator = Evaluator::new(&backend, max_evaluations);
+        let best = genetic_algorithm(&mut rng, &mut evaluator);
+        evaluator.print_result("Genetic algorithm", &best);
+
+        let mut rng = Rng::seeded(seed);
+        let mut evaluator = Evaluator::new(&backend, max_evaluations);
+        let best = random_inputs(&mut rng, &mut evaluator);
+        evaluator.print_result("Random inputs", &best);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Individual {
+    genome: Input,
+    fitness: f64,
+}
+
+/// Executes the genomes in the target program and counts the executions (the
+/// budget), like the `Evaluator` of search-algorithms1. Also keeps the
+/// coverage of all executions together, and the first crashing input.
+struct Evaluator<'a> {
+    backend: &'a dyn CoverageBackend,
+    max_evaluations: usize,
+    evaluations: usize,
+    coverage: Coverage,
+    crash: Option<Input>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(backend: &'a dyn CoverageBackend, max_evaluations: usize) -> Self {
+        Self {
+            backend,
+            max_evaluations,
+            evaluations: 0,
+            coverage: Coverage::new(),
+            crash: None,
+        }
+    }
+
+    /// Execute a genome and wrap it into an individual. The fitness is the
+    /// number of covered lines; a crash gives no coverage, so its fitness is
+    /// 0 (but it is recorded, and ends the search). An execution that fails
+    /// with an error also gets fitness 0 (and still counts for the budget).
+    fn evaluate(&mut self, genome: Input) -> Individual {
+        self.evaluations += 1;
+        let result = match self.backend.run(&genome) {
+            Ok(result) => result,
+            Err(err) => {
+                eprintln!("[!] Evaluation {}: {}", self.evaluations, err);
+                return Individual {
+                    genome,
+                    fitness: 0.0,
+                };
+            }
+        };
+        let fitness = match result {
+            RunResult::Ok(coverage) => {
+                let fitness = coverage.len() as f64;
+                self.coverage.extend(coverage);
+                fitness
+            }
+            RunResult::Crash(_) => {
+                self.crash.get_or_insert(genome.clone());
+                0.0
+            }
+        };
+        Individual { genome, fitness }
+    }
+
+    /// Create and evaluate a new random individual: a printable string of 1
+    /// to 10 characters.
+    fn random(&mut self, rng: &mut Rng) -> Individual {
+        let len = rng.range(1, 10 + 1);
+        self.evaluate(Input::from_str(&rng.ascii_printable(len)))
+    }
+
+    /// Stop when the budget is used up, or a crash was found.
+    fn is_done(&self) -> bool {
+        self.evaluations >= self.max_evaluations || self.crash.is_some()
+    }
+
+    fn print_result(&self, name: &str, best: &Individual) {
+        println!(
+            "    - {:18} best fitness {:3} ({:?}), {:3} lines covered overall after {:5} executions{}",
+            name,
+            best.fitness,
+            best.genome,
+            self.coverage.len(),
+            self.evaluations,
+            match &self.crash {
+                Some(input) => format!(", crash with {:?}", input),
+                None => String::new(),
+            }
+        );
+    }
+}
+
+/// Run the genetic algorithm until the evaluator is done and return the best
+/// evolved individual.
+fn genetic_algorithm(rng: &mut Rng, evaluator: &mut Evaluator) -> Individual {
+    /* Constants for the algorithm. ******************************************/
+    let population_size: usize = 20;
+    let select_tournament_size = 5;
+    let crossover_rate: f64 = 0.5;
+    let mutation_rate: f64 = 1.0;
+    // Number of mutations applied to a mutant.
+    let max_mutations: u64 = 3;
+    // Number of best individuals that are carried over to the next generation
+    // unchanged (elitism).
+    let elite_size: usize = 2;
+    /*************************************************************************/
+
+    // Generate new population of random individuals.
+    let mut population: Vec<_> = (0..population_size)
+        .map(|_| evaluator.random(rng))
+        .collect();
+    let mut best = fittest(&population).clone();
+
+    let mut generation = 0;
+    while !evaluator.is_done() {
+        // Selection.
+        let mut new_population = Vec::with_capacity(population_size);
+        while new_population.len() < population_size {
+            let choices: Vec<_> = (0..select_tournament_size)
+                .map(|_| rng.choice(&population))
+                .collect();
+            new_population.push(fittest_ref(&choices).genome.clone());
+        }
+
+        // Crossover.
+        for chunk in new_population.chunks_mut(2) {
+            if let [parent1, parent2] = chunk {
+                if rng.f64() < crossover_rate {
+                    splice(rng, parent1, parent2);
+                }
+            }
+        }
+
+        // Mutation.
+        let new_population: Vec<_> = new_population
+            .into_iter()
+            .map(|mut mutant| {
+                if rng.f64() < mutation_rate {
+                    for _ in 0..rng.range(1, max_mutations + 1) {
+                        mutant = fuzzer::mutate(rng, mutant, Operators::Text);
+                    }
+                }
+                mutant
+            })
+            .collect();
+
+        // Replace population with next generation / new population (as far
+        // as the budget allows). Unlike OneMax, where a random bitflip rarely
+        // destroys much, most mutations and splices of an input that gets
+        // deep into the program lose that progress again; without the elite,
+        // the population drifts back to trivial inputs.
+        population.sort_by(|a, b| compare(b, a));
+        population.truncate(elite_size);
+        for genome in new_population.into_iter().skip(elite_size) {
+            if evaluator.is_done() {
+                break;
+            }
+            population.push(evaluator.evaluate(genome));
+        }
+
+        // Print status.
+        let generation_best = fittest(&population);
+        if generation_best.fitness > best.fitness {
+            best = generation_best.clone();
+            if !cli::quiet() {
+                println!(
+                    "      Generation {:4}: Best Fitness = {} ({:?})",
+                    generation, best.fitness, best.genome
+                );
+            }
+        }
+        generation += 1;
+    }
+
+    best
+}
+
+/// Baseline: only random inputs, without any feedback.
+fn random_inputs(rng: &mut Rng, evaluator: &mut Evaluator) -> Individual {
+    let mut best = evaluator.random(rng);
+    while !evaluator.is_done() {
+        let individual = evaluator.random(rng);
+        if individual.fitness > best.fitness {
+            best = individual;
+        }
+    }
+    best
+}
+
+/// Order by fitness, and among individuals with the same fitness prefer the
+/// shorter input: otherwise the inputs grow with every splice, and a mutation
+/// gets less and less likely to hit the one position that matters.
+fn compare(a: &Individual, b: &Individual) -> std::cmp::Ordering {
+    a.fitness
+        .partial_cmp(&b.fitness)
+        .unwrap()
+        .then(b.genome.0.len().cmp(&a.genome.0.len()))
+}
+
+fn fittest(population: &[Individual]) -> &Individual {
+    population.iter().max_by(|a, b| compare(a, b)).unwrap()
+}
+
+fn fittest_ref<'a>(population: &[&'a Individual]) -> &'a Individual {
+    population.iter().max_by(|a, b| compare(a, b)).unwrap()
+}
+
+/// Splice crossover: cut both inputs at a random point each, and swap the
+/// tails (so unlike the one-point crossover of bitstrings, the lengths of the
+/// inputs can change).
+fn splice(rng: &mut Rng, genome1: &mut Input, genome2: &mut Input) {
+    let point1 = rng.usize_below(genome1.0.len() + 1);
+    let point2 = rng.usize_below(genome2.0.len() + 1);
+    let tail1 = genome1.0.split_off(point1);
+    let tail2 = genome2.0.split_off(point2);
+    genome1.0.extend(tail2);
+    genome2.0.extend(tail1);
+}
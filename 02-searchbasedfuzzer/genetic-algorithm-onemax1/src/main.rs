@@ -1,11 +1,16 @@
 // SPDX-FileCopyrightText: 2025 stfnw
 // SPDX-License-Identifier: MIT
 
-use rand::Rng;
+mod cli;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 fn main() {
-    let mut rng = rand::rng();
-    let individual = genetic_algorithm(&mut rng);
+    let individual = match cli::seed() {
+        Some(seed) => genetic_algorithm(&mut StdRng::seed_from_u64(seed)),
+        None => genetic_algorithm(&mut rand::rng()),
+    };
     println!("Best Individual: {}", individual);
 }
 
@@ -18,7 +23,7 @@ fn genetic_algorithm(rng: &mut impl Rng) -> Individual {
 
     // Here we can for example run either a fixed number of generations, or
     // until the fitness value hits a maximum value that is "good enough".
-    let generations: usize = 1000;
+    let generations: usize = cli::iterations(1000);
     // In this case the maximum possible fitness is the genome size.
     let good_enough_fitness: usize = genome_size;
     /*************************************************************************/
@@ -50,10 +55,12 @@ fn genetic_algorithm(rng: &mut impl Rng) -> Individual {
 
         // Print status.
         let best_fitness = population.0[0].fitness();
-        println!(
-            "Generation {:4}: Best Fitness = {}",
-            generation, best_fitness
-        );
+        if !cli::quiet() {
+            println!(
+                "Generation {:4}: Best Fitness = {}",
+                generation, best_fitness
+            );
+        }
 
         if best_fitness >= good_enough_fitness {
             break;
@@ -11,7 +11,13 @@ pub struct Rng {
 impl Rng {
     /// Create a new PRNG with a seed based on current time.
     pub fn new() -> Self {
-        Self::seeded(unsafe { core::arch::x86_64::_rdtsc() })
+        // The system clock (and not the timestamp counter of x86, `_rdtsc`),
+        // so that this works on every platform. The process id separates
+        // processes started in the same clock tick.
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Self::seeded(nanos ^ ((std::process::id() as u64) << 32))
     }
 
     /// Create a new PRNG from a seed value.
@@ -37,12 +43,23 @@ impl Rng {
         (self.u64() as f64) / (u64::MAX as f64)
     }
 
-    /// Create random number in given range [min,max).
-    /// Uses naive way that leads to slightly non-uniform distribution.
+    /// Create random number in given range [min,max) (`max` exclusive, like
+    /// Python's `randrange`).
+    /// Uniformly distributed: the modulo alone would prefer the small results
+    /// when the size of the range doesn't divide 2^64, so the (2^64 mod range)
+    /// smallest random numbers are rejected. This happens with probability
+    /// below range/2^64, so in practice the results are the same as those of
+    /// the plain modulo.
     pub fn range(&mut self, min: u64, max: u64) -> u64 {
         assert!(min < max, "{} >= {}", min, max);
         let range = max - min;
-        min + (self.next() % range)
+        let threshold = range.wrapping_neg() % range; // 2^64 mod range
+        loop {
+            let x = self.next();
+            if x >= threshold {
+                return min + (x % range);
+            }
+        }
     }
 
     /// Create random number in range [0,max).
@@ -50,6 +67,26 @@ impl Rng {
         self.range(0, max)
     }
 
+    /// Create random index in range [0,max), e.g. a position in a slice.
+    pub fn usize_below(&mut self, max: usize) -> usize {
+        self.int(max as u64) as usize
+    }
+
+    /// Create random byte in the given range, with both bounds inclusive (so
+    /// that all bytes up to 0xff can be given): `rng.u8_range(0x20..=0x7e)`.
+    pub fn u8_range(&mut self, range: std::ops::RangeInclusive<u8>) -> u8 {
+        let (min, max) = range.into_inner();
+        self.range(min as u64, max as u64 + 1) as u8
+    }
+
+    /// Fill a buffer with random bytes (eight bytes per random number).
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
     /// Create a random boolean value.
     pub fn bool(&mut self) -> bool {
         match self.int(2) {
@@ -61,38 +98,32 @@ impl Rng {
 
     /// Create a random sequence of bytes.
     pub fn bytes(&mut self, len: u64) -> Vec<u8> {
-        let mut res = Vec::new();
-        for _ in 0..len {
-            res.push(self.int(0x100) as u8);
-        }
+        let mut res = vec![0; len as usize];
+        self.fill_bytes(&mut res);
         res
     }
 
-    /// Create a random sequence of bytes where each byte lies in
-    /// [byte_min, byte_max).
-    pub fn bytes_range(&mut self, len: u64, min: u64, max: u64) -> Vec<u8> {
-        let mut res = Vec::new();
-        for _ in 0..len {
-            res.push(self.range(min, max) as u8);
-        }
-        res
+    /// Create a random sequence of bytes where each byte lies in the given
+    /// range (both bounds inclusive).
+    pub fn bytes_range(&mut self, len: u64, range: std::ops::RangeInclusive<u8>) -> Vec<u8> {
+        (0..len).map(|_| self.u8_range(range.clone())).collect()
     }
 
     /// Create a random ascii string.
     pub fn ascii(&mut self, len: u64) -> String {
-        let res = self.bytes_range(len, 0, 0x7f + 1);
+        let res = self.bytes_range(len, 0..=0x7f);
         String::from_utf8(res).unwrap()
     }
 
     /// Create a random printable ascii string.
     pub fn ascii_printable(&mut self, len: u64) -> String {
-        let res = self.bytes_range(len, 0x20, 0x7e + 1);
+        let res = self.bytes_range(len, 0x20..=0x7e);
         String::from_utf8(res).unwrap()
     }
 
     /// Randomly choose an element of a slice.
     pub fn choice<'a, T>(&mut self, v: &'a [T]) -> &'a T {
-        let pos = self.int(v.len() as u64) as usize;
+        let pos = self.usize_below(v.len());
         &v[pos]
     }
 }
@@ -1,10 +1,11 @@
 // SPDX-FileCopyrightText: 2025 stfnw
 // SPDX-License-Identifier: MIT
 
+mod cli;
 mod rng;
 
 fn main() {
-    let mut rng = rng::Rng::seeded(42);
+    let mut rng = rng::Rng::seeded(cli::seed().unwrap_or(42));
     let individual = genetic_algorithm(&mut rng);
     println!("Best Individual: {}", individual);
 }
@@ -18,7 +19,7 @@ fn genetic_algorithm(rng: &mut rng::Rng) -> Individual {
 
     // Here we can for example run either a fixed number of generations, or
     // until the fitness value hits a maximum value that is "good enough".
-    let generations: usize = 1000;
+    let generations: usize = cli::iterations(1000);
     // In this case the maximum possible fitness is the genome size.
     let good_enough_fitness: usize = genome_size;
     /*************************************************************************/
@@ -60,10 +61,12 @@ fn genetic_algorithm(rng: &mut rng::Rng) -> Individual {
 
         // Print status.
         let best_fitness = population.0[0].fitness();
-        println!(
-            "Generation {:4}: Best Fitness = {}",
-            generation, best_fitness
-        );
+        if !cli::quiet() {
+            println!(
+                "Generation {:4}: Best Fitness = {}",
+                generation, best_fitness
+            );
+        }
 
         if best_fitness >= good_enough_fitness {
             break;
@@ -120,7 +123,7 @@ fn select(rng: &mut rng::Rng, population: &Population, fitnesses: &[f64]) -> Ind
 fn crossover(rng: &mut rng::Rng, parent1: &Individual, parent2: &Individual) -> Individual {
     assert!(parent1.genome.len() == parent2.genome.len());
     let genome_size = parent1.genome.len();
-    let point = rng.int(genome_size as u64) as usize;
+    let point = rng.usize_below(genome_size);
 
     let mut child_chromosome = Vec::with_capacity(genome_size);
     for i in 0..genome_size {
@@ -3,10 +3,11 @@
 //
 // SPDX-License-Identifier: LGPL-3.0-only
 
+mod cli;
 mod rng;
 
 fn main() {
-    let mut rng = rng::Rng::seeded(42);
+    let mut rng = rng::Rng::seeded(cli::seed().unwrap_or(42));
     let individual = genetic_algorithm(&mut rng);
     println!("Best Individual: {}", individual);
 }
@@ -23,7 +24,7 @@ fn genetic_algorithm(rng: &mut rng::Rng) -> Individual {
 
     // Here we can for example run either a fixed number of generations, or
     // until the fitness value hits a maximum value that is "good enough".
-    let generations: usize = 1000;
+    let generations: usize = cli::iterations(1000);
     // In this case the maximum possible fitness is the genome size.
     let good_enough_fitness: f64 = genome_size as f64;
     /*************************************************************************/
@@ -56,10 +57,12 @@ fn genetic_algorithm(rng: &mut rng::Rng) -> Individual {
 
         // Print status.
         let best_fitness = population.0[0].fitness;
-        println!(
-            "Generation {:4}: Best Fitness = {}",
-            generation, best_fitness
-        );
+        if !cli::quiet() {
+            println!(
+                "Generation {:4}: Best Fitness = {}",
+                generation, best_fitness
+            );
+        }
 
         if best_fitness >= good_enough_fitness {
             break;
@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// From https://www.fuzzingbook.org/html/SearchBasedFuzzer.html
+// Compare different search algorithms on the same fitness landscape.
+
+mod cli;
+mod rng;
+mod search;
+
+use std::collections::HashSet;
+
+use search::{
+    Evaluator, GeneticAlgorithm, HillClimber, OneMax, RandomWalk, SearchAlgorithm,
+    SimulatedAnnealing, TemperatureSchedule, Tournament,
+};
+
+fn main() {
+    let landscape = OneMax { genome_size: 400 };
+
+    // Every algorithm gets the same budget of fitness evaluations.
+    let max_evaluations = cli::iterations(100_000);
+
+    let algorithms: Vec<Box<dyn SearchAlgorithm>> = vec![
+        Box::new(RandomWalk),
+        Box::new(HillClimber),
+        Box::new(SimulatedAnnealing {
+            schedule: TemperatureSchedule::Exponential {
+                t0: 2.0,
+                alpha: 0.999,
+            },
+        }),
+        Box::new(SimulatedAnnealing {
+            schedule: TemperatureSchedule::Linear {
+                t0: 2.0,
+                steps: max_evaluations / 2,
+            },
+        }),
+        Box::new(GeneticAlgorithm::default()),
+    ];
+
+    let seed = cli::seed().unwrap_or(42);
+    println!("[+] Running with random seed {}", seed);
+
+    for mut algorithm in algorithms {
+        // Same seed for each algorithm.
+        let mut rng = rng::Rng::seeded(seed);
+        let mut evaluator = Evaluator::new(&landscape, max_evaluations);
+
+        let best = algorithm.search(&mut rng, &mut evaluator);
+
+        println!(
+            "{:28}: Best Fitness = {:4} after {:6} evaluations",
+            algorithm.name(),
+            best.fitness,
+            evaluator.evaluations
+        );
+    }
+
+    // Tournament variants, from no selection pressure at all (size 1) to
+    // very high pressure.
+    let tournaments = [
+        Tournament {
+            size: 1,
+            ..Default::default()
+        },
+        Tournament {
+            size: 2,
+            ..Default::default()
+        },
+        Tournament::default(),
+        Tournament {
+            replacement: false,
+            ..Default::default()
+        },
+        Tournament {
+            size: 7,
+            ..Default::default()
+        },
+        Tournament {
+            size: 7,
+            p: 0.5,
+            ..Default::default()
+        },
+        Tournament {
+            size: 20,
+            replacement: false,
+            ..Default::default()
+        },
+    ];
+
+    // Selection pressure, measured directly: select a new population from a
+    // population with fitness values 0 to 99, and look at how much better
+    // the selected individuals are, how often the best one is copied, and
+    // how many individuals are lost (not selected at all).
+    println!();
+    println!("[+] Selection pressure (population with fitness 0..99, mean of 1000 selections):");
+    let population: Vec<f64> = (0..100).map(|f| f as f64).collect();
+    for tournament in tournaments.iter() {
+        let mut rng = rng::Rng::seeded(seed);
+        let (mut mean, mut best, mut lost) = (0.0, 0.0, 0.0);
+        let rounds = 1000;
+        for _ in 0..rounds {
+            let selected: Vec<f64> = (0..population.len())
+                .map(|_| *tournament.select(&mut rng, &population, |f| *f))
+                .collect();
+            mean += selected.iter().sum::<f64>() / selected.len() as f64;
+            best += selected.iter().filter(|&&f| f == 99.0).count() as f64;
+            let distinct: HashSet<u64> = selected.iter().map(|f| *f as u64).collect();
+            lost += (population.len() - distinct.len()) as f64;
+        }
+        println!(
+            "    - {:28} mean fitness {:4.1}, best one selected {:4.1} times, {:4.1}% lost",
+            tournament.to_string(),
+            mean / rounds as f64,
+            best / rounds as f64,
+            lost / rounds as f64
+        );
+    }
+
+    // And the effect on the genetic algorithm.
+    println!();
+    println!("[+] Genetic algorithm with these tournaments:");
+    for tournament in tournaments.iter() {
+        let mut rng = rng::Rng::seeded(seed);
+        let mut evaluator = Evaluator::new(&landscape, max_evaluations);
+        let mut algorithm = GeneticAlgorithm {
+            selection: tournament.clone(),
+            ..Default::default()
+        };
+        let best = algorithm.search(&mut rng, &mut evaluator);
+        println!(
+            "    - {:28}: Best Fitness = {:4} after {:6} evaluations",
+            tournament.to_string(),
+            best.fitness,
+            evaluator.evaluations
+        );
+    }
+}
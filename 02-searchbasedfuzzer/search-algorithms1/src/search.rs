@@ -0,0 +1,442 @@
+// SPDX-FileCopyrightText: 2025 Original python code: Distributed Evolutionary Algorithms in Python (DEAP) https://github.com/DEAP/deap
+// SPDX-FileCopyrightText: 2025 Rust translation and adaptation: stfnw
+//
+// SPDX-License-Identifier: LGPL-3.0-only
+
+use crate::rng::Rng;
+
+/// Fitness landscape over bit-string genomes that the search algorithms
+/// operate on. All algorithms are run against the same landscape, so that
+/// their results are directly comparable.
+pub trait Landscape {
+    /// Number of genes / bits of each genome.
+    fn genome_size(&self) -> usize;
+
+    /// Compute the fitness of a genome (higher is better).
+    fn fitness(&self, genome: &[bool]) -> f64;
+
+    /// Fitness value that is "good enough" to stop the search early.
+    fn good_enough_fitness(&self) -> f64;
+}
+
+/// The OneMax problem: the fitness is the number of bits that are set.
+pub struct OneMax {
+    pub genome_size: usize,
+}
+
+impl Landscape for OneMax {
+    fn genome_size(&self) -> usize {
+        self.genome_size
+    }
+
+    fn fitness(&self, genome: &[bool]) -> f64 {
+        genome.iter().filter(|&&gene| gene).count() as f64
+    }
+
+    fn good_enough_fitness(&self) -> f64 {
+        // In this case the maximum possible fitness is the genome size.
+        self.genome_size as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Individual {
+    pub genome: Vec<bool>,
+    pub fitness: f64,
+}
+
+impl std::fmt::Display for Individual {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for &bit in self.genome.iter() {
+            write!(f, "{}", bit as usize)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a landscape and counts the number of fitness evaluations. The number
+/// of evaluations is the budget shared by all algorithms (instead of e.g.
+/// generations or iterations, which are not comparable between algorithms).
+pub struct Evaluator<'a> {
+    landscape: &'a dyn Landscape,
+    max_evaluations: usize,
+    pub evaluations: usize,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(landscape: &'a dyn Landscape, max_evaluations: usize) -> Self {
+        Self {
+            landscape,
+            max_evaluations,
+            evaluations: 0,
+        }
+    }
+
+    pub fn genome_size(&self) -> usize {
+        self.landscape.genome_size()
+    }
+
+    /// Evaluate a genome and wrap it into an individual.
+    pub fn evaluate(&mut self, genome: Vec<bool>) -> Individual {
+        self.evaluations += 1;
+        let fitness = self.landscape.fitness(&genome);
+        Individual { genome, fitness }
+    }
+
+    /// Create and evaluate a new random individual.
+    pub fn random(&mut self, rng: &mut Rng) -> Individual {
+        let genome = (0..self.genome_size()).map(|_| rng.bool()).collect();
+        self.evaluate(genome)
+    }
+
+    /// Check whether the search should stop: either the evaluation budget is
+    /// used up, or the best individual found so far is good enough.
+    pub fn is_done(&self, best: &Individual) -> bool {
+        self.evaluations >= self.max_evaluations
+            || best.fitness >= self.landscape.good_enough_fitness()
+    }
+}
+
+/// Common interface of all search algorithms.
+pub trait SearchAlgorithm {
+    fn name(&self) -> String;
+
+    /// Run the search until the evaluator reports that it is done and return
+    /// the best individual found.
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator) -> Individual;
+}
+
+/// Flip exactly one random bit of the genome (the neighbourhood of a genome
+/// are all genomes with hamming distance 1).
+fn random_neighbour(rng: &mut Rng, individual: &Individual) -> Vec<bool> {
+    let mut genome = individual.genome.clone();
+    let pos = rng.usize_below(genome.len());
+    genome[pos] = !genome[pos];
+    genome
+}
+
+/* Genetic algorithm. ********************************************************/
+
+/// Tournament selection: draw `size` contestants from the population, and
+/// let the fittest one win. The selection pressure (how strongly fitter
+/// individuals are preferred) grows with the size of the tournament: with
+/// size 1, selection is uniformly random; with the size of the population
+/// (and without replacement), the best individual always wins, and the
+/// population quickly loses its diversity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tournament {
+    pub size: usize,
+
+    /// Draw the contestants with replacement (the same individual can be
+    /// drawn several times, as in onemax5), or without (`size` distinct
+    /// individuals). Without replacement, the weakest individuals never win
+    /// a tournament of size > 1, and the best one wins every tournament it
+    /// takes part in, which slightly increases the pressure.
+    pub replacement: bool,
+
+    /// Probability that the fittest contestant wins (p-tournament): otherwise
+    /// the second fittest wins with probability p, and so on (the last one
+    /// wins if nobody else did). With p < 1, weaker individuals get a chance
+    /// even in large tournaments, which lowers the pressure without making
+    /// the tournaments smaller.
+    pub p: f64,
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Self {
+            size: 3,
+            replacement: true,
+            p: 1.0,
+        }
+    }
+}
+
+impl Tournament {
+    /// Run one tournament on the population and return the winner. Panics if
+    /// the tournament size is 0 or the population is empty (there would be no
+    /// winner).
+    pub fn select<'a, T>(
+        &self,
+        rng: &mut Rng,
+        population: &'a [T],
+        fitness: impl Fn(&T) -> f64,
+    ) -> &'a T {
+        assert!(self.size >= 1, "Tournament size must be at least 1");
+        assert!(!population.is_empty(), "Tournament on an empty population");
+        let mut contestants = match self.replacement {
+            true => (0..self.size).map(|_| rng.choice(population)).collect(),
+            false => rng.sample(population, self.size.min(population.len())),
+        };
+        if self.p >= 1.0 {
+            return contestants
+                .into_iter()
+                .max_by(|a, b| fitness(a).partial_cmp(&fitness(b)).unwrap())
+                .unwrap();
+        }
+
+        // Fittest first (stable, so equally fit contestants keep the random
+        // order in which they were drawn).
+        contestants.sort_by(|a, b| fitness(b).partial_cmp(&fitness(a)).unwrap());
+        let last = contestants.len() - 1;
+        for (i, contestant) in contestants.iter().enumerate() {
+            if i == last || rng.f64() < self.p {
+                return contestant;
+            }
+        }
+        unreachable!();
+    }
+}
+
+impl std::fmt::Display for Tournament {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "tournament {}", self.size)?;
+        if !self.replacement {
+            write!(f, ", w/o repl.")?;
+        }
+        if self.p < 1.0 {
+            write!(f, ", p {}", self.p)?;
+        }
+        Ok(())
+    }
+}
+
+/// Genetic algorithm with tournament selection, one-point crossover and
+/// bit-flip mutation (see genetic-algorithm-onemax5).
+pub struct GeneticAlgorithm {
+    pub population_size: usize,
+    pub selection: Tournament,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+    pub mutation_rate_bitflip: f64,
+}
+
+impl Default for GeneticAlgorithm {
+    fn default() -> Self {
+        Self {
+            population_size: 300,
+            selection: Tournament::default(),
+            crossover_rate: 0.5,
+            mutation_rate: 0.2,
+            mutation_rate_bitflip: 0.005,
+        }
+    }
+}
+
+impl SearchAlgorithm for GeneticAlgorithm {
+    fn name(&self) -> String {
+        match self.selection == Tournament::default() {
+            true => "Genetic algorithm".to_string(),
+            false => format!("GA ({})", self.selection),
+        }
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator) -> Individual {
+        // Generate new population of random individuals.
+        let mut population: Vec<_> = (0..self.population_size)
+            .map(|_| evaluator.random(rng))
+            .collect();
+        let mut best = fittest(&population).clone();
+
+        while !evaluator.is_done(&best) {
+            // Selection.
+            let mut new_population = Vec::with_capacity(self.population_size);
+            while new_population.len() < self.population_size {
+                let winner = self.selection.select(rng, &population, |i| i.fitness);
+                new_population.push(winner.genome.clone());
+            }
+
+            // Crossover.
+            for chunk in new_population.chunks_mut(2) {
+                if let [parent1, parent2] = chunk {
+                    if rng.f64() < self.crossover_rate {
+                        crossover(rng, parent1, parent2);
+                    }
+                }
+            }
+
+            // Mutation.
+            for mutant in new_population.iter_mut() {
+                if rng.f64() < self.mutation_rate {
+                    for gene in mutant.iter_mut() {
+                        if rng.f64() < self.mutation_rate_bitflip {
+                            *gene = !*gene; // Flip the gene
+                        }
+                    }
+                }
+            }
+
+            // Replace population with next generation / new population.
+            population = new_population
+                .into_iter()
+                .map(|genome| evaluator.evaluate(genome))
+                .collect();
+
+            let generation_best = fittest(&population);
+            if generation_best.fitness > best.fitness {
+                best = generation_best.clone();
+            }
+        }
+
+        best
+    }
+}
+
+fn fittest(population: &[Individual]) -> &Individual {
+    population
+        .iter()
+        .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
+        .unwrap()
+}
+
+/// One-point crossover between genomes.
+fn crossover(rng: &mut Rng, genome1: &mut [bool], genome2: &mut [bool]) {
+    assert!(genome1.len() == genome2.len());
+
+    let point = rng.range(1, genome1.len() as u64) as usize;
+
+    // Swap bits before crossover point.
+    for i in 0..point {
+        (genome1[i], genome2[i]) = (genome2[i], genome1[i]);
+    }
+}
+
+/* Random walk. **************************************************************/
+
+/// Baseline without any guidance: always move to a random neighbour, and
+/// simply remember the best individual that was encountered on the way.
+pub struct RandomWalk;
+
+impl SearchAlgorithm for RandomWalk {
+    fn name(&self) -> String {
+        "Random walk".to_string()
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator) -> Individual {
+        let mut current = evaluator.random(rng);
+        let mut best = current.clone();
+
+        while !evaluator.is_done(&best) {
+            current = evaluator.evaluate(random_neighbour(rng, &current));
+            if current.fitness > best.fitness {
+                best = current.clone();
+            }
+        }
+
+        best
+    }
+}
+
+/* Hillclimbing. *************************************************************/
+
+/// Steepest-ascent hillclimber: evaluate all neighbours of the current
+/// individual and move to the best one, as long as it is an improvement.
+/// When stuck in a local optimum, restart from a new random individual.
+pub struct HillClimber;
+
+impl SearchAlgorithm for HillClimber {
+    fn name(&self) -> String {
+        "Steepest-ascent hillclimber".to_string()
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator) -> Individual {
+        let mut current = evaluator.random(rng);
+        let mut best = current.clone();
+
+        while !evaluator.is_done(&best) {
+            let mut best_neighbour: Option<Individual> = None;
+            for i in 0..current.genome.len() {
+                let mut genome = current.genome.clone();
+                genome[i] = !genome[i];
+                let neighbour = evaluator.evaluate(genome);
+                // Update the best individual right away, so that a neighbour
+                // that is good enough stops the search without evaluating the
+                // rest of the neighbourhood.
+                if neighbour.fitness > best.fitness {
+                    best = neighbour.clone();
+                }
+                if best_neighbour
+                    .as_ref()
+                    .is_none_or(|b| neighbour.fitness > b.fitness)
+                {
+                    best_neighbour = Some(neighbour);
+                }
+                if evaluator.is_done(&best) {
+                    break;
+                }
+            }
+
+            match best_neighbour {
+                Some(neighbour) if neighbour.fitness > current.fitness => current = neighbour,
+                // Local optimum: random restart.
+                _ => current = evaluator.random(rng),
+            }
+
+            if current.fitness > best.fitness {
+                best = current.clone();
+            }
+        }
+
+        best
+    }
+}
+
+/* Simulated annealing. ******************************************************/
+
+/// Temperature schedule for simulated annealing. `k` is the current step.
+pub enum TemperatureSchedule {
+    /// T(k) = t0 * alpha^k
+    Exponential { t0: f64, alpha: f64 },
+    /// T(k) = t0 * (1 - k / steps), linearly decreasing to zero.
+    Linear { t0: f64, steps: usize },
+}
+
+impl TemperatureSchedule {
+    pub fn temperature(&self, k: usize) -> f64 {
+        match self {
+            TemperatureSchedule::Exponential { t0, alpha } => t0 * alpha.powi(k as i32),
+            TemperatureSchedule::Linear { t0, steps } => {
+                t0 * (1.0 - (k as f64 / *steps as f64)).max(0.0)
+            }
+        }
+    }
+}
+
+/// Simulated annealing: move to a random neighbour if it is better, and also
+/// accept worse neighbours with probability exp(delta / T), where the
+/// temperature T decreases over time according to a schedule.
+pub struct SimulatedAnnealing {
+    pub schedule: TemperatureSchedule,
+}
+
+impl SearchAlgorithm for SimulatedAnnealing {
+    fn name(&self) -> String {
+        match self.schedule {
+            TemperatureSchedule::Exponential { .. } => "Simulated annealing (exp.)".to_string(),
+            TemperatureSchedule::Linear { .. } => "Simulated annealing (linear)".to_string(),
+        }
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator) -> Individual {
+        let mut current = evaluator.random(rng);
+        let mut best = current.clone();
+
+        let mut k = 0;
+        while !evaluator.is_done(&best) {
+            let temperature = self.schedule.temperature(k);
+            let neighbour = evaluator.evaluate(random_neighbour(rng, &current));
+
+            let delta = neighbour.fitness - current.fitness;
+            if delta >= 0.0 || (temperature > 0.0 && rng.f64() < (delta / temperature).exp()) {
+                current = neighbour;
+            }
+
+            if current.fitness > best.fitness {
+                best = current.clone();
+            }
+            k += 1;
+        }
+
+        best
+    }
+}
@@ -0,0 +1,27 @@
+#!/usr/bin/gnuplot
+
+# Plot the history of the genetic algorithm run (plot.data, written by
+# `cargo run`): fitness of the population and its diversity per generation.
+
+set title "Genetic algorithm on OneMax"
+
+# set terminal wxt size 1000,800
+set terminal pdf
+set output "plot.pdf"
+
+set xlabel "Generation"
+set ylabel "Fitness"
+set y2label "Diversity (mean distance to the best individual)"
+set ytics nomirror
+set y2tics
+
+set grid
+set key bottom right
+
+# Columns: generation evaluations best mean min diversity
+plot "plot.data" using 1:3 with lines linewidth 2 title "Best", \
+     "plot.data" using 1:4 with lines linewidth 2 title "Mean", \
+     "plot.data" using 1:5 with lines linewidth 2 title "Min", \
+     "plot.data" using 1:6 axes x1y2 with lines linewidth 2 title "Diversity"
+
+# pause -1
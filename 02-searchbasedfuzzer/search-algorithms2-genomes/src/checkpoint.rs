@@ -0,0 +1,108 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Checkpoints of genetic algorithm runs: the complete state between two
+// generations (population, best individual, counters, Rng state, and the
+// history so far), so that a long run can be interrupted and resumed later.
+// Resuming a run from a checkpoint continues exactly like the uninterrupted
+// run would have (same random choices, same results).
+//
+// Format (text, one item per line):
+//   generation <generation>
+//   evaluations <evaluations>
+//   rng <state>
+//   best <fitness> <genome>
+//   individual <fitness> <genome>     (one line per individual)
+//   stats <generation statistics as CSV>     (one line per generation)
+// The genomes are encoded with `Genome::encode`, and floats are printed with as
+// many digits as needed to parse them back exactly.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::genome::Genome;
+use crate::history::{GenerationStats, History};
+use crate::search::Individual;
+
+#[derive(Debug, Clone)]
+pub struct Checkpoint<G> {
+    /// Number of the next generation.
+    pub generation: usize,
+    pub evaluations: usize,
+    pub rng_state: u64,
+    pub best: Individual<G>,
+    pub population: Vec<Individual<G>>,
+    pub history: History,
+}
+
+impl<G: Genome> Checkpoint<G> {
+    /// Write the checkpoint (to a temporary file first, which is then renamed,
+    /// so that an interrupted write doesn't destroy the previous checkpoint).
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let individual = |i: &Individual<G>| format!("{} {}", i.fitness, i.genome.encode());
+        let mut content = format!(
+            "generation {}\nevaluations {}\nrng {}\nbest {}\n",
+            self.generation,
+            self.evaluations,
+            self.rng_state,
+            individual(&self.best)
+        );
+        for i in self.population.iter() {
+            content += &format!("individual {}\n", individual(i));
+        }
+        for stats in self.history.0.iter() {
+            content += &format!("stats {}\n", stats.to_csv());
+        }
+
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content).ok_or_else(|| {
+            let err = format!("Invalid checkpoint {}", path.display());
+            io::Error::new(io::ErrorKind::InvalidData, err)
+        })
+    }
+
+    fn parse(content: &str) -> Option<Self> {
+        let individual = |s: &str| {
+            let (fitness, genome) = s.split_once(' ')?;
+            Some(Individual {
+                genome: G::decode(genome)?,
+                fitness: fitness.parse().ok()?,
+            })
+        };
+
+        let (mut generation, mut evaluations, mut rng_state, mut best) = (None, None, None, None);
+        let mut population = Vec::new();
+        let mut history = History::default();
+        for line in content.lines() {
+            let (key, value) = line.split_once(' ')?;
+            match key {
+                "generation" => generation = Some(value.parse().ok()?),
+                "evaluations" => evaluations = Some(value.parse().ok()?),
+                "rng" => rng_state = Some(value.parse().ok()?),
+                "best" => best = Some(individual(value)?),
+                "individual" => population.push(individual(value)?),
+                "stats" => history.0.push(GenerationStats::from_csv(value)?),
+                _ => return None,
+            }
+        }
+        if population.is_empty() {
+            return None;
+        }
+        Some(Self {
+            generation: generation?,
+            evaluations: evaluations?,
+            rng_state: rng_state?,
+            best: best?,
+            population,
+            history,
+        })
+    }
+}
@@ -0,0 +1,373 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Genome representations for the search algorithms: what a genome is, how it
+// is mutated and recombined, and what its neighbours are.
+//
+// - `Vec<bool>`: bitstrings (OneMax), with bit-flip mutation and one-point
+//   crossover. Numbers can be encoded in them either in plain binary or in
+//   Gray code (`decode_binary`, `decode_gray`).
+// - `IntVector`: vectors of bounded integers, e.g. the numeric commandline
+//   options of a program, with Gaussian mutation rounded to integers and
+//   one-point crossover.
+// - `RealVector`: vectors of bounded floats, with Gaussian mutation and blend
+//   crossover (BLX-alpha).
+
+use crate::rng::Rng;
+
+/// Operations of the search algorithms on genomes. Creating random genomes is
+/// up to the landscape, since it knows their size (and bounds).
+pub trait Genome: Clone {
+    /// Mutate every gene with probability `rate`.
+    fn mutate(&mut self, rng: &mut Rng, rate: f64);
+
+    /// Recombine two genomes (in place).
+    fn crossover(&mut self, other: &mut Self, rng: &mut Rng);
+
+    /// All genomes that differ by the smallest possible change of one gene.
+    fn neighbours(&self) -> Vec<Self>;
+
+    /// A random neighbour.
+    fn random_neighbour(&self, rng: &mut Rng) -> Self;
+
+    /// Distance between two genomes (for measuring the diversity of a
+    /// population).
+    fn distance(&self, other: &Self) -> f64;
+
+    /// Encode as string without whitespace (for checkpoints).
+    fn encode(&self) -> String;
+
+    /// Inverse of `encode`; `None` if the string is not a valid encoding.
+    fn decode(s: &str) -> Option<Self>;
+}
+
+/* Bitstrings. ***************************************************************/
+
+impl Genome for Vec<bool> {
+    fn mutate(&mut self, rng: &mut Rng, rate: f64) {
+        for gene in self.iter_mut() {
+            if rng.f64() < rate {
+                *gene = !*gene; // Flip the gene
+            }
+        }
+    }
+
+    /// One-point crossover.
+    fn crossover(&mut self, other: &mut Self, rng: &mut Rng) {
+        assert!(self.len() == other.len());
+
+        let point = rng.range(1, self.len() as u64) as usize;
+
+        // Swap bits before crossover point.
+        for i in 0..point {
+            (self[i], other[i]) = (other[i], self[i]);
+        }
+    }
+
+    /// All genomes with hamming distance 1.
+    fn neighbours(&self) -> Vec<Self> {
+        (0..self.len())
+            .map(|i| {
+                let mut genome = self.clone();
+                genome[i] = !genome[i];
+                genome
+            })
+            .collect()
+    }
+
+    /// Flip exactly one random bit.
+    fn random_neighbour(&self, rng: &mut Rng) -> Self {
+        let mut genome = self.clone();
+        let pos = rng.usize_below(genome.len());
+        genome[pos] = !genome[pos];
+        genome
+    }
+
+    /// Hamming distance.
+    fn distance(&self, other: &Self) -> f64 {
+        self.iter()
+            .zip(other.iter())
+            .filter(|(a, b)| a != b)
+            .count() as f64
+    }
+
+    /// `0` and `1` per bit.
+    fn encode(&self) -> String {
+        self.iter()
+            .map(|&bit| if bit { '1' } else { '0' })
+            .collect()
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        s.chars()
+            .map(|c| match c {
+                '0' => Some(false),
+                '1' => Some(true),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Unsigned number encoded in plain binary, most significant bit first.
+/// Neighbouring numbers can differ in many bits (e.g. 0111 and 1000), so a
+/// search that flips single bits can get stuck right next to its target
+/// ("Hamming cliff").
+pub fn decode_binary(bits: &[bool]) -> u64 {
+    bits.iter().fold(0, |n, &bit| (n << 1) | bit as u64)
+}
+
+/// Unsigned number encoded in Gray code, most significant bit first:
+/// neighbouring numbers differ in exactly one bit, so every number can be
+/// increased and decreased by 1 by flipping a single bit.
+pub fn decode_gray(bits: &[bool]) -> u64 {
+    // Bit i of the number is the xor of all Gray code bits up to i.
+    let mut xor = false;
+    bits.iter().fold(0, |n, &bit| {
+        xor ^= bit;
+        (n << 1) | xor as u64
+    })
+}
+
+/* Integer vectors. **********************************************************/
+
+/// Vector of integers in `min..=max`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntVector {
+    pub genes: Vec<i64>,
+    pub min: i64,
+    pub max: i64,
+
+    /// Standard deviation of the mutation (as fraction of the range): large
+    /// values explore, small ones fine-tune.
+    pub sigma: f64,
+}
+
+impl IntVector {
+    /// Random genes, uniformly distributed over the range.
+    pub fn random(rng: &mut Rng, len: usize, min: i64, max: i64, sigma: f64) -> Self {
+        let genes = (0..len)
+            .map(|_| min + rng.int((max - min + 1) as u64) as i64)
+            .collect();
+        Self {
+            genes,
+            min,
+            max,
+            sigma,
+        }
+    }
+}
+
+impl Genome for IntVector {
+    /// Gaussian mutation, rounded to the next integer (but always changing
+    /// the gene by at least 1).
+    fn mutate(&mut self, rng: &mut Rng, rate: f64) {
+        let sigma = self.sigma * (self.max - self.min) as f64;
+        for gene in self.genes.iter_mut() {
+            if rng.f64() < rate {
+                let delta = rng.gaussian(0.0, sigma).round() as i64;
+                let delta = match delta {
+                    0 if rng.f64() < 0.5 => -1,
+                    0 => 1,
+                    delta => delta,
+                };
+                *gene = (*gene + delta).clamp(self.min, self.max);
+            }
+        }
+    }
+
+    /// One-point crossover.
+    fn crossover(&mut self, other: &mut Self, rng: &mut Rng) {
+        assert!(self.genes.len() == other.genes.len());
+        if self.genes.len() < 2 {
+            return;
+        }
+
+        let point = rng.range(1, self.genes.len() as u64) as usize;
+        for i in 0..point {
+            (self.genes[i], other.genes[i]) = (other.genes[i], self.genes[i]);
+        }
+    }
+
+    /// Every gene increased or decreased by 1.
+    fn neighbours(&self) -> Vec<Self> {
+        let mut res = Vec::new();
+        for i in 0..self.genes.len() {
+            for delta in [-1, 1] {
+                let gene = self.genes[i] + delta;
+                if self.min <= gene && gene <= self.max {
+                    let mut genome = self.clone();
+                    genome.genes[i] = gene;
+                    res.push(genome);
+                }
+            }
+        }
+        res
+    }
+
+    fn random_neighbour(&self, rng: &mut Rng) -> Self {
+        let neighbours = self.neighbours();
+        rng.choice(&neighbours).clone()
+    }
+
+    /// Sum of the differences of the genes, relative to the range.
+    fn distance(&self, other: &Self) -> f64 {
+        let sum: i64 = (self.genes.iter().zip(other.genes.iter()))
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+        sum as f64 / (self.max - self.min) as f64
+    }
+
+    /// `<min>/<max>/<sigma>/<gene>,<gene>,...`
+    fn encode(&self) -> String {
+        let genes: Vec<String> = self.genes.iter().map(|gene| gene.to_string()).collect();
+        format!(
+            "{}/{}/{}/{}",
+            self.min,
+            self.max,
+            self.sigma,
+            genes.join(",")
+        )
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        let [min, max, sigma, genes] = s.split('/').collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        Some(Self {
+            genes: genes
+                .split(',')
+                .map(|gene| gene.parse().ok())
+                .collect::<Option<_>>()?,
+            min: min.parse().ok()?,
+            max: max.parse().ok()?,
+            sigma: sigma.parse().ok()?,
+        })
+    }
+}
+
+/* Real vectors. *************************************************************/
+
+/// Vector of floats in `min..=max`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealVector {
+    pub genes: Vec<f64>,
+    pub min: f64,
+    pub max: f64,
+
+    /// Standard deviation of the mutation (as fraction of the range); also
+    /// the step size of the neighbours.
+    pub sigma: f64,
+
+    /// How far the children of the blend crossover can lie outside of the
+    /// interval between the parents (as fraction of its length).
+    pub alpha: f64,
+}
+
+impl RealVector {
+    /// Random genes, uniformly distributed over the range.
+    pub fn random(rng: &mut Rng, len: usize, min: f64, max: f64, sigma: f64) -> Self {
+        let genes = (0..len).map(|_| min + rng.f64() * (max - min)).collect();
+        Self {
+            genes,
+            min,
+            max,
+            sigma,
+            alpha: 0.5,
+        }
+    }
+
+    fn step(&self) -> f64 {
+        self.sigma * (self.max - self.min)
+    }
+}
+
+impl Genome for RealVector {
+    /// Gaussian mutation.
+    fn mutate(&mut self, rng: &mut Rng, rate: f64) {
+        let sigma = self.step();
+        for gene in self.genes.iter_mut() {
+            if rng.f64() < rate {
+                *gene = (*gene + rng.gaussian(0.0, sigma)).clamp(self.min, self.max);
+            }
+        }
+    }
+
+    /// Blend crossover (BLX-alpha): for each gene, both children get a random
+    /// value in the interval between the values of the parents, extended by
+    /// `alpha` times its length on both sides. Unlike one-point crossover,
+    /// this creates new values, and can also move beyond the parents.
+    fn crossover(&mut self, other: &mut Self, rng: &mut Rng) {
+        assert!(self.genes.len() == other.genes.len());
+
+        for (x, y) in self.genes.iter_mut().zip(other.genes.iter_mut()) {
+            let d = (*x - *y).abs();
+            let low = x.min(*y) - self.alpha * d;
+            let high = x.max(*y) + self.alpha * d;
+            *x = (low + rng.f64() * (high - low)).clamp(self.min, self.max);
+            *y = (low + rng.f64() * (high - low)).clamp(self.min, self.max);
+        }
+    }
+
+    /// Every gene increased or decreased by one step (`sigma`).
+    fn neighbours(&self) -> Vec<Self> {
+        let step = self.step();
+        let mut res = Vec::new();
+        for i in 0..self.genes.len() {
+            for delta in [-step, step] {
+                let mut genome = self.clone();
+                genome.genes[i] = (self.genes[i] + delta).clamp(self.min, self.max);
+                res.push(genome);
+            }
+        }
+        res
+    }
+
+    /// One gene changed by a Gaussian step.
+    fn random_neighbour(&self, rng: &mut Rng) -> Self {
+        let mut genome = self.clone();
+        let i = rng.usize_below(genome.genes.len());
+        genome.genes[i] =
+            (genome.genes[i] + rng.gaussian(0.0, self.step())).clamp(self.min, self.max);
+        genome
+    }
+
+    /// Sum of the differences of the genes, relative to the range.
+    fn distance(&self, other: &Self) -> f64 {
+        let sum: f64 = (self.genes.iter().zip(other.genes.iter()))
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+        sum / (self.max - self.min)
+    }
+
+    /// `<min>/<max>/<sigma>/<alpha>/<gene>,<gene>,...` (floats are printed
+    /// with as many digits as needed to parse them back exactly).
+    fn encode(&self) -> String {
+        let genes: Vec<String> = self.genes.iter().map(|gene| gene.to_string()).collect();
+        format!(
+            "{}/{}/{}/{}/{}",
+            self.min,
+            self.max,
+            self.sigma,
+            self.alpha,
+            genes.join(",")
+        )
+    }
+
+    fn decode(s: &str) -> Option<Self> {
+        let [min, max, sigma, alpha, genes] = s.split('/').collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        Some(Self {
+            genes: genes
+                .split(',')
+                .map(|gene| gene.parse().ok())
+                .collect::<Option<_>>()?,
+            min: min.parse().ok()?,
+            max: max.parse().ok()?,
+            sigma: sigma.parse().ok()?,
+            alpha: alpha.parse().ok()?,
+        })
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Statistics of the generations of a genetic algorithm run, for seeing how the
+// fitness develops over time (and whether the population converged too
+// early). Exported as CSV or JSON, and as `plot.data` for gnuplot (see
+// plot.plt).
+//
+// The diversity of a generation is the mean distance (`Genome::distance`) of
+// the individuals to the best one: it drops to 0 when the population has
+// converged to copies of the best individual.
+
+use crate::genome::Genome;
+use crate::search::Individual;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationStats {
+    pub generation: usize,
+    /// Fitness evaluations so far (including this generation).
+    pub evaluations: usize,
+    pub best: f64,
+    pub mean: f64,
+    pub min: f64,
+    pub diversity: f64,
+}
+
+impl GenerationStats {
+    pub fn new<G: Genome>(
+        generation: usize,
+        evaluations: usize,
+        population: &[Individual<G>],
+    ) -> Self {
+        let fitness = population.iter().map(|i| i.fitness);
+        let best = population
+            .iter()
+            .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
+            .unwrap();
+        let n = population.len() as f64;
+        Self {
+            generation,
+            evaluations,
+            best: best.fitness,
+            mean: fitness.clone().sum::<f64>() / n,
+            min: fitness.fold(f64::INFINITY, f64::min),
+            diversity: population
+                .iter()
+                .map(|i| i.genome.distance(&best.genome))
+                .sum::<f64>()
+                / n,
+        }
+    }
+
+    const FIELDS: [&'static str; 6] = [
+        "generation",
+        "evaluations",
+        "best",
+        "mean",
+        "min",
+        "diversity",
+    ];
+
+    fn values(&self) -> [String; 6] {
+        [
+            self.generation.to_string(),
+            self.evaluations.to_string(),
+            self.best.to_string(),
+            self.mean.to_string(),
+            self.min.to_string(),
+            self.diversity.to_string(),
+        ]
+    }
+
+    /// Values separated by `,`, in the order of `FIELDS`.
+    pub fn to_csv(&self) -> String {
+        self.values().join(",")
+    }
+
+    /// Inverse of `to_csv`.
+    pub fn from_csv(line: &str) -> Option<Self> {
+        let values: Vec<&str> = line.split(',').collect();
+        let [generation, evaluations, best, mean, min, diversity] = values[..] else {
+            return None;
+        };
+        Some(Self {
+            generation: generation.parse().ok()?,
+            evaluations: evaluations.parse().ok()?,
+            best: best.parse().ok()?,
+            mean: mean.parse().ok()?,
+            min: min.parse().ok()?,
+            diversity: diversity.parse().ok()?,
+        })
+    }
+}
+
+/// Statistics of all generations of a run, in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History(pub Vec<GenerationStats>);
+
+impl History {
+    /// With a header line.
+    pub fn to_csv(&self) -> String {
+        let mut res = GenerationStats::FIELDS.join(",") + "\n";
+        for stats in self.0.iter() {
+            res += &stats.to_csv();
+            res += "\n";
+        }
+        res
+    }
+
+    /// Array of objects, one per generation (and per line).
+    pub fn to_json(&self) -> String {
+        let objects: Vec<String> = self
+            .0
+            .iter()
+            .map(|stats| {
+                let fields: Vec<String> = GenerationStats::FIELDS
+                    .iter()
+                    .zip(stats.values())
+                    .map(|(field, value)| format!("\"{}\": {}", field, json_number(value)))
+                    .collect();
+                format!("  {{{}}}", fields.join(", "))
+            })
+            .collect();
+        format!("[\n{}\n]\n", objects.join(",\n"))
+    }
+
+    /// Columns separated by spaces, for gnuplot (`plot.plt`).
+    pub fn to_plot_data(&self) -> String {
+        self.0
+            .iter()
+            .map(|stats| stats.values().join(" ") + "\n")
+            .collect()
+    }
+}
+
+/// JSON has no infinity or NaN (e.g. the fitness of a landscape that returns
+/// them).
+fn json_number(value: String) -> String {
+    match value.parse::<f64>() {
+        Ok(f) if !f.is_finite() => "null".to_string(),
+        _ => value,
+    }
+}
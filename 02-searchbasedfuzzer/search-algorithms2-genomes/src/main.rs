@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// From https://www.fuzzingbook.org/html/SearchBasedFuzzer.html
+// Compare different search algorithms on the same fitness landscape, with
+// different genome representations.
+
+mod checkpoint;
+mod cli;
+mod genome;
+mod history;
+mod rng;
+mod search;
+mod testme;
+
+use std::fs;
+use std::path::PathBuf;
+
+use genome::Genome;
+use search::{
+    Evaluator, GeneticAlgorithm, HillClimber, Landscape, OneMax, RandomWalk, SearchAlgorithm,
+    SimulatedAnnealing, TemperatureSchedule,
+};
+use testme::{TestMeBits, TestMeInt, TestMeReal};
+
+fn main() {
+    let seed = cli::seed().unwrap_or(42);
+    println!("[+] Running with random seed {}", seed);
+
+    // Every algorithm gets the same budget of fitness evaluations.
+    let max_evaluations = cli::iterations(100_000);
+
+    println!();
+    println!("[+] OneMax (bitstrings of 400 bits):");
+    compare(
+        &OneMax { genome_size: 400 },
+        seed,
+        max_evaluations,
+        0.005,
+        |_| String::new(),
+    );
+
+    println!();
+    println!("[+] test_me(x, y) with integers:");
+    compare(&TestMeInt, seed, max_evaluations, 0.5, |genome| {
+        format!("x = {}, y = {}", genome.genes[0], genome.genes[1])
+    });
+
+    for gray in [false, true] {
+        let landscape = TestMeBits { gray };
+        println!();
+        println!(
+            "[+] test_me(x, y) with integers encoded as 16 bits each ({}):",
+            match gray {
+                false => "binary",
+                true => "Gray code",
+            }
+        );
+        compare(&landscape, seed, max_evaluations, 1.0 / 32.0, |genome| {
+            let (x, y) = landscape.decode(genome);
+            format!("x = {}, y = {}", x, y)
+        });
+    }
+
+    println!();
+    println!("[+] test_me(x, y) with floats (distance below 0.01 is good enough):");
+    compare(
+        &TestMeReal { epsilon: 0.01 },
+        seed,
+        max_evaluations,
+        0.5,
+        |genome| format!("x = {:.3}, y = {:.3}", genome.genes[0], genome.genes[1]),
+    );
+
+    history_and_checkpoints(seed, max_evaluations);
+}
+
+/// The genetic algorithm on OneMax, with the statistics of every generation
+/// (written to plot.data for plot.plt, and optionally to `--history <file>`,
+/// as JSON if the file name ends with `.json`, as CSV otherwise). Then the
+/// same run again, but interrupted after a part of the budget and resumed from
+/// the checkpoint (`--checkpoint <file>`, in the temporary directory by
+/// default).
+fn history_and_checkpoints(seed: u64, max_evaluations: usize) {
+    let landscape = OneMax { genome_size: 400 };
+
+    println!();
+    println!("[+] Genetic algorithm on OneMax, history of the generations:");
+    let mut rng = rng::Rng::seeded(seed);
+    let mut evaluator = Evaluator::new(&landscape, max_evaluations);
+    let mut algorithm = GeneticAlgorithm::default();
+    let best = algorithm.search(&mut rng, &mut evaluator);
+    let history = algorithm.history.clone();
+    if !cli::quiet() {
+        for stats in history.0.iter().filter(|stats| stats.generation % 25 == 0) {
+            println!(
+                "    - Generation {:3}: best {:3}, mean {:5.1}, min {:3}, diversity {:5.1}",
+                stats.generation, stats.best, stats.mean, stats.min, stats.diversity
+            );
+        }
+    }
+    println!(
+        "    Best Fitness = {} after {} generations and {} evaluations",
+        best.fitness,
+        history.0.len() - 1,
+        evaluator.evaluations
+    );
+    let plot_data = cli::out_file("plot.data");
+    fs::write(&plot_data, history.to_plot_data()).unwrap();
+    println!(
+        "    Wrote {} (plot with: gnuplot plot.plt)",
+        plot_data.display()
+    );
+    if let Some(path) = cli::option("--history") {
+        let content = match path.ends_with(".json") {
+            true => history.to_json(),
+            false => history.to_csv(),
+        };
+        fs::write(&path, content).unwrap();
+        println!("    Wrote {}", path);
+    }
+
+    println!();
+    println!("[+] The same run, interrupted and resumed from a checkpoint:");
+    let path = cli::option("--checkpoint").map_or_else(
+        || std::env::temp_dir().join("search-algorithms2-genomes.checkpoint"),
+        PathBuf::from,
+    );
+    let interrupted = max_evaluations * 3 / 10;
+    let mut rng = rng::Rng::seeded(seed);
+    let mut evaluator = Evaluator::new(&landscape, interrupted);
+    let mut algorithm = GeneticAlgorithm {
+        checkpoint: Some(path.clone()),
+        ..Default::default()
+    };
+    let first = algorithm.search(&mut rng, &mut evaluator);
+    println!(
+        "    Stopped after {} evaluations with Best Fitness = {}, checkpoint in {}",
+        evaluator.evaluations,
+        first.fitness,
+        path.display()
+    );
+
+    // Fresh Rng and evaluator: everything comes from the checkpoint.
+    let mut rng = rng::Rng::seeded(0);
+    let mut evaluator = Evaluator::new(&landscape, max_evaluations);
+    let mut algorithm = GeneticAlgorithm {
+        resume: Some(path.clone()),
+        ..Default::default()
+    };
+    let resumed = algorithm.search(&mut rng, &mut evaluator);
+    println!(
+        "    Resumed: Best Fitness = {} after {} evaluations",
+        resumed.fitness, evaluator.evaluations
+    );
+    println!(
+        "    Same best individual and history as the uninterrupted run: {}",
+        resumed.genome == best.genome && algorithm.history == history
+    );
+    fs::remove_file(&path).unwrap();
+}
+
+/// Run all search algorithms on the landscape with the same seed and budget,
+/// and print the best individual (with `show`) of each. The genetic algorithm
+/// mutates each gene of a mutant with probability `mutation_rate_gene`
+/// (about one gene per mutant works well).
+fn compare<G: Genome>(
+    landscape: &dyn Landscape<G>,
+    seed: u64,
+    max_evaluations: usize,
+    mutation_rate_gene: f64,
+    show: impl Fn(&G) -> String,
+) {
+    let algorithms: Vec<Box<dyn SearchAlgorithm<G>>> = vec![
+        Box::new(RandomWalk),
+        Box::new(HillClimber),
+        Box::new(SimulatedAnnealing {
+            schedule: TemperatureSchedule::Exponential {
+                t0: 2.0,
+                alpha: 0.999,
+            },
+        }),
+        Box::new(SimulatedAnnealing {
+            schedule: TemperatureSchedule::Linear {
+                t0: 2.0,
+                steps: max_evaluations / 2,
+            },
+        }),
+        Box::new(GeneticAlgorithm {
+            mutation_rate_gene,
+            ..Default::default()
+        }),
+    ];
+
+    for mut algorithm in algorithms {
+        // Same seed for each algorithm.
+        let mut rng = rng::Rng::seeded(seed);
+        let mut evaluator = Evaluator::new(landscape, max_evaluations);
+
+        let best = algorithm.search(&mut rng, &mut evaluator);
+
+        let line = format!(
+            "    - {:28}: Best Fitness = {:8.2} after {:6} evaluations   {}",
+            algorithm.name(),
+            // Without negative zero.
+            best.fitness + 0.0,
+            evaluator.evaluations,
+            show(&best.genome)
+        );
+        println!("{}", line.trim_end());
+    }
+}
@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: xorshift64 implementation from G. Marsaglia, “Xorshift RNGs,” J. Stat. Soft., vol. 8, no. 14, pp. 1–6, Jul. 2003, doi: 10.18637/jss.v008.i14.
+//
+// SPDX-FileCopyrightText: 2025 Choices and bisect implementation translated from Python; original code: Python Software Foundation
+// SPDX-License-Identifier: PSF-2.0
+//
+// SPDX-FileCopyrightText: 2025 Rest of implementation and scaffolding: stfnw
+// SPDX-License-Identifier: MIT
+
+pub struct Rng {
+    pub state: u64,
+}
+
+#[allow(dead_code)]
+impl Rng {
+    /// Create a new PRNG with a seed based on current time.
+    pub fn new() -> Self {
+        // The system clock (and not the timestamp counter of x86, `_rdtsc`),
+        // so that this works on every platform. The process id separates
+        // processes started in the same clock tick.
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Self::seeded(nanos ^ ((std::process::id() as u64) << 32))
+    }
+
+    /// Create a new PRNG from a seed value.
+    pub fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Create new random number and advance the internal state.
+    pub fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Create random u64.
+    pub fn u64(&mut self) -> u64 {
+        self.next()
+    }
+
+    /// Create random float in [0,1.0)
+    pub fn f64(&mut self) -> f64 {
+        (self.u64() as f64) / (u64::MAX as f64)
+    }
+
+    /// Create random number in given range [min,max) (`max` exclusive, like
+    /// Python's `randrange`).
+    /// Uniformly distributed: the modulo alone would prefer the small results
+    /// when the size of the range doesn't divide 2^64, so the (2^64 mod range)
+    /// smallest random numbers are rejected. This happens with probability
+    /// below range/2^64, so in practice the results are the same as those of
+    /// the plain modulo.
+    pub fn range(&mut self, min: u64, max: u64) -> u64 {
+        assert!(min < max, "{} >= {}", min, max);
+        let range = max - min;
+        let threshold = range.wrapping_neg() % range; // 2^64 mod range
+        loop {
+            let x = self.next();
+            if x >= threshold {
+                return min + (x % range);
+            }
+        }
+    }
+
+    /// Create random number in range [0,max).
+    pub fn int(&mut self, max: u64) -> u64 {
+        self.range(0, max)
+    }
+
+    /// Create random index in range [0,max), e.g. a position in a slice.
+    pub fn usize_below(&mut self, max: usize) -> usize {
+        self.int(max as u64) as usize
+    }
+
+    /// Create random byte in the given range, with both bounds inclusive (so
+    /// that all bytes up to 0xff can be given): `rng.u8_range(0x20..=0x7e)`.
+    pub fn u8_range(&mut self, range: std::ops::RangeInclusive<u8>) -> u8 {
+        let (min, max) = range.into_inner();
+        self.range(min as u64, max as u64 + 1) as u8
+    }
+
+    /// Fill a buffer with random bytes (eight bytes per random number).
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// Create a random boolean value.
+    pub fn bool(&mut self) -> bool {
+        match self.int(2) {
+            0 => false,
+            1 => true,
+            _ => panic!("Can't happen"),
+        }
+    }
+
+    /// Create a random sequence of bytes.
+    pub fn bytes(&mut self, len: u64) -> Vec<u8> {
+        let mut res = vec![0; len as usize];
+        self.fill_bytes(&mut res);
+        res
+    }
+
+    /// Create a random sequence of bytes where each byte lies in the given
+    /// range (both bounds inclusive).
+    pub fn bytes_range(&mut self, len: u64, range: std::ops::RangeInclusive<u8>) -> Vec<u8> {
+        (0..len).map(|_| self.u8_range(range.clone())).collect()
+    }
+
+    /// Create a random ascii string.
+    pub fn ascii(&mut self, len: u64) -> String {
+        let res = self.bytes_range(len, 0..=0x7f);
+        String::from_utf8(res).unwrap()
+    }
+
+    /// Create a random printable ascii string.
+    pub fn ascii_printable(&mut self, len: u64) -> String {
+        let res = self.bytes_range(len, 0x20..=0x7e);
+        String::from_utf8(res).unwrap()
+    }
+
+    /// Randomly choose an element of a slice.
+    pub fn choice<'a, T>(&mut self, v: &'a [T]) -> &'a T {
+        let pos = self.usize_below(v.len());
+        &v[pos]
+    }
+
+    /// Randomly choose one element from a slice given weights/propabilities.
+    /// Translated from https://github.com/python/cpython/blob/9634085af3670b1eb654e3c7820aca66f358f39f/Lib/random.py#L460
+    /// and https://github.com/python/cpython/blob/9634085af3670b1eb654e3c7820aca66f358f39f/Lib/bisect.py#L21
+    pub fn choice_w<'a, T>(&mut self, v: &'a [T], weights: &[f64]) -> &'a T {
+        assert!(v.len() == weights.len(), "{} != {}", v.len(), weights.len());
+        let mut cumuluative_weights = Vec::new();
+        let mut tmp = 0.0;
+        for w in weights {
+            assert!(*w >= 0.0, "Weight must be non-negative {}", w);
+            tmp += w;
+            cumuluative_weights.push(tmp);
+        }
+        self.choice_cw(v, &cumuluative_weights)
+    }
+
+    pub fn choice_cw<'a, T>(&mut self, v: &'a [T], cumulative_weights: &[f64]) -> &'a T {
+        assert!(
+            v.len() == cumulative_weights.len(),
+            "{} != {}",
+            v.len(),
+            cumulative_weights.len()
+        );
+
+        let total = *cumulative_weights.last().unwrap();
+        assert!(total > 0.0, "Total weight must be non-zero: {}", total);
+
+        let pos = bisect(
+            cumulative_weights,
+            self.f64() * total,
+            0,
+            cumulative_weights.len() - 1,
+        );
+
+        &v[pos]
+    }
+
+    /// Shuffle a slice in place (Fisher-Yates shuffle).
+    pub fn shuffle<T>(&mut self, v: &mut [T]) {
+        for i in (1..v.len()).rev() {
+            let j = self.usize_below(i + 1);
+            v.swap(i, j);
+        }
+    }
+
+    /// Randomly choose `k` distinct elements of a slice (sampling without
+    /// replacement). The order of the returned elements is random as well.
+    pub fn sample<'a, T>(&mut self, v: &'a [T], k: usize) -> Vec<&'a T> {
+        assert!(k <= v.len(), "Sample larger than population {} > {}", k, v.len());
+        // Partial Fisher-Yates shuffle over the indices: only the first k
+        // positions need to be determined.
+        let mut indices: Vec<usize> = (0..v.len()).collect();
+        for i in 0..k {
+            let j = self.range(i as u64, v.len() as u64) as usize;
+            indices.swap(i, j);
+        }
+        indices[..k].iter().map(|&i| &v[i]).collect()
+    }
+
+    /// Create random float from a normal distribution with mean `mu` and
+    /// standard deviation `sigma` (Box-Muller transform).
+    pub fn gaussian(&mut self, mu: f64, sigma: f64) -> f64 {
+        let u1 = self.f64_nonzero();
+        let u2 = self.f64();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        mu + sigma * z
+    }
+
+    /// Create random number from a geometric distribution: the number of
+    /// failed Bernoulli trials with success probability `p` before the first
+    /// success (support 0, 1, 2, ...). Uses inversion of the CDF.
+    pub fn geometric(&mut self, p: f64) -> u64 {
+        assert!(0.0 < p && p <= 1.0, "Probability must be in (0,1]: {}", p);
+        if p == 1.0 {
+            return 0;
+        }
+        (self.f64_nonzero().ln() / (1.0 - p).ln()).floor() as u64
+    }
+
+    /// Create random float in (0,1.0) (for taking logarithms).
+    fn f64_nonzero(&mut self) -> f64 {
+        loop {
+            let u = self.f64();
+            if 0.0 < u && u < 1.0 {
+                return u;
+            }
+        }
+    }
+}
+
+fn bisect(v: &[f64], x: f64, mut lo: usize, mut hi: usize) -> usize {
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if x < v[mid] {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    lo
+}
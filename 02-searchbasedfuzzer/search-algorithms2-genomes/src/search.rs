@@ -0,0 +1,495 @@
+// SPDX-FileCopyrightText: 2025 Original python code: Distributed Evolutionary Algorithms in Python (DEAP) https://github.com/DEAP/deap
+// SPDX-FileCopyrightText: 2025 Rust translation and adaptation: stfnw
+//
+// SPDX-License-Identifier: LGPL-3.0-only
+
+use std::path::PathBuf;
+
+use crate::checkpoint::Checkpoint;
+use crate::genome::Genome;
+use crate::history::{GenerationStats, History};
+use crate::rng::Rng;
+
+/// Fitness landscape over genomes of type `G` that the search algorithms
+/// operate on. All algorithms are run against the same landscape, so that
+/// their results are directly comparable.
+pub trait Landscape<G: Genome> {
+    /// Create a new random genome.
+    fn random(&self, rng: &mut Rng) -> G;
+
+    /// Compute the fitness of a genome (higher is better).
+    fn fitness(&self, genome: &G) -> f64;
+
+    /// Fitness value that is "good enough" to stop the search early.
+    fn good_enough_fitness(&self) -> f64;
+}
+
+/// The OneMax problem: the fitness is the number of bits that are set.
+pub struct OneMax {
+    pub genome_size: usize,
+}
+
+impl Landscape<Vec<bool>> for OneMax {
+    fn random(&self, rng: &mut Rng) -> Vec<bool> {
+        (0..self.genome_size).map(|_| rng.bool()).collect()
+    }
+
+    fn fitness(&self, genome: &Vec<bool>) -> f64 {
+        genome.iter().filter(|&&gene| gene).count() as f64
+    }
+
+    fn good_enough_fitness(&self) -> f64 {
+        // In this case the maximum possible fitness is the genome size.
+        self.genome_size as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Individual<G> {
+    pub genome: G,
+    pub fitness: f64,
+}
+
+impl std::fmt::Display for Individual<Vec<bool>> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for &bit in self.genome.iter() {
+            write!(f, "{}", bit as usize)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a landscape and counts the number of fitness evaluations. The number
+/// of evaluations is the budget shared by all algorithms (instead of e.g.
+/// generations or iterations, which are not comparable between algorithms).
+pub struct Evaluator<'a, G: Genome> {
+    landscape: &'a dyn Landscape<G>,
+    max_evaluations: usize,
+    pub evaluations: usize,
+}
+
+impl<'a, G: Genome> Evaluator<'a, G> {
+    pub fn new(landscape: &'a dyn Landscape<G>, max_evaluations: usize) -> Self {
+        Self {
+            landscape,
+            max_evaluations,
+            evaluations: 0,
+        }
+    }
+
+    /// Evaluate a genome and wrap it into an individual.
+    pub fn evaluate(&mut self, genome: G) -> Individual<G> {
+        self.evaluations += 1;
+        let fitness = self.landscape.fitness(&genome);
+        Individual { genome, fitness }
+    }
+
+    /// Create and evaluate a new random individual.
+    pub fn random(&mut self, rng: &mut Rng) -> Individual<G> {
+        let genome = self.landscape.random(rng);
+        self.evaluate(genome)
+    }
+
+    /// Check whether the search should stop: either the evaluation budget is
+    /// used up, or the best individual found so far is good enough.
+    pub fn is_done(&self, best: &Individual<G>) -> bool {
+        self.evaluations >= self.max_evaluations
+            || best.fitness >= self.landscape.good_enough_fitness()
+    }
+}
+
+/// Common interface of all search algorithms (for genomes of type `G`).
+pub trait SearchAlgorithm<G: Genome> {
+    fn name(&self) -> String;
+
+    /// Run the search until the evaluator reports that it is done and return
+    /// the best individual found.
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator<G>) -> Individual<G>;
+}
+
+/* Genetic algorithm. ********************************************************/
+
+/// Tournament selection: draw `size` contestants from the population, and
+/// let the fittest one win. The selection pressure (how strongly fitter
+/// individuals are preferred) grows with the size of the tournament: with
+/// size 1, selection is uniformly random; with the size of the population
+/// (and without replacement), the best individual always wins, and the
+/// population quickly loses its diversity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tournament {
+    pub size: usize,
+
+    /// Draw the contestants with replacement (the same individual can be
+    /// drawn several times, as in onemax5), or without (`size` distinct
+    /// individuals). Without replacement, the weakest individuals never win
+    /// a tournament of size > 1, and the best one wins every tournament it
+    /// takes part in, which slightly increases the pressure.
+    pub replacement: bool,
+
+    /// Probability that the fittest contestant wins (p-tournament): otherwise
+    /// the second fittest wins with probability p, and so on (the last one
+    /// wins if nobody else did). With p < 1, weaker individuals get a chance
+    /// even in large tournaments, which lowers the pressure without making
+    /// the tournaments smaller.
+    pub p: f64,
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Self {
+            size: 3,
+            replacement: true,
+            p: 1.0,
+        }
+    }
+}
+
+impl Tournament {
+    /// Run one tournament on the population and return the winner. Panics if
+    /// the tournament size is 0 or the population is empty (there would be no
+    /// winner).
+    pub fn select<'a, T>(
+        &self,
+        rng: &mut Rng,
+        population: &'a [T],
+        fitness: impl Fn(&T) -> f64,
+    ) -> &'a T {
+        assert!(self.size >= 1, "Tournament size must be at least 1");
+        assert!(!population.is_empty(), "Tournament on an empty population");
+        let mut contestants = match self.replacement {
+            true => (0..self.size).map(|_| rng.choice(population)).collect(),
+            false => rng.sample(population, self.size.min(population.len())),
+        };
+        if self.p >= 1.0 {
+            return contestants
+                .into_iter()
+                .max_by(|a, b| fitness(a).partial_cmp(&fitness(b)).unwrap())
+                .unwrap();
+        }
+
+        // Fittest first (stable, so equally fit contestants keep the random
+        // order in which they were drawn).
+        contestants.sort_by(|a, b| fitness(b).partial_cmp(&fitness(a)).unwrap());
+        let last = contestants.len() - 1;
+        for (i, contestant) in contestants.iter().enumerate() {
+            if i == last || rng.f64() < self.p {
+                return contestant;
+            }
+        }
+        unreachable!();
+    }
+}
+
+impl std::fmt::Display for Tournament {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "tournament {}", self.size)?;
+        if !self.replacement {
+            write!(f, ", w/o repl.")?;
+        }
+        if self.p < 1.0 {
+            write!(f, ", p {}", self.p)?;
+        }
+        Ok(())
+    }
+}
+
+/// Genetic algorithm with tournament selection, and the crossover and
+/// mutation of the genome (see genetic-algorithm-onemax5).
+pub struct GeneticAlgorithm {
+    pub population_size: usize,
+    pub selection: Tournament,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+    /// Probability that a gene of a mutant is mutated.
+    pub mutation_rate_gene: f64,
+
+    /// Statistics of each generation of the last run (the initial population
+    /// is generation 0).
+    pub history: History,
+
+    /// Write a checkpoint to this file every `checkpoint_interval`
+    /// generations, and at the end of the run.
+    pub checkpoint: Option<PathBuf>,
+    pub checkpoint_interval: usize,
+
+    /// Continue the run saved in this checkpoint, instead of starting with a
+    /// new random population. The evaluations of the checkpoint count towards
+    /// the budget.
+    pub resume: Option<PathBuf>,
+}
+
+impl Default for GeneticAlgorithm {
+    fn default() -> Self {
+        Self {
+            population_size: 300,
+            selection: Tournament::default(),
+            crossover_rate: 0.5,
+            mutation_rate: 0.2,
+            mutation_rate_gene: 0.005,
+            history: History::default(),
+            checkpoint: None,
+            checkpoint_interval: 10,
+            resume: None,
+        }
+    }
+}
+
+impl<G: Genome> SearchAlgorithm<G> for GeneticAlgorithm {
+    fn name(&self) -> String {
+        match self.selection == Tournament::default() {
+            true => "Genetic algorithm".to_string(),
+            false => format!("GA ({})", self.selection),
+        }
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator<G>) -> Individual<G> {
+        let (mut generation, mut population, mut best) = match &self.resume {
+            Some(path) => {
+                let checkpoint = Checkpoint::load(path)
+                    .unwrap_or_else(|err| panic!("Can't resume from {}: {}", path.display(), err));
+                evaluator.evaluations = checkpoint.evaluations;
+                rng.state = checkpoint.rng_state;
+                self.history = checkpoint.history;
+                (
+                    checkpoint.generation,
+                    checkpoint.population,
+                    checkpoint.best,
+                )
+            }
+            None => {
+                // Generate new population of random individuals.
+                let population: Vec<_> = (0..self.population_size)
+                    .map(|_| evaluator.random(rng))
+                    .collect();
+                let best = fittest(&population).clone();
+                self.history = History::default();
+                self.history
+                    .0
+                    .push(GenerationStats::new(0, evaluator.evaluations, &population));
+                (1, population, best)
+            }
+        };
+
+        while !evaluator.is_done(&best) {
+            if generation % self.checkpoint_interval == 0 {
+                self.save_checkpoint(generation, rng, evaluator, &population, &best);
+            }
+
+            // Selection.
+            let mut new_population = Vec::with_capacity(self.population_size);
+            while new_population.len() < self.population_size {
+                let winner = self.selection.select(rng, &population, |i| i.fitness);
+                new_population.push(winner.genome.clone());
+            }
+
+            // Crossover.
+            for chunk in new_population.chunks_mut(2) {
+                if let [parent1, parent2] = chunk {
+                    if rng.f64() < self.crossover_rate {
+                        parent1.crossover(parent2, rng);
+                    }
+                }
+            }
+
+            // Mutation.
+            for mutant in new_population.iter_mut() {
+                if rng.f64() < self.mutation_rate {
+                    mutant.mutate(rng, self.mutation_rate_gene);
+                }
+            }
+
+            // Replace population with next generation / new population.
+            population = new_population
+                .into_iter()
+                .map(|genome| evaluator.evaluate(genome))
+                .collect();
+
+            let generation_best = fittest(&population);
+            if generation_best.fitness > best.fitness {
+                best = generation_best.clone();
+            }
+            self.history.0.push(GenerationStats::new(
+                generation,
+                evaluator.evaluations,
+                &population,
+            ));
+            generation += 1;
+        }
+
+        self.save_checkpoint(generation, rng, evaluator, &population, &best);
+        best
+    }
+}
+
+impl GeneticAlgorithm {
+    /// Write the state before generation `generation` to the checkpoint file
+    /// (if there is one).
+    fn save_checkpoint<G: Genome>(
+        &self,
+        generation: usize,
+        rng: &Rng,
+        evaluator: &Evaluator<G>,
+        population: &[Individual<G>],
+        best: &Individual<G>,
+    ) {
+        let Some(path) = &self.checkpoint else {
+            return;
+        };
+        let checkpoint = Checkpoint {
+            generation,
+            evaluations: evaluator.evaluations,
+            rng_state: rng.state,
+            best: best.clone(),
+            population: population.to_vec(),
+            history: self.history.clone(),
+        };
+        checkpoint
+            .save(path)
+            .unwrap_or_else(|err| panic!("Can't write checkpoint {}: {}", path.display(), err));
+    }
+}
+
+fn fittest<G>(population: &[Individual<G>]) -> &Individual<G> {
+    population
+        .iter()
+        .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
+        .unwrap()
+}
+
+/* Random walk. **************************************************************/
+
+/// Baseline without any guidance: always move to a random neighbour, and
+/// simply remember the best individual that was encountered on the way.
+pub struct RandomWalk;
+
+impl<G: Genome> SearchAlgorithm<G> for RandomWalk {
+    fn name(&self) -> String {
+        "Random walk".to_string()
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator<G>) -> Individual<G> {
+        let mut current = evaluator.random(rng);
+        let mut best = current.clone();
+
+        while !evaluator.is_done(&best) {
+            current = evaluator.evaluate(current.genome.random_neighbour(rng));
+            if current.fitness > best.fitness {
+                best = current.clone();
+            }
+        }
+
+        best
+    }
+}
+
+/* Hillclimbing. *************************************************************/
+
+/// Steepest-ascent hillclimber: evaluate all neighbours of the current
+/// individual and move to the best one, as long as it is an improvement.
+/// When stuck in a local optimum, restart from a new random individual.
+pub struct HillClimber;
+
+impl<G: Genome> SearchAlgorithm<G> for HillClimber {
+    fn name(&self) -> String {
+        "Steepest-ascent hillclimber".to_string()
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator<G>) -> Individual<G> {
+        let mut current = evaluator.random(rng);
+        let mut best = current.clone();
+
+        while !evaluator.is_done(&best) {
+            let mut best_neighbour: Option<Individual<G>> = None;
+            for genome in current.genome.neighbours() {
+                let neighbour = evaluator.evaluate(genome);
+                // Update the best individual right away, so that a neighbour
+                // that is good enough stops the search without evaluating the
+                // rest of the neighbourhood.
+                if neighbour.fitness > best.fitness {
+                    best = neighbour.clone();
+                }
+                if best_neighbour
+                    .as_ref()
+                    .is_none_or(|b| neighbour.fitness > b.fitness)
+                {
+                    best_neighbour = Some(neighbour);
+                }
+                if evaluator.is_done(&best) {
+                    break;
+                }
+            }
+
+            match best_neighbour {
+                Some(neighbour) if neighbour.fitness > current.fitness => current = neighbour,
+                // Local optimum: random restart.
+                _ => current = evaluator.random(rng),
+            }
+
+            if current.fitness > best.fitness {
+                best = current.clone();
+            }
+        }
+
+        best
+    }
+}
+
+/* Simulated annealing. ******************************************************/
+
+/// Temperature schedule for simulated annealing. `k` is the current step.
+pub enum TemperatureSchedule {
+    /// T(k) = t0 * alpha^k
+    Exponential { t0: f64, alpha: f64 },
+    /// T(k) = t0 * (1 - k / steps), linearly decreasing to zero.
+    Linear { t0: f64, steps: usize },
+}
+
+impl TemperatureSchedule {
+    pub fn temperature(&self, k: usize) -> f64 {
+        match self {
+            TemperatureSchedule::Exponential { t0, alpha } => t0 * alpha.powi(k as i32),
+            TemperatureSchedule::Linear { t0, steps } => {
+                t0 * (1.0 - (k as f64 / *steps as f64)).max(0.0)
+            }
+        }
+    }
+}
+
+/// Simulated annealing: move to a random neighbour if it is better, and also
+/// accept worse neighbours with probability exp(delta / T), where the
+/// temperature T decreases over time according to a schedule.
+pub struct SimulatedAnnealing {
+    pub schedule: TemperatureSchedule,
+}
+
+impl<G: Genome> SearchAlgorithm<G> for SimulatedAnnealing {
+    fn name(&self) -> String {
+        match self.schedule {
+            TemperatureSchedule::Exponential { .. } => "Simulated annealing (exp.)".to_string(),
+            TemperatureSchedule::Linear { .. } => "Simulated annealing (linear)".to_string(),
+        }
+    }
+
+    fn search(&mut self, rng: &mut Rng, evaluator: &mut Evaluator<G>) -> Individual<G> {
+        let mut current = evaluator.random(rng);
+        let mut best = current.clone();
+
+        let mut k = 0;
+        while !evaluator.is_done(&best) {
+            let temperature = self.schedule.temperature(k);
+            let neighbour = evaluator.evaluate(current.genome.random_neighbour(rng));
+
+            let delta = neighbour.fitness - current.fitness;
+            if delta >= 0.0 || (temperature > 0.0 && rng.f64() < (delta / temperature).exp()) {
+                current = neighbour;
+            }
+
+            if current.fitness > best.fitness {
+                best = current.clone();
+            }
+            k += 1;
+        }
+
+        best
+    }
+}
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Search-based fuzzing of numeric parameters, from
+// https://www.fuzzingbook.org/html/SearchBasedFuzzer.html: the function under
+// test takes two numbers (think of the numeric commandline options of a
+// program), and the goal is to reach the branch that is only taken if
+// `x == 2 * (y + 1)`. Random inputs practically never hit it, but the branch
+// distance `|x - 2 * (y + 1)|` tells how close an input got, and the fitness
+// is the negated distance (0 is optimal).
+//
+// The same problem with the different genome representations: integers
+// directly, integers encoded as bitstrings (in binary or in Gray code), and
+// floats (where the distance only gets close to 0).
+
+use crate::genome::{self, IntVector, RealVector};
+use crate::rng::Rng;
+use crate::search::Landscape;
+
+/// Range of both parameters: 16-bit signed integers.
+pub const MIN: i64 = -32768;
+pub const MAX: i64 = 32767;
+
+/// Number of bits per parameter for the bitstring encodings.
+const BITS: usize = 16;
+
+/// Branch distance of the condition `x == 2 * (y + 1)`.
+pub fn branch_distance(x: f64, y: f64) -> f64 {
+    (x - 2.0 * (y + 1.0)).abs()
+}
+
+/// Both parameters as integer genes.
+pub struct TestMeInt;
+
+impl Landscape<IntVector> for TestMeInt {
+    fn random(&self, rng: &mut Rng) -> IntVector {
+        IntVector::random(rng, 2, MIN, MAX, 0.01)
+    }
+
+    fn fitness(&self, genome: &IntVector) -> f64 {
+        -branch_distance(genome.genes[0] as f64, genome.genes[1] as f64)
+    }
+
+    fn good_enough_fitness(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Both parameters as 16 bits each, in plain binary or Gray code (offset by
+/// `MIN`, so that all bits 0 is the smallest value).
+pub struct TestMeBits {
+    pub gray: bool,
+}
+
+impl TestMeBits {
+    pub fn decode(&self, genome: &[bool]) -> (i64, i64) {
+        let decode = match self.gray {
+            true => genome::decode_gray,
+            false => genome::decode_binary,
+        };
+        (
+            MIN + decode(&genome[..BITS]) as i64,
+            MIN + decode(&genome[BITS..]) as i64,
+        )
+    }
+}
+
+impl Landscape<Vec<bool>> for TestMeBits {
+    fn random(&self, rng: &mut Rng) -> Vec<bool> {
+        (0..2 * BITS).map(|_| rng.bool()).collect()
+    }
+
+    fn fitness(&self, genome: &Vec<bool>) -> f64 {
+        let (x, y) = self.decode(genome);
+        -branch_distance(x as f64, y as f64)
+    }
+
+    fn good_enough_fitness(&self) -> f64 {
+        0.0
+    }
+}
+
+/// Both parameters as floats; an input counts as hitting the branch if the
+/// distance is below `epsilon`.
+pub struct TestMeReal {
+    pub epsilon: f64,
+}
+
+impl Landscape<RealVector> for TestMeReal {
+    fn random(&self, rng: &mut Rng) -> RealVector {
+        RealVector::random(rng, 2, MIN as f64, MAX as f64, 0.01)
+    }
+
+    fn fitness(&self, genome: &RealVector) -> f64 {
+        -branch_distance(genome.genes[0], genome.genes[1])
+    }
+
+    fn good_enough_fitness(&self) -> f64 {
+        -self.epsilon
+    }
+}
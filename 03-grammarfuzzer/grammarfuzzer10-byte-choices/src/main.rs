@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod decisions;
+mod examplegrammars;
+mod grammarfuzzer;
+mod mutator;
+mod rng;
+
+use std::collections::BTreeSet;
+
+use grammarfuzzer::{fuzz_tree_from_bytes, GrammarCost};
+use mutator::Input;
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let grammar: GrammarCost = examplegrammars::expr_grammar().into();
+    let expr = |choices: &Input| fuzz_tree_from_bytes(&grammar, &choices.0).all_leafs();
+
+    let quiet = cli::quiet();
+
+    // Any byte string gives an expression.
+    if !quiet {
+        println!();
+        println!("[+] Expressions from random choice strings:");
+    }
+    for len in [0, 4, 8, 16, 32] {
+        let choices = Input(rng.bytes(len));
+        if !quiet {
+            println!("    {:2} bytes: {}", len, expr(&choices));
+        }
+    }
+
+    // Mutate the choice string with byte-level mutations; for comparison also
+    // apply the same mutations directly to the expression.
+    let choices = Input(rng.bytes(24));
+    let parent = expr(&choices);
+    if !quiet {
+        println!();
+        println!("[+] Mutants of {}:", parent);
+        println!("    {:30} mutated expression", "mutated choices");
+    }
+    for _ in 0..10 {
+        // Same mutation (same seed) for both.
+        let seed = rng.next();
+        let mutant = mutator::mutate(&mut Rng::seeded(seed), choices.clone());
+        let direct = mutator::mutate(&mut Rng::seeded(seed), Input(parent.as_bytes().to_vec()));
+        if !quiet {
+            println!(
+                "    {:30} {}",
+                expr(&mutant),
+                String::from_utf8_lossy(&direct.0)
+            );
+        }
+    }
+
+    // Statistics over many mutants (each mutant is mutated further with
+    // some probability, to get away from the parent).
+    let n = cli::iterations(10000);
+    let (mut distinct_choices, mut distinct_direct) = (BTreeSet::new(), BTreeSet::new());
+    let (mut valid_choices, mut valid_direct) = (0, 0);
+    for _ in 0..n {
+        let (mut mutant, mut direct) = (choices.clone(), Input(parent.as_bytes().to_vec()));
+        loop {
+            let seed = rng.next();
+            mutant = mutator::mutate(&mut Rng::seeded(seed), mutant);
+            direct = mutator::mutate(&mut Rng::seeded(seed), direct);
+            if rng.int(2) == 0 {
+                break;
+            }
+        }
+        let mutant = expr(&mutant);
+        let direct = String::from_utf8_lossy(&direct.0).to_string();
+        valid_choices += is_valid_expr(&mutant) as usize;
+        valid_direct += is_valid_expr(&direct) as usize;
+        distinct_choices.insert(mutant);
+        distinct_direct.insert(direct);
+    }
+    println!();
+    println!("[+] {} mutants:", n);
+    println!(
+        "    - mutated choices:    {:5} valid, {:5} distinct",
+        valid_choices,
+        distinct_choices.len()
+    );
+    println!(
+        "    - mutated expression: {:5} valid, {:5} distinct",
+        valid_direct,
+        distinct_direct.len()
+    );
+}
+
+/// Check whether a string is a valid expression of the expression grammar
+/// (recursive descent parser).
+fn is_valid_expr(s: &str) -> bool {
+    fn expr(s: &[u8], pos: &mut usize) -> bool {
+        term(s, pos) && (!eat(s, pos, b"+-") || expr(s, pos))
+    }
+    fn term(s: &[u8], pos: &mut usize) -> bool {
+        factor(s, pos) && (!eat(s, pos, b"*/") || term(s, pos))
+    }
+    fn factor(s: &[u8], pos: &mut usize) -> bool {
+        if eat(s, pos, b"+-") {
+            factor(s, pos)
+        } else if eat(s, pos, b"(") {
+            expr(s, pos) && eat(s, pos, b")")
+        } else {
+            integer(s, pos) && (!eat(s, pos, b".") || integer(s, pos))
+        }
+    }
+    fn integer(s: &[u8], pos: &mut usize) -> bool {
+        let start = *pos;
+        while s.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+            *pos += 1;
+        }
+        *pos > start
+    }
+    fn eat(s: &[u8], pos: &mut usize, chars: &[u8]) -> bool {
+        if s.get(*pos).is_some_and(|c| chars.contains(c)) {
+            *pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    let mut pos = 0;
+    expr(s.as_bytes(), &mut pos) && pos == s.len()
+}
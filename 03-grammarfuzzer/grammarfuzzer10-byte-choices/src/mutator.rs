@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// The byte-level mutations from 02-mutationfuzzer.
+
+use crate::rng::Rng;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Input(pub Vec<u8>);
+
+/// Choose a random mutation strategy and apply it to the input.
+pub fn mutate(rng: &mut Rng, s: Input) -> Input {
+    match rng.int(3) {
+        0 => delete_random_character(rng, s),
+        1 => insert_random_character(rng, s),
+        2 => flip_random_bit(rng, s),
+        _ => panic!("Can't happen"),
+    }
+}
+
+fn delete_random_character(rng: &mut Rng, mut s: Input) -> Input {
+    if s.0.is_empty() {
+        s
+    } else {
+        let pos = rng.usize_below(s.0.len());
+        s.0.remove(pos);
+        s
+    }
+}
+
+fn insert_random_character(rng: &mut Rng, mut s: Input) -> Input {
+    let pos = rng.usize_below(s.0.len() + 1);
+    let chr = rng.u8_range(32..=127);
+    s.0.insert(pos, chr);
+    s
+}
+
+fn flip_random_bit(rng: &mut Rng, mut s: Input) -> Input {
+    if s.0.is_empty() {
+        return s;
+    }
+    let pos = rng.usize_below(s.0.len());
+    let bit = 1 << rng.int(7);
+    s.0[pos] ^= bit;
+    s
+}
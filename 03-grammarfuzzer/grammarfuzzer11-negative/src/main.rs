@@ -0,0 +1,163 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod negative;
+mod rng;
+
+use std::path::PathBuf;
+use std::process;
+
+use grammarfuzzer::{expand_tree, GrammarCost, Tree};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let grammar = examplegrammars::expr_grammar();
+    let grammar_cost: GrammarCost = examplegrammars::expr_grammar().into();
+    let parser = Parser::new(&cli::target("expr"));
+
+    // Small trees, so that the breakage is easy to spot.
+    let fuzz_tree = |rng: &mut Rng| {
+        let mut tree = Tree::NT("start".to_string(), Vec::new());
+        expand_tree(rng, &grammar_cost, &mut tree, 0, 20);
+        tree
+    };
+
+    // The examples are created (but not printed) with --quiet as well, so
+    // that the statistics are the same.
+    let quiet = cli::quiet();
+    if !quiet {
+        println!();
+        println!("[+] Broken expressions:");
+    }
+    for _ in 0..10 {
+        let tree = fuzz_tree(&mut rng);
+        let (broken, breakage) = negative::break_tree(&mut rng, &grammar, &tree).unwrap();
+        let (valid, broken) = (tree.all_leafs(), broken.all_leafs());
+        if !quiet {
+            println!("       {}", valid);
+            println!("    -> {}", broken);
+            println!(
+                "       {} (invalid: {}, rejected by expr: {})",
+                breakage,
+                !is_valid_expr(&broken),
+                !parser.accepts(&broken)
+            );
+        }
+    }
+
+    // Check whether the broken inputs are actually invalid (with a reference
+    // parser), and whether `expr` rejects exactly the invalid ones.
+    let n = cli::iterations(500);
+    println!();
+    println!("[+] {} broken expressions per kind:", n);
+    for kind in negative::KINDS {
+        let (mut invalid, mut rejected, mut wrong) = (0, 0, Vec::new());
+        for _ in 0..n {
+            // Not every tree can be broken in every way (e.g. `5` has no
+            // separator to duplicate).
+            let broken = loop {
+                let tree = fuzz_tree(&mut rng);
+                if let Some((broken, _)) = negative::break_tree_by(&mut rng, &grammar, &tree, kind)
+                {
+                    break broken.all_leafs();
+                }
+            };
+            let is_invalid = !is_valid_expr(&broken);
+            let is_rejected = !parser.accepts(&broken);
+            invalid += is_invalid as usize;
+            rejected += is_rejected as usize;
+            if is_invalid != is_rejected {
+                wrong.push(broken);
+            }
+        }
+        println!(
+            "    - {:14} {:3} invalid, {:3} rejected by expr, {} wrongly handled {:?}",
+            format!("{:?}:", kind),
+            invalid,
+            rejected,
+            wrong.len(),
+            wrong.iter().take(3).collect::<Vec<_>>()
+        );
+    }
+}
+
+/// Program under test: the parser/evaluator for expressions in `expr.c`. It
+/// exits with status 2 on syntax errors.
+struct Parser {
+    binary: PathBuf,
+}
+
+impl Parser {
+    fn new(program: &str) -> Self {
+        let binary = std::env::temp_dir().join(format!("{}-{}", program, process::id()));
+        let status = process::Command::new("gcc")
+            .args(["-Wall", "-O2", "-o"])
+            .arg(&binary)
+            .arg(format!("{}.c", program))
+            .status()
+            .unwrap();
+        assert!(status.success(), "Compiling {}.c failed", program);
+        Self { binary }
+    }
+
+    fn accepts(&self, input: &str) -> bool {
+        process::Command::new(&self.binary)
+            .arg(input)
+            .stdout(process::Stdio::null())
+            .status()
+            .unwrap()
+            .success()
+    }
+}
+
+impl Drop for Parser {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.binary);
+    }
+}
+
+/// Check whether a string is a valid expression of the expression grammar
+/// (recursive descent parser; reference for checking `expr`).
+fn is_valid_expr(s: &str) -> bool {
+    fn expr(s: &[u8], pos: &mut usize) -> bool {
+        term(s, pos) && (!eat(s, pos, b"+-") || expr(s, pos))
+    }
+    fn term(s: &[u8], pos: &mut usize) -> bool {
+        factor(s, pos) && (!eat(s, pos, b"*/") || term(s, pos))
+    }
+    fn factor(s: &[u8], pos: &mut usize) -> bool {
+        if eat(s, pos, b"+-") {
+            factor(s, pos)
+        } else if eat(s, pos, b"(") {
+            expr(s, pos) && eat(s, pos, b")")
+        } else {
+            integer(s, pos) && (!eat(s, pos, b".") || integer(s, pos))
+        }
+    }
+    fn integer(s: &[u8], pos: &mut usize) -> bool {
+        let start = *pos;
+        while s.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+            *pos += 1;
+        }
+        *pos > start
+    }
+    fn eat(s: &[u8], pos: &mut usize, chars: &[u8]) -> bool {
+        if s.get(*pos).is_some_and(|c| chars.contains(c)) {
+            *pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    let mut pos = 0;
+    expr(s.as_bytes(), &mut pos) && pos == s.len()
+}
@@ -0,0 +1,198 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Negative fuzzing: create inputs that are *almost* valid, by breaking a valid
+// derivation tree in one place. Random strings are usually rejected right at
+// the first character, so they only ever test the first few checks of a
+// parser; near-valid inputs get deep into the parser before they fail, and
+// test its error handling there (e.g. a missing closing parenthesis after a
+// long nested expression).
+//
+// Each broken input comes with a description of what was broken. Note that
+// breaking a rule doesn't always give an invalid input (e.g. replacing the `+`
+// in `1+2` with `*` gives another valid expression), so whether the input is
+// actually invalid still has to be checked separately.
+
+use std::collections::BTreeSet;
+
+use crate::grammarfuzzer::{Grammar, Tree};
+use crate::rng::Rng;
+
+/// How a derivation tree is broken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    /// Replace a terminal with one that no rule allows at this position.
+    WrongTerminal,
+    /// Drop a terminal.
+    Drop,
+    /// Duplicate a separator (a terminal that consists of punctuation only).
+    Duplicate,
+}
+
+pub const KINDS: [Kind; 3] = [Kind::WrongTerminal, Kind::Drop, Kind::Duplicate];
+
+/// What was broken in a derivation tree.
+#[derive(Clone, Debug)]
+pub struct Breakage {
+    pub kind: Kind,
+
+    /// Nonterminal whose rule application was broken (with angle brackets).
+    pub nonterminal: String,
+
+    /// The terminal that was replaced, dropped or duplicated.
+    pub token: String,
+
+    /// The wrong terminal (only for `Kind::WrongTerminal`).
+    pub replacement: Option<String>,
+}
+
+impl std::fmt::Display for Breakage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.kind {
+            Kind::WrongTerminal => write!(
+                f,
+                "replaced \"{}\" with \"{}\" in {}",
+                self.token,
+                self.replacement.as_ref().unwrap(),
+                self.nonterminal
+            ),
+            Kind::Drop => write!(f, "dropped \"{}\" from {}", self.token, self.nonterminal),
+            Kind::Duplicate => write!(f, "duplicated \"{}\" in {}", self.token, self.nonterminal),
+        }
+    }
+}
+
+/// Terminal leaf of a derivation tree.
+struct Leaf {
+    /// Path (list of child indices, starting from the root) to the parent.
+    parent: Vec<usize>,
+
+    /// Index of the leaf among the children of the parent.
+    index: usize,
+
+    /// Name of the parent nonterminal (with angle brackets).
+    nonterminal: String,
+
+    token: String,
+}
+
+/// Break a valid derivation tree in one random place, with a random kind of
+/// breakage (if the tree has no place for this kind, another kind is tried).
+/// Returns `None` if the tree can't be broken at all (no terminals).
+pub fn break_tree(rng: &mut Rng, grammar: &Grammar, tree: &Tree) -> Option<(Tree, Breakage)> {
+    let first = rng.usize_below(KINDS.len());
+    (0..KINDS.len())
+        .map(|i| KINDS[(first + i) % KINDS.len()])
+        .find_map(|kind| break_tree_by(rng, grammar, tree, kind))
+}
+
+/// Break a valid derivation tree in one random place with the given kind of
+/// breakage. Returns `None` if the tree has no place for this kind.
+pub fn break_tree_by(
+    rng: &mut Rng,
+    grammar: &Grammar,
+    tree: &Tree,
+    kind: Kind,
+) -> Option<(Tree, Breakage)> {
+    let terminals = grammar.terminals();
+
+    // Candidate leafs, for `WrongTerminal` together with the terminals that
+    // are wrong at their position.
+    let candidates: Vec<(Leaf, Vec<String>)> = leafs(tree)
+        .into_iter()
+        .filter_map(|leaf| match kind {
+            Kind::WrongTerminal => {
+                let wrong = wrong_terminals(grammar, &terminals, &leaf);
+                (!wrong.is_empty()).then_some((leaf, wrong))
+            }
+            Kind::Drop => Some((leaf, Vec::new())),
+            Kind::Duplicate => is_separator(&leaf.token).then_some((leaf, Vec::new())),
+        })
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let (leaf, wrong) = rng.choice(&candidates);
+    let replacement = (!wrong.is_empty()).then(|| rng.choice(wrong).clone());
+
+    let mut tree = tree.clone();
+    if let Tree::NT(_, children) = node_mut(&mut tree, &leaf.parent) {
+        match kind {
+            Kind::WrongTerminal => children[leaf.index] = Tree::T(replacement.clone().unwrap()),
+            Kind::Drop => {
+                children.remove(leaf.index);
+            }
+            Kind::Duplicate => children.insert(leaf.index, Tree::T(leaf.token.clone())),
+        }
+    } else {
+        panic!("Can't happen");
+    }
+
+    let breakage = Breakage {
+        kind,
+        nonterminal: leaf.nonterminal.clone(),
+        token: leaf.token.clone(),
+        replacement,
+    };
+    Some((tree, breakage))
+}
+
+/// Terminals of the grammar that no expansion of the parent nonterminal
+/// allows at the position of the leaf.
+fn wrong_terminals(grammar: &Grammar, terminals: &BTreeSet<String>, leaf: &Leaf) -> Vec<String> {
+    let allowed: BTreeSet<_> = grammar
+        .expansions(&leaf.nonterminal)
+        .iter()
+        .filter_map(|expansion| expansion.get(leaf.index))
+        .collect();
+    terminals
+        .iter()
+        .filter(|terminal| !allowed.contains(terminal))
+        .cloned()
+        .collect()
+}
+
+fn is_separator(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_punctuation())
+}
+
+/// All terminal leafs of a derivation tree (except empty ones, which don't
+/// show up in the input).
+fn leafs(tree: &Tree) -> Vec<Leaf> {
+    let mut res = Vec::new();
+    let mut stack = vec![(tree, Vec::new())];
+    while let Some((cur, path)) = stack.pop() {
+        if let Tree::NT(name, children) = cur {
+            for (i, child) in children.iter().enumerate() {
+                match child {
+                    Tree::NT(_, _) => {
+                        let mut childpath = path.clone();
+                        childpath.push(i);
+                        stack.push((child, childpath));
+                    }
+                    Tree::T(token) if !token.is_empty() => res.push(Leaf {
+                        parent: path.clone(),
+                        index: i,
+                        nonterminal: format!("<{}>", name),
+                        token: token.clone(),
+                    }),
+                    Tree::T(_) => (),
+                }
+            }
+        }
+    }
+    res
+}
+
+fn node_mut<'a>(tree: &'a mut Tree, path: &[usize]) -> &'a mut Tree {
+    let mut cur = tree;
+    for &i in path {
+        cur = match cur {
+            Tree::NT(_, children) => &mut children[i],
+            Tree::T(_) => panic!("Can't happen"),
+        };
+    }
+    cur
+}
@@ -0,0 +1,77 @@
+// Example program: parser for messages consisting of a version and
+// length-prefixed records (see `record_grammar` in examplegrammars.rs), e.g.
+// `v2 5:hello,3:abc,`. Like many parsers of binary formats, it checks the
+// version and the length fields before looking at the actual content, so
+// inputs that are only syntactically valid are rejected early.
+//
+// Exit status: 0 accepted, 2 syntax error, 3 unsupported version, 4 length
+// field doesn't match the payload.
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static const char *s;
+
+static long number(void) {
+    if (*s < '0' || *s > '9') {
+        exit(2);
+    }
+    long v = 0;
+    while (*s >= '0' && *s <= '9') {
+        if (v > 1000000) {
+            exit(2); // Number too large.
+        }
+        v = v * 10 + (*s - '0');
+        s++;
+    }
+    return v;
+}
+
+int main(int argc, char *argv[]) {
+    if (argc != 2) {
+        return 1;
+    }
+    s = argv[1];
+
+    if (*s++ != 'v') {
+        return 2;
+    }
+    long version = number();
+    if (version < 1 || version > 3) {
+        return 3;
+    }
+    if (*s++ != ' ') {
+        return 2;
+    }
+
+    int records = 0;
+    long total = 0;
+    while (*s != '\0') {
+        long length = number();
+        if (*s++ != ':') {
+            return 2;
+        }
+        if ((long)strlen(s) < length + 1 || s[length] != ',') {
+            return 4;
+        }
+        for (long i = 0; i < length; i++) {
+            if (s[i] < 'a' || s[i] > 'z') {
+                return 2;
+            }
+        }
+        s += length + 1;
+        records++;
+        total += length;
+    }
+    if (records == 0) {
+        return 2;
+    }
+
+    // The actual processing of the content.
+    printf("version %ld: %d records, %ld bytes\n", version, records, total);
+    if (version == 3 && records > 3) {
+        printf("many records\n");
+    }
+    return 0;
+}
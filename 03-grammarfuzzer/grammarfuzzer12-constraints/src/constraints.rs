@@ -0,0 +1,134 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Lightweight semantic constraints for numeric nonterminals, which a
+// context-free grammar can't express (e.g. that a length field equals the
+// length of the payload that follows). Without them, structured inputs
+// generated from the grammar are almost always rejected in the first checks of
+// the target, and never reach the actual processing.
+//
+// The constraints are not considered during generation; instead they are
+// enforced in a post-processing pass over the derivation tree, which replaces
+// the subtrees of the constrained nonterminals with the required values.
+// (The replaced subtrees then consist of a single terminal and no longer
+// follow the grammar rules, but the resulting string does.)
+
+use crate::grammarfuzzer::Tree;
+use crate::rng::Rng;
+
+/// Semantic constraint on numeric nonterminals (with angle brackets).
+#[derive(Clone, Debug)]
+pub enum Constraint {
+    /// The value of the nonterminal is in `min..=max` (values outside are
+    /// replaced with random values from the range).
+    Range {
+        nonterminal: String,
+        min: u64,
+        max: u64,
+    },
+
+    /// Within each subtree of `scope`, the (first) nonterminal `field`
+    /// holds the length in bytes of the string of the (first) nonterminal
+    /// `of`.
+    LengthOf {
+        scope: String,
+        field: String,
+        of: String,
+    },
+}
+
+/// Enforce the constraints on a derivation tree, in the given order (later
+/// constraints may depend on the results of earlier ones).
+pub fn apply(rng: &mut Rng, tree: &mut Tree, constraints: &[Constraint]) {
+    for constraint in constraints.iter() {
+        match constraint {
+            Constraint::Range {
+                nonterminal,
+                min,
+                max,
+            } => {
+                for node in subtrees_mut(tree, nonterminal) {
+                    let value = node.all_leafs().parse::<u64>().ok();
+                    if !value.is_some_and(|value| *min <= value && value <= *max) {
+                        // The size of the full range 0..=u64::MAX doesn't
+                        // fit into an u64.
+                        let value = match (max - min).checked_add(1) {
+                            Some(size) => min + rng.int(size),
+                            None => rng.next(),
+                        };
+                        set_value(node, &value.to_string());
+                    }
+                }
+            }
+
+            Constraint::LengthOf { scope, field, of } => {
+                for node in subtrees_mut(tree, scope) {
+                    let length = match subtrees_mut(node, of).first() {
+                        Some(of) => of.all_leafs().len(),
+                        None => continue,
+                    };
+                    if let Some(field) = subtrees_mut(node, field).into_iter().next() {
+                        set_value(field, &length.to_string());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Check whether a derivation tree satisfies the constraints.
+pub fn check(tree: &Tree, constraints: &[Constraint]) -> bool {
+    let mut tree = tree.clone();
+    constraints.iter().all(|constraint| match constraint {
+        Constraint::Range {
+            nonterminal,
+            min,
+            max,
+        } => subtrees_mut(&mut tree, nonterminal).iter().all(|node| {
+            node.all_leafs()
+                .parse::<u64>()
+                .is_ok_and(|value| *min <= value && value <= *max)
+        }),
+
+        Constraint::LengthOf { scope, field, of } => {
+            subtrees_mut(&mut tree, scope).into_iter().all(|node| {
+                let length = subtrees_mut(node, of)
+                    .first()
+                    .map(|of| of.all_leafs().len());
+                let value = subtrees_mut(node, field)
+                    .first()
+                    .map(|field| field.all_leafs());
+                match (length, value) {
+                    (Some(length), Some(value)) => value == length.to_string(),
+                    _ => true,
+                }
+            })
+        }
+    })
+}
+
+/// Replace the children of a nonterminal with a single terminal.
+fn set_value(node: &mut Tree, value: &str) {
+    match node {
+        Tree::NT(_, children) => *children = vec![Tree::T(value.to_string())],
+        Tree::T(_) => panic!("Can't happen"),
+    }
+}
+
+/// All outermost subtrees with the given nonterminal (with angle brackets)
+/// as root, in order from left to right (subtrees nested inside of them are
+/// not included).
+fn subtrees_mut<'a>(tree: &'a mut Tree, nonterminal: &str) -> Vec<&'a mut Tree> {
+    let mut res = Vec::new();
+    let mut stack = vec![tree];
+    while let Some(cur) = stack.pop() {
+        let matches = matches!(cur, Tree::NT(name, _) if format!("<{}>", name) == nonterminal);
+        if matches {
+            res.push(cur);
+        } else if let Tree::NT(_, children) = cur {
+            stack.extend(children.iter_mut().rev());
+        }
+    }
+    res
+}
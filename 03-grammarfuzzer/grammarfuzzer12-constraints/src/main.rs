@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod constraints;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process;
+
+use constraints::Constraint;
+use grammarfuzzer::{expand_tree, GrammarCost, Tree};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let grammar: GrammarCost = examplegrammars::record_grammar().into();
+    let target = Target::new(&cli::target("records"));
+
+    let constraints = [
+        Constraint::Range {
+            nonterminal: "<version>".to_string(),
+            min: 1,
+            max: 3,
+        },
+        Constraint::LengthOf {
+            scope: "<record>".to_string(),
+            field: "<length>".to_string(),
+            of: "<payload>".to_string(),
+        },
+    ];
+
+    let fuzz_tree = |rng: &mut Rng| {
+        let mut tree = Tree::NT("start".to_string(), Vec::new());
+        expand_tree(rng, &grammar, &mut tree, 5, 40);
+        tree
+    };
+
+    // The examples are created (but not printed) with --quiet as well, so
+    // that the statistics are the same.
+    let quiet = cli::quiet();
+    if !quiet {
+        println!();
+        println!("[+] Messages before / after enforcing the constraints:");
+    }
+    for _ in 0..5 {
+        let mut tree = fuzz_tree(&mut rng);
+        let before = tree.all_leafs();
+        constraints::apply(&mut rng, &mut tree, &constraints);
+        assert!(constraints::check(&tree, &constraints));
+        if !quiet {
+            println!("       {}", before);
+            println!("    -> {}", tree.all_leafs());
+        }
+    }
+
+    let n = cli::iterations(1000);
+    for apply in [false, true] {
+        let mut results: BTreeMap<&str, usize> = BTreeMap::new();
+        for _ in 0..n {
+            let mut tree = fuzz_tree(&mut rng);
+            if apply {
+                constraints::apply(&mut rng, &mut tree, &constraints);
+            }
+            *results.entry(target.run(&tree.all_leafs())).or_default() += 1;
+        }
+        println!();
+        println!(
+            "[+] {} messages {} constraints:",
+            n,
+            if apply { "with" } else { "without" }
+        );
+        for (result, count) in results.iter() {
+            println!("    - {:26} {:4}", result, count);
+        }
+    }
+}
+
+/// Program under test: the message parser in `records.c`.
+struct Target {
+    binary: PathBuf,
+}
+
+impl Target {
+    fn new(program: &str) -> Self {
+        let binary = std::env::temp_dir().join(format!("{}-{}", program, process::id()));
+        let status = process::Command::new("gcc")
+            .args(["-Wall", "-O2", "-o"])
+            .arg(&binary)
+            .arg(format!("{}.c", program))
+            .status()
+            .unwrap();
+        assert!(status.success(), "Compiling {}.c failed", program);
+        Self { binary }
+    }
+
+    /// Run the program and describe how far the input got.
+    fn run(&self, input: &str) -> &'static str {
+        let status = process::Command::new(&self.binary)
+            .arg(input)
+            .stdout(process::Stdio::null())
+            .status()
+            .unwrap();
+        match status.code() {
+            Some(0) => "accepted",
+            Some(2) => "syntax error",
+            Some(3) => "unsupported version",
+            Some(4) => "length field mismatch",
+            _ => "other",
+        }
+    }
+}
+
+impl Drop for Target {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.binary);
+    }
+}
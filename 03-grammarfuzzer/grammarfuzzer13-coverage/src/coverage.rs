@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Coverage of the grammar itself (instead of the code of the program under
+// test): which expansions of the grammar were used by a corpus of derivation
+// trees. Expansions that a fuzzing campaign never generated are parts of the
+// input format that the program was never tested with, independent of whether
+// code coverage of the program is available at all.
+
+use std::collections::BTreeMap;
+
+use crate::grammarfuzzer::{Expansion, Grammar, Nonterminal, Tree};
+
+/// Tracks how often each expansion of a grammar was used.
+pub struct GrammarCoverage {
+    /// Expansions of each nonterminal (with angle brackets) in the order of
+    /// the grammar, together with their number of uses.
+    counts: BTreeMap<Nonterminal, Vec<(Expansion, usize)>>,
+
+    /// Number of added derivation trees.
+    trees: usize,
+}
+
+impl GrammarCoverage {
+    pub fn new(grammar: &Grammar) -> Self {
+        let counts = grammar
+            .nonterminals()
+            .into_iter()
+            .map(|nonterminal| {
+                // Weighted expansions occur multiple times in the grammar, but
+                // are only counted once here.
+                let mut expansions: Vec<(Expansion, usize)> = Vec::new();
+                for expansion in grammar.expansions(&nonterminal) {
+                    if !expansions.iter().any(|(e, _)| e == expansion) {
+                        expansions.push((expansion.clone(), 0));
+                    }
+                }
+                (nonterminal, expansions)
+            })
+            .collect();
+        Self { counts, trees: 0 }
+    }
+
+    /// Record the expansions used in a derivation tree. Unexpanded
+    /// nonterminals and nodes that don't correspond to any expansion of the
+    /// grammar (e.g. subtrees that were replaced afterwards) are ignored.
+    pub fn add(&mut self, tree: &Tree) {
+        self.trees += 1;
+
+        let mut stack = vec![tree];
+        while let Some(cur) = stack.pop() {
+            let Tree::NT(name, children) = cur else {
+                continue;
+            };
+            if children.is_empty() {
+                continue;
+            }
+
+            let expansion: Expansion = children
+                .iter()
+                .map(|child| match child {
+                    Tree::NT(name, _) => format!("<{}>", name),
+                    Tree::T(name) => name.clone(),
+                })
+                .collect();
+            if let Some(expansions) = self.counts.get_mut(&format!("<{}>", name)) {
+                if let Some((_, count)) = expansions.iter_mut().find(|(e, _)| *e == expansion) {
+                    *count += 1;
+                }
+            }
+
+            stack.extend(children.iter());
+        }
+    }
+
+    /// Number of used expansions and total number of expansions.
+    pub fn covered(&self) -> (usize, usize) {
+        let expansions = self.counts.values().flatten();
+        let covered = expansions.clone().filter(|(_, count)| *count > 0).count();
+        (covered, expansions.count())
+    }
+
+    /// Percentage of used expansions.
+    pub fn percentage(&self) -> f64 {
+        let (covered, total) = self.covered();
+        percentage(covered, total)
+    }
+
+    /// Expansions that were never used, as (nonterminal, expansion).
+    pub fn uncovered(&self) -> Vec<(&Nonterminal, &Expansion)> {
+        self.counts
+            .iter()
+            .flat_map(|(nonterminal, expansions)| {
+                expansions
+                    .iter()
+                    .filter(|(_, count)| *count == 0)
+                    .map(move |(expansion, _)| (nonterminal, expansion))
+            })
+            .collect()
+    }
+
+    /// Human-readable report: overall coverage, and coverage per nonterminal
+    /// together with its uncovered expansions.
+    pub fn report(&self) -> String {
+        let (covered, total) = self.covered();
+        let mut lines = vec![format!(
+            "Expansion coverage over {} trees: {}/{} ({:.1}%)",
+            self.trees,
+            covered,
+            total,
+            percentage(covered, total)
+        )];
+
+        let maxnonterminallength = self.counts.keys().map(|x| x.len()).max().unwrap_or(10);
+        for (nonterminal, expansions) in self.counts.iter() {
+            let covered = expansions.iter().filter(|(_, count)| *count > 0).count();
+            let uncovered: Vec<_> = expansions
+                .iter()
+                .filter(|(_, count)| *count == 0)
+                .map(|(expansion, _)| format_expansion(expansion))
+                .collect();
+            let mut line = format!(
+                "    {:maxnonterminallength$} {:3}/{:<3} {:5.1}%",
+                nonterminal,
+                covered,
+                expansions.len(),
+                percentage(covered, expansions.len())
+            );
+            if !uncovered.is_empty() {
+                line.push_str(&format!("   uncovered: {}", uncovered.join(" | ")));
+            }
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+}
+
+fn percentage(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        100.0 * covered as f64 / total as f64
+    }
+}
+
+/// Format an expansion like in the `Display` of `Grammar` (terminals in
+/// quotes).
+fn format_expansion(expansion: &Expansion) -> String {
+    expansion
+        .iter()
+        .map(|symbol| {
+            if symbol.starts_with('<') && symbol.ends_with('>') {
+                symbol.to_string()
+            } else {
+                format!("\"{}\"", symbol)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod coverage;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+
+use std::collections::BTreeMap;
+
+use coverage::GrammarCoverage;
+use grammarfuzzer::{expand_tree, fuzz, Expr, Grammar, GrammarCost, GrammarError, Tree};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let n = cli::iterations(1000);
+    let quiet = cli::quiet();
+
+    let grammar = examplegrammars::expr_grammar();
+    let grammar_cost = GrammarCost::try_from(examplegrammars::expr_grammar()).unwrap();
+
+    // A small campaign of small trees leaves parts of the grammar untested.
+    let mut coverage = GrammarCoverage::new(&grammar);
+    println!();
+    println!("[+] Small expressions:");
+    for _ in 0..3 {
+        let tree = fuzz_tree(&mut rng, &grammar_cost, 5);
+        println!("    {}", tree.all_leafs());
+        coverage.add(&tree);
+    }
+    if !quiet {
+        println!();
+        println!("{}", coverage.report());
+    }
+
+    // Larger grammar (many alternatives for single characters).
+    let grammar = examplegrammars::json_grammar().to_bnf();
+    let grammar_cost = GrammarCost::try_from(examplegrammars::json_grammar().to_bnf()).unwrap();
+    println!();
+    println!("[+] JSON: coverage depending on number and size of trees:");
+    for max_expansions in [10, 50, 200] {
+        let mut coverage = GrammarCoverage::new(&grammar);
+        let mut progress = Vec::new();
+        for i in 1..=n {
+            coverage.add(&fuzz_tree(&mut rng, &grammar_cost, max_expansions));
+            if [1, 10, 100, 1000, n].contains(&i) {
+                progress.push(format!(
+                    "{:4} trees {:5.1}% ({:3} uncovered)",
+                    i,
+                    coverage.percentage(),
+                    coverage.uncovered().len()
+                ));
+            }
+        }
+        println!(
+            "    {:3} expansions: {}",
+            max_expansions,
+            progress.join(", ")
+        );
+    }
+
+    // Duplicate productions are ignored; biasing the random choice has to be
+    // done explicitly.
+    println!();
+    println!("[+] Weighted productions:");
+    let mut grammar = Grammar::new();
+    grammar.add_production("<start>", &["<digit>"]);
+    grammar.add_productions("<digit>", &["0", "1", "2"]);
+    grammar.add_production("<digit>", &["1"]);
+    grammar.add_production_weighted("<digit>", &["0"], 7);
+    println!("{}", grammar);
+    println!("    Duplicates: {:?}", grammar.duplicates());
+    let grammar_cost = GrammarCost::try_from(grammar).unwrap();
+    let mut counts = BTreeMap::new();
+    for _ in 0..n {
+        *counts
+            .entry(fuzz_tree(&mut rng, &grammar_cost, 10).all_leafs())
+            .or_insert(0) += 1;
+    }
+    println!("    Digits in {} trees: {:?}", n, counts);
+
+    // Grammars that can't be used for generating strings are rejected
+    // upfront, instead of looping forever during expansion.
+    println!();
+    println!("[+] Non-terminating grammar:");
+    let mut grammar = Grammar::new();
+    grammar.add_production("<start>", &["<list>"]);
+    grammar.add_production("<list>", &["<item>", ",", "<list>"]);
+    grammar.add_production("<item>", &["x"]);
+    print_result(fuzz(&mut rng, grammar));
+
+    println!("[+] Undefined nonterminal:");
+    let mut grammar = Grammar::new();
+    grammar.add_production("<start>", &["<item>", "<items>"]);
+    grammar.add_production("<item>", &["x"]);
+    print_result(fuzz(&mut rng, grammar));
+
+    println!("[+] Derivation tree with a nonterminal that is not in the grammar:");
+    let grammar_cost = GrammarCost::try_from(examplegrammars::expr_grammar()).unwrap();
+    let mut tree = Tree::NT(
+        "start".to_string(),
+        vec![Tree::NT("number".to_string(), Vec::new())],
+    );
+    print_result(expand_tree(&mut rng, &grammar_cost, &mut tree, 0, 10).map(|_| tree.all_leafs()));
+
+    println!("[+] Second EBNF production for the same nonterminal:");
+    let mut ebnf = examplegrammars::json_grammar();
+    let result = ebnf.try_add_production("start", Expr::T("{}".to_string()));
+    print_result(result.map(|_| ebnf.to_bnf().to_string()));
+}
+
+fn fuzz_tree(rng: &mut Rng, grammar: &GrammarCost, max_expansions: usize) -> Tree {
+    let mut tree = Tree::NT("start".to_string(), Vec::new());
+    expand_tree(rng, grammar, &mut tree, 0, max_expansions).unwrap();
+    tree
+}
+
+fn print_result(result: Result<String, GrammarError>) {
+    match result {
+        Ok(s) => println!("    {}", s),
+        Err(err) => println!("    Error: {}", err),
+    }
+}
@@ -0,0 +1,1168 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Not all parts of the grammar API are used by the examples.
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::rng::Rng;
+
+/// Represents a context-free-grammar as a set/map of production rules.
+/// For easier processability the expansions of the production rules are grouped
+/// by nonterminal. This results in a mapping Nonterminal -> Vec<Vec<String>>.
+/// The outer Vec are the different alternatives/choices of the rule.
+/// The inner Vec is the sequence / string that the nonterminal expands to.
+/// Each inner Vec corresponds to one production rule Nonterminal -> Vec<String>
+/// in the formal grammar.
+/// By convention nonterminal symbols are enclosed in angle brackets (`<nonterminal>`)
+/// and terminal symbols are plain strings (`"terminal"`).
+#[derive(PartialEq, Eq, Debug)]
+pub struct Grammar(HashMap<Nonterminal, Vec<Expansion>>);
+pub type Nonterminal = String;
+pub type Expansion = Vec<String>; // Right-hand-side of a production rule.
+
+/// Errors for grammars that can't be used (for generating strings).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GrammarError {
+    /// A nonterminal is used (in an expansion, as start symbol or in a
+    /// derivation tree) but has no expansions.
+    UndefinedNonterminal(Nonterminal),
+
+    /// Nonterminals that can't be expanded into a finite string (every
+    /// expansion is recursive).
+    NonTerminating(Vec<Nonterminal>),
+
+    /// A nonterminal got more than one EBNF production rule.
+    DuplicateNonterminal(Nonterminal),
+}
+
+impl std::fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GrammarError::UndefinedNonterminal(nonterminal) => write!(
+                f,
+                "Nonterminal {} is referenced/used in the RHS but not defined \
+                in the LHS of any production rule",
+                nonterminal
+            ),
+            GrammarError::NonTerminating(nonterminals) => write!(
+                f,
+                "Nonterminals {} can't be expanded into a finite string \
+                (every expansion is recursive)",
+                nonterminals.join(", ")
+            ),
+            GrammarError::DuplicateNonterminal(nonterminal) => write!(
+                f,
+                "Can't add production for same nonterminal twice {}",
+                nonterminal
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GrammarError {}
+
+/// Context-free grammar annotated with pre-computed cost values for symbols /
+/// expansions.
+pub struct GrammarCost {
+    grammar: Grammar,
+    cost_by_symbol: HashMap<String, SymbolCost>,
+    cost_by_expansion: HashMap<Expansion, SymbolCost>,
+}
+
+impl std::fmt::Display for Grammar {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let maxnonterminallength = self.0.keys().map(|x| x.len()).max().unwrap_or(10);
+        for (nonterminal, expansions) in self.0.iter() {
+            writeln!(
+                f,
+                "{:maxnonterminallength$} -> {}",
+                nonterminal,
+                expansions
+                    .iter()
+                    .map(|expansion| expansion
+                        .iter()
+                        .map(|symbol| if Grammar::is_nonterminal(symbol) {
+                            symbol.to_string()
+                        } else {
+                            format!("\"{}\"", symbol)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Grammar {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// Add a single production of the form: nonterminal -> [symbols]
+    /// to the grammar.
+    pub fn add_production(&mut self, nonterminal: &str, expansion: &[&str]) {
+        self.add_production_(
+            nonterminal.to_string(),
+            expansion.iter().map(|x| x.to_string()).collect(),
+        );
+    }
+
+    /// Add a single production of the form: nonterminal -> [symbols]
+    /// to the grammar (for owned values).
+    /// Expansions that the nonterminal already has are ignored (with a
+    /// warning): since expansions are chosen uniformly at random, a duplicate
+    /// would silently make it more likely. Use `add_production_weighted` for
+    /// biasing the choice on purpose.
+    pub fn add_production_(&mut self, nonterminal: Nonterminal, expansion: Expansion) {
+        if self.expansions(&nonterminal).contains(&expansion) {
+            eprintln!(
+                "[!] Ignoring duplicate production {} -> {:?}",
+                nonterminal, expansion
+            );
+            return;
+        }
+        self.push_expansion(nonterminal, expansion);
+    }
+
+    /// Add a single production of the form: nonterminal -> [symbols]
+    /// to the grammar `weight` times, so that it is chosen `weight` times as
+    /// often as an expansion with weight 1 during random expansion.
+    pub fn add_production_weighted(
+        &mut self,
+        nonterminal: &str,
+        expansion: &[&str],
+        weight: usize,
+    ) {
+        assert!(weight > 0, "Weight of a production must be at least 1");
+        let expansion: Expansion = expansion.iter().map(|x| x.to_string()).collect();
+        for _ in 0..weight {
+            self.push_expansion(nonterminal.to_string(), expansion.clone());
+        }
+    }
+
+    /// Helper function for adding lots of productions which each have only one
+    /// alternative to the grammar.
+    pub fn add_productions(&mut self, nonterminal: &str, expansions: &[&str]) {
+        for expansion in expansions.iter() {
+            self.add_production(nonterminal, &[expansion]);
+        }
+    }
+
+    /// Add an expansion without checking for duplicates.
+    fn push_expansion(&mut self, nonterminal: Nonterminal, expansion: Expansion) {
+        match self.0.get_mut(&nonterminal) {
+            Some(expansions) => expansions.push(expansion),
+            None => {
+                self.0.insert(nonterminal, vec![expansion]);
+            }
+        }
+    }
+
+    /// Productions that occur more than once (either on purpose, with
+    /// `add_production_weighted`, or by modifying a grammar in another way),
+    /// together with their number of occurrences.
+    pub fn duplicates(&self) -> Vec<(Nonterminal, Expansion, usize)> {
+        let mut res = Vec::new();
+        for nonterminal in self.nonterminals() {
+            let expansions = self.expansions(&nonterminal);
+            for (i, expansion) in expansions.iter().enumerate() {
+                let count = expansions.iter().filter(|e| *e == expansion).count();
+                // Only report the first occurrence.
+                if count > 1 && !expansions[..i].contains(expansion) {
+                    res.push((nonterminal.clone(), expansion.clone(), count));
+                }
+            }
+        }
+        res
+    }
+
+    /// Create a copy of the grammar with only the actually used/reachable
+    /// production rules. Fails if the grammar is invalid.
+    pub fn trim(&self) -> Result<Grammar, GrammarError> {
+        let mut res = Grammar::new();
+
+        // Set of already processed/seen nonterminals (this prevents infinite
+        // loops in recursive productions).
+        let mut seen_nonterminals = HashSet::new();
+
+        let mut stack = Vec::new();
+        stack.push("<start>".to_string());
+
+        while let Some(nonterminal) = stack.pop() {
+            if seen_nonterminals.contains(&nonterminal) {
+                continue;
+            }
+            seen_nonterminals.insert(nonterminal.clone());
+
+            match self.0.get(&nonterminal) {
+                Some(expansions) => {
+                    for expansion in expansions.iter() {
+                        res.push_expansion(nonterminal.clone(), expansion.clone());
+                        for symbol in expansion.iter() {
+                            if Grammar::is_nonterminal(symbol) {
+                                stack.push(symbol.clone());
+                            }
+                        }
+                    }
+                }
+
+                None => {
+                    // A referenced nonterminal is not actually defined.
+                    // The grammar is invalid.
+                    return Err(GrammarError::UndefinedNonterminal(nonterminal));
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Check that the given grammar satisfies some sensible rules.
+    pub fn is_valid(&self) -> bool {
+        match self.trim() {
+            Ok(grammar) => *self == grammar,
+            Err(_) => false,
+        }
+    }
+
+    /// Expansions of a nonterminal (with angle brackets); empty if the
+    /// nonterminal is not defined.
+    pub fn expansions(&self, nonterminal: &str) -> &[Expansion] {
+        self.0.get(nonterminal).map(|e| e.as_slice()).unwrap_or(&[])
+    }
+
+    /// All nonterminals that have expansions in the grammar (with angle
+    /// brackets).
+    pub fn nonterminals(&self) -> BTreeSet<Nonterminal> {
+        self.0.keys().cloned().collect()
+    }
+
+    /// All terminal symbols that occur in the grammar (except the empty
+    /// string).
+    pub fn terminals(&self) -> BTreeSet<String> {
+        self.0
+            .values()
+            .flatten()
+            .flatten()
+            .filter(|symbol| !symbol.is_empty() && !Grammar::is_nonterminal(symbol))
+            .cloned()
+            .collect()
+    }
+
+    /// Determines if a given symbol name represents a nonterminal.
+    /// This is only by convention and not actually enforced anywhere.
+    fn is_nonterminal(s: &str) -> bool {
+        s.starts_with("<") && s.ends_with(">")
+    }
+
+    /// Trim nonterminal symbol name angle brackets.
+    fn trim_angle_brackets(s: &str) -> &str {
+        s.trim_start_matches("<").trim_end_matches(">")
+    }
+}
+
+/// Pre-compute expansion costs. Fails if the grammar can't be used for
+/// generating strings: if a nonterminal is used but not defined, or if a
+/// nonterminal can't be expanded into a finite string (every expansion is
+/// recursive), since expanding it would never terminate.
+impl std::convert::TryFrom<Grammar> for GrammarCost {
+    type Error = GrammarError;
+
+    fn try_from(grammar: Grammar) -> Result<Self, Self::Error> {
+        let used = grammar
+            .0
+            .values()
+            .flatten()
+            .flatten()
+            .filter(|symbol| Grammar::is_nonterminal(symbol));
+        for nonterminal in std::iter::once(&"<start>".to_string()).chain(used) {
+            if !grammar.0.contains_key(nonterminal) {
+                return Err(GrammarError::UndefinedNonterminal(nonterminal.clone()));
+            }
+        }
+
+        let mut cost_by_symbol = HashMap::new();
+        let mut cost_by_expansion = HashMap::new();
+
+        for (symbol, expansions) in grammar.0.iter() {
+            cost_by_symbol.insert(
+                symbol.clone(),
+                symbol_cost(&grammar, symbol, &HashSet::new())?,
+            );
+
+            for expansion in expansions.iter() {
+                cost_by_expansion.insert(
+                    expansion.clone(),
+                    expansion_cost(&grammar, expansion, &HashSet::new())?,
+                );
+            }
+        }
+
+        let mut infinite: Vec<_> = cost_by_symbol
+            .iter()
+            .filter(|(_, cost)| **cost == SymbolCost::Infinite)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        if !infinite.is_empty() {
+            infinite.sort();
+            return Err(GrammarError::NonTerminating(infinite));
+        }
+
+        Ok(Self {
+            grammar,
+            cost_by_symbol,
+            cost_by_expansion,
+        })
+    }
+}
+
+/// Context-free-grammar with support for EBNF constructs.
+#[derive(PartialEq, Eq)]
+pub struct Ebnf(HashMap<Nonterminal, Expr>);
+
+/// EBNF syntax expression.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Expr {
+    Alt(Vec<Expr>),  // Alternative/choice between elements.
+    Seq(Vec<Expr>),  // Sequence of elements.
+    Opt(Box<Expr>),  // Optional occurrence of zero or one times (?).
+    Plus(Box<Expr>), // Occurrence of one or more times (+).
+    Star(Box<Expr>), // Occurrence of an arbitrary number of times (including zero) (*).
+    NT(String),      // Nonterminal symbol.
+    T(String),       // Terminal symbol.
+}
+
+// Shorthand functions for easier construction of Expr variants.
+// (Handle cloning/boxing/slicing).
+#[rustfmt::skip]
+pub fn alt(expr: &[Expr])   -> Expr { Expr::Alt(expr.to_vec()) }
+#[rustfmt::skip]
+pub fn seq(expr: &[Expr])   -> Expr { Expr::Seq(expr.to_vec()) }
+#[rustfmt::skip]
+pub fn opt(expr: Expr)      -> Expr { Expr::Opt(Box::new(expr)) }
+#[rustfmt::skip]
+pub fn plus(expr: Expr)     -> Expr { Expr::Plus(Box::new(expr)) }
+#[rustfmt::skip]
+pub fn star(expr: Expr)     -> Expr { Expr::Star(Box::new(expr)) }
+#[rustfmt::skip]
+pub fn nt(s: &str)          -> Expr { Expr::NT(s.to_string()) }
+#[rustfmt::skip]
+pub fn t(s: &str)           -> Expr { Expr::T(s.to_string()) }
+
+/// Create new symbol and dispatch to nonterminal or terminal symbol based
+/// on the name and wether it is enclosed in angle brackets or not.
+pub fn s(s: &str) -> Expr {
+    if s.starts_with("<") && s.ends_with(">") {
+        nt(s.trim_start_matches("<").trim_end_matches(">"))
+    } else {
+        t(s)
+    }
+}
+
+impl std::fmt::Display for Ebnf {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let maxnonterminallength = self.0.keys().map(|x| x.len()).max().unwrap_or(10);
+        for (nonterminal, expr) in self.0.iter() {
+            writeln!(f, "{:maxnonterminallength$} -> {}", nonterminal, expr)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Expr::Alt(v) => write!(
+                f,
+                "{}",
+                v.iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("  |  ")
+            ),
+            Expr::Seq(v) => write!(
+                f,
+                "{}",
+                v.iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            Expr::Opt(expr) => write!(f, "({})?", expr),
+            Expr::Plus(expr) => write!(f, "({})+", expr),
+            Expr::Star(expr) => write!(f, "({})*", expr),
+            Expr::NT(s) => write!(f, "<{}>", s),
+            Expr::T(s) => write!(f, "\"{}\"", s),
+        }
+    }
+}
+
+impl Ebnf {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// Add a production rule to the grammar. Panics if the nonterminal already
+    /// has a production rule (for grammars defined in code; see
+    /// `try_add_production` otherwise).
+    pub fn add_production(&mut self, nonterminal: &str, expr: Expr) {
+        if let Err(err) = self.try_add_production(nonterminal, expr) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Add a production rule to the grammar. Fails if the nonterminal already
+    /// has a production rule.
+    pub fn try_add_production(
+        &mut self,
+        nonterminal: &str,
+        expr: Expr,
+    ) -> Result<(), GrammarError> {
+        match self.0.get_mut(nonterminal) {
+            Some(_) => Err(GrammarError::DuplicateNonterminal(nonterminal.to_string())),
+            None => {
+                self.0.insert(nonterminal.to_string(), expr);
+                Ok(())
+            }
+        }
+    }
+
+    /// Convert a slice of printable values to a list of terminal expressions.
+    /// This allows easy construction of alternatives of ranges/iterators.
+    fn to_terminals<T: ToString>(v: &[T]) -> Vec<Expr> {
+        let mut res = Vec::new();
+        for el in v.iter() {
+            res.push(Expr::T(el.to_string()));
+        }
+        res
+    }
+
+    /// Convert a grammar from EBNF to BNF by replacing regular language constructs
+    /// / expressions with direct production rules.
+    pub fn to_bnf(&self) -> Grammar {
+        let mut bnf = Grammar::new();
+
+        // Iterate over each production rule and expand out and flatten all extended
+        // syntax constructs.
+        for (nonterminal, expression) in self.0.iter() {
+            let mut symbolcounter = 0; // Needed for generating fresh new symbol names.
+            let expansions = Ebnf::to_bnf_expr(&mut bnf, expression, &mut symbolcounter);
+            for expansion in expansions.into_iter() {
+                bnf.add_production_(format!("<{}>", nonterminal), expansion);
+            }
+        }
+
+        bnf
+    }
+
+    /// Generate a unique nonterminal symbol name that does not yet occur in the
+    /// given grammar.
+    fn new_nonterminal(bnf: &Grammar, i: &mut usize) -> String {
+        loop {
+            let symbol = format!("<symbol{}>", i);
+            if !bnf.0.contains_key(&symbol) {
+                return symbol;
+            }
+            *i += 1;
+        }
+    }
+
+    /// Convert an EBNF expression into our BNF CFG grammar representation.
+    /// This requires translating regular constructs like `?`/`+`/"`*`,
+    /// as well as fully flattening nested groupings (alternatives and sequences).
+    fn to_bnf_expr(bnf: &mut Grammar, expression: &Expr, i: &mut usize) -> Vec<Expansion> {
+        match expression {
+            // Alternatives are represented as top-level Vecs.
+            Expr::Alt(exprs) => {
+                let mut res = Vec::new();
+                for expr in exprs {
+                    res.extend(Ebnf::to_bnf_expr(bnf, expr, i));
+                }
+                res
+            }
+
+            // Sequences are represented as inner Vecs.
+            // Therefore we need to expand each nested expression.
+            // If an expression expands to multiple alternatives or to one
+            // alternative with multiple elements in the sequence, we need
+            // to introduce a new nonterminal symbol and insert one level of
+            // indirection, in order to be able to fully flatten the grammar
+            // representation.
+            Expr::Seq(exprs) => {
+                let mut res = Vec::new();
+                for expr in exprs {
+                    let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
+                    if expr_expansions.len() == 1 && expr_expansions[0].len() == 1 {
+                        // We can shortcut and don't need to add a useless new
+                        // intermediate nonterminal symbol that would only expand
+                        // to *one single* other symbol anyway.
+                        res.push(expr_expansions[0][0].clone());
+                    } else {
+                        let s = Ebnf::new_nonterminal(bnf, i);
+                        for expr_expansion in expr_expansions.into_iter() {
+                            bnf.add_production_(s.clone(), expr_expansion);
+                        }
+                        res.push(s);
+                    }
+                }
+                vec![res]
+            }
+
+            // > An expression <symbol>? becomes <new-symbol>, where <new-symbol> ::= <empty>  | <symbol>.
+            // Since an expression can expand to multiple alternatives/sequences,
+            // we need to perform this substitution for all possible candidates.
+            Expr::Opt(expr) => {
+                let s = Ebnf::new_nonterminal(bnf, i);
+                let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
+                for expr_expansion in expr_expansions.into_iter() {
+                    bnf.add_production_(s.clone(), expr_expansion);
+                }
+                // Since the empty string / epsilon does not depend on the expansion,
+                // we can avoid duplicates and insert it once at the end (and not
+                // over and over inside the loop).
+                bnf.add_production_(s.clone(), vec!["".to_string()]);
+                vec![vec![s]]
+            }
+
+            // > An expression <symbol>+ becomes <new-symbol>, where <new-symbol> ::= <symbol> | <symbol><new-symbol>.
+            // Since an expression can expand to multiple alternatives/sequences,
+            // we need to perform this substitution for all possible candidates.
+            Expr::Plus(expr) => {
+                let s = Ebnf::new_nonterminal(bnf, i);
+                let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
+                for mut expr_expansion in expr_expansions.into_iter() {
+                    bnf.add_production_(s.clone(), expr_expansion.clone());
+                    expr_expansion.push(s.clone());
+                    bnf.add_production_(s.clone(), expr_expansion);
+                }
+                vec![vec![s]]
+            }
+
+            // > An expression <symbol>* becomes <new-symbol>, where <new-symbol> ::= <empty>  | <symbol><new-symbol>.
+            // Since an expression can expand to multiple alternatives/sequences,
+            // we need to perform this substitution for all possible candidates.
+            Expr::Star(expr) => {
+                let s = Ebnf::new_nonterminal(bnf, i);
+                let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
+                for mut expr_expansion in expr_expansions.into_iter() {
+                    expr_expansion.push(s.clone());
+                    bnf.add_production_(s.clone(), expr_expansion);
+                }
+                // Since the empty string / epsilon does not depend on the expansion,
+                // we can avoid duplicates and insert it once at the end (and not
+                // over and over inside the loop).
+                bnf.add_production_(s.clone(), vec!["".to_string()]);
+                vec![vec![s]]
+            }
+
+            Expr::NT(s) => vec![vec![format!("<{}>", s)]],
+            Expr::T(s) => vec![vec![s.clone()]],
+        }
+    }
+
+    /// Create a copy of the grammar with only the actually used/reachable
+    /// production rules. Fails if the grammar is invalid.
+    fn trim(&self) -> Result<Ebnf, GrammarError> {
+        let mut res = Ebnf::new();
+
+        let mut seen_nonterminals = HashSet::new();
+
+        let mut stack_nonterminals = Vec::new();
+        stack_nonterminals.push("<start>".to_string());
+
+        // Iterate over all reachable nonterminals/production rules and add each
+        // production rule to the new grammar.
+        while let Some(nonterminal) = stack_nonterminals.pop() {
+            if seen_nonterminals.contains(&nonterminal) {
+                continue;
+            }
+            seen_nonterminals.insert(nonterminal.clone());
+
+            // Iterate over the expression and extract all nonterminals.
+            let expr_root = match self.0.get(&nonterminal) {
+                Some(expr_root) => expr_root,
+                None => {
+                    // A referenced nonterminal is not actually defined.
+                    // The grammar is invalid.
+                    return Err(GrammarError::UndefinedNonterminal(nonterminal));
+                }
+            };
+            res.add_production(&nonterminal, expr_root.clone());
+
+            let mut stack_exprs: Vec<&Expr> = Vec::new();
+            stack_exprs.push(expr_root);
+
+            while let Some(expr) = stack_exprs.pop() {
+                match expr {
+                    Expr::Alt(exprs) => stack_exprs.extend(exprs),
+                    Expr::Seq(exprs) => stack_exprs.extend(exprs),
+                    Expr::Opt(expr) => stack_exprs.push(expr),
+                    Expr::Plus(expr) => stack_exprs.push(expr),
+                    Expr::Star(expr) => stack_exprs.push(expr),
+                    Expr::NT(s) => stack_nonterminals.push(s.clone()),
+                    Expr::T(_) => (),
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
+    fn is_valid(&self) -> bool {
+        match self.trim() {
+            Ok(grammar) => *self == grammar,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Derivation tree in a given grammar.
+#[derive(Clone, Debug)]
+pub enum Tree {
+    /// Nonterminal symbol (inner node in the tree) consisting of a symbol name
+    /// and a list of child nodes / children.
+    NT(String, Vec<Tree>),
+    /// Terminal symbol (leaf of the tree) consisting only of a symbol name
+    /// (= final text for this tree part); it has no children.
+    T(String),
+}
+
+/// Differing subtrees of two derivation trees (see `Tree::diff`).
+#[derive(Clone, Debug)]
+pub struct TreeDiff {
+    /// Path (list of child indices, starting from the root) to the subtree.
+    pub path: Vec<usize>,
+
+    pub left: Tree,
+    pub right: Tree,
+}
+
+impl std::fmt::Display for TreeDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Nonterminals together with the string they derive.
+        let describe = |tree: &Tree| match tree {
+            Tree::NT(_, _) => format!("{} \"{}\"", tree.get_name(), tree.all_leafs()),
+            Tree::T(_) => tree.get_name(),
+        };
+        write!(
+            f,
+            "{:?}: {} -> {}",
+            self.path,
+            describe(&self.left),
+            describe(&self.right)
+        )
+    }
+}
+
+// Shorthand functions for easier construction of derivation trees.
+// Similar to grammar shorthand functions. Prefix `t` stands for `tree`.
+#[rustfmt::skip]
+fn tnt(name: &str, children: &[Tree]) -> Tree { Tree::NT(name.to_string(), children.to_vec()) }
+#[rustfmt::skip]
+fn tt(name: &str)                     -> Tree { Tree::T(name.to_string()) }
+fn ts(s: &str) -> Tree {
+    if Grammar::is_nonterminal(s) {
+        tnt(Grammar::trim_angle_brackets(s), &[])
+    } else {
+        tt(s)
+    }
+}
+
+impl Tree {
+    /// Returns a dot / graphviz definition of the derivation tree / graph.
+    /// (Does iterative pre-order traversal of the tree).
+    /// It can be rendered e.g. as follows: dot -Tpdf tree.dot -o tree.pdf
+    pub fn to_dot(&self) -> String {
+        self.to_dot_highlighted(&[])
+    }
+
+    /// Returns a dot / graphviz definition of the derivation tree, in which the
+    /// subtrees that differ from another tree (see `diff`) are highlighted.
+    pub fn to_dot_diff(&self, diffs: &[TreeDiff]) -> String {
+        let paths: Vec<_> = diffs.iter().map(|diff| diff.path.clone()).collect();
+        self.to_dot_highlighted(&paths)
+    }
+
+    /// Returns a dot / graphviz definition of the derivation tree, in which the
+    /// subtrees at the given paths are highlighted (red).
+    fn to_dot_highlighted(&self, highlight: &[Vec<usize>]) -> String {
+        let mut lines = vec![
+            "digraph DerivationTree {".to_string(),
+            "".to_string(),
+            "    node [shape=plain];".to_string(),
+            "".to_string(),
+        ];
+
+        let mut node_count = 0;
+        let mut queue: VecDeque<(&Tree, Option<usize>, Vec<usize>)> = VecDeque::new();
+        queue.push_back((self, None, Vec::new()));
+
+        while let Some((cur, parent, path)) = queue.pop_front() {
+            node_count += 1;
+            let highlighted = highlight.iter().any(|h| path.starts_with(h));
+            lines.push(format!(
+                "    n{} [label=\"{}\"{}];",
+                node_count,
+                Tree::to_dot_label(&cur.get_name()),
+                if highlighted { " fontcolor=red" } else { "" }
+            ));
+
+            if let Some(parent) = parent {
+                lines.push(format!("    n{} -> n{};", parent, node_count));
+                lines.push("".to_string());
+            }
+
+            match cur {
+                Tree::NT(_, children) => {
+                    for (i, child) in children.iter().enumerate() {
+                        let mut childpath = path.clone();
+                        childpath.push(i);
+                        queue.push_back((child, Some(node_count), childpath));
+                    }
+                }
+
+                Tree::T(_) => {
+                    // Edge to this node was already added previously.
+                    // Since there are no children for terminal symbols, there
+                    // is nothing left to do.
+                }
+            }
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Returns an indented rendering of the derivation tree with box-drawing
+    /// characters, for inspecting trees in the terminal, e.g.:
+    ///
+    /// ```text
+    /// <start>
+    /// └── <expr>
+    ///     ├── <term>
+    ///     │   └── <factor>
+    ///     ...
+    /// ```
+    pub fn to_ascii(&self) -> String {
+        self.to_ascii_depth(usize::MAX)
+    }
+
+    /// Like `to_ascii`, but only down to the given depth (the root has depth
+    /// 0). Deeper subtrees are collapsed into one line with the string they
+    /// derive.
+    pub fn to_ascii_depth(&self, max_depth: usize) -> String {
+        let mut lines = Vec::new();
+        // Nodes with the prefix for their own line and for the lines of their
+        // children (pre-order traversal, so the children are pushed in
+        // reverse).
+        let mut stack = vec![(self, 0, String::new(), String::new())];
+        while let Some((cur, depth, prefix, childprefix)) = stack.pop() {
+            match cur {
+                Tree::NT(_, children) if !children.is_empty() && depth >= max_depth => {
+                    lines.push(format!(
+                        "{}{} … \"{}\"",
+                        prefix,
+                        cur.get_name(),
+                        cur.all_leafs()
+                    ));
+                }
+
+                Tree::NT(_, children) => {
+                    lines.push(format!("{}{}", prefix, cur.get_name()));
+                    for (i, child) in children.iter().enumerate().rev() {
+                        let (branch, indent) = if i == children.len() - 1 {
+                            ("└── ", "    ")
+                        } else {
+                            ("├── ", "│   ")
+                        };
+                        stack.push((
+                            child,
+                            depth + 1,
+                            format!("{}{}", childprefix, branch),
+                            format!("{}{}", childprefix, indent),
+                        ));
+                    }
+                }
+
+                Tree::T(_) => lines.push(format!("{}{}", prefix, cur.get_name())),
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Get the symbol name as a string. Depending on the kind of symbol, the
+    /// symbol name is wrapped into either double quotes (terminal symbol), or
+    /// angle brackets (nonterminal symbols).
+    fn get_name(&self) -> String {
+        match self {
+            Tree::NT(name, _) => format!("<{}>", name),
+            Tree::T(name) => format!("\"{}\"", name),
+        }
+    }
+
+    /// Escape symbol name for usage as vertex/node label in a dot/graphviz file.
+    fn to_dot_label(s: &str) -> String {
+        s.chars()
+            .map(|c| {
+                if !(0x21 <= c as u32 && c as u32 <= 0x7d) {
+                    "_".to_string()
+                } else if [',', '<', '>', '\\', '"'].contains(&c) {
+                    format!("\\{}", c)
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect()
+    }
+
+    /// Subtree at the given path (list of child indices, starting from the
+    /// root).
+    pub fn subtree(&self, path: &[usize]) -> Option<&Tree> {
+        let mut cur = self;
+        for &i in path {
+            cur = match cur {
+                Tree::NT(_, children) => children.get(i)?,
+                Tree::T(_) => return None,
+            };
+        }
+        Some(cur)
+    }
+
+    /// Mutable subtree at the given path (list of child indices, starting
+    /// from the root).
+    pub fn subtree_mut(&mut self, path: &[usize]) -> Option<&mut Tree> {
+        let mut cur = self;
+        for &i in path {
+            cur = match cur {
+                Tree::NT(_, children) => children.get_mut(i)?,
+                Tree::T(_) => return None,
+            };
+        }
+        Some(cur)
+    }
+
+    /// Structural differences to another derivation tree: the outermost
+    /// subtrees (with their paths) that differ, i.e. where the symbols differ
+    /// or where the same nonterminal was expanded differently. Subtrees below
+    /// nodes with the same expansion are compared recursively, so a change
+    /// deep inside the tree is reported only there, and not for all of its
+    /// ancestors.
+    pub fn diff(&self, other: &Tree) -> Vec<TreeDiff> {
+        let mut res = Vec::new();
+        let mut stack = vec![(self, other, Vec::new())];
+        while let Some((left, right, path)) = stack.pop() {
+            match (left, right) {
+                (Tree::NT(a, left_children), Tree::NT(b, right_children))
+                    if a == b
+                        && left_children.len() == right_children.len()
+                        && left_children
+                            .iter()
+                            .zip(right_children.iter())
+                            .all(|(l, r)| l.get_name() == r.get_name()) =>
+                {
+                    // Same expansion: compare the children (in reverse, so
+                    // that the differences are reported from left to right).
+                    for (i, (l, r)) in left_children
+                        .iter()
+                        .zip(right_children.iter())
+                        .enumerate()
+                        .rev()
+                    {
+                        let mut childpath = path.clone();
+                        childpath.push(i);
+                        stack.push((l, r, childpath));
+                    }
+                }
+
+                (Tree::T(a), Tree::T(b)) if a == b => (),
+
+                _ => res.push(TreeDiff {
+                    path,
+                    left: left.clone(),
+                    right: right.clone(),
+                }),
+            }
+        }
+        res
+    }
+
+    /// Concatenate all leafs of the derivation tree (terminals, and yet
+    /// unexpanded nonterminals) into one string.
+    pub fn all_leafs(&self) -> String {
+        let mut res: Vec<String> = Vec::new();
+        self.all_leafs_(&mut res);
+        res.join("")
+    }
+
+    fn all_leafs_(&self, res: &mut Vec<String>) {
+        match self {
+            Tree::NT(name, children) => {
+                if children.is_empty() {
+                    res.push(format!(" <{}> ", name));
+                }
+                for child in children.iter() {
+                    child.all_leafs_(res);
+                }
+            }
+
+            Tree::T(name) => res.push(name.clone()),
+        }
+    }
+
+    /// Collect pointers to nodes that can be expanded (nonterminals that do not
+    /// yet have any children assigned).
+    fn get_expandable_nonterminals(&mut self) -> Vec<&mut Tree> {
+        let mut res: Vec<&mut Tree> = Vec::new();
+
+        let mut queue: VecDeque<&mut Tree> = VecDeque::new();
+        queue.push_back(self);
+
+        while let Some(cur) = queue.pop_front() {
+            // We first determine whether this node is a nonterminal with empty
+            // / no children (then it is expandable).
+            // As far as I know, we can't do what we want here in a single match
+            // since we would then have to borrow children either as mutable
+            // (for iterating over them and pushing mutable refs to the queue)
+            // or as immutable (for pushing cur to the result list), depending
+            // on its inner/destructured value.
+
+            let mut expandable = false;
+            if let Tree::NT(_, children) = cur {
+                if children.is_empty() {
+                    expandable = true;
+                }
+            }
+
+            if expandable {
+                res.push(cur);
+            } else {
+                // `if` is only there for destructuring.
+                if let Tree::NT(_, children) = cur {
+                    for child in children.iter_mut() {
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
+
+/// Create a random string from a context-free grammar.
+pub fn fuzz(rng: &mut Rng, grammar: Grammar) -> Result<String, GrammarError> {
+    Ok(fuzz_tree(rng, grammar)?.all_leafs())
+}
+
+/// Create a random derivation tree from a context-free grammar. Fails if
+/// the grammar can't be used for generating strings (see `GrammarCost`).
+pub fn fuzz_tree(rng: &mut Rng, grammar: Grammar) -> Result<Tree, GrammarError> {
+    let grammar_cost = GrammarCost::try_from(grammar)?;
+    fuzz_tree_(rng, &grammar_cost)
+}
+
+/// Create a random derivation tree from a context-free grammar with already
+/// pre-computed costs (for creating many trees from the same grammar).
+pub fn fuzz_tree_(rng: &mut Rng, grammar: &GrammarCost) -> Result<Tree, GrammarError> {
+    let mut tree = Tree::NT("start".to_string(), Vec::new());
+    expand_tree(rng, grammar, &mut tree, 80, 200)?;
+    Ok(tree)
+}
+
+/// Expand nonterminals in the derivation tree in three phases:
+///
+///   1. Increase as much as possible by choosing expansions that lead to largest
+///      number of children.
+///
+///   2. Randomly expand leaf-nonterminals.
+///
+///   3. Shrink as much as possible by choosing expansions that lead to smallest
+///      number of children.
+///
+/// Fails if the tree contains a nonterminal that is not defined in the grammar
+/// (the tree is then only partially expanded).
+pub fn expand_tree(
+    rng: &mut Rng,
+    grammar: &GrammarCost,
+    tree: &mut Tree,
+    min_expansions: usize, // Perform this much expansions in the first phase.
+    max_expansions: usize, // Perform this much expansions in the second phase.
+) -> Result<(), GrammarError> {
+    // Traverse down the tree to find non-expanded leaf-nonterminals.
+    let mut expandable = tree.get_expandable_nonterminals();
+
+    // Number of performed node expansions.
+    let mut num_expansions = 0;
+
+    // Max expansion (increase size as much as possible).
+    while !expandable.is_empty() && num_expansions < min_expansions {
+        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::MaxCost)?;
+        num_expansions += 1;
+    }
+
+    // Random expansion.
+    while !expandable.is_empty() && num_expansions < max_expansions {
+        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::Random)?;
+        num_expansions += 1;
+    }
+
+    // Min expansion (increase size as little as possible / shrink).
+    while !expandable.is_empty() {
+        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::MinCost)?;
+    }
+
+    Ok(())
+}
+
+/// Minimum cost of all expansions of a symbol. Infinite recursion is mapped
+/// to the value `Infinite`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum SymbolCost {
+    Finite(usize),
+    Infinite,
+}
+
+impl std::ops::Add for SymbolCost {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (SymbolCost::Finite(a), SymbolCost::Finite(b)) => SymbolCost::Finite(a + b),
+            (SymbolCost::Infinite, _) => SymbolCost::Infinite,
+            (_, SymbolCost::Infinite) => SymbolCost::Infinite,
+        }
+    }
+}
+
+fn symbol_cost(
+    grammar: &Grammar,
+    symbol: &str,
+    seen: &HashSet<String>,
+) -> Result<SymbolCost, GrammarError> {
+    let expansions = grammar
+        .0
+        .get(symbol)
+        .ok_or_else(|| GrammarError::UndefinedNonterminal(symbol.to_string()))?;
+    let mut min = SymbolCost::Infinite;
+    for expansion in expansions {
+        let mut seen = seen.clone();
+        seen.insert(symbol.to_string());
+        let tmp = expansion_cost(grammar, expansion, &seen)?;
+        min = std::cmp::min(tmp, min);
+    }
+    Ok(min)
+}
+
+fn expansion_cost(
+    grammar: &Grammar,
+    expansion: &Expansion,
+    seen: &HashSet<String>,
+) -> Result<SymbolCost, GrammarError> {
+    let nonterminals: Vec<_> = expansion
+        .iter()
+        .filter(|symbol| Grammar::is_nonterminal(symbol))
+        .collect();
+    if nonterminals.iter().any(|symbol| seen.contains(*symbol)) {
+        Ok(SymbolCost::Infinite)
+    } else {
+        let mut cost = SymbolCost::Finite(1);
+        for symbol in nonterminals {
+            cost = cost + symbol_cost(grammar, symbol, seen)?;
+        }
+        Ok(cost)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum ExpandStrategy {
+    MinCost,
+    Random,
+    MaxCost,
+}
+
+/// Expand a leaf-non-terminal symbol with rules from a specific grammar
+/// while following a specific expansion strategy.
+fn expand_node_by_strategy(
+    rng: &mut Rng,
+    grammar: &GrammarCost,
+    expandable: &mut Vec<&mut Tree>,
+    strategy: ExpandStrategy,
+) -> Result<(), GrammarError> {
+    // Choose random not-yet-expanded nonterminal symbol / node.
+    let treeidx = rng.usize_below(expandable.len());
+    let tree: &mut Tree = expandable.remove(treeidx);
+
+    // I don't know how to assert destructured enum values concisely...
+    // All these conditions should have been checked before calling this function.
+    if let Tree::NT(_, children) = tree {
+        if !children.is_empty() {
+            panic!("Can't happen");
+        }
+    } else {
+        panic!("Can't happen");
+    }
+
+    let name = tree.get_name();
+    let expansions = grammar
+        .grammar
+        .0
+        .get(&name)
+        .ok_or_else(|| GrammarError::UndefinedNonterminal(name.clone()))?;
+
+    let expansion = match strategy {
+        ExpandStrategy::Random => rng.choice(expansions),
+        ExpandStrategy::MinCost | ExpandStrategy::MaxCost => {
+            let costs: Vec<_> = expansions
+                .iter()
+                .map(|expansion| (expansion, grammar.cost_by_expansion.get(expansion).unwrap()))
+                .collect();
+
+            let cost = match strategy {
+                ExpandStrategy::MinCost => *costs.iter().map(|(_, c)| c).min().unwrap(),
+                ExpandStrategy::MaxCost => *costs.iter().map(|(_, c)| c).max().unwrap(),
+                _ => panic!("Can't happen"),
+            };
+
+            let choices: Vec<_> = costs
+                .into_iter()
+                .filter(|(_, c)| match strategy {
+                    ExpandStrategy::MinCost => *c <= cost,
+                    ExpandStrategy::MaxCost => *c >= cost,
+                    _ => panic!("Can't happen"),
+                })
+                .map(|(exp, _)| exp)
+                .collect();
+
+            // Randomly choose expansion from all valid expansions.
+            *rng.choice(&choices)
+        }
+    };
+    let expansion = expansion.iter().map(|s| ts(s)).collect::<Vec<_>>();
+
+    // Modify derivation tree with expanded children.
+    *tree = Tree::NT(Grammar::trim_angle_brackets(&name).to_string(), expansion);
+
+    // Update expandable nonterminals: Add newly created not-yet expanded
+    // nonterminals / tree leafs to the list.
+    match tree {
+        Tree::NT(_, children) => {
+            for symbol in children.iter_mut() {
+                if let Tree::NT(_, children2) = symbol {
+                    assert!(children2.is_empty());
+                    expandable.push(symbol);
+                }
+                // else: Ignore terminal symbols.
+            }
+        }
+        _ => panic!("Can't happen"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+
+use grammarfuzzer::{expand_tree, GrammarCost, Tree};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let grammar = GrammarCost::try_from(examplegrammars::expr_grammar()).unwrap();
+
+    // Mutate trees by re-generating a random subtree, and compare them with
+    // the original.
+    for _ in 0..cli::iterations(5) {
+        let (tree, path, mutated) = mutate(&mut rng, &grammar, 15);
+        let diffs = tree.diff(&mutated);
+        println!();
+        println!("[+] Re-generated subtree at {:?}:", path);
+        println!("    {}", tree.all_leafs());
+        println!("    {}", mutated.all_leafs());
+        if diffs.is_empty() {
+            println!("    (no differences)");
+        }
+        for diff in diffs.iter() {
+            println!("    - {}", diff);
+        }
+    }
+
+    // Small tree with a difference below the top level, so that the dot
+    // output stays short and shows both highlighted and unchanged parts.
+    let (tree, mutated) = loop {
+        let (tree, _, mutated) = mutate(&mut rng, &grammar, 3);
+        let diffs = tree.diff(&mutated);
+        if !diffs.is_empty() && diffs.iter().all(|diff| diff.path.len() > 2) {
+            break (tree, mutated);
+        }
+    };
+    if cli::quiet() {
+        return;
+    }
+    println!();
+    println!("[+] {}:", tree.all_leafs());
+    println!("{}", tree.to_ascii());
+    println!("[+] {}:", mutated.all_leafs());
+    println!("{}", mutated.to_ascii());
+
+    println!();
+    println!(
+        "[+] {} -> {} with highlighted differences:",
+        tree.all_leafs(),
+        mutated.all_leafs()
+    );
+    println!("{}", mutated.to_dot_diff(&tree.diff(&mutated)));
+
+    // Larger tree, only the top levels.
+    let (tree, _, _) = mutate(&mut rng, &grammar, 15);
+    println!();
+    println!("[+] {} (up to depth 4):", tree.all_leafs());
+    println!("{}", tree.to_ascii_depth(4));
+}
+
+/// Generate a random derivation tree, and a copy of it with a random subtree
+/// re-generated (returns the tree, the path of the subtree, and the copy).
+fn mutate(rng: &mut Rng, grammar: &GrammarCost, max_expansions: usize) -> (Tree, Vec<usize>, Tree) {
+    let mut tree = Tree::NT("start".to_string(), Vec::new());
+    expand_tree(rng, grammar, &mut tree, 0, max_expansions).unwrap();
+
+    let mut mutated = tree.clone();
+    let paths = nonterminal_paths(&mutated);
+    let path = rng.choice(&paths).clone();
+    if let Some(Tree::NT(_, children)) = mutated.subtree_mut(&path) {
+        children.clear();
+    }
+    expand_tree(rng, grammar, &mut mutated, 0, 5).unwrap();
+
+    (tree, path, mutated)
+}
+
+/// Paths (lists of child indices, starting from the root) of all nonterminal
+/// nodes of a derivation tree.
+fn nonterminal_paths(tree: &Tree) -> Vec<Vec<usize>> {
+    let mut res = Vec::new();
+    let mut stack = vec![(tree, Vec::new())];
+    while let Some((cur, path)) = stack.pop() {
+        if let Tree::NT(_, children) = cur {
+            for (i, child) in children.iter().enumerate() {
+                let mut childpath = path.clone();
+                childpath.push(i);
+                stack.push((child, childpath));
+            }
+            res.push(path);
+        }
+    }
+    res
+}
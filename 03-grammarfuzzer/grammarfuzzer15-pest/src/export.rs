@@ -0,0 +1,257 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Export of EBNF grammars in standard notations, for using them with other
+// tools (the `Display` format of `Ebnf` is specific to this code):
+//
+//   - W3C EBNF, as used in the XML specification
+//     (https://www.w3.org/TR/xml/#sec-notation): `name ::= a b | c? d* e+`.
+//   - ISO/IEC 14977 EBNF: `name = a, b | [c], {d}, e, {e} ;`.
+//
+// Escaping: neither notation has escape sequences in string literals. Literals
+// are quoted with double quotes, or with single quotes if they contain double
+// quotes; literals containing both are split into several concatenated
+// literals. Control characters are written as character references (`#xA`)
+// in W3C EBNF, and as special sequences (`? U+000A ?`) in ISO EBNF.
+// Characters in nonterminal names that are not allowed by the notation are
+// replaced with `_`.
+//
+// Empty alternatives are expressed as optional parts, since there is no
+// empty string literal in W3C EBNF.
+
+use crate::grammarfuzzer::{Ebnf, Expr};
+
+/// Grammar in W3C EBNF notation.
+pub fn to_w3c(ebnf: &Ebnf) -> String {
+    let productions = ebnf.productions();
+    let names: Vec<_> = productions
+        .iter()
+        .map(|(nonterminal, _)| w3c_name(nonterminal))
+        .collect();
+    let maxnamelength = names.iter().map(|name| name.len()).max().unwrap_or(10);
+
+    let mut res = String::new();
+    for (name, (_, expr)) in names.iter().zip(productions) {
+        let expr = w3c_expr(&simplify(expr), 0);
+        res.push_str(&format!("{:maxnamelength$} ::= {}\n", name, expr));
+    }
+    res
+}
+
+/// Grammar in ISO/IEC 14977 EBNF notation.
+pub fn to_iso14977(ebnf: &Ebnf) -> String {
+    let mut res = String::new();
+    for (nonterminal, expr) in ebnf.productions() {
+        let expr = iso_expr(&simplify(expr), 0);
+        if expr.is_empty() {
+            res.push_str(&format!("{} = ;\n", iso_name(nonterminal)));
+        } else {
+            res.push_str(&format!("{} = {} ;\n", iso_name(nonterminal), expr));
+        }
+    }
+    res
+}
+
+/// Remove empty strings: in sequences they are dropped, and alternatives
+/// with an empty alternative become optional. Nested alternatives and
+/// sequences are flattened. Only a completely empty expression stays
+/// `T("")`.
+fn simplify(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Alt(exprs) => {
+            let mut alternatives = Vec::new();
+            let mut empty = false;
+            for expr in exprs.iter().map(simplify) {
+                match expr {
+                    Expr::Alt(exprs) => alternatives.extend(exprs),
+                    // Optional alternatives are merged into this one.
+                    Expr::Opt(expr) => {
+                        empty = true;
+                        match *expr {
+                            Expr::Alt(exprs) => alternatives.extend(exprs),
+                            expr => alternatives.push(expr),
+                        }
+                    }
+                    Expr::T(s) if s.is_empty() => empty = true,
+                    expr => alternatives.push(expr),
+                }
+            }
+            let expr = match alternatives.len() {
+                0 => return Expr::T("".to_string()),
+                1 => alternatives.pop().unwrap(),
+                _ => Expr::Alt(alternatives),
+            };
+            if empty {
+                optional(expr)
+            } else {
+                expr
+            }
+        }
+
+        Expr::Seq(exprs) => {
+            let mut items = Vec::new();
+            for expr in exprs.iter().map(simplify) {
+                match expr {
+                    Expr::Seq(exprs) => items.extend(exprs),
+                    Expr::T(s) if s.is_empty() => (),
+                    expr => items.push(expr),
+                }
+            }
+            match items.len() {
+                0 => Expr::T("".to_string()),
+                1 => items.pop().unwrap(),
+                _ => Expr::Seq(items),
+            }
+        }
+
+        Expr::Opt(expr) => match simplify(expr) {
+            Expr::T(s) if s.is_empty() => Expr::T(s),
+            expr => optional(expr),
+        },
+        Expr::Star(expr) => match simplify(expr) {
+            Expr::T(s) if s.is_empty() => Expr::T(s),
+            // (e?)* is the same as e*.
+            Expr::Opt(expr) => Expr::Star(expr),
+            expr => Expr::Star(Box::new(expr)),
+        },
+        Expr::Plus(expr) => match simplify(expr) {
+            Expr::T(s) if s.is_empty() => Expr::T(s),
+            // (e?)+ is the same as e*.
+            Expr::Opt(expr) => Expr::Star(expr),
+            expr => Expr::Plus(Box::new(expr)),
+        },
+
+        Expr::NT(_) | Expr::T(_) => expr.clone(),
+    }
+}
+
+/// Optional expression (without nesting optional/repeated expressions).
+fn optional(expr: Expr) -> Expr {
+    match expr {
+        Expr::Opt(_) | Expr::Star(_) => expr,
+        Expr::Plus(expr) => Expr::Star(expr),
+        expr => Expr::Opt(Box::new(expr)),
+    }
+}
+
+/// Split a terminal into parts that can be written as string literals, and
+/// single control characters.
+fn terminal_parts(s: &str) -> Vec<TerminalPart> {
+    let mut res = Vec::new();
+    let mut literal = String::new();
+    for c in s.chars() {
+        let both_quotes =
+            (c == '"' && literal.contains('\'')) || (c == '\'' && literal.contains('"'));
+        if (c.is_control() || both_quotes) && !literal.is_empty() {
+            res.push(TerminalPart::Literal(std::mem::take(&mut literal)));
+        }
+        if c.is_control() {
+            res.push(TerminalPart::Char(c));
+        } else {
+            literal.push(c);
+        }
+    }
+    if !literal.is_empty() {
+        res.push(TerminalPart::Literal(literal));
+    }
+    res
+}
+
+enum TerminalPart {
+    /// String without control characters, and without both kinds of quotes.
+    Literal(String),
+    /// Control character.
+    Char(char),
+}
+
+/// String literal, with quotes that don't occur in the string.
+fn quote(s: &str) -> String {
+    if s.contains('"') {
+        format!("'{}'", s)
+    } else {
+        format!("\"{}\"", s)
+    }
+}
+
+fn w3c_name(nonterminal: &str) -> String {
+    nonterminal
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "_-.".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn iso_name(nonterminal: &str) -> String {
+    nonterminal
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// W3C EBNF expression; `prec` is the precedence of the context (0: top
+/// level or alternative, 1: element of a sequence, 2: operand of a postfix
+/// operator), for adding parentheses where needed.
+fn w3c_expr(expr: &Expr, prec: usize) -> String {
+    let (res, own) = match expr {
+        Expr::Alt(exprs) => (join(exprs, " | ", |expr| w3c_expr(expr, 0)), 0),
+        Expr::Seq(exprs) => (join(exprs, " ", |expr| w3c_expr(expr, 1)), 1),
+        Expr::Opt(expr) => (format!("{}?", w3c_expr(expr, 2)), 2),
+        Expr::Star(expr) => (format!("{}*", w3c_expr(expr, 2)), 2),
+        Expr::Plus(expr) => (format!("{}+", w3c_expr(expr, 2)), 2),
+        Expr::NT(s) => (w3c_name(s), 2),
+        Expr::T(s) if s.is_empty() => ("\"\"".to_string(), 2),
+        Expr::T(s) => {
+            let parts: Vec<_> = terminal_parts(s)
+                .iter()
+                .map(|part| match part {
+                    TerminalPart::Literal(s) => quote(s),
+                    TerminalPart::Char(c) => format!("#x{:X}", *c as u32),
+                })
+                .collect();
+            (parts.join(" "), if parts.len() == 1 { 2 } else { 1 })
+        }
+    };
+    if own < prec {
+        format!("({})", res)
+    } else {
+        res
+    }
+}
+
+/// ISO EBNF expression; `prec` as for `w3c_expr` (only 0 and 1, since
+/// optional and repeated expressions have brackets).
+fn iso_expr(expr: &Expr, prec: usize) -> String {
+    match expr {
+        Expr::Alt(exprs) => {
+            let res = join(exprs, " | ", |expr| iso_expr(expr, 0));
+            if prec > 0 {
+                format!("({})", res)
+            } else {
+                res
+            }
+        }
+        Expr::Seq(exprs) => join(exprs, ", ", |expr| iso_expr(expr, 1)),
+        Expr::Opt(expr) => format!("[{}]", iso_expr(expr, 0)),
+        Expr::Star(expr) => format!("{{{}}}", iso_expr(expr, 0)),
+        Expr::Plus(expr) => format!("{}, {{{}}}", iso_expr(expr, 1), iso_expr(expr, 0)),
+        Expr::NT(s) => iso_name(s),
+        Expr::T(s) => terminal_parts(s)
+            .iter()
+            .map(|part| match part {
+                TerminalPart::Literal(s) => quote(s),
+                TerminalPart::Char(c) => format!("? U+{:04X} ?", *c as u32),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+fn join(exprs: &[Expr], separator: &str, f: impl Fn(&Expr) -> String) -> String {
+    exprs.iter().map(f).collect::<Vec<_>>().join(separator)
+}
@@ -0,0 +1,118 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod export;
+mod grammarfuzzer;
+mod pest;
+mod rng;
+
+use std::path::Path;
+
+use grammarfuzzer::{expand_tree, Ebnf, GrammarCost, Tree};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let quiet = cli::quiet();
+
+    for (file, start) in [("json.pest", "json"), ("ini.pest", "file")] {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+        let src = std::fs::read_to_string(&path).unwrap();
+        let ebnf = pest::parse(&src, start).unwrap();
+        println!();
+        println!("[+] Grammar imported from {}:", file);
+        if !quiet {
+            println!("{}", ebnf);
+        }
+
+        let grammar = GrammarCost::try_from(ebnf.to_bnf()).unwrap();
+        println!("[+] Generated inputs:");
+        // Increasing numbers of expansions in the first phase, for small as
+        // well as larger inputs.
+        for min_expansions in 0..8 {
+            let mut tree = Tree::NT("start".to_string(), Vec::new());
+            expand_tree(&mut rng, &grammar, &mut tree, min_expansions, 40).unwrap();
+            println!("    {:?}", tree.all_leafs());
+        }
+    }
+
+    // Export for the Python code of the fuzzingbook.
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("ini.pest");
+    let ebnf = pest::parse(&std::fs::read_to_string(path).unwrap(), "file").unwrap();
+    if !quiet {
+        println!();
+        println!("[+] ini.pest as fuzzingbook grammar:");
+        println!("{}", ebnf.to_bnf().to_python_srange());
+    }
+
+    println!();
+    println!("[+] Invalid grammars:");
+    for (src, start) in [
+        ("digits = { ASCII_DIGIT+ ", "digits"),
+        ("digits = { ASCII_DIGIT{2,x} }", "digits"),
+        ("digit = { '0'..'9' }\ndigit = { \"x\" }", "digit"),
+        ("digit = { '0'..'9' }", "number"),
+    ] {
+        match pest::parse(src, start) {
+            Ok(ebnf) => println!("    {}", ebnf),
+            Err(err) => println!("    {:?}: {}", src, err),
+        }
+    }
+
+    // Export in standard EBNF notations.
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("ini.pest");
+    let ebnf = pest::parse(&std::fs::read_to_string(path).unwrap(), "file").unwrap();
+    if !quiet {
+        println!();
+        println!("[+] ini.pest in W3C EBNF:");
+        println!("{}", export::to_w3c(&ebnf));
+        println!("[+] ini.pest in ISO 14977 EBNF:");
+        println!("{}", export::to_iso14977(&ebnf));
+    }
+
+    // Compare the exports of all example grammars with the expected outputs
+    // in golden/ (which are re-generated with --bless).
+    let bless = cli::flag("--bless");
+    let mut grammars = vec![
+        ("expr", Ebnf::from(&examplegrammars::expr_grammar())),
+        ("cgi", Ebnf::from(&examplegrammars::cgi_grammar())),
+        ("title", Ebnf::from(&examplegrammars::title_grammar())),
+        ("json", examplegrammars::json_grammar()),
+    ];
+    for (file, start) in [("json.pest", "json"), ("ini.pest", "file")] {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+        let ebnf = pest::parse(&std::fs::read_to_string(path).unwrap(), start).unwrap();
+        grammars.push((file, ebnf));
+    }
+
+    println!("[+] Golden files:");
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("golden");
+    let mut failed = false;
+    for (name, ebnf) in grammars.iter() {
+        for (extension, export) in [
+            ("w3c.ebnf", export::to_w3c(ebnf)),
+            ("iso.ebnf", export::to_iso14977(ebnf)),
+        ] {
+            let filename = format!("{}.{}", name, extension);
+            let path = golden.join(&filename);
+            if bless {
+                std::fs::create_dir_all(&golden).unwrap();
+                std::fs::write(&path, export).unwrap();
+                println!("    golden/{} written", filename);
+            } else if std::fs::read_to_string(&path).is_ok_and(|expected| expected == export) {
+                println!("    golden/{} ok", filename);
+            } else {
+                println!("    golden/{} differs (re-generate with --bless)", filename);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,608 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Importer for pest (https://pest.rs) PEG grammars, so that the grammars of
+// input formats that are already defined for pest parsers can be reused for
+// generating inputs.
+//
+// Supported is a practical subset of the pest syntax:
+//
+//   - rules `name = { ... }`, also silent (`_`), atomic (`@`), compound-atomic
+//     (`$`) and non-atomic (`!`) ones;
+//   - sequences (`~`), ordered choices (`|`), groups, `?`, `*`, `+` and
+//     repetition counts (`{n}`, `{n,}`, `{,m}`, `{n,m}`);
+//   - string literals (also case-insensitive `^"..."`), character literals,
+//     character ranges (`'a'..'z'`) and the most common builtin rules (`ANY`,
+//     `ASCII_DIGIT`, `ASCII_ALPHA`, `NEWLINE`, `SOI`, `EOI`, ...);
+//   - implicit whitespace: if `WHITESPACE` / `COMMENT` rules are defined,
+//     `(WHITESPACE | COMMENT)*` is inserted between the elements of
+//     sequences and repetitions in non-atomic rules, like pest does. Rules
+//     called from atomic rules are atomic as well (pest decides this per
+//     call, here a rule is atomic if it is called from any atomic rule).
+//
+// PEGs are not context-free grammars, though: choices are ordered, and
+// lookaheads (`&e`, `!e`) restrict what can follow. The ordering is ignored
+// here, and lookaheads are dropped, except for the common idiom `!e ~ ANY`
+// ("any character except e"), which is converted into the corresponding
+// character alternatives if `e` consists of single characters. So generated
+// strings can still be rejected by the pest parser in some cases.
+
+use std::collections::BTreeSet;
+
+use crate::grammarfuzzer::{Ebnf, Expr};
+
+/// Parse a pest grammar into an EBNF grammar; `start` is the name of the
+/// rule from which inputs are generated (a `<start>` rule expanding to it is
+/// added).
+pub fn parse(s: &str, start: &str) -> Result<Ebnf, String> {
+    let mut parser = Parser {
+        s: s.chars().collect(),
+        pos: 0,
+    };
+    let rules = parser.rules()?;
+
+    // Implicit whitespace inserted in non-atomic rules.
+    let implicit: Vec<_> = ["WHITESPACE", "COMMENT"]
+        .iter()
+        .filter(|name| rules.iter().any(|rule| rule.name == **name))
+        .map(|name| Expr::NT(name.to_string()))
+        .collect();
+    let implicit = match implicit.len() {
+        0 => None,
+        1 => Some(Expr::Star(Box::new(implicit[0].clone()))),
+        _ => Some(Expr::Star(Box::new(Expr::Alt(implicit)))),
+    };
+
+    if !rules.iter().any(|rule| rule.name == start) {
+        return Err(format!("Start rule {} is not defined", start));
+    }
+
+    let atomic = atomic_rules(&rules);
+
+    let mut ebnf = Ebnf::new();
+    if start != "start" {
+        ebnf.try_add_production("start", Expr::NT(start.to_string()))
+            .map_err(|err| err.to_string())?;
+    }
+    for rule in rules.iter() {
+        let ws = if atomic.contains(&rule.name) {
+            None
+        } else {
+            implicit.as_ref()
+        };
+        ebnf.try_add_production(&rule.name, convert(&rule.expr, ws))
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(ebnf)
+}
+
+/// Rule of a pest grammar.
+struct Rule {
+    name: String,
+
+    /// Atomic rules (`@` and `$`) don't get implicit whitespace.
+    atomic: bool,
+
+    expr: Pest,
+}
+
+/// Names of the rules without implicit whitespace: atomic rules, the rules
+/// called from them, and the whitespace and comment rules themselves.
+fn atomic_rules(rules: &[Rule]) -> BTreeSet<String> {
+    let mut res = BTreeSet::new();
+    let mut stack: Vec<String> = rules
+        .iter()
+        .filter(|rule| rule.atomic || rule.name == "WHITESPACE" || rule.name == "COMMENT")
+        .map(|rule| rule.name.clone())
+        .collect();
+    while let Some(name) = stack.pop() {
+        if !res.insert(name.clone()) {
+            continue;
+        }
+        if let Some(rule) = rules.iter().find(|rule| rule.name == name) {
+            rule.expr.idents(&mut stack);
+        }
+    }
+    res
+}
+
+/// Expression of a pest grammar.
+#[derive(Clone, Debug)]
+enum Pest {
+    Str(String),
+    Range(char, char),
+    Ident(String),
+    Seq(Vec<Pest>),
+    Choice(Vec<Pest>),
+    Opt(Box<Pest>),
+    Star(Box<Pest>),
+    Plus(Box<Pest>),
+    /// Repetition count `{min,max}`.
+    Rep(Box<Pest>, usize, Option<usize>),
+    /// Positive lookahead `&e`.
+    Pos(Box<Pest>),
+    /// Negative lookahead `!e`.
+    Neg(Box<Pest>),
+}
+
+impl Pest {
+    /// Collect the names of the rules referenced in the expression.
+    fn idents(&self, res: &mut Vec<String>) {
+        match self {
+            Pest::Str(_) | Pest::Range(_, _) => {}
+            Pest::Ident(name) => res.push(name.clone()),
+            Pest::Seq(exprs) | Pest::Choice(exprs) => {
+                exprs.iter().for_each(|expr| expr.idents(res))
+            }
+            Pest::Opt(expr)
+            | Pest::Star(expr)
+            | Pest::Plus(expr)
+            | Pest::Rep(expr, _, _)
+            | Pest::Pos(expr)
+            | Pest::Neg(expr) => expr.idents(res),
+        }
+    }
+}
+
+/// Recursive descent parser for pest grammars:
+///
+///   grammar  := rule*
+///   rule     := ident "=" ("_" | "@" | "$" | "!")? "{" choice "}"
+///   choice   := "|"? sequence ("|" sequence)*
+///   sequence := prefix ("~" prefix)*
+///   prefix   := ("&" | "!")* postfix
+///   postfix  := atom ("?" | "*" | "+" | "{" count "}")*
+///   atom     := string | "^" string | char (".." char)? | ident | "(" choice ")"
+struct Parser {
+    s: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn rules(&mut self) -> Result<Vec<Rule>, String> {
+        let mut rules = Vec::new();
+        self.skip();
+        while self.pos < self.s.len() {
+            rules.push(self.rule()?);
+            self.skip();
+        }
+        Ok(rules)
+    }
+
+    fn rule(&mut self) -> Result<Rule, String> {
+        let name = self.ident()?;
+        self.expect('=')?;
+        self.skip();
+        let atomic = match self.peek() {
+            Some('@') | Some('$') => {
+                self.pos += 1;
+                true
+            }
+            Some('_') | Some('!') => {
+                self.pos += 1;
+                false
+            }
+            _ => false,
+        };
+        self.expect('{')?;
+        let expr = self.choice()?;
+        self.expect('}')?;
+        Ok(Rule { name, atomic, expr })
+    }
+
+    fn choice(&mut self) -> Result<Pest, String> {
+        self.skip();
+        // Leading `|` is allowed.
+        if self.peek() == Some('|') {
+            self.pos += 1;
+        }
+        let mut res = vec![self.sequence()?];
+        while self.eat('|') {
+            res.push(self.sequence()?);
+        }
+        Ok(if res.len() == 1 {
+            res.pop().unwrap()
+        } else {
+            Pest::Choice(res)
+        })
+    }
+
+    fn sequence(&mut self) -> Result<Pest, String> {
+        let mut res = vec![self.prefix()?];
+        while self.eat('~') {
+            res.push(self.prefix()?);
+        }
+        Ok(if res.len() == 1 {
+            res.pop().unwrap()
+        } else {
+            Pest::Seq(res)
+        })
+    }
+
+    fn prefix(&mut self) -> Result<Pest, String> {
+        if self.eat('&') {
+            Ok(Pest::Pos(Box::new(self.prefix()?)))
+        } else if self.eat('!') {
+            Ok(Pest::Neg(Box::new(self.prefix()?)))
+        } else {
+            self.postfix()
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Pest, String> {
+        let mut res = self.atom()?;
+        loop {
+            self.skip();
+            res = match self.peek() {
+                Some('?') => Pest::Opt(Box::new(res)),
+                Some('*') => Pest::Star(Box::new(res)),
+                Some('+') => Pest::Plus(Box::new(res)),
+                Some('{') => {
+                    self.pos += 1;
+                    let (min, max) = self.count()?;
+                    self.skip();
+                    if self.peek() != Some('}') {
+                        return Err(self.error("Expected } after repetition count"));
+                    }
+                    Pest::Rep(Box::new(res), min, max)
+                }
+                _ => return Ok(res),
+            };
+            self.pos += 1;
+        }
+    }
+
+    /// Repetition count (`n`, `n,`, `,m` or `n,m`) without the braces.
+    fn count(&mut self) -> Result<(usize, Option<usize>), String> {
+        let min = self.number()?;
+        if self.eat(',') {
+            let max = self.number()?;
+            Ok((min.unwrap_or(0), max))
+        } else {
+            match min {
+                Some(n) => Ok((n, Some(n))),
+                None => Err(self.error("Expected repetition count")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Option<usize>, String> {
+        self.skip();
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        let s: String = self.s[start..self.pos].iter().collect();
+        s.parse()
+            .map(Some)
+            .map_err(|_| self.error("Invalid repetition count"))
+    }
+
+    fn atom(&mut self) -> Result<Pest, String> {
+        self.skip();
+        match self.peek() {
+            Some('"') => Ok(Pest::Str(self.string('"')?)),
+            Some('^') => {
+                // Case-insensitive string; generated as written.
+                self.pos += 1;
+                Ok(Pest::Str(self.string('"')?))
+            }
+            Some('\'') => {
+                let from = self.char()?;
+                self.skip();
+                if self.s[self.pos..].starts_with(&['.', '.']) {
+                    self.pos += 2;
+                    self.skip();
+                    let to = self.char()?;
+                    Ok(Pest::Range(from, to))
+                } else {
+                    Ok(Pest::Str(from.to_string()))
+                }
+            }
+            Some('(') => {
+                self.pos += 1;
+                let res = self.choice()?;
+                self.expect(')')?;
+                Ok(res)
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => Ok(Pest::Ident(self.ident()?)),
+            Some(c) => Err(self.error(&format!("Unexpected character {:?}", c))),
+            None => Err(self.error("Unexpected end of grammar")),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        self.skip();
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("Expected identifier"));
+        }
+        Ok(self.s[start..self.pos].iter().collect())
+    }
+
+    /// String literal delimited by `quote`, with escape sequences.
+    fn string(&mut self, quote: char) -> Result<String, String> {
+        self.expect(quote)?;
+        let mut res = String::new();
+        loop {
+            match self.peek() {
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(res);
+                }
+                Some('\\') => res.push(self.escape()?),
+                Some(c) => {
+                    self.pos += 1;
+                    res.push(c);
+                }
+                None => return Err(self.error("Unterminated literal")),
+            }
+        }
+    }
+
+    /// Character literal.
+    fn char(&mut self) -> Result<char, String> {
+        let s = self.string('\'')?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(self.error("Character literal must contain exactly one character")),
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        self.pos += 1; // Backslash.
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error("Unterminated escape"))?;
+        self.pos += 1;
+        match c {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0' => Ok('\0'),
+            '\\' | '"' | '\'' => Ok(c),
+            'u' => {
+                self.expect('{')?;
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                    self.pos += 1;
+                }
+                let hex: String = self.s[start..self.pos].iter().collect();
+                self.expect('}')?;
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("Invalid unicode escape"))
+            }
+            _ => Err(self.error(&format!("Unknown escape sequence \\{}", c))),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.s.get(self.pos).copied()
+    }
+
+    /// Skip whitespace and comments, then consume the character if it is the
+    /// expected one.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected {:?}", c)))
+        }
+    }
+
+    /// Skip whitespace and comments.
+    fn skip(&mut self) {
+        loop {
+            while self.peek().is_some_and(|c| c.is_whitespace()) {
+                self.pos += 1;
+            }
+            if self.s[self.pos..].starts_with(&['/', '/']) {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if self.s[self.pos..].starts_with(&['/', '*']) {
+                while self.pos < self.s.len() && !self.s[self.pos..].starts_with(&['*', '/']) {
+                    self.pos += 1;
+                }
+                self.pos = std::cmp::min(self.pos + 2, self.s.len());
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Error message with the position (line:column) in the grammar.
+    fn error(&self, msg: &str) -> String {
+        let before = &self.s[..self.pos];
+        let line = before.iter().filter(|c| **c == '\n').count() + 1;
+        let column = self.pos - before.iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1) + 1;
+        format!("{}:{}: {}", line, column, msg)
+    }
+}
+
+/// Characters generated for `ANY` (printable ASCII characters).
+fn any_chars() -> Vec<char> {
+    (0x20u8..0x7f).map(char::from).collect()
+}
+
+/// Builtin rules of pest, as character alternatives.
+fn builtin(name: &str) -> Option<Expr> {
+    let chars: Vec<char> = match name {
+        "ANY" => any_chars(),
+        "ASCII_DIGIT" => ('0'..='9').collect(),
+        "ASCII_NONZERO_DIGIT" => ('1'..='9').collect(),
+        "ASCII_HEX_DIGIT" => ('0'..='9').chain('a'..='f').chain('A'..='F').collect(),
+        "ASCII_ALPHA_LOWER" => ('a'..='z').collect(),
+        "ASCII_ALPHA_UPPER" => ('A'..='Z').collect(),
+        "ASCII_ALPHA" => ('a'..='z').chain('A'..='Z').collect(),
+        "ASCII_ALPHANUMERIC" => ('a'..='z').chain('A'..='Z').chain('0'..='9').collect(),
+        "NEWLINE" => {
+            return Some(Expr::Alt(vec![
+                Expr::T("\n".into()),
+                Expr::T("\r\n".into()),
+            ]))
+        }
+        "SOI" | "EOI" => return Some(Expr::T("".into())),
+        _ => return None,
+    };
+    Some(chars_expr(&chars))
+}
+
+fn chars_expr(chars: &[char]) -> Expr {
+    Expr::Alt(chars.iter().map(|c| Expr::T(c.to_string())).collect())
+}
+
+/// Single characters matched by a (lookahead) expression, if it only consists
+/// of single characters.
+fn single_chars(expr: &Pest) -> Option<Vec<char>> {
+    match expr {
+        Pest::Str(s) if s.chars().count() == 1 => s.chars().next().map(|c| vec![c]),
+        Pest::Range(from, to) => Some((*from..=*to).collect()),
+        Pest::Choice(exprs) => {
+            let mut res = Vec::new();
+            for expr in exprs {
+                res.extend(single_chars(expr)?);
+            }
+            Some(res)
+        }
+        Pest::Ident(name) => match builtin(name) {
+            Some(Expr::Alt(exprs)) if name != "NEWLINE" => Some(
+                exprs
+                    .iter()
+                    .filter_map(|expr| match expr {
+                        Expr::T(s) => s.chars().next(),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Convert a pest expression into an EBNF expression; `ws` is the implicit
+/// whitespace to insert (for non-atomic rules).
+fn convert(expr: &Pest, ws: Option<&Expr>) -> Expr {
+    match expr {
+        Pest::Str(s) => Expr::T(s.clone()),
+        Pest::Range(from, to) => chars_expr(&(*from..=*to).collect::<Vec<_>>()),
+        Pest::Ident(name) => builtin(name).unwrap_or_else(|| Expr::NT(name.clone())),
+
+        Pest::Seq(exprs) => {
+            let mut items = Vec::new();
+            let mut i = 0;
+            while i < exprs.len() {
+                match (&exprs[i], exprs.get(i + 1)) {
+                    // `!e ~ ANY`: any character except the ones of `e`.
+                    (Pest::Neg(excluded), Some(Pest::Ident(any))) if any == "ANY" => {
+                        match single_chars(excluded) {
+                            Some(excluded) => {
+                                let chars: Vec<_> = any_chars()
+                                    .into_iter()
+                                    .filter(|c| !excluded.contains(c))
+                                    .collect();
+                                items.push(chars_expr(&chars));
+                            }
+                            None => items.push(convert(&exprs[i + 1], ws)),
+                        }
+                        i += 2;
+                    }
+                    // Other lookaheads, as well as start and end of input, are
+                    // dropped.
+                    (Pest::Neg(_), _) | (Pest::Pos(_), _) => i += 1,
+                    (Pest::Ident(name), _) if name == "SOI" || name == "EOI" => i += 1,
+                    (expr, _) => {
+                        items.push(convert(expr, ws));
+                        i += 1;
+                    }
+                }
+            }
+            separated(items, ws)
+        }
+
+        Pest::Choice(exprs) => Expr::Alt(exprs.iter().map(|expr| convert(expr, ws)).collect()),
+        Pest::Opt(expr) => Expr::Opt(Box::new(convert(expr, ws))),
+        Pest::Star(expr) => repeat(convert(expr, ws), 0, None, ws),
+        Pest::Plus(expr) => repeat(convert(expr, ws), 1, None, ws),
+        Pest::Rep(expr, min, max) => repeat(convert(expr, ws), *min, *max, ws),
+
+        // Lookahead on its own: matches the empty string.
+        Pest::Pos(_) | Pest::Neg(_) => Expr::T("".to_string()),
+    }
+}
+
+/// Sequence of the items, with implicit whitespace in between.
+fn separated(items: Vec<Expr>, ws: Option<&Expr>) -> Expr {
+    let mut res = Vec::new();
+    for item in items {
+        if let (Some(ws), false) = (ws, res.is_empty()) {
+            res.push(ws.clone());
+        }
+        res.push(item);
+    }
+    match res.len() {
+        0 => Expr::T("".to_string()),
+        1 => res.pop().unwrap(),
+        _ => Expr::Seq(res),
+    }
+}
+
+/// Repetition of an expression between `min` and `max` (unbounded if `None`)
+/// times, with implicit whitespace between the repetitions.
+fn repeat(expr: Expr, min: usize, max: Option<usize>, ws: Option<&Expr>) -> Expr {
+    // Each further repetition (after the first one) is preceded by whitespace.
+    let further = match ws {
+        Some(ws) => Expr::Seq(vec![ws.clone(), expr.clone()]),
+        None => expr.clone(),
+    };
+
+    let mut items: Vec<Expr> = (0..min)
+        .map(|i| {
+            if i == 0 {
+                expr.clone()
+            } else {
+                further.clone()
+            }
+        })
+        .collect();
+    let optional = |first: bool| if first { expr.clone() } else { further.clone() };
+    match max {
+        None if min == 0 => items.push(Expr::Opt(Box::new(Expr::Seq(vec![
+            expr.clone(),
+            Expr::Star(Box::new(further.clone())),
+        ])))),
+        None => items.push(Expr::Star(Box::new(further.clone()))),
+        Some(max) => {
+            for i in min..max {
+                items.push(Expr::Opt(Box::new(optional(i == 0))));
+            }
+        }
+    }
+
+    match items.len() {
+        0 => Expr::T("".to_string()),
+        1 => items.pop().unwrap(),
+        _ => Expr::Seq(items),
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+
+use grammarfuzzer::{expand_tree, GrammarCost, Tree};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    // JSON grammar with the printable characters of strings as one terminal
+    // set, and the same grammar with one expansion per character.
+    let grammar = examplegrammars::json_grammar().to_bnf();
+    let grammar_expanded = grammar.expand_terminal_sets();
+    println!();
+    println!("[+] JSON <character>:");
+    for line in grammar.to_string().lines() {
+        if line.starts_with("<character>") {
+            println!("    {}", line);
+        }
+    }
+
+    println!();
+    println!("[+] With terminal set / with one expansion per character:");
+    let expansions = (grammar.num_expansions(), grammar_expanded.num_expansions());
+    let grammar = GrammarCost::try_from(grammar).unwrap();
+    let grammar_expanded = GrammarCost::try_from(grammar_expanded).unwrap();
+    println!(
+        "    Expansions:         {:4} / {:4}",
+        expansions.0, expansions.1
+    );
+    println!(
+        "    Cost table entries: {:4} / {:4}",
+        grammar.num_costs(),
+        grammar_expanded.num_costs()
+    );
+    println!(
+        "    Escaped characters: {:5.1}% / {:5.1}%",
+        escape_percentage(&mut rng, &grammar),
+        escape_percentage(&mut rng, &grammar_expanded)
+    );
+
+    println!();
+    println!("[+] Generated JSON strings:");
+    for _ in 0..5 {
+        let mut tree = Tree::NT("string".to_string(), Vec::new());
+        expand_tree(&mut rng, &grammar, &mut tree, 5, 20).unwrap();
+        println!("    {}", tree.all_leafs());
+    }
+    println!();
+    println!("[+] Generated JSON:");
+    for min_expansions in [0, 5, 10, 15, 20] {
+        let mut tree = Tree::NT("start".to_string(), Vec::new());
+        expand_tree(&mut rng, &grammar, &mut tree, min_expansions, 40).unwrap();
+        println!("    {}", tree.all_leafs());
+    }
+
+    // Existing BNF grammars can be converted.
+    let grammar = examplegrammars::expr_grammar();
+    let grouped = grammar.group_terminal_sets(5);
+    println!();
+    println!("[+] Expression grammar with grouped terminal sets:");
+    if !cli::quiet() {
+        println!("{}", grouped);
+    }
+    println!(
+        "    Same grammar after expanding the sets again: {}",
+        grouped.expand_terminal_sets() == grammar
+    );
+    if !cli::quiet() {
+        println!();
+        println!("[+] As fuzzingbook grammar:");
+        println!("{}", grouped.to_python_srange());
+    }
+}
+
+/// Percentage of JSON string characters that are escape sequences.
+fn escape_percentage(rng: &mut Rng, grammar: &GrammarCost) -> f64 {
+    let n = cli::iterations(10000);
+    let escapes = (0..n)
+        .filter(|_| {
+            let mut tree = Tree::NT("character".to_string(), Vec::new());
+            expand_tree(rng, grammar, &mut tree, 0, 10).unwrap();
+            tree.all_leafs().starts_with('\\')
+        })
+        .count();
+    100.0 * escapes as f64 / n as f64
+}
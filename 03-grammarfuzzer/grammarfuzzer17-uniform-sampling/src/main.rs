@@ -0,0 +1,95 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+mod uniform;
+
+use std::collections::HashMap;
+
+use grammarfuzzer::Grammar;
+use rng::Rng;
+use uniform::{Selection, TreeCounts};
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    // Binary trees: small enough to check that every tree is generated
+    // equally often.
+    let mut grammar = Grammar::new();
+    grammar.add_production("<start>", &["<tree>"]);
+    grammar.add_production("<tree>", &["(", "<tree>", "<tree>", ")"]);
+    grammar.add_production("<tree>", &["x"]);
+    let counts = TreeCounts::new(&grammar, 5).unwrap();
+    println!();
+    println!(
+        "[+] Binary trees with depth <= 5: {} trees",
+        counts.count("<start>", 5).round()
+    );
+    for selection in [Selection::Uniform, Selection::TreeCounts] {
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+        for _ in 0..26000 {
+            let tree = counts.sample(&mut rng, selection).unwrap();
+            *frequencies.entry(tree.all_leafs()).or_insert(0) += 1;
+        }
+        println!(
+            "    {:10}: {:2} different trees, generated {:5} to {:5} times (\"x\": {})",
+            format!("{:?}", selection),
+            frequencies.len(),
+            frequencies.values().min().unwrap(),
+            frequencies.values().max().unwrap(),
+            frequencies["x"]
+        );
+    }
+
+    // Expressions: uniform choice of expansions mostly generates short
+    // expressions, even with a large depth budget.
+    let grammar = examplegrammars::expr_grammar();
+    let counts = TreeCounts::new(&grammar, 8).unwrap();
+    println!();
+    println!(
+        "[+] Expressions with depth <= 8: about {:.2e} trees",
+        counts.count("<start>", 8)
+    );
+    for selection in [Selection::Uniform, Selection::TreeCounts] {
+        let trees: Vec<_> = (0..cli::iterations(1000))
+            .map(|_| counts.sample(&mut rng, selection).unwrap())
+            .collect();
+        let length: usize = trees.iter().map(|tree| tree.all_leafs().len()).sum();
+        let depth: usize = trees.iter().map(|tree| tree.depth()).sum();
+        println!(
+            "    {:10}: average length {:5.1}, average depth {:4.1}",
+            format!("{:?}", selection),
+            length as f64 / trees.len() as f64,
+            depth as f64 / trees.len() as f64
+        );
+        if !cli::quiet() {
+            for tree in trees.iter().take(4) {
+                println!("        {}", tree.all_leafs());
+            }
+        }
+    }
+
+    // JSON, with the terminal set for characters in strings (one tree per
+    // character).
+    let grammar = examplegrammars::json_grammar().to_bnf();
+    let counts = TreeCounts::new(&grammar, 12).unwrap();
+    println!();
+    println!("[+] JSON with depth <= 12:");
+    for _ in 0..5 {
+        let tree = counts.sample(&mut rng, Selection::TreeCounts).unwrap();
+        println!("    {:?}", tree.all_leafs());
+    }
+
+    println!();
+    println!("[+] Depth limit below the minimum depth:");
+    let counts = TreeCounts::new(&grammar, 3).unwrap();
+    match counts.sample(&mut rng, Selection::TreeCounts) {
+        Ok(tree) => println!("    {}", tree.all_leafs()),
+        Err(err) => println!("    Error: {}", err),
+    }
+}
@@ -0,0 +1,185 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Uniform sampling of derivation trees up to a maximum depth.
+//
+// Choosing uniformly between the expansions of a nonterminal favors small and
+// shallow trees: a non-recursive expansion is chosen as often as a recursive
+// one, although far more trees start with the recursive one. Instead, each
+// expansion can be chosen proportional to the number of different trees that
+// can be derived with it in the remaining depth budget. Then each derivation
+// tree (up to the maximum depth) is generated with the same probability (as
+// in e.g. Grammarinator, or McKenzie: "Generating strings at random from a
+// context free grammar").
+//
+// The number of trees grows doubly exponentially with the depth, so the
+// counts are kept as logarithms (and are approximations for large counts).
+
+use std::collections::HashMap;
+
+use crate::grammarfuzzer::{Expansion, Grammar, GrammarError, Nonterminal, TerminalSet, Tree};
+use crate::rng::Rng;
+
+/// How to choose between the expansions of a nonterminal (among the ones that
+/// fit into the remaining depth budget).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selection {
+    /// Every expansion with the same probability.
+    Uniform,
+
+    /// Proportional to the number of derivable trees (uniform over trees).
+    TreeCounts,
+}
+
+/// Numbers of derivation trees of each nonterminal by maximum depth.
+pub struct TreeCounts<'a> {
+    grammar: &'a Grammar,
+    max_depth: usize,
+
+    /// Natural logarithm of the number of trees with depth at most d (index),
+    /// negative infinity if there are none.
+    counts: HashMap<Nonterminal, Vec<f64>>,
+}
+
+impl<'a> TreeCounts<'a> {
+    /// Count trees up to `max_depth` (the depth of a nonterminal node that
+    /// only has terminal children is 1). Fails if a used nonterminal is not
+    /// defined.
+    pub fn new(grammar: &'a Grammar, max_depth: usize) -> Result<Self, GrammarError> {
+        grammar.trim()?;
+
+        let nonterminals = grammar.nonterminals();
+        let mut counts: HashMap<Nonterminal, Vec<f64>> = nonterminals
+            .iter()
+            .map(|nonterminal| (nonterminal.clone(), vec![f64::NEG_INFINITY]))
+            .collect();
+
+        for depth in 1..=max_depth {
+            for nonterminal in nonterminals.iter() {
+                let count = logsumexp(
+                    &alternatives(grammar, nonterminal)
+                        .iter()
+                        .map(|alternative| log_count(&counts, alternative, depth))
+                        .collect::<Vec<_>>(),
+                );
+                counts.get_mut(nonterminal).unwrap().push(count);
+            }
+        }
+
+        Ok(Self {
+            grammar,
+            max_depth,
+            counts,
+        })
+    }
+
+    /// Approximate number of derivation trees of a nonterminal (with angle
+    /// brackets) with depth at most `depth`.
+    pub fn count(&self, nonterminal: &str, depth: usize) -> f64 {
+        self.counts[nonterminal][depth].exp()
+    }
+
+    /// Generate a random derivation tree of `<start>` with depth at most
+    /// `max_depth`.
+    pub fn sample(&self, rng: &mut Rng, selection: Selection) -> Result<Tree, GrammarError> {
+        if self.counts["<start>"][self.max_depth] == f64::NEG_INFINITY {
+            return Err(GrammarError::NoTreeWithinDepth(self.max_depth));
+        }
+        Ok(self.sample_nonterminal(rng, selection, "<start>", self.max_depth))
+    }
+
+    /// Expand a nonterminal that has at least one tree within the depth.
+    fn sample_nonterminal(
+        &self,
+        rng: &mut Rng,
+        selection: Selection,
+        nonterminal: &str,
+        depth: usize,
+    ) -> Tree {
+        let alternatives = alternatives(self.grammar, nonterminal);
+        let counts: Vec<_> = alternatives
+            .iter()
+            .map(|alternative| log_count(&self.counts, alternative, depth))
+            .collect();
+
+        // Only the expansions that fit into the depth budget.
+        let max = counts.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<_> = counts
+            .iter()
+            .map(|count| match (count.is_finite(), selection) {
+                (false, _) => 0.0,
+                (true, Selection::Uniform) => 1.0,
+                (true, Selection::TreeCounts) => (count - max).exp(),
+            })
+            .collect();
+
+        let children = match rng.choice_w(&alternatives, &weights) {
+            Alternative::Expansion(expansion) => expansion
+                .iter()
+                .map(|symbol| {
+                    if Grammar::is_nonterminal(symbol) {
+                        self.sample_nonterminal(rng, selection, symbol, depth - 1)
+                    } else {
+                        Tree::T(symbol.clone())
+                    }
+                })
+                .collect(),
+            Alternative::Set(set) => vec![Tree::T(set.sample(rng).to_string())],
+        };
+        Tree::NT(
+            Grammar::trim_angle_brackets(nonterminal).to_string(),
+            children,
+        )
+    }
+}
+
+/// Expansion of a nonterminal, or its terminal set.
+enum Alternative<'a> {
+    Expansion(&'a Expansion),
+    Set(&'a TerminalSet),
+}
+
+fn alternatives<'a>(grammar: &'a Grammar, nonterminal: &str) -> Vec<Alternative<'a>> {
+    grammar
+        .expansions(nonterminal)
+        .iter()
+        .map(Alternative::Expansion)
+        .chain(grammar.terminal_set(nonterminal).map(Alternative::Set))
+        .collect()
+}
+
+/// Logarithm of the number of trees of a nonterminal node with the given
+/// alternative and depth at most `depth`: the product of the numbers of trees
+/// of the nonterminals in the expansion with depth at most `depth - 1` (a
+/// terminal set has one tree per character).
+fn log_count(
+    counts: &HashMap<Nonterminal, Vec<f64>>,
+    alternative: &Alternative,
+    depth: usize,
+) -> f64 {
+    if depth == 0 {
+        return f64::NEG_INFINITY;
+    }
+    match alternative {
+        Alternative::Expansion(expansion) => expansion
+            .iter()
+            .filter(|symbol| Grammar::is_nonterminal(symbol))
+            .map(|symbol| counts[symbol.as_str()][depth - 1])
+            .sum(),
+        Alternative::Set(set) => (set.len() as f64).ln(),
+    }
+}
+
+/// Logarithm of the sum of the exponentials (without overflow).
+fn logsumexp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values
+        .iter()
+        .map(|value| (value - max).exp())
+        .sum::<f64>()
+        .ln()
+}
@@ -0,0 +1,423 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Generate Rust data types for the derivation trees of a grammar: one enum per
+// nonterminal, with one variant per expansion, whose fields are the
+// nonterminals of the expansion (the terminals are implied by the variant).
+// Each type gets a random constructor `arbitrary(rng, depth)` and a `Display`
+// implementation that unparses it (`to_string()`).
+//
+// Compared to the string-labeled `Tree`, the generated types can be
+// post-processed with ordinary pattern matching, e.g. for evaluating an
+// expression or rewriting parts of a generated input, and the compiler checks
+// that only valid trees are constructed.
+//
+// For `<expr> -> <term> "+" <expr> | <term>`:
+//
+// ```rust
+// pub enum Expr {
+//     TermPlusExpr(Box<Term>, Box<Expr>),
+//     Term(Box<Term>),
+// }
+// ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::grammarfuzzer::{Expansion, Grammar, GrammarError};
+
+/// Rust source code of a module with the types for all nonterminals reachable
+/// from `<start>`. The module uses `crate::rng::Rng` for the random
+/// constructors. Ebnf grammars can be converted with `Ebnf::to_bnf` first.
+pub fn rust(grammar: &Grammar) -> Result<String, GrammarError> {
+    let grammar = grammar.trim()?;
+    let nonterminals = grammar.nonterminals();
+    let types = type_names(&nonterminals);
+    let depths = min_depths(&grammar)?;
+
+    let mut res = String::new();
+    res += "// Generated from the grammar below by `codegen::rust`, don't edit.\n";
+    res += "//\n";
+    let width = nonterminals.iter().map(|n| n.len()).max().unwrap_or(0);
+    for nonterminal in nonterminals.iter() {
+        // Like `Grammar`'s `Display`, but with escaped terminals (which may
+        // contain newlines).
+        let expansions: Vec<_> = grammar
+            .expansions(nonterminal)
+            .iter()
+            .map(|expansion| {
+                expansion
+                    .iter()
+                    .map(|symbol| match Grammar::is_nonterminal(symbol) {
+                        true => symbol.clone(),
+                        false => format!("{:?}", symbol),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .chain(grammar.terminal_set(nonterminal).map(|set| set.to_string()))
+            .collect();
+        res += &format!("// {:width$} -> {}\n", nonterminal, expansions.join(" | "));
+    }
+    res += "\n";
+    res += "#![allow(dead_code, clippy::enum_variant_names)]\n\n";
+    res += "use crate::rng::Rng;\n";
+
+    for nonterminal in nonterminals.iter() {
+        let name = &types[nonterminal];
+        let variants = variants(&grammar, nonterminal, &types, &depths);
+
+        // Type.
+        res += &format!("\n/// `{}`\n", nonterminal);
+        res += "#[derive(Clone, Debug, PartialEq, Eq)]\n";
+        res += &format!("pub enum {} {{\n", name);
+        for variant in variants.iter() {
+            match variant.fields().as_slice() {
+                [] => res += &format!("    {},\n", variant.name),
+                fields => res += &format!("    {}({}),\n", variant.name, fields.join(", ")),
+            }
+        }
+        res += "}\n";
+
+        // Random constructor.
+        res += &format!("\nimpl {} {{\n", name);
+        res += "    /// Minimum depth of a derivation tree (in nonterminals).\n";
+        res += &format!(
+            "    pub const MIN_DEPTH: usize = {};\n\n",
+            depths[nonterminal]
+        );
+        res += "    /// Random value with a derivation tree of depth at most `depth` (or\n";
+        res += "    /// `MIN_DEPTH`, if that is larger). The variants that fit into the\n";
+        res += "    /// depth are chosen uniformly.\n";
+        res += "    pub fn arbitrary(rng: &mut Rng, depth: usize) -> Self {\n";
+        res += "        let depth = depth.max(Self::MIN_DEPTH);\n";
+        res += &format!(
+            "        let depths = [{}];\n",
+            variants
+                .iter()
+                .map(|variant| variant.depth.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        res += "        let choices: Vec<usize> = (0..depths.len()).filter(|&i| depths[i] <= depth).collect();\n";
+        res += "        match *rng.choice(&choices) {\n";
+        for (i, variant) in variants.iter().enumerate() {
+            res += &format!("            {} => {},\n", i, variant.constructor(name));
+        }
+        res += "            _ => panic!(\"Can't happen\"),\n";
+        res += "        }\n";
+        res += "    }\n";
+        res += "}\n";
+
+        // Unparser.
+        res += &format!("\nimpl std::fmt::Display for {} {{\n", name);
+        res += "    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {\n";
+        res += "        match self {\n";
+        for variant in variants.iter() {
+            res += &format!("            {},\n", variant.unparser(name));
+        }
+        res += "        }\n";
+        res += "    }\n";
+        res += "}\n";
+    }
+    Ok(res)
+}
+
+/// One symbol of an expansion, as far as the generated code is concerned.
+enum Symbol {
+    Terminal(String),
+    /// Type name of the nonterminal.
+    Nonterminal(String),
+}
+
+/// Variant of the enum of a nonterminal.
+struct Variant {
+    name: String,
+    symbols: Vec<Symbol>,
+    /// Characters of the terminal set, for the variant of the terminal set
+    /// (with a `char` field instead of symbols).
+    chars: Option<String>,
+    /// Minimum depth of the derivation trees starting with this variant.
+    depth: usize,
+}
+
+impl Variant {
+    fn fields(&self) -> Vec<String> {
+        if self.chars.is_some() {
+            return vec!["char".to_string()];
+        }
+        self.symbols
+            .iter()
+            .filter_map(|symbol| match symbol {
+                Symbol::Terminal(_) => None,
+                Symbol::Nonterminal(name) => Some(format!("Box<{}>", name)),
+            })
+            .collect()
+    }
+
+    /// Expression constructing a random value of this variant.
+    fn constructor(&self, type_name: &str) -> String {
+        if let Some(chars) = &self.chars {
+            return format!(
+                "{}::{}(*rng.choice(&{:?}.chars().collect::<Vec<_>>()))",
+                type_name, self.name, chars
+            );
+        }
+        let fields: Vec<_> = self
+            .symbols
+            .iter()
+            .filter_map(|symbol| match symbol {
+                Symbol::Terminal(_) => None,
+                Symbol::Nonterminal(name) => {
+                    Some(format!("Box::new({}::arbitrary(rng, depth - 1))", name))
+                }
+            })
+            .collect();
+        match fields.as_slice() {
+            [] => format!("{}::{}", type_name, self.name),
+            _ => format!("{}::{}({})", type_name, self.name, fields.join(", ")),
+        }
+    }
+
+    /// Match arm writing the string of this variant.
+    fn unparser(&self, type_name: &str) -> String {
+        if self.chars.is_some() {
+            return format!("{}::{}(c) => write!(f, \"{{}}\", c)", type_name, self.name);
+        }
+        let mut text = String::new();
+        let mut format = String::new();
+        let mut fields = Vec::new();
+        for symbol in self.symbols.iter() {
+            match symbol {
+                Symbol::Terminal(terminal) => {
+                    text += terminal;
+                    format += &terminal.replace('{', "{{").replace('}', "}}");
+                }
+                Symbol::Nonterminal(_) => {
+                    format += "{}";
+                    fields.push(format!("x{}", fields.len()));
+                }
+            }
+        }
+        let pattern = match fields.as_slice() {
+            [] => format!("{}::{}", type_name, self.name),
+            _ => format!("{}::{}({})", type_name, self.name, fields.join(", ")),
+        };
+        if format.is_empty() {
+            format!("{} => Ok(())", pattern)
+        } else if fields.is_empty() {
+            format!("{} => f.write_str({:?})", pattern, text)
+        } else {
+            format!(
+                "{} => write!(f, {:?}, {})",
+                pattern,
+                format,
+                fields.join(", ")
+            )
+        }
+    }
+}
+
+fn variants(
+    grammar: &Grammar,
+    nonterminal: &str,
+    types: &BTreeMap<String, String>,
+    depths: &BTreeMap<String, usize>,
+) -> Vec<Variant> {
+    let mut res: Vec<Variant> = Vec::new();
+    let mut used = BTreeSet::new();
+    for (i, expansion) in grammar.expansions(nonterminal).iter().enumerate() {
+        let symbols: Vec<_> = expansion
+            .iter()
+            .filter(|symbol| !symbol.is_empty())
+            .map(|symbol| match Grammar::is_nonterminal(symbol) {
+                true => Symbol::Nonterminal(types[symbol].clone()),
+                false => Symbol::Terminal(symbol.clone()),
+            })
+            .collect();
+        let depth = expansion
+            .iter()
+            .filter(|symbol| Grammar::is_nonterminal(symbol))
+            .map(|symbol| depths[symbol])
+            .max()
+            .unwrap_or(0)
+            + 1;
+        res.push(Variant {
+            name: unique(variant_name(expansion, types, i), &mut used),
+            symbols,
+            chars: None,
+            depth,
+        });
+    }
+    if let Some(set) = grammar.terminal_set(nonterminal) {
+        res.push(Variant {
+            name: unique("Char".to_string(), &mut used),
+            symbols: Vec::new(),
+            chars: Some(set.chars().iter().collect()),
+            depth: 1,
+        });
+    }
+    res
+}
+
+/// Name of the variant of an expansion, from the names of its symbols (e.g.
+/// `TermPlusExpr` for `<term> "+" <expr>`). Long names are replaced with
+/// `Alt<i>`.
+fn variant_name(expansion: &Expansion, types: &BTreeMap<String, String>, i: usize) -> String {
+    let mut name = String::new();
+    for symbol in expansion.iter() {
+        if Grammar::is_nonterminal(symbol) {
+            name += &types[symbol];
+        } else if symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+            name += &camel_case(symbol);
+        } else {
+            for c in symbol.chars() {
+                name += &char_name(c);
+            }
+        }
+    }
+    if name.is_empty() {
+        name = "Empty".to_string();
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name = format!("T{}", name);
+    }
+    if name.len() > 32 {
+        name = format!("Alt{}", i);
+    }
+    name
+}
+
+fn char_name(c: char) -> String {
+    let name = match c {
+        '+' => "Plus",
+        '-' => "Minus",
+        '*' => "Star",
+        '/' => "Slash",
+        '\\' => "Backslash",
+        '(' => "LParen",
+        ')' => "RParen",
+        '[' => "LBracket",
+        ']' => "RBracket",
+        '{' => "LBrace",
+        '}' => "RBrace",
+        '<' => "Lt",
+        '>' => "Gt",
+        '=' => "Eq",
+        '.' => "Dot",
+        ',' => "Comma",
+        ':' => "Colon",
+        ';' => "Semi",
+        '"' => "Quote",
+        '\'' => "Apostrophe",
+        '`' => "Backtick",
+        '!' => "Bang",
+        '?' => "Question",
+        '%' => "Percent",
+        '&' => "Amp",
+        '|' => "Pipe",
+        '^' => "Caret",
+        '~' => "Tilde",
+        '#' => "Hash",
+        '@' => "At",
+        '$' => "Dollar",
+        '_' => "Underscore",
+        ' ' => "Space",
+        '\t' => "Tab",
+        '\n' => "Newline",
+        '\r' => "Cr",
+        c if c.is_ascii_alphanumeric() => return c.to_ascii_uppercase().to_string(),
+        c => return format!("U{:X}", c as u32),
+    };
+    name.to_string()
+}
+
+/// `json-value` -> `JsonValue`.
+fn camel_case(s: &str) -> String {
+    s.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+/// Append a number to `name` if it is already used.
+fn unique(name: String, used: &mut BTreeSet<String>) -> String {
+    let mut res = name.clone();
+    let mut i = 1;
+    while used.contains(&res) {
+        res = format!("{}{}", name, i);
+        i += 1;
+    }
+    used.insert(res.clone());
+    res
+}
+
+/// Type names of the nonterminals (`<json-value>` -> `JsonValue`), avoiding
+/// names that clash with the types used in the generated code.
+fn type_names(nonterminals: &BTreeSet<String>) -> BTreeMap<String, String> {
+    let mut used: BTreeSet<String> = ["Self", "Box", "Vec", "String", "Option", "Result", "Rng"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let mut res = BTreeMap::new();
+    for nonterminal in nonterminals.iter() {
+        let mut name = camel_case(Grammar::trim_angle_brackets(nonterminal));
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            name = format!("N{}", name);
+        }
+        if used.contains(&name) {
+            name += "Node";
+        }
+        res.insert(nonterminal.clone(), unique(name, &mut used));
+    }
+    res
+}
+
+/// Minimum depth of the derivation trees of each nonterminal (a nonterminal
+/// that expands into terminals only has depth 1), by fixpoint iteration like
+/// the minimum cost.
+fn min_depths(grammar: &Grammar) -> Result<BTreeMap<String, usize>, GrammarError> {
+    let nonterminals = grammar.nonterminals();
+    let mut depths: BTreeMap<String, usize> = BTreeMap::new();
+    loop {
+        let mut changed = false;
+        for nonterminal in nonterminals.iter() {
+            let set = grammar.terminal_set(nonterminal).map(|_| 1);
+            let depth = grammar
+                .expansions(nonterminal)
+                .iter()
+                .filter_map(|expansion| {
+                    expansion
+                        .iter()
+                        .filter(|symbol| Grammar::is_nonterminal(symbol))
+                        .map(|symbol| depths.get(symbol).copied())
+                        .try_fold(0, |max, depth| Some(max.max(depth?)))
+                        .map(|max| max + 1)
+                })
+                .chain(set)
+                .min();
+            if let Some(depth) = depth {
+                if depths.get(nonterminal) != Some(&depth) {
+                    depths.insert(nonterminal.clone(), depth);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let nonterminating: Vec<_> = nonterminals
+        .into_iter()
+        .filter(|nonterminal| !depths.contains_key(nonterminal))
+        .collect();
+    if !nonterminating.is_empty() {
+        return Err(GrammarError::NonTerminating(nonterminating));
+    }
+    Ok(depths)
+}
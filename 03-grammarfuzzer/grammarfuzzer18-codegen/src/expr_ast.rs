@@ -0,0 +1,258 @@
+// Generated from the grammar below by `codegen::rust`, don't edit.
+//
+// <digit>   -> "0" | "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9"
+// <expr>    -> <term> "+" <expr> | <term> "-" <expr> | <term>
+// <factor>  -> "+" <factor> | "-" <factor> | "(" <expr> ")" | <integer> "." <integer> | <integer>
+// <integer> -> <digit> <integer> | <digit>
+// <start>   -> <expr>
+// <term>    -> <factor> "*" <term> | <factor> "/" <term> | <factor>
+
+#![allow(dead_code, clippy::enum_variant_names)]
+
+use crate::rng::Rng;
+
+/// `<digit>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Digit {
+    T0,
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+}
+
+impl Digit {
+    /// Minimum depth of a derivation tree (in nonterminals).
+    pub const MIN_DEPTH: usize = 1;
+
+    /// Random value with a derivation tree of depth at most `depth` (or
+    /// `MIN_DEPTH`, if that is larger). The variants that fit into the
+    /// depth are chosen uniformly.
+    pub fn arbitrary(rng: &mut Rng, depth: usize) -> Self {
+        let depth = depth.max(Self::MIN_DEPTH);
+        let depths = [1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        let choices: Vec<usize> = (0..depths.len()).filter(|&i| depths[i] <= depth).collect();
+        match *rng.choice(&choices) {
+            0 => Digit::T0,
+            1 => Digit::T1,
+            2 => Digit::T2,
+            3 => Digit::T3,
+            4 => Digit::T4,
+            5 => Digit::T5,
+            6 => Digit::T6,
+            7 => Digit::T7,
+            8 => Digit::T8,
+            9 => Digit::T9,
+            _ => panic!("Can't happen"),
+        }
+    }
+}
+
+impl std::fmt::Display for Digit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Digit::T0 => f.write_str("0"),
+            Digit::T1 => f.write_str("1"),
+            Digit::T2 => f.write_str("2"),
+            Digit::T3 => f.write_str("3"),
+            Digit::T4 => f.write_str("4"),
+            Digit::T5 => f.write_str("5"),
+            Digit::T6 => f.write_str("6"),
+            Digit::T7 => f.write_str("7"),
+            Digit::T8 => f.write_str("8"),
+            Digit::T9 => f.write_str("9"),
+        }
+    }
+}
+
+/// `<expr>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    TermPlusExpr(Box<Term>, Box<Expr>),
+    TermMinusExpr(Box<Term>, Box<Expr>),
+    Term(Box<Term>),
+}
+
+impl Expr {
+    /// Minimum depth of a derivation tree (in nonterminals).
+    pub const MIN_DEPTH: usize = 5;
+
+    /// Random value with a derivation tree of depth at most `depth` (or
+    /// `MIN_DEPTH`, if that is larger). The variants that fit into the
+    /// depth are chosen uniformly.
+    pub fn arbitrary(rng: &mut Rng, depth: usize) -> Self {
+        let depth = depth.max(Self::MIN_DEPTH);
+        let depths = [6, 6, 5];
+        let choices: Vec<usize> = (0..depths.len()).filter(|&i| depths[i] <= depth).collect();
+        match *rng.choice(&choices) {
+            0 => Expr::TermPlusExpr(Box::new(Term::arbitrary(rng, depth - 1)), Box::new(Expr::arbitrary(rng, depth - 1))),
+            1 => Expr::TermMinusExpr(Box::new(Term::arbitrary(rng, depth - 1)), Box::new(Expr::arbitrary(rng, depth - 1))),
+            2 => Expr::Term(Box::new(Term::arbitrary(rng, depth - 1))),
+            _ => panic!("Can't happen"),
+        }
+    }
+}
+
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Expr::TermPlusExpr(x0, x1) => write!(f, "{}+{}", x0, x1),
+            Expr::TermMinusExpr(x0, x1) => write!(f, "{}-{}", x0, x1),
+            Expr::Term(x0) => write!(f, "{}", x0),
+        }
+    }
+}
+
+/// `<factor>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Factor {
+    PlusFactor(Box<Factor>),
+    MinusFactor(Box<Factor>),
+    LParenExprRParen(Box<Expr>),
+    IntegerDotInteger(Box<Integer>, Box<Integer>),
+    Integer(Box<Integer>),
+}
+
+impl Factor {
+    /// Minimum depth of a derivation tree (in nonterminals).
+    pub const MIN_DEPTH: usize = 3;
+
+    /// Random value with a derivation tree of depth at most `depth` (or
+    /// `MIN_DEPTH`, if that is larger). The variants that fit into the
+    /// depth are chosen uniformly.
+    pub fn arbitrary(rng: &mut Rng, depth: usize) -> Self {
+        let depth = depth.max(Self::MIN_DEPTH);
+        let depths = [4, 4, 6, 3, 3];
+        let choices: Vec<usize> = (0..depths.len()).filter(|&i| depths[i] <= depth).collect();
+        match *rng.choice(&choices) {
+            0 => Factor::PlusFactor(Box::new(Factor::arbitrary(rng, depth - 1))),
+            1 => Factor::MinusFactor(Box::new(Factor::arbitrary(rng, depth - 1))),
+            2 => Factor::LParenExprRParen(Box::new(Expr::arbitrary(rng, depth - 1))),
+            3 => Factor::IntegerDotInteger(Box::new(Integer::arbitrary(rng, depth - 1)), Box::new(Integer::arbitrary(rng, depth - 1))),
+            4 => Factor::Integer(Box::new(Integer::arbitrary(rng, depth - 1))),
+            _ => panic!("Can't happen"),
+        }
+    }
+}
+
+impl std::fmt::Display for Factor {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Factor::PlusFactor(x0) => write!(f, "+{}", x0),
+            Factor::MinusFactor(x0) => write!(f, "-{}", x0),
+            Factor::LParenExprRParen(x0) => write!(f, "({})", x0),
+            Factor::IntegerDotInteger(x0, x1) => write!(f, "{}.{}", x0, x1),
+            Factor::Integer(x0) => write!(f, "{}", x0),
+        }
+    }
+}
+
+/// `<integer>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Integer {
+    DigitInteger(Box<Digit>, Box<Integer>),
+    Digit(Box<Digit>),
+}
+
+impl Integer {
+    /// Minimum depth of a derivation tree (in nonterminals).
+    pub const MIN_DEPTH: usize = 2;
+
+    /// Random value with a derivation tree of depth at most `depth` (or
+    /// `MIN_DEPTH`, if that is larger). The variants that fit into the
+    /// depth are chosen uniformly.
+    pub fn arbitrary(rng: &mut Rng, depth: usize) -> Self {
+        let depth = depth.max(Self::MIN_DEPTH);
+        let depths = [3, 2];
+        let choices: Vec<usize> = (0..depths.len()).filter(|&i| depths[i] <= depth).collect();
+        match *rng.choice(&choices) {
+            0 => Integer::DigitInteger(Box::new(Digit::arbitrary(rng, depth - 1)), Box::new(Integer::arbitrary(rng, depth - 1))),
+            1 => Integer::Digit(Box::new(Digit::arbitrary(rng, depth - 1))),
+            _ => panic!("Can't happen"),
+        }
+    }
+}
+
+impl std::fmt::Display for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Integer::DigitInteger(x0, x1) => write!(f, "{}{}", x0, x1),
+            Integer::Digit(x0) => write!(f, "{}", x0),
+        }
+    }
+}
+
+/// `<start>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Start {
+    Expr(Box<Expr>),
+}
+
+impl Start {
+    /// Minimum depth of a derivation tree (in nonterminals).
+    pub const MIN_DEPTH: usize = 6;
+
+    /// Random value with a derivation tree of depth at most `depth` (or
+    /// `MIN_DEPTH`, if that is larger). The variants that fit into the
+    /// depth are chosen uniformly.
+    pub fn arbitrary(rng: &mut Rng, depth: usize) -> Self {
+        let depth = depth.max(Self::MIN_DEPTH);
+        let depths = [6];
+        let choices: Vec<usize> = (0..depths.len()).filter(|&i| depths[i] <= depth).collect();
+        match *rng.choice(&choices) {
+            0 => Start::Expr(Box::new(Expr::arbitrary(rng, depth - 1))),
+            _ => panic!("Can't happen"),
+        }
+    }
+}
+
+impl std::fmt::Display for Start {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Start::Expr(x0) => write!(f, "{}", x0),
+        }
+    }
+}
+
+/// `<term>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+    FactorStarTerm(Box<Factor>, Box<Term>),
+    FactorSlashTerm(Box<Factor>, Box<Term>),
+    Factor(Box<Factor>),
+}
+
+impl Term {
+    /// Minimum depth of a derivation tree (in nonterminals).
+    pub const MIN_DEPTH: usize = 4;
+
+    /// Random value with a derivation tree of depth at most `depth` (or
+    /// `MIN_DEPTH`, if that is larger). The variants that fit into the
+    /// depth are chosen uniformly.
+    pub fn arbitrary(rng: &mut Rng, depth: usize) -> Self {
+        let depth = depth.max(Self::MIN_DEPTH);
+        let depths = [5, 5, 4];
+        let choices: Vec<usize> = (0..depths.len()).filter(|&i| depths[i] <= depth).collect();
+        match *rng.choice(&choices) {
+            0 => Term::FactorStarTerm(Box::new(Factor::arbitrary(rng, depth - 1)), Box::new(Term::arbitrary(rng, depth - 1))),
+            1 => Term::FactorSlashTerm(Box::new(Factor::arbitrary(rng, depth - 1)), Box::new(Term::arbitrary(rng, depth - 1))),
+            2 => Term::Factor(Box::new(Factor::arbitrary(rng, depth - 1))),
+            _ => panic!("Can't happen"),
+        }
+    }
+}
+
+impl std::fmt::Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Term::FactorStarTerm(x0, x1) => write!(f, "{}*{}", x0, x1),
+            Term::FactorSlashTerm(x0, x1) => write!(f, "{}/{}", x0, x1),
+            Term::Factor(x0) => write!(f, "{}", x0),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod codegen;
+mod examplegrammars;
+#[rustfmt::skip]
+mod expr_ast;
+mod grammarfuzzer;
+mod rng;
+
+use expr_ast::{Expr, Factor, Start, Term};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    // The types for the expression grammar are generated into
+    // src/expr_ast.rs (with `--write`), and used below.
+    let code = codegen::rust(&examplegrammars::expr_grammar()).unwrap();
+    println!();
+    if cli::flag("--write") {
+        std::fs::write("src/expr_ast.rs", &code).unwrap();
+        println!("[+] Wrote src/expr_ast.rs, rebuild to use it");
+        return;
+    }
+    match std::fs::read_to_string("src/expr_ast.rs") {
+        Ok(existing) if existing == code => println!("[+] src/expr_ast.rs is up to date"),
+        _ => println!("[!] src/expr_ast.rs is outdated, regenerate it with --write"),
+    }
+
+    // Random expressions, post-processed programmatically: evaluated, and
+    // rewritten to avoid division.
+    println!();
+    println!("[+] Random expressions with depth <= 9:");
+    for _ in 0..cli::iterations(5) {
+        let Start::Expr(expr) = Start::arbitrary(&mut rng, 9);
+        println!("    {} = {}", expr, eval_expr(&expr));
+        let rewritten = without_division_expr(&expr);
+        if rewritten != *expr {
+            println!("        without division: {}", rewritten);
+        }
+    }
+
+    // EBNF grammars are converted to BNF first.
+    let code = codegen::rust(&examplegrammars::json_grammar().to_bnf()).unwrap();
+    println!();
+    println!(
+        "[+] JSON grammar: {} types, {} lines of code",
+        code.matches("\npub enum ").count(),
+        code.lines().count()
+    );
+    if !cli::quiet() {
+        let start = code.find("pub enum Value ").unwrap();
+        let end = start + code[start..].find("}\n").unwrap();
+        for line in code[start..=end].lines() {
+            println!("    {}", line);
+        }
+    }
+}
+
+fn eval_expr(expr: &Expr) -> f64 {
+    match expr {
+        Expr::TermPlusExpr(term, expr) => eval_term(term) + eval_expr(expr),
+        Expr::TermMinusExpr(term, expr) => eval_term(term) - eval_expr(expr),
+        Expr::Term(term) => eval_term(term),
+    }
+}
+
+fn eval_term(term: &Term) -> f64 {
+    match term {
+        Term::FactorStarTerm(factor, term) => eval_factor(factor) * eval_term(term),
+        Term::FactorSlashTerm(factor, term) => eval_factor(factor) / eval_term(term),
+        Term::Factor(factor) => eval_factor(factor),
+    }
+}
+
+fn eval_factor(factor: &Factor) -> f64 {
+    match factor {
+        Factor::PlusFactor(factor) => eval_factor(factor),
+        Factor::MinusFactor(factor) => -eval_factor(factor),
+        Factor::LParenExprRParen(expr) => eval_expr(expr),
+        Factor::IntegerDotInteger(integer, fraction) => {
+            format!("{}.{}", integer, fraction).parse().unwrap()
+        }
+        Factor::Integer(integer) => integer.to_string().parse().unwrap(),
+    }
+}
+
+/// Replace every `/` with `*` (e.g. to avoid divisions by zero in generated
+/// test inputs).
+fn without_division_expr(expr: &Expr) -> Expr {
+    let term = |term: &Term| Box::new(without_division_term(term));
+    match expr {
+        Expr::TermPlusExpr(t, e) => Expr::TermPlusExpr(term(t), Box::new(without_division_expr(e))),
+        Expr::TermMinusExpr(t, e) => {
+            Expr::TermMinusExpr(term(t), Box::new(without_division_expr(e)))
+        }
+        Expr::Term(t) => Expr::Term(term(t)),
+    }
+}
+
+fn without_division_term(term: &Term) -> Term {
+    let factor = |factor: &Factor| Box::new(without_division_factor(factor));
+    match term {
+        Term::FactorStarTerm(f, t) | Term::FactorSlashTerm(f, t) => {
+            Term::FactorStarTerm(factor(f), Box::new(without_division_term(t)))
+        }
+        Term::Factor(f) => Term::Factor(factor(f)),
+    }
+}
+
+fn without_division_factor(factor: &Factor) -> Factor {
+    match factor {
+        Factor::PlusFactor(f) => Factor::PlusFactor(Box::new(without_division_factor(f))),
+        Factor::MinusFactor(f) => Factor::MinusFactor(Box::new(without_division_factor(f))),
+        Factor::LParenExprRParen(e) => Factor::LParenExprRParen(Box::new(without_division_expr(e))),
+        Factor::IntegerDotInteger(_, _) | Factor::Integer(_) => factor.clone(),
+    }
+}
@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+mod unstructured;
+
+use std::collections::BTreeSet;
+
+use grammarfuzzer::{fuzz_tree_from, GrammarCost};
+use rng::Rng;
+use unstructured::Unstructured;
+
+/// Maximum number of expansions driven by the input bytes.
+const MAX_EXPANSIONS: usize = 200;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let expr_grammar = GrammarCost::try_from(examplegrammars::expr_grammar()).unwrap();
+    let json_grammar = GrammarCost::try_from(examplegrammars::json_grammar().to_bnf()).unwrap();
+
+    // What a cargo-fuzz target would do with the bytes it gets.
+    let generate = |grammar: &GrammarCost, data: &[u8]| {
+        let mut u = Unstructured::new(data);
+        fuzz_tree_from(&mut u, grammar, MAX_EXPANSIONS).all_leafs()
+    };
+
+    // Any byte string gives a valid input; more bytes give larger inputs.
+    println!();
+    println!("[+] Expressions from random byte strings:");
+    for len in [0, 4, 8, 16, 32] {
+        let data = rng.bytes(len);
+        println!("    {:2} bytes: {}", len, generate(&expr_grammar, &data));
+    }
+    println!();
+    println!("[+] JSON from random byte strings:");
+    for len in [0, 8, 16, 32, 64] {
+        let data = rng.bytes(len);
+        println!("    {:2} bytes: {:?}", len, generate(&json_grammar, &data));
+    }
+
+    // The same bytes always give the same input (libFuzzer relies on this
+    // for reproducing crashes), and mutating a byte mostly changes a part.
+    // Only the first bytes are mutated here, later ones are often unused.
+    let data = rng.bytes(64);
+    let parent = generate(&json_grammar, &data);
+    assert_eq!(parent, generate(&json_grammar, &data));
+    println!();
+    println!("[+] Mutants of {:?} (one byte changed):", parent);
+    for _ in 0..5 {
+        let mut mutant = data.clone();
+        let i = rng.int(8) as usize;
+        mutant[i] = rng.int(256) as u8;
+        if !cli::quiet() {
+            println!("    byte {}: {:?}", i, generate(&json_grammar, &mutant));
+        }
+    }
+
+    // How diverse are the inputs from random bytes?
+    let n = cli::iterations(10000);
+    let inputs: Vec<_> = (0..n)
+        .map(|_| {
+            let len = rng.int(65);
+            generate(&json_grammar, &rng.bytes(len))
+        })
+        .collect();
+    let distinct: BTreeSet<_> = inputs.iter().collect();
+    println!();
+    println!(
+        "[+] {} random byte strings (0-64 bytes): {} distinct JSON inputs, average length {:.1}",
+        n,
+        distinct.len(),
+        inputs.iter().map(|input| input.len()).sum::<usize>() as f64 / inputs.len() as f64
+    );
+}
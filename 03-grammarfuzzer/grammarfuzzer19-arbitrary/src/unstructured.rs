@@ -0,0 +1,82 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Grammar-based generation driven by the raw bytes of a fuzzer input, like the
+// `arbitrary` crate does for Rust types: cargo-fuzz (libFuzzer) only produces
+// byte strings, and `arbitrary::Unstructured` turns them into structured
+// values by consuming a few bytes per decision. With `fuzz_tree_from`, the
+// grammars of this crate can be used the same way, as structured input
+// generators in cargo-fuzz targets.
+//
+// `ChoiceSource` is the interface the grammar expansion needs. `Unstructured`
+// implements it for a byte slice with the same conventions as
+// `arbitrary::Unstructured` (which isn't available here, since the crates of
+// this repo don't have dependencies). In a cargo-fuzz target, the trait can
+// be implemented for `arbitrary::Unstructured` directly:
+//
+// ```rust
+// impl ChoiceSource for arbitrary::Unstructured<'_> {
+//     fn choose_index(&mut self, len: usize) -> usize {
+//         arbitrary::Unstructured::choose_index(self, len).unwrap_or(0)
+//     }
+//     fn is_empty(&self) -> bool {
+//         arbitrary::Unstructured::is_empty(self)
+//     }
+// }
+// ```
+
+/// Source of the decisions during grammar expansion.
+pub trait ChoiceSource {
+    /// Index of the chosen one of `len` options (`len > 0`).
+    fn choose_index(&mut self, len: usize) -> usize;
+
+    /// Whether there are no more decisions (all further choices would be 0).
+    fn is_empty(&self) -> bool;
+}
+
+/// Decisions taken from a byte string, like `arbitrary::Unstructured`: each
+/// choice consumes as many bytes as needed to cover the number of options
+/// (none if there is only one, one for up to 256 options, and so on), read as
+/// big-endian integer modulo the number of options. Once the bytes are used
+/// up, all choices are 0.
+pub struct Unstructured<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Unstructured<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Integer in the range `min..=max`.
+    pub fn int_in_range(&mut self, min: usize, max: usize) -> usize {
+        assert!(min <= max, "Empty range");
+        let range = (max - min) as u64;
+        let mut value: u64 = 0;
+        let mut covered: u64 = 0;
+        while covered < range {
+            let Some((&byte, rest)) = self.data.split_first() else {
+                break;
+            };
+            self.data = rest;
+            value = value << 8 | byte as u64;
+            covered = covered << 8 | 0xff;
+        }
+        min + match range.checked_add(1) {
+            Some(n) => (value % n) as usize,
+            None => value as usize,
+        }
+    }
+}
+
+impl ChoiceSource for Unstructured<'_> {
+    fn choose_index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "Choice between no options");
+        self.int_in_range(0, len - 1)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
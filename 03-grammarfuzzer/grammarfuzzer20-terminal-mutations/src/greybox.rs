@@ -0,0 +1,328 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Coverage-guided grammar fuzzing: the greybox loop of 02-greyboxfuzzer, but
+// operating on derivation trees instead of bytes.
+// Besides the structural mutations (swapping and regenerating subtrees), there
+// are terminal-level mutations that only change the text of a leaf (numbers,
+// letter case, length) and keep the structure of the tree: a middle ground
+// between byte-level havoc, which mostly breaks the syntax, and regenerating
+// subtrees, which changes the structure.
+// From https://www.fuzzingbook.org/html/GreyboxGrammarFuzzer.html
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::grammarfuzzer::{expand_tree, fuzz_tree_, GrammarCost, Tree};
+use crate::rng::Rng;
+use crate::runner::{Coverage, CoverageH, GcovRunner, RunResult};
+
+/// Statistics relevant during fuzzing.
+#[derive(Default)]
+pub struct Statistics {
+    /// Number of times an input was tested.
+    pub fuzz_cases: usize,
+
+    /// Corpus: all derivation trees with unique coverage, together with a hash
+    /// of the associated coverage information.
+    pub corpus: Vec<(Tree, CoverageH)>,
+
+    /// All subtrees of the trees in the corpus, grouped by the name of their
+    /// root nonterminal. These are the building blocks for mutations.
+    pub fragments: HashMap<String, Vec<Tree>>,
+
+    /// Hashes of all coverages seen during fuzzing, together with the count /
+    /// frequency they occurred.
+    pub coverage_db: BTreeMap<CoverageH, usize>,
+
+    /// Union of all coverages that were achieved during execution.
+    pub coverage_all: Coverage,
+
+    /// Trace/history of coverage_all sizes.
+    pub coverage_cumul: Vec<usize>,
+}
+
+impl Statistics {
+    /// Add a derivation tree to the corpus and remember all its fragments.
+    fn add_to_corpus(&mut self, tree: Tree, coverageh: CoverageH) {
+        add_fragments(&mut self.fragments, &tree);
+        self.corpus.push((tree, coverageh));
+    }
+}
+
+fn add_fragments(fragments: &mut HashMap<String, Vec<Tree>>, tree: &Tree) {
+    if let Tree::NT(name, children) = tree {
+        fragments
+            .entry(name.clone())
+            .or_default()
+            .push(tree.clone());
+        for child in children.iter() {
+            add_fragments(fragments, child);
+        }
+    }
+}
+
+/// Kinds of tree mutations the fuzzer uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutations {
+    /// Swap subtrees for fragments and regenerate subtrees.
+    Structural,
+    /// Only change the text of terminal leaves.
+    Terminal,
+    /// Both of the above.
+    All,
+}
+
+/// Run `n` fuzz cases of coverage-guided grammar fuzzing.
+/// The first `n_seeds` fuzz cases are freshly generated from the grammar; after
+/// that derivation trees from the corpus are mutated.
+pub fn run(
+    rng: &mut Rng,
+    grammar: &GrammarCost,
+    runner: &GcovRunner,
+    stats: &mut Statistics,
+    n: usize,
+    n_seeds: usize,
+    mutations: Mutations,
+) {
+    for _ in 0..n {
+        let tree = if stats.fuzz_cases < n_seeds || stats.corpus.is_empty() {
+            fuzz_tree_(rng, grammar)
+        } else {
+            let mut tree = power_schedule_choose(rng, stats).clone();
+            for _ in 0..rng.range(1, 4 + 1) {
+                tree = mutate(rng, grammar, &stats.fragments, mutations, tree);
+            }
+            tree
+        };
+
+        if let RunResult::Ok(coverage) = runner.run(&tree.all_leafs()) {
+            let coverageh = CoverageH::new(&coverage);
+            match stats.coverage_db.get_mut(&coverageh) {
+                None => {
+                    // We have some new coverage.
+                    stats.coverage_db.insert(coverageh.clone(), 1);
+                    stats.add_to_corpus(tree, coverageh);
+                }
+                Some(count) => *count += 1,
+            }
+            stats.coverage_all.extend(coverage);
+        }
+
+        stats.coverage_cumul.push(stats.coverage_all.len());
+        stats.fuzz_cases += 1;
+    }
+}
+
+/// Choose a tree from the corpus according to an exponential power schedule
+/// as implemented in AFL (see 02-greyboxfuzzer/greyboxfuzzer5).
+fn power_schedule_choose<'a>(rng: &mut Rng, stats: &'a Statistics) -> &'a Tree {
+    let exponent = 5.0;
+    let fitness: Vec<_> = stats
+        .corpus
+        .iter()
+        .map(|(_, coverageh)| (1.0 / (stats.coverage_db[coverageh] as f64)).powf(exponent))
+        .collect();
+    &rng.choice_w(&stats.corpus, &fitness).0
+}
+
+/// Choose a random tree mutation and apply it.
+pub fn mutate(
+    rng: &mut Rng,
+    grammar: &GrammarCost,
+    fragments: &HashMap<String, Vec<Tree>>,
+    mutations: Mutations,
+    tree: Tree,
+) -> Tree {
+    let choice = match mutations {
+        Mutations::Structural => rng.int(2),
+        Mutations::Terminal => 2,
+        Mutations::All => rng.int(3),
+    };
+    match choice {
+        0 => swap_fragment(rng, fragments, tree),
+        1 => regenerate_subtree(rng, grammar, tree),
+        2 => mutate_terminal(rng, tree),
+        _ => panic!("Can't happen"),
+    }
+}
+
+/// Replace a random subtree with a fragment (from the corpus) that has the
+/// same root nonterminal. The result is again a valid derivation tree.
+fn swap_fragment(rng: &mut Rng, fragments: &HashMap<String, Vec<Tree>>, mut tree: Tree) -> Tree {
+    let paths = nonterminal_paths(&tree);
+    let path: &Vec<usize> = rng.choice(&paths);
+    let node = node_mut(&mut tree, path);
+    if let Tree::NT(name, _) = node {
+        if let Some(candidates) = fragments.get(name) {
+            *node = rng.choice(candidates).clone();
+        }
+    }
+    tree
+}
+
+/// Throw away a random subtree and expand its root nonterminal anew.
+fn regenerate_subtree(rng: &mut Rng, grammar: &GrammarCost, mut tree: Tree) -> Tree {
+    let paths = nonterminal_paths(&tree);
+    let path: &Vec<usize> = rng.choice(&paths);
+    let node = node_mut(&mut tree, path);
+    if let Tree::NT(_, children) = node {
+        children.clear();
+    }
+    // Keep the new subtrees small.
+    expand_tree(rng, grammar, node, 0, 10);
+    tree
+}
+
+/// Interesting numbers for numeric perturbation, similar to AFL's
+/// interesting values (boundaries of integer types and other edge cases).
+const INTERESTING_NUMBERS: &[i64] = &[
+    -2147483648,
+    -32768,
+    -129,
+    -128,
+    -1,
+    0,
+    1,
+    16,
+    32,
+    64,
+    100,
+    127,
+    128,
+    255,
+    256,
+    512,
+    1000,
+    1024,
+    4096,
+    32767,
+    32768,
+    65535,
+    65536,
+    2147483647,
+    4294967295,
+];
+
+/// Lengths for boundary-length strings.
+const BOUNDARY_LENGTHS: &[usize] = &[0, 1, 2, 127, 128, 255, 256, 1023, 1024, 4095, 4096];
+
+/// Change the text of a random (non-empty) terminal leaf, keeping the
+/// structure of the tree. The new text isn't necessarily derivable from the
+/// grammar anymore, but everything around it is.
+fn mutate_terminal(rng: &mut Rng, mut tree: Tree) -> Tree {
+    let paths = terminal_paths(&tree);
+    if paths.is_empty() {
+        return tree;
+    }
+    let path: &Vec<usize> = rng.choice(&paths);
+    let Tree::T(text) = node_mut(&mut tree, path) else {
+        panic!("Can't happen");
+    };
+    // Only choose among the operations that apply to this terminal.
+    let mut operations: Vec<fn(&mut Rng, &str) -> String> = vec![boundary_length];
+    if text.parse::<i64>().is_ok() {
+        operations.push(perturb_number);
+    }
+    if text.chars().any(|c| c.is_ascii_alphabetic()) {
+        operations.push(flip_case);
+    }
+    *text = rng.choice(&operations)(rng, text);
+    tree
+}
+
+/// Add a small delta to a number (as AFL's arithmetic mutations do), or
+/// replace it with an interesting number.
+fn perturb_number(rng: &mut Rng, text: &str) -> String {
+    let value: i64 = text.parse().unwrap();
+    match rng.int(2) {
+        0 => {
+            let delta = rng.range(1, 35 + 1) as i64;
+            let delta = if rng.int(2) == 0 { delta } else { -delta };
+            value.saturating_add(delta).to_string()
+        }
+        1 => rng.choice(INTERESTING_NUMBERS).to_string(),
+        _ => panic!("Can't happen"),
+    }
+}
+
+/// Flip the case of a single letter, or of the whole text.
+fn flip_case(rng: &mut Rng, text: &str) -> String {
+    let flip = |c: char| match c {
+        c if c.is_ascii_lowercase() => c.to_ascii_uppercase(),
+        c => c.to_ascii_lowercase(),
+    };
+    match rng.int(2) {
+        0 => {
+            let letters: Vec<_> = text
+                .char_indices()
+                .filter(|(_, c)| c.is_ascii_alphabetic())
+                .map(|(i, _)| i)
+                .collect();
+            let i = *rng.choice(&letters);
+            text.char_indices()
+                .map(|(j, c)| if i == j { flip(c) } else { c })
+                .collect()
+        }
+        1 => text.chars().map(flip).collect(),
+        _ => panic!("Can't happen"),
+    }
+}
+
+/// Repeat the text (cut off at the end) up to a boundary length, e.g. to hit
+/// fixed-size buffers or length limits.
+fn boundary_length(rng: &mut Rng, text: &str) -> String {
+    let len = *rng.choice(BOUNDARY_LENGTHS);
+    text.chars().cycle().take(len).collect()
+}
+
+/// Paths (lists of child indices, starting from the root) to all non-empty
+/// terminal leaves of a derivation tree.
+fn terminal_paths(tree: &Tree) -> Vec<Vec<usize>> {
+    let mut res = Vec::new();
+    let mut stack = vec![(tree, Vec::new())];
+    while let Some((cur, path)) = stack.pop() {
+        match cur {
+            Tree::NT(_, children) => {
+                for (i, child) in children.iter().enumerate() {
+                    let mut childpath = path.clone();
+                    childpath.push(i);
+                    stack.push((child, childpath));
+                }
+            }
+            Tree::T(text) if !text.is_empty() => res.push(path),
+            Tree::T(_) => {}
+        }
+    }
+    res
+}
+
+/// Paths (lists of child indices, starting from the root) to all nonterminal
+/// nodes of a derivation tree.
+fn nonterminal_paths(tree: &Tree) -> Vec<Vec<usize>> {
+    let mut res = Vec::new();
+    let mut stack = vec![(tree, Vec::new())];
+    while let Some((cur, path)) = stack.pop() {
+        if let Tree::NT(_, children) = cur {
+            for (i, child) in children.iter().enumerate() {
+                let mut childpath = path.clone();
+                childpath.push(i);
+                stack.push((child, childpath));
+            }
+            res.push(path);
+        }
+    }
+    res
+}
+
+fn node_mut<'a>(tree: &'a mut Tree, path: &[usize]) -> &'a mut Tree {
+    let mut cur = tree;
+    for &i in path {
+        cur = match cur {
+            Tree::NT(_, children) => &mut children[i],
+            Tree::T(_) => panic!("Can't happen"),
+        };
+    }
+    cur
+}
@@ -0,0 +1,100 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod greybox;
+mod rng;
+mod runner;
+
+use std::io::Write;
+use std::time::Instant;
+
+use grammarfuzzer::GrammarCost;
+use greybox::Mutations;
+use rng::Rng;
+
+fn main() {
+    let seed = cli::seed().unwrap_or(42);
+    println!("[+] Running with random seed {}", seed);
+
+    let n = cli::iterations(2000);
+    let quiet = cli::quiet();
+
+    let runner = runner::GcovRunner::new(&cli::target("expr"));
+    let grammar: GrammarCost = examplegrammars::expr_grammar().into();
+
+    let mut coverage_cumuls = Vec::new();
+
+    // Generating all inputs from the grammar (without any feedback) is just
+    // the special case where all fuzz cases are seeds.
+    for (name, n_seeds, mutations) in [
+        ("Blackbox grammar fuzzer", n, Mutations::Structural),
+        (
+            "Greybox grammar fuzzer, structural mutations",
+            10,
+            Mutations::Structural,
+        ),
+        (
+            "Greybox grammar fuzzer, terminal mutations",
+            10,
+            Mutations::Terminal,
+        ),
+        ("Greybox grammar fuzzer, all mutations", 10, Mutations::All),
+    ] {
+        // Same seed for each fuzzer.
+        let mut rng = Rng::seeded(seed);
+        let mut stats = greybox::Statistics::default();
+
+        let start = Instant::now();
+        greybox::run(
+            &mut rng, &grammar, &runner, &mut stats, n, n_seeds, mutations,
+        );
+        let end = Instant::now();
+
+        println!();
+        println!("[+] {}:", name);
+        println!(
+            "    - Runtime:                   {:0.4}s",
+            (end - start).as_secs_f64()
+        );
+        println!("    - Trees in corpus:           {}", stats.corpus.len());
+        if quiet {
+            println!(
+                "    - All coverage:              {}",
+                stats.coverage_all.len()
+            );
+        } else {
+            println!(
+                "    - All coverage:              {} {:?}",
+                stats.coverage_all.len(),
+                stats.coverage_all
+            );
+        }
+
+        let mut inputs: Vec<_> = stats
+            .corpus
+            .iter()
+            .map(|(tree, _)| tree.all_leafs())
+            .collect();
+        inputs.sort_by_key(|input| input.len());
+        if !quiet {
+            println!("    - Shortest inputs in corpus:");
+            for input in inputs.iter().take(5) {
+                println!("        {}", input);
+            }
+        }
+
+        coverage_cumuls.push(stats.coverage_cumul);
+    }
+
+    let mut logfile = std::fs::File::create(cli::out_file("plot.data")).unwrap();
+    for i in 0..n {
+        let columns: Vec<_> = coverage_cumuls.iter().map(|c| c[i].to_string()).collect();
+        writeln!(logfile, "{} {}", i, columns.join(" ")).unwrap();
+    }
+    logfile.flush().unwrap();
+}
@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Location is a linenumber
+type Location = usize;
+
+/// Statement coverage.
+pub type Coverage = BTreeSet<Location>;
+
+/// Newtype wrapper for hash of coverage information (for strong typing).
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone)]
+pub struct CoverageH(u64);
+impl CoverageH {
+    pub fn new(coverage: &Coverage) -> Self {
+        Self(hash(coverage))
+    }
+}
+
+pub fn hash<T: Hash>(val: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    val.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Debug, Eq, PartialEq)]
+/// Results of the execution of an external program (essentially an Option).
+pub enum RunResult {
+    /// Program exits in any other way not due to crash/signal.
+    Ok(Coverage),
+    /// Program crashes.
+    Crash,
+}
+
+/// Runs a C program compiled with gcc/gcov instrumentation, passing the input
+/// as its single commandline argument, and traces the statement coverage.
+pub struct GcovRunner {
+    /// Name of the program; the source code is expected at `<program>.c`.
+    program: String,
+}
+
+/// Number of test runs so far; used for naming temporary directories.
+static TESTRUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl GcovRunner {
+    /// Compile the C program. This is done once here and not on each run,
+    /// since the source code doesn't change between fuzz cases.
+    pub fn new(program: &str) -> Self {
+        process::Command::new("gcc")
+            .args(["-Wall", "-g", "--coverage", "-o", program])
+            .arg(format!("{}.c", program))
+            .output()
+            .unwrap();
+
+        Self {
+            program: program.to_string(),
+        }
+    }
+
+    /// Run the program with the input as its single commandline argument
+    /// and trace coverage data.
+    pub fn run(&self, input: &str) -> RunResult {
+        let program = &self.program;
+
+        // Create new temporary directory for running without conflicts. The
+        // name is derived from a global counter (and not from the PRNG), so
+        // that running the target doesn't influence the random choices of the
+        // fuzzer.
+        let root = format!(
+            "testrun-{}-{}",
+            process::id(),
+            TESTRUN_COUNTER.fetch_add(1, Ordering::Relaxed),
+        );
+
+        // Create a temporary directory that acts as root for this fuzz case execution.
+        fs::create_dir(&root).unwrap();
+        // Copy over the relevant data resulting from the initial program compilation.
+        // This is needed for later gathering code coverage with `gcov`.
+        for file in [
+            format!("{}.c", program),
+            program.clone(),
+            format!("{}.gcno", program),
+        ] {
+            fs::copy(&file, format!("{}/{}", root, file)).unwrap();
+        }
+
+        // Run the program.
+        let exitstatus = process::Command::new(fs::canonicalize(program).unwrap())
+            .current_dir(&root)
+            .arg(input)
+            // Instruct gcov to write the collected information into the
+            // temporary root of this fuzz case (see
+            // 02-greyboxfuzzer/greyboxfuzzer5).
+            .env("GCOV_PREFIX", ".")
+            .env("GCOV_PREFIX_STRIP", "20")
+            .stdout(process::Stdio::null())
+            .spawn()
+            .unwrap()
+            .wait()
+            .unwrap();
+
+        // Generate coverage data using gcov.
+        process::Command::new("gcov")
+            .current_dir(&root)
+            .arg(format!("{}.c", program))
+            .output()
+            .unwrap();
+
+        // "Parse" (process) gcov coverage file.
+        let mut coverage = BTreeSet::new();
+        for line in fs::read_to_string(format!("{}/{}.c.gcov", root, program))
+            .unwrap()
+            .lines()
+        {
+            let elems = line.split(':').collect::<Vec<_>>();
+            let covered = elems[0].trim();
+            let line_number = elems[1].trim().parse::<usize>().unwrap();
+            if covered.starts_with("-") || covered.starts_with("#") {
+                continue;
+            }
+            coverage.insert(line_number);
+        }
+
+        // Cleanup compiled and generated files.
+        fs::remove_dir_all(root).unwrap();
+
+        if exitstatus.success() {
+            RunResult::Ok(coverage)
+        } else {
+            RunResult::Crash
+        }
+    }
+}
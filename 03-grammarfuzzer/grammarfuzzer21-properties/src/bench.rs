@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Small benchmark harness for the grammar code (cost computation, tree
+// expansion, BNF conversion), in the spirit of criterion: each benchmark is
+// run repeatedly for a fixed time, and the median time per run is reported.
+// Results can be saved to `bench.data`, and each run is compared with the
+// last saved results, so that performance regressions from refactorings show
+// up.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::examplegrammars;
+use crate::grammarfuzzer::{expand_tree, GrammarCost, Tree};
+use crate::rng::Rng;
+
+/// File with the saved results (one line per benchmark and run:
+/// `<label> <benchmark> <median in ns>`).
+pub const BENCH_DATA: &str = "bench.data";
+
+/// Measuring time per benchmark.
+const BUDGET: Duration = Duration::from_millis(500);
+
+/// Results that are this much slower than the saved ones are reported.
+const REGRESSION_FACTOR: f64 = 1.25;
+
+pub struct Measurement {
+    pub name: String,
+    /// Number of measured runs.
+    pub runs: usize,
+    /// Median time per run.
+    pub median: Duration,
+}
+
+/// Measure `f` on fresh inputs from `setup` (which is not measured), for at
+/// least `BUDGET` and 10 runs (after one warm-up run).
+pub fn measure<I, O>(
+    name: &str,
+    mut setup: impl FnMut() -> I,
+    mut f: impl FnMut(I) -> O,
+) -> Measurement {
+    black_box(f(setup()));
+
+    let mut times = Vec::new();
+    let start = Instant::now();
+    while start.elapsed() < BUDGET || times.len() < 10 {
+        let input = setup();
+        let run = Instant::now();
+        black_box(f(input));
+        times.push(run.elapsed());
+    }
+    times.sort();
+    Measurement {
+        name: name.to_string(),
+        runs: times.len(),
+        median: times[times.len() / 2],
+    }
+}
+
+/// Run all benchmarks.
+pub fn run(rng: &mut Rng) -> Vec<Measurement> {
+    let json = examplegrammars::json_grammar();
+    let json_bnf = json.to_bnf();
+    let json_cost = GrammarCost::try_from(json.to_bnf()).unwrap();
+    let expr_cost = GrammarCost::try_from(examplegrammars::expr_grammar()).unwrap();
+
+    let mut res = vec![
+        measure("to_bnf/json", || (), |_| json.to_bnf()),
+        measure(
+            "grammarcost/expr",
+            examplegrammars::expr_grammar,
+            |grammar| GrammarCost::try_from(grammar).unwrap(),
+        ),
+        measure(
+            "grammarcost/json",
+            || json_bnf.clone(),
+            |grammar| GrammarCost::try_from(grammar).unwrap(),
+        ),
+    ];
+    for (name, grammar) in [("expr", &expr_cost), ("json", &json_cost)] {
+        for expansions in [10, 100, 1000] {
+            res.push(measure(
+                &format!("fuzz_tree/{}/{}", name, expansions),
+                || Tree::NT("start".to_string(), Vec::new()),
+                |mut tree| {
+                    expand_tree(rng, grammar, &mut tree, expansions, expansions).unwrap();
+                    tree
+                },
+            ));
+        }
+    }
+    res
+}
+
+/// The last saved result of each benchmark, with its label.
+pub fn load() -> BTreeMap<String, (String, Duration)> {
+    let mut res = BTreeMap::new();
+    let Ok(data) = fs::read_to_string(BENCH_DATA) else {
+        return res;
+    };
+    for line in data.lines() {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let [label, name, ns] = fields.as_slice() else {
+            panic!("Invalid line in {}: {}", BENCH_DATA, line);
+        };
+        let ns = ns.parse().unwrap();
+        res.insert(
+            name.to_string(),
+            (label.to_string(), Duration::from_nanos(ns)),
+        );
+    }
+    res
+}
+
+/// Append the results to `BENCH_DATA` under the given label (e.g. a commit).
+pub fn save(label: &str, measurements: &[Measurement]) {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(BENCH_DATA)
+        .unwrap();
+    for m in measurements {
+        writeln!(file, "{} {} {}", label, m.name, m.median.as_nanos()).unwrap();
+    }
+}
+
+/// Print the results, compared with the saved ones. Returns the number of
+/// regressions.
+pub fn report(measurements: &[Measurement], saved: &BTreeMap<String, (String, Duration)>) -> usize {
+    let mut regressions = 0;
+    for m in measurements {
+        let comparison = match saved.get(&m.name) {
+            Some((label, before)) => {
+                let factor = m.median.as_secs_f64() / before.as_secs_f64();
+                if factor > REGRESSION_FACTOR {
+                    regressions += 1;
+                }
+                format!(
+                    "{}{:.2}x of {} ({})",
+                    if factor > REGRESSION_FACTOR {
+                        "[!] "
+                    } else {
+                        ""
+                    },
+                    factor,
+                    format_duration(*before),
+                    label
+                )
+            }
+            None => String::new(),
+        };
+        println!(
+            "    - {:24} {:>10} ({:6} runs)  {}",
+            m.name,
+            format_duration(m.median),
+            m.runs,
+            comparison
+        );
+    }
+    regressions
+}
+
+fn format_duration(duration: Duration) -> String {
+    let ns = duration.as_nanos();
+    match ns {
+        0..1_000 => format!("{}ns", ns),
+        1_000..1_000_000 => format!("{:.1}µs", ns as f64 / 1e3),
+        _ => format!("{:.2}ms", ns as f64 / 1e6),
+    }
+}
@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod bench;
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod properties;
+mod rng;
+mod unstructured;
+
+use properties::Bounds;
+use rng::Rng;
+
+/// Number of random grammars per property.
+const CASES: usize = 500;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+    println!();
+
+    if cli::flag("--bench") {
+        run_bench(&mut rng);
+        return;
+    }
+
+    let cases = cli::iterations(CASES);
+    let bounds = Bounds::default();
+    println!("[+] Random grammars: {:?}", bounds);
+    let mut failures = 0;
+
+    // Check a property on random grammars, and report the first counterexample.
+    let mut check =
+        |name: &str, rng: &mut Rng, property: &mut dyn FnMut(&mut Rng) -> Result<(), String>| {
+            for i in 0..cases {
+                if let Err(err) = property(rng) {
+                    println!("[!] {}: failed in case {}:", name, i + 1);
+                    for line in err.lines() {
+                        println!("        {}", line);
+                    }
+                    failures += 1;
+                    return;
+                }
+            }
+            println!("    - {:32} {} cases passed", name, cases);
+        };
+
+    check("trim is idempotent", &mut rng, &mut |rng| {
+        let grammar = properties::random_grammar(rng, &bounds);
+        properties::trim_is_idempotent(&grammar).map_err(|err| format!("{}\n{}", err, grammar))
+    });
+
+    // Random EBNF grammars are often invalid (undefined or unreachable
+    // nonterminals), which makes the property hold trivially; count them.
+    let mut valid = 0;
+    check("to_bnf preserves validity", &mut rng, &mut |rng| {
+        let grammar = properties::random_ebnf(rng, &bounds);
+        if !grammar.is_valid() {
+            return Ok(());
+        }
+        valid += 1;
+        properties::to_bnf_preserves_validity(&grammar)
+            .map_err(|err| format!("{}\nEBNF grammar:\n{}", err, grammar))
+    });
+    println!("        ({} of them with a valid EBNF grammar)", valid);
+
+    check("trees are derivations", &mut rng, &mut |rng| {
+        let grammar = properties::random_grammar(rng, &bounds);
+        properties::trees_are_derivations(rng, &grammar, 10)
+            .map_err(|err| format!("{}\n{}", err, grammar))
+    });
+
+    check("costs are monotone", &mut rng, &mut |rng| {
+        let grammar = properties::random_grammar(rng, &bounds);
+        properties::costs_are_monotone(rng, &grammar).map_err(|err| format!("{}\n{}", err, grammar))
+    });
+
+    // The same properties for the example grammars.
+    println!();
+    println!("[+] Example grammars:");
+    let json = examplegrammars::json_grammar();
+    for (name, grammar) in [
+        ("expr", examplegrammars::expr_grammar()),
+        ("cgi", examplegrammars::cgi_grammar()),
+        ("title", examplegrammars::title_grammar()),
+        ("json", json.to_bnf()),
+    ] {
+        let res = properties::trim_is_idempotent(&grammar)
+            .and_then(|_| properties::trees_are_derivations(&mut rng, &grammar, 100))
+            .and_then(|_| properties::costs_are_monotone(&mut rng, &grammar));
+        match res {
+            Ok(()) => println!("    - {:32} passed", name),
+            Err(err) => {
+                println!("[!] {}: failed: {}", name, err);
+                failures += 1;
+            }
+        }
+    }
+    match properties::to_bnf_preserves_validity(&json) {
+        Ok(()) if json.is_valid() => println!("    - {:32} passed", "json (EBNF to BNF)"),
+        Ok(()) => {
+            println!("[!] json (EBNF to BNF): the EBNF grammar is invalid");
+            failures += 1;
+        }
+        Err(err) => {
+            println!("[!] json (EBNF to BNF): failed: {}", err);
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        println!();
+        println!("[!] {} properties failed", failures);
+        std::process::exit(1);
+    }
+}
+
+/// Run the benchmarks and compare them with the saved results; with
+/// `--save <label>`, the results are saved as well.
+fn run_bench(rng: &mut Rng) {
+    let label = cli::option("--save");
+
+    let saved = bench::load();
+    println!("[+] Benchmarks (median time per run):");
+    let measurements = bench::run(rng);
+    let regressions = bench::report(&measurements, &saved);
+    if let Some(label) = label {
+        bench::save(&label, &measurements);
+        println!("[+] Saved results as {} in {}", label, bench::BENCH_DATA);
+    }
+    if regressions > 0 {
+        println!();
+        println!("[!] {} benchmarks got slower", regressions);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Property-based checks of the grammar subsystem: random (small, valid)
+// grammars are generated, and invariants that have to hold for every grammar
+// are checked on them. A violated invariant comes with the grammar as
+// counterexample.
+//
+// There is no parser for grammars yet, so instead of parsing the generated
+// strings, the derivation trees are checked against the grammar (every node
+// must be expanded by one of the expansions of its nonterminal).
+
+use crate::grammarfuzzer::{
+    fuzz_tree_, fuzz_tree_from, Ebnf, Expr, Grammar, GrammarCost, TerminalSet, Tree,
+};
+use crate::rng::Rng;
+use crate::unstructured::Unstructured;
+
+/// Bounds for random grammars.
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    /// Number of nonterminals (including the start symbol).
+    pub nonterminals: usize,
+    /// Number of expansions per nonterminal.
+    pub expansions: usize,
+    /// Number of symbols per expansion.
+    pub symbols: usize,
+    /// Nesting depth of EBNF expressions.
+    pub depth: usize,
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Self {
+            nonterminals: 5,
+            expansions: 3,
+            symbols: 3,
+            depth: 3,
+        }
+    }
+}
+
+/// Terminals used in random grammars (including the empty string).
+const TERMINALS: &[&str] = &["a", "b", "xy", ""];
+
+/// Name of the i-th nonterminal of a random grammar (without angle brackets).
+fn nonterminal_name(i: usize) -> String {
+    match i {
+        0 => "start".to_string(),
+        i => format!("n{}", i),
+    }
+}
+
+fn random_terminal(rng: &mut Rng) -> String {
+    rng.choice(TERMINALS).to_string()
+}
+
+/// Random BNF grammar. Every nonterminal has (at least) one expansion that
+/// only uses nonterminals with a higher index, so that all nonterminals can
+/// be expanded into a finite string; the other expansions can be recursive.
+/// Some nonterminals get a terminal set, and some may be unreachable.
+pub fn random_grammar(rng: &mut Rng, bounds: &Bounds) -> Grammar {
+    let n = rng.range(1, bounds.nonterminals as u64 + 1) as usize;
+    let mut grammar = Grammar::new();
+    for i in 0..n {
+        let nonterminal = format!("<{}>", nonterminal_name(i));
+        for j in 0..rng.range(1, bounds.expansions as u64 + 1) {
+            let expansion: Vec<String> = (0..rng.range(1, bounds.symbols as u64 + 1))
+                .map(|_| {
+                    // Base expansion (j = 0): only "later" nonterminals.
+                    let min = if j == 0 { i + 1 } else { 0 };
+                    if min < n && rng.int(2) == 0 {
+                        format!(
+                            "<{}>",
+                            nonterminal_name(rng.range(min as u64, n as u64) as usize)
+                        )
+                    } else {
+                        random_terminal(rng)
+                    }
+                })
+                .collect();
+            if !grammar.expansions(&nonterminal).contains(&expansion) {
+                grammar.add_production_(nonterminal.clone(), expansion);
+            }
+        }
+        if rng.int(4) == 0 {
+            grammar.add_terminal_set(&nonterminal, TerminalSet::range('0', '9'));
+        }
+    }
+    grammar
+}
+
+/// Random EBNF grammar (nonterminals may be unreachable or non-terminating).
+pub fn random_ebnf(rng: &mut Rng, bounds: &Bounds) -> Ebnf {
+    let n = rng.range(1, bounds.nonterminals as u64 + 1) as usize;
+    let mut grammar = Ebnf::new();
+    for i in 0..n {
+        grammar.add_production(&nonterminal_name(i), random_expr(rng, bounds, n, 0));
+    }
+    grammar
+}
+
+fn random_expr(rng: &mut Rng, bounds: &Bounds, n: usize, depth: usize) -> Expr {
+    let children = |rng: &mut Rng| -> Vec<Expr> {
+        (0..rng.range(1, bounds.symbols as u64 + 1))
+            .map(|_| random_expr(rng, bounds, n, depth + 1))
+            .collect()
+    };
+    // Only symbols at the maximum depth.
+    let kind = match depth < bounds.depth {
+        true => rng.int(8),
+        false => rng.int(3),
+    };
+    match kind {
+        0 => Expr::NT(nonterminal_name(rng.usize_below(n))),
+        1 => Expr::T(random_terminal(rng)),
+        2 => Expr::Set(TerminalSet::range('0', '9')),
+        3 => Expr::Alt(children(rng)),
+        4 => Expr::Seq(children(rng)),
+        5 => Expr::Opt(Box::new(random_expr(rng, bounds, n, depth + 1))),
+        6 => Expr::Plus(Box::new(random_expr(rng, bounds, n, depth + 1))),
+        7 => Expr::Star(Box::new(random_expr(rng, bounds, n, depth + 1))),
+        _ => panic!("Can't happen"),
+    }
+}
+
+/// Trimming is idempotent, and gives a valid grammar.
+pub fn trim_is_idempotent(grammar: &Grammar) -> Result<(), String> {
+    let trimmed = grammar.trim().map_err(|err| err.to_string())?;
+    if !trimmed.is_valid() {
+        return Err(format!("Trimmed grammar is invalid:\n{}", trimmed));
+    }
+    match trimmed.trim() {
+        Ok(twice) if twice == trimmed => Ok(()),
+        Ok(twice) => Err(format!(
+            "Trimmed twice:\n{}\nTrimmed once:\n{}",
+            twice, trimmed
+        )),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Converting a valid EBNF grammar to BNF gives a valid grammar.
+pub fn to_bnf_preserves_validity(grammar: &Ebnf) -> Result<(), String> {
+    let bnf = grammar.to_bnf();
+    match bnf.is_valid() {
+        true => Ok(()),
+        false => Err(format!("BNF grammar is invalid:\n{}", bnf)),
+    }
+}
+
+/// Generated derivation trees (randomly, and from random bytes) are fully
+/// expanded derivations in the grammar, and need at least as many expansions
+/// as the cost of the start symbol.
+pub fn trees_are_derivations(rng: &mut Rng, grammar: &Grammar, n: usize) -> Result<(), String> {
+    let costs = GrammarCost::try_from(grammar.clone()).map_err(|err| err.to_string())?;
+    let min_expansions = costs.cost("<start>").unwrap();
+    for i in 0..n {
+        let tree = match i % 2 {
+            0 => fuzz_tree_(rng, &costs).map_err(|err| err.to_string())?,
+            _ => {
+                let len = rng.int(65);
+                fuzz_tree_from(&mut Unstructured::new(&rng.bytes(len)), &costs, 50)
+            }
+        };
+        let expansions = check_derivation(grammar, &tree)
+            .map_err(|err| format!("{} in tree of {:?}", err, tree.all_leafs()))?;
+        if expansions < min_expansions {
+            return Err(format!(
+                "Tree of {:?} has {} expansions, but <start> costs {}",
+                tree.all_leafs(),
+                expansions,
+                min_expansions
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check that every node of the tree is expanded by one of the expansions of
+/// its nonterminal (or its terminal set). Returns the number of expansions.
+fn check_derivation(grammar: &Grammar, tree: &Tree) -> Result<usize, String> {
+    let mut expansions = 0;
+    let mut stack = vec![tree];
+    while let Some(cur) = stack.pop() {
+        let Tree::NT(name, children) = cur else {
+            continue;
+        };
+        let nonterminal = format!("<{}>", name);
+        let symbols: Vec<String> = children
+            .iter()
+            .map(|child| match child {
+                Tree::NT(name, _) => format!("<{}>", name),
+                Tree::T(name) => name.clone(),
+            })
+            .collect();
+        let from_set = match (grammar.terminal_set(&nonterminal), symbols.as_slice()) {
+            (Some(set), [symbol]) if !Grammar::is_nonterminal(symbol) => {
+                symbol.chars().count() == 1 && set.contains(symbol.chars().next().unwrap())
+            }
+            _ => false,
+        };
+        if children.is_empty() {
+            return Err(format!("Unexpanded {}", nonterminal));
+        }
+        if !from_set && !grammar.expansions(&nonterminal).contains(&symbols) {
+            return Err(format!("{} -> {:?} is no expansion", nonterminal, symbols));
+        }
+        expansions += 1;
+        stack.extend(children);
+    }
+    Ok(expansions)
+}
+
+/// The cost of each nonterminal is the minimum over its expansions of one
+/// plus the costs of their nonterminals (a terminal set costs 1), and adding
+/// an expansion never increases a cost.
+pub fn costs_are_monotone(rng: &mut Rng, grammar: &Grammar) -> Result<(), String> {
+    let costs = GrammarCost::try_from(grammar.clone()).map_err(|err| err.to_string())?;
+    for nonterminal in grammar.nonterminals() {
+        let cost = costs.cost(&nonterminal).unwrap();
+        let mut alternatives: Vec<usize> = grammar
+            .expansions(&nonterminal)
+            .iter()
+            .map(|expansion| {
+                1 + expansion
+                    .iter()
+                    .filter(|symbol| Grammar::is_nonterminal(symbol))
+                    .map(|symbol| costs.cost(symbol).unwrap())
+                    .sum::<usize>()
+            })
+            .collect();
+        if grammar.terminal_set(&nonterminal).is_some() {
+            alternatives.push(1);
+        }
+        let min = alternatives.into_iter().min().unwrap();
+        if cost != min {
+            return Err(format!(
+                "{} costs {}, but its cheapest expansion costs {}",
+                nonterminal, cost, min
+            ));
+        }
+    }
+
+    // One more (random) expansion for a random nonterminal.
+    let nonterminals: Vec<_> = grammar.nonterminals().into_iter().collect();
+    let nonterminal = rng.choice(&nonterminals).clone();
+    let symbol = match rng.int(2) {
+        0 => rng.choice(&nonterminals).clone(),
+        _ => random_terminal(rng),
+    };
+    let mut extended = grammar.clone();
+    if !grammar
+        .expansions(&nonterminal)
+        .contains(&vec![symbol.clone()])
+    {
+        extended.add_production_(nonterminal.clone(), vec![symbol.clone()]);
+    }
+    let extended_costs = GrammarCost::try_from(extended).map_err(|err| err.to_string())?;
+    for other in nonterminals.iter() {
+        let (before, after) = (
+            costs.cost(other).unwrap(),
+            extended_costs.cost(other).unwrap(),
+        );
+        if after > before {
+            return Err(format!(
+                "Adding {} -> {:?} increases the cost of {} from {} to {}",
+                nonterminal, symbol, other, before, after
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+mod unstructured;
+
+use std::fs;
+use std::path::Path;
+
+use grammarfuzzer::{GrammarCost, Tree};
+use rng::Rng;
+
+/// Number of generated strings per grammar and size class.
+const N: usize = 100;
+
+/// Size classes of the generated corpora, with their target length.
+const SIZES: [(&str, usize); 3] = [("tiny", 10), ("medium", 100), ("huge", 1000)];
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let corpus = cli::option("--corpus");
+    let n = cli::iterations(N);
+    let quiet = cli::quiet();
+
+    // Only the grammar given with --target (e.g. `--target json`).
+    let selected = cli::target("all");
+
+    for (name, grammar) in [
+        ("expr", examplegrammars::expr_grammar()),
+        ("cgi", examplegrammars::cgi_grammar()),
+        ("title", examplegrammars::title_grammar()),
+        ("json", examplegrammars::json_grammar().to_bnf()),
+    ] {
+        if selected != "all" && selected != name {
+            continue;
+        }
+        let grammar = GrammarCost::try_from(grammar).unwrap();
+        println!();
+        println!(
+            "[+] {} grammar (shortest string: {} characters):",
+            name,
+            grammar.len_range("<start>").unwrap().min
+        );
+
+        // Trees without a target, for comparison.
+        let trees: Vec<Tree> = (0..n)
+            .map(|_| grammarfuzzer::fuzz_tree_(&mut rng, &grammar).unwrap())
+            .collect();
+        print_metrics("no target", &trees);
+
+        for (size, len) in SIZES {
+            let trees: Vec<Tree> = (0..n)
+                .map(|_| grammarfuzzer::fuzz_tree_with_target_len(&mut rng, &grammar, len))
+                .collect();
+            print_metrics(&format!("{} ({})", size, len), &trees);
+            if !quiet {
+                println!("          e.g. {:?}", truncate(&trees[0].all_leafs(), 60));
+            }
+
+            // The inputs in one directory, and their metrics next to it (so
+            // that the corpus only contains inputs).
+            if let Some(corpus) = &corpus {
+                let dir = Path::new(corpus).join(format!("{}-{}", name, size));
+                fs::create_dir_all(&dir).unwrap();
+                let mut metadata = String::new();
+                for (i, tree) in trees.iter().enumerate() {
+                    let file = format!("{:04}", i);
+                    fs::write(dir.join(&file), tree.all_leafs()).unwrap();
+                    metadata += &format!(
+                        "{} length {} depth {} nodes {}\n",
+                        file,
+                        tree.terminal_len(),
+                        tree.depth(),
+                        tree.node_count()
+                    );
+                }
+                fs::write(dir.with_extension("meta"), metadata).unwrap();
+            }
+        }
+    }
+
+    // Fuzzing only components of the JSON format.
+    println!();
+    println!("[+] Slices of the json grammar:");
+    let json = examplegrammars::json_grammar().to_bnf();
+    for nonterminal in ["<string>", "<number>", "<array>"] {
+        let slice = json.slice(nonterminal).unwrap();
+        println!(
+            "    - {:10} {:2} nonterminals, {:3} expansions (of {}, {})",
+            nonterminal,
+            slice.nonterminals().len(),
+            slice.num_expansions(),
+            json.nonterminals().len(),
+            json.num_expansions()
+        );
+        let grammar = GrammarCost::try_from(slice).unwrap();
+        for _ in 0..3 {
+            let input = grammarfuzzer::fuzz_with_target_len(&mut rng, &grammar, 20);
+            if !quiet {
+                println!("          {:?}", input);
+            }
+        }
+    }
+
+    if let Some(corpus) = corpus {
+        println!();
+        println!("[+] Wrote corpora to {}", corpus);
+    }
+}
+
+/// Size distribution of the trees: length of the strings, depth and number
+/// of nodes.
+fn print_metrics(label: &str, trees: &[Tree]) {
+    let length: Vec<usize> = trees.iter().map(|tree| tree.terminal_len()).collect();
+    let depth: Vec<usize> = trees.iter().map(|tree| tree.depth()).collect();
+    let nodes: Vec<usize> = trees.iter().map(|tree| tree.node_count()).collect();
+    println!("    - {:14} {}", label, summary("length", &length));
+    println!("      {:14} {}", "", summary("depth", &depth));
+    println!("      {:14} {}", "", summary("nodes", &nodes));
+}
+
+/// Minimum, median and maximum.
+fn summary(name: &str, values: &[usize]) -> String {
+    let mut values = values.to_vec();
+    values.sort();
+    format!(
+        "{:6} min {:5}   median {:5}   max {:5}",
+        name,
+        values[0],
+        values[values.len() / 2],
+        values[values.len() - 1]
+    )
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    match s.chars().count() > max {
+        true => format!("{}...", s.chars().take(max).collect::<String>()),
+        false => s.to_string(),
+    }
+}
@@ -0,0 +1,121 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Command-line options shared by the example binaries, so that they can be
+// scripted for experiments. The defaults are the constants of each example;
+// an example ignores the options that have no meaning for it. The shared
+// options can also be given as environment variables (`--out-dir DIR` as
+// `OUT_DIR=DIR`); the command line takes precedence.
+//
+//   --seed N          seed of the random number generator
+//   --iterations N    number of fuzz cases/inputs/generations
+//   --target NAME     program (or grammar) under test
+//   --out-dir DIR     directory for the output files (created if missing)
+//   --quiet           only print the summary, not every input
+
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Value of `--name VALUE` or `--name=VALUE` on the command line.
+pub fn option(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return Some(
+                args.next()
+                    .unwrap_or_else(|| panic!("Missing value for {}", name)),
+            );
+        }
+        if let Some(value) = arg.strip_prefix(&format!("{}=", name)) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Whether `--name` is given on the command line.
+pub fn flag(name: &str) -> bool {
+    std::env::args().skip(1).any(|arg| arg == name)
+}
+
+/// The arguments that are neither options nor values of the shared options
+/// (other options of an example must not take values, or use `--name=VALUE`).
+pub fn positional() -> Vec<String> {
+    let mut res = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if ["--seed", "--iterations", "--target", "--out-dir"].contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with("--") {
+            res.push(arg);
+        }
+    }
+    res
+}
+
+/// Value of an option parsed as `T`, or `default` if it isn't given.
+pub fn parsed<T: FromStr>(name: &str, default: T) -> T
+where
+    T::Err: Debug,
+{
+    option(name).map_or(default, |value| parse(name, &value))
+}
+
+/// The seed, if one is given (the examples fall back to a fixed or a
+/// time-based seed).
+pub fn seed() -> Option<u64> {
+    shared("--seed").map(|value| parse("--seed", &value))
+}
+
+pub fn iterations(default: usize) -> usize {
+    shared("--iterations").map_or(default, |value| parse("--iterations", &value))
+}
+
+pub fn target(default: &str) -> String {
+    shared("--target").unwrap_or(default.to_string())
+}
+
+/// Output directory (created if it doesn't exist yet).
+pub fn out_dir(default: &str) -> PathBuf {
+    let dir = PathBuf::from(shared("--out-dir").unwrap_or(default.to_string()));
+    std::fs::create_dir_all(&dir)
+        .unwrap_or_else(|e| panic!("Creating output directory {}: {}", dir.display(), e));
+    dir
+}
+
+/// Path of an output file in the output directory (by default the current
+/// directory).
+pub fn out_file(name: &str) -> PathBuf {
+    out_dir(".").join(name)
+}
+
+/// `--quiet`, or the environment variable `QUIET` set to anything but empty
+/// or `0`.
+pub fn quiet() -> bool {
+    flag("--quiet") || std::env::var("QUIET").is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// A shared option from the command line, or else from the environment
+/// variable `NAME` (upper case, `-` replaced by `_`).
+fn shared(name: &str) -> Option<String> {
+    option(name).or_else(|| {
+        let var = name
+            .trim_start_matches('-')
+            .replace('-', "_")
+            .to_uppercase();
+        std::env::var(var).ok()
+    })
+}
+
+fn parse<T: FromStr>(name: &str, value: &str) -> T
+where
+    T::Err: Debug,
+{
+    value
+        .parse()
+        .unwrap_or_else(|_| panic!("Invalid value for {}: {}", name, value))
+}
@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Streaming enumeration of all derivation trees up to a maximum depth, and
+// reservoir sampling over it.
+//
+// A derivation tree is identified by the sequence of choices taken when
+// expanding it depth-first from left to right: at each nonterminal node the
+// index of the alternative (expansion, or character of the terminal set)
+// among the ones that fit into the remaining depth budget. `Enumerator` walks
+// through these choice sequences like an odometer: the next tree repeats the
+// choices of the current one up to the last choice that can be increased,
+// increases it, and takes the first alternative for all choices after it.
+// Since only alternatives that fit into the depth budget are counted, every
+// sequence gives a complete tree, and every tree is generated exactly once.
+// Only the current choice sequence is kept, so the enumeration takes time
+// proportional to the size of the language, but no memory for it.
+//
+// Reservoir sampling (Vitter's algorithm R) draws a uniform sample of fixed
+// size from such a stream without knowing its length: the i-th item (counting
+// from 1) replaces a random item of the reservoir with probability k / i.
+// Unlike `TreeCounts` from grammarfuzzer17, whose counts are approximations
+// (logarithms) for large languages, this is exactly uniform, but needs to see
+// the whole language, so it is only practical for languages of up to a few
+// million trees.
+
+use std::collections::HashMap;
+
+use crate::grammarfuzzer::{Expansion, Grammar, GrammarError, Nonterminal, Tree};
+use crate::rng::Rng;
+
+/// Iterator over all derivation trees of `<start>` with depth at most
+/// `max_depth` (the depth of a nonterminal node that only has terminal
+/// children is 1).
+pub struct Enumerator<'a> {
+    grammar: &'a Grammar,
+    max_depth: usize,
+
+    /// Minimum depth of a tree of each nonterminal.
+    min_depth: HashMap<Nonterminal, usize>,
+
+    /// Choices (prefix) of the next tree; the choices after it are 0. `None`
+    /// once all trees have been generated.
+    next: Option<Vec<usize>>,
+}
+
+/// Expansion of a nonterminal, or one character of its terminal set.
+enum Alternative<'a> {
+    Expansion(&'a Expansion),
+    Char(char),
+}
+
+impl<'a> Enumerator<'a> {
+    /// Fails if a used nonterminal is not defined, or if `<start>` has no tree
+    /// within the depth.
+    pub fn new(grammar: &'a Grammar, max_depth: usize) -> Result<Self, GrammarError> {
+        grammar.trim()?;
+        let min_depth = min_depths(grammar);
+        if min_depth["<start>"] > max_depth {
+            return Err(GrammarError::NoTreeWithinDepth(max_depth));
+        }
+        Ok(Self {
+            grammar,
+            max_depth,
+            min_depth,
+            next: Some(Vec::new()),
+        })
+    }
+
+    /// The alternatives of a nonterminal that fit into depth `depth`.
+    fn alternatives(&self, nonterminal: &str, depth: usize) -> Vec<Alternative<'a>> {
+        let expansions = self.grammar.expansions(nonterminal).iter().filter(|e| {
+            e.iter()
+                .filter(|symbol| Grammar::is_nonterminal(symbol))
+                .all(|symbol| self.min_depth[symbol.as_str()] < depth)
+        });
+        let chars = self
+            .grammar
+            .terminal_set(nonterminal)
+            .map(|set| set.chars());
+        expansions
+            .map(Alternative::Expansion)
+            .chain(chars.into_iter().flatten().copied().map(Alternative::Char))
+            .collect()
+    }
+
+    /// Expand a nonterminal with the choices of `prefix` (0 after its end), and
+    /// record the choices with their number of alternatives.
+    fn expand(
+        &self,
+        nonterminal: &str,
+        depth: usize,
+        prefix: &[usize],
+        choices: &mut Vec<(usize, usize)>,
+    ) -> Tree {
+        let alternatives = self.alternatives(nonterminal, depth);
+        let choice = prefix.get(choices.len()).copied().unwrap_or(0);
+        choices.push((choice, alternatives.len()));
+        let children = match alternatives[choice] {
+            Alternative::Expansion(expansion) => expansion
+                .iter()
+                .map(|symbol| match Grammar::is_nonterminal(symbol) {
+                    true => self.expand(symbol, depth - 1, prefix, choices),
+                    false => Tree::T(symbol.clone()),
+                })
+                .collect(),
+            Alternative::Char(c) => vec![Tree::T(c.to_string())],
+        };
+        Tree::NT(
+            Grammar::trim_angle_brackets(nonterminal).to_string(),
+            children,
+        )
+    }
+}
+
+impl Iterator for Enumerator<'_> {
+    type Item = Tree;
+
+    fn next(&mut self) -> Option<Tree> {
+        let prefix = self.next.take()?;
+        let mut choices = Vec::new();
+        let tree = self.expand("<start>", self.max_depth, &prefix, &mut choices);
+
+        // Increase the last choice that isn't at its last alternative yet.
+        self.next = choices
+            .iter()
+            .rposition(|(choice, len)| choice + 1 < *len)
+            .map(|i| {
+                let mut next: Vec<usize> = choices[..i].iter().map(|c| c.0).collect();
+                next.push(choices[i].0 + 1);
+                next
+            });
+        Some(tree)
+    }
+}
+
+/// Minimum depth of a tree of each nonterminal (by fixpoint iteration;
+/// `usize::MAX` for nonterminals without finite tree).
+fn min_depths(grammar: &Grammar) -> HashMap<Nonterminal, usize> {
+    let mut depths: HashMap<Nonterminal, usize> = grammar
+        .nonterminals()
+        .into_iter()
+        .map(|nonterminal| (nonterminal, usize::MAX))
+        .collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for nonterminal in grammar.nonterminals() {
+            let mut min = match grammar.terminal_set(&nonterminal) {
+                Some(_) => 1,
+                None => usize::MAX,
+            };
+            for expansion in grammar.expansions(&nonterminal) {
+                let depth = expansion
+                    .iter()
+                    .filter(|symbol| Grammar::is_nonterminal(symbol))
+                    .map(|symbol| depths[symbol.as_str()])
+                    .max()
+                    .unwrap_or(0);
+                min = min.min(depth.saturating_add(1));
+            }
+            if min < depths[&nonterminal] {
+                depths.insert(nonterminal, min);
+                changed = true;
+            }
+        }
+    }
+    depths
+}
+
+/// Uniform sample of (at most) `k` items of `items`, without keeping more
+/// than `k` of them in memory. Returns the sample (in no particular order)
+/// and the total number of items.
+pub fn reservoir_sample<T>(
+    rng: &mut Rng,
+    items: impl IntoIterator<Item = T>,
+    k: usize,
+) -> (Vec<T>, usize) {
+    let mut reservoir = Vec::with_capacity(k);
+    let mut n = 0;
+    for item in items {
+        n += 1;
+        if reservoir.len() < k {
+            reservoir.push(item);
+        } else {
+            let i = rng.usize_below(n);
+            if i < k {
+                reservoir[i] = item;
+            }
+        }
+    }
+    (reservoir, n)
+}
@@ -0,0 +1,129 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod enumerate;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+mod unstructured;
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use enumerate::Enumerator;
+use grammarfuzzer::{Grammar, TerminalSet};
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    // Binary trees: small enough to check that every tree is sampled equally
+    // often.
+    let mut grammar = Grammar::new();
+    grammar.add_production("<start>", &["<tree>"]);
+    grammar.add_production("<tree>", &["(", "<tree>", "<tree>", ")"]);
+    grammar.add_production("<tree>", &["x"]);
+    let trees: Vec<String> = Enumerator::new(&grammar, 5)
+        .unwrap()
+        .map(|tree| tree.all_leafs())
+        .collect();
+    println!();
+    println!("[+] Binary trees with depth <= 5: {} trees", trees.len());
+    if !cli::quiet() {
+        println!("    {}", trees[..6].join(" "));
+    }
+    let n = cli::iterations(5200);
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for _ in 0..n {
+        let (sample, _) =
+            enumerate::reservoir_sample(&mut rng, Enumerator::new(&grammar, 5).unwrap(), 5);
+        for tree in sample {
+            *frequencies.entry(tree.all_leafs()).or_insert(0) += 1;
+        }
+    }
+    println!(
+        "    {} samples of 5: {} different trees, sampled {} to {} times (\"x\": {})",
+        n,
+        frequencies.len(),
+        frequencies.values().min().unwrap(),
+        frequencies.values().max().unwrap(),
+        frequencies["x"]
+    );
+
+    // Larger languages: the enumeration takes time, but only the sample is
+    // kept in memory.
+    for (name, grammar, depth) in [
+        ("Expressions", examplegrammars::expr_grammar(), 6),
+        ("CGI strings", examplegrammars::cgi_grammar(), 6),
+        ("JSON", examplegrammars::json_grammar().to_bnf(), 8),
+    ] {
+        let start = Instant::now();
+        let enumerator = Enumerator::new(&grammar, depth).unwrap();
+        let (sample, n) = enumerate::reservoir_sample(&mut rng, enumerator, 5);
+        println!();
+        println!(
+            "[+] {} with depth <= {}: {} trees ({:.2}s)",
+            name,
+            depth,
+            n,
+            start.elapsed().as_secs_f64()
+        );
+        if !cli::quiet() {
+            for tree in sample {
+                println!("    {:?}", tree.all_leafs());
+            }
+        }
+    }
+
+    // Grammar diffs: a hand-made change, and what a refactoring does.
+    println!();
+    println!("[+] Diff of the expression grammar with modulo and hex numbers:");
+    let expr = examplegrammars::expr_grammar();
+    let mut modified = Grammar::new();
+    for nonterminal in expr.nonterminals() {
+        for expansion in expr.expansions(&nonterminal) {
+            if expansion.iter().any(|symbol| symbol == ".") {
+                continue;
+            }
+            modified.add_production_(nonterminal.clone(), expansion.clone());
+            if expansion.iter().any(|symbol| symbol == "/") {
+                modified.add_production(&nonterminal, &["<factor>", "%", "<term>"]);
+            }
+        }
+    }
+    modified.add_production("<factor>", &["0x", "<hexdigits>"]);
+    modified.add_production("<hexdigits>", &["<hexdigit>", "<hexdigits>"]);
+    modified.add_production("<hexdigits>", &["<hexdigit>"]);
+    modified.add_terminal_set("<hexdigit>", TerminalSet::new("0123456789abcdef".chars()));
+    print!("{}", indent(&expr.diff(&modified).to_string()));
+
+    println!();
+    println!("[+] Diff of the expression grammar with grouped terminal sets:");
+    print!(
+        "{}",
+        indent(&expr.diff(&expr.group_terminal_sets(5)).to_string())
+    );
+    let roundtrip = expr.group_terminal_sets(5).expand_terminal_sets();
+    println!(
+        "    Expanded again: {}",
+        match expr.diff(&roundtrip) {
+            diff if diff.is_empty() => "no differences".to_string(),
+            diff => format!("{} nonterminals differ", diff.0.len()),
+        }
+    );
+
+    // Depth limit below the minimum depth.
+    println!();
+    println!("[+] Depth limit below the minimum depth:");
+    let grammar = examplegrammars::expr_grammar();
+    if let Err(err) = Enumerator::new(&grammar, 3) {
+        println!("    Error: {}", err);
+    }
+}
+
+fn indent(s: &str) -> String {
+    s.lines().map(|line| format!("    {}\n", line)).collect()
+}
@@ -0,0 +1,154 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Golden files of generated inputs: for a key (grammar, seed and generation
+// options), the inputs are generated once and stored; later, they are
+// regenerated from the key and compared. Changes to the expansion algorithms
+// (or to the grammars, or the Rng) that alter the generated inputs are then
+// detected, and the golden files are updated deliberately (`--golden save`)
+// instead of the outputs changing silently.
+//
+// The inputs are generated single-threaded from one Rng seeded with the seed,
+// so they don't depend on the number of threads of the batch generation.
+//
+// Format of a golden file (`<dir>/<grammar>-seed<seed>-n<n>[-len<len>]`): the
+// key on the first line (`# grammar <name> seed <seed> n <n> len <len|->`),
+// then one input per line (escaped like a Rust string literal, so that every
+// input is one line and the files can be diffed).
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::grammarfuzzer::{self, GrammarCost};
+use crate::rng::Rng;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Key {
+    pub grammar: String,
+    pub seed: u64,
+    /// Number of inputs.
+    pub n: usize,
+    /// Target length (`fuzz_tree_with_target_len`), or the usual expansion.
+    pub len: Option<usize>,
+}
+
+impl Key {
+    pub fn file_name(&self) -> String {
+        let mut res = format!("{}-seed{}-n{}", self.grammar, self.seed, self.n);
+        if let Some(len) = self.len {
+            res += &format!("-len{}", len);
+        }
+        res
+    }
+
+    fn header(&self) -> String {
+        let len = self.len.map_or("-".to_string(), |len| len.to_string());
+        format!(
+            "# grammar {} seed {} n {} len {}",
+            self.grammar, self.seed, self.n, len
+        )
+    }
+
+    fn parse_header(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["#", "grammar", grammar, "seed", seed, "n", n, "len", len] => Some(Self {
+                grammar: grammar.to_string(),
+                seed: seed.parse().ok()?,
+                n: n.parse().ok()?,
+                len: match *len {
+                    "-" => None,
+                    len => Some(len.parse().ok()?),
+                },
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The inputs of a key, one per line.
+pub fn generate(key: &Key, grammar: &GrammarCost) -> Vec<String> {
+    let mut rng = Rng::seeded(key.seed);
+    (0..key.n)
+        .map(|_| {
+            let tree = match key.len {
+                Some(len) => grammarfuzzer::fuzz_tree_with_target_len(&mut rng, grammar, len),
+                None => grammarfuzzer::fuzz_tree_(&mut rng, grammar).unwrap(),
+            };
+            format!("{:?}", tree.all_leafs())
+        })
+        .collect()
+}
+
+/// Generate the inputs of a key, and store them in directory `dir`.
+pub fn save(dir: &Path, key: &Key, grammar: &GrammarCost) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut content = key.header() + "\n";
+    for line in generate(key, grammar) {
+        content += &line;
+        content += "\n";
+    }
+    fs::write(dir.join(key.file_name()), content)
+}
+
+/// Result of comparing one golden file with the regenerated inputs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Same,
+    /// First differing input (index, golden, regenerated), or a different
+    /// number of inputs (`None` for the missing one).
+    Differs(usize, Option<String>, Option<String>),
+    /// The file can't be read or has no valid key, or the grammar is unknown.
+    Invalid(String),
+}
+
+/// Regenerate the inputs of every golden file in directory `dir` and compare
+/// them. `grammar_by_name` gives the grammar of a key.
+pub fn check_all(
+    dir: &Path,
+    grammar_by_name: impl Fn(&str) -> Option<GrammarCost>,
+) -> io::Result<Vec<(String, Outcome)>> {
+    let mut files: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    files.sort();
+
+    let mut res = Vec::new();
+    for file in files {
+        let name = file.file_name().unwrap().to_string_lossy().to_string();
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                res.push((name, Outcome::Invalid(err.to_string())));
+                continue;
+            }
+        };
+        let mut lines = content.lines();
+        let Some(key) = lines.next().and_then(Key::parse_header) else {
+            res.push((name, Outcome::Invalid("Missing or invalid key".to_string())));
+            continue;
+        };
+        let Some(grammar) = grammar_by_name(&key.grammar) else {
+            let err = format!("Unknown grammar {}", key.grammar);
+            res.push((name, Outcome::Invalid(err)));
+            continue;
+        };
+
+        let golden: Vec<&str> = lines.collect();
+        let regenerated = generate(&key, &grammar);
+        let outcome = match (0..golden.len().max(regenerated.len()))
+            .find(|i| golden.get(*i).copied() != regenerated.get(*i).map(|s| s.as_str()))
+        {
+            None => Outcome::Same,
+            Some(i) => Outcome::Differs(
+                i,
+                golden.get(i).map(|s| s.to_string()),
+                regenerated.get(i).cloned(),
+            ),
+        };
+        res.push((name, outcome));
+    }
+    Ok(res)
+}
@@ -0,0 +1,308 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod golden;
+mod grammarfuzzer;
+mod rng;
+mod sha256;
+mod unstructured;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Instant;
+
+use grammarfuzzer::{Grammar, GrammarCost, Tree};
+use rng::Rng;
+
+/// Generated input with its metadata (one line of the manifest).
+struct Sample {
+    grammar: &'static str,
+    index: usize,
+    thread: usize,
+    /// State of the thread's Rng before the input was generated (see
+    /// `--regenerate`).
+    rng: String,
+    file: String,
+    size: usize,
+    depth: usize,
+    nodes: usize,
+    sha256: String,
+}
+
+impl Sample {
+    /// JSON object on one line (all strings are plain ASCII without quotes,
+    /// so they don't need escaping).
+    fn to_json(&self, seed: u64) -> String {
+        format!(
+            "{{\"file\": \"{}\", \"grammar\": \"{}\", \"index\": {}, \"seed\": {}, \"thread\": {}, \
+            \"rng\": \"{}\", \"size\": {}, \"depth\": {}, \"nodes\": {}, \"sha256\": \"{}\"}}",
+            self.file,
+            self.grammar,
+            self.index,
+            seed,
+            self.thread,
+            self.rng,
+            self.size,
+            self.depth,
+            self.nodes,
+            self.sha256
+        )
+    }
+}
+
+fn grammar_by_name(name: &str) -> Option<Grammar> {
+    match name {
+        "expr" => Some(examplegrammars::expr_grammar()),
+        "cgi" => Some(examplegrammars::cgi_grammar()),
+        "title" => Some(examplegrammars::title_grammar()),
+        "json" => Some(examplegrammars::json_grammar().to_bnf()),
+        _ => None,
+    }
+}
+
+const GRAMMARS: [&str; 4] = ["expr", "cgi", "title", "json"];
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    // `--out` and `-n` are the older names of `--out-dir` and `--iterations`.
+    let out = cli::option("--out").map_or_else(|| cli::out_dir("output"), PathBuf::from);
+    let n = cli::iterations(cli::parsed("-n", 100));
+    let threads: usize = cli::option("--threads").map_or_else(
+        || thread::available_parallelism().map_or(1, |n| n.get()),
+        |n| n.parse().expect("Invalid --threads"),
+    );
+    let len: Option<usize> = cli::option("--len").map(|n| n.parse().expect("Invalid --len"));
+    // `--target` selects a single grammar.
+    let names: Vec<&'static str> = match cli::option("--grammars").unwrap_or(cli::target("all")) {
+        names if names == "all" => GRAMMARS.to_vec(),
+        names => names
+            .split(',')
+            .map(|name| {
+                *GRAMMARS.iter().find(|g| **g == name).unwrap_or_else(|| {
+                    panic!("Unknown grammar {}, expected one of {:?}", name, GRAMMARS)
+                })
+            })
+            .collect(),
+    };
+    if cli::flag("--costs") {
+        for name in names.iter() {
+            let grammar = GrammarCost::try_from(grammar_by_name(name).unwrap()).unwrap();
+            println!();
+            println!("[+] Costs of the {} grammar:", name);
+            for line in grammar.report().lines() {
+                println!("    {}", line);
+            }
+        }
+        return;
+    }
+
+    // Regenerate a single input of a manifest from its grammar and Rng state
+    // (the same `--len` has to be given as for the manifest).
+    if let Some(entry) = cli::option("--regenerate") {
+        let (name, state) = entry
+            .split_once(':')
+            .expect("--regenerate takes <grammar>:<rng state>");
+        let grammar = grammar_by_name(name).unwrap_or_else(|| panic!("Unknown grammar {}", name));
+        let grammar = GrammarCost::try_from(grammar).unwrap();
+        let mut rng = Rng::from_state_hex(rng.initialseed, state)
+            .unwrap_or_else(|| panic!("Invalid rng state {}", state));
+        println!("{}", generate(&mut rng, &grammar, len).all_leafs());
+        return;
+    }
+
+    if let Some(mode) = cli::option("--golden") {
+        let dir = PathBuf::from(cli::option("--golden-dir").unwrap_or("golden".to_string()));
+        match mode.as_str() {
+            "save" => save_golden(&dir, &names, rng.initialseed, n, len),
+            "check" => check_golden(&dir),
+            _ => panic!("Invalid --golden {}, expected save or check", mode),
+        }
+        return;
+    }
+
+    println!(
+        "[+] Generating {} inputs per grammar ({}) into {} with {} threads{}",
+        n,
+        names.join(", "),
+        out.display(),
+        threads,
+        match len {
+            Some(len) => format!(", target length {}", len),
+            None => String::new(),
+        }
+    );
+
+    let grammars: Vec<(&str, GrammarCost)> = names
+        .iter()
+        .map(|name| {
+            let grammar = GrammarCost::try_from(grammar_by_name(name).unwrap()).unwrap();
+            fs::create_dir_all(out.join(name)).unwrap();
+            (*name, grammar)
+        })
+        .collect();
+
+    // One Rng per thread, split off the main one. Each thread generates the
+    // inputs whose index is its number modulo the number of threads, so the
+    // output only depends on the seed and the number of threads.
+    let start = Instant::now();
+    let rngs: Vec<Rng> = (0..threads).map(|_| rng.split()).collect();
+    let mut samples: Vec<Sample> = thread::scope(|scope| {
+        let handles: Vec<_> = rngs
+            .into_iter()
+            .enumerate()
+            .map(|(thread, mut rng)| {
+                let grammars = &grammars;
+                let out = &out;
+                scope.spawn(move || {
+                    let mut samples = Vec::new();
+                    for (name, grammar) in grammars.iter() {
+                        for index in (thread..n).step_by(threads) {
+                            let state = rng.state_hex();
+                            let tree = generate(&mut rng, grammar, len);
+                            samples.push(write_sample(out, name, index, thread, state, &tree));
+                        }
+                    }
+                    samples
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    let elapsed = start.elapsed();
+
+    samples.sort_by_key(|sample| {
+        let grammar = names.iter().position(|name| *name == sample.grammar);
+        (grammar, sample.index)
+    });
+    let manifest: String = samples
+        .iter()
+        .map(|sample| sample.to_json(rng.initialseed) + "\n")
+        .collect();
+    fs::write(out.join("manifest.jsonl"), manifest).unwrap();
+
+    println!(
+        "[+] Wrote {} inputs and {} in {:.2}s",
+        samples.len(),
+        out.join("manifest.jsonl").display(),
+        elapsed.as_secs_f64()
+    );
+    for name in names.iter() {
+        let sizes: Vec<usize> = samples
+            .iter()
+            .filter(|sample| sample.grammar == *name)
+            .map(|sample| sample.size)
+            .collect();
+        let depths: Vec<usize> = samples
+            .iter()
+            .filter(|sample| sample.grammar == *name)
+            .map(|sample| sample.depth)
+            .collect();
+        println!(
+            "    - {:6} size {:6.1} (max {:5}), depth {:5.1} (max {:3})",
+            name,
+            mean(&sizes),
+            sizes.iter().max().unwrap_or(&0),
+            mean(&depths),
+            depths.iter().max().unwrap_or(&0)
+        );
+    }
+}
+
+/// Generate one derivation tree: with `--len`, of about that length, otherwise
+/// with the usual three expansion phases.
+fn generate(rng: &mut Rng, grammar: &GrammarCost, len: Option<usize>) -> Tree {
+    match len {
+        Some(len) => grammarfuzzer::fuzz_tree_with_target_len(rng, grammar, len),
+        None => grammarfuzzer::fuzz_tree_(rng, grammar).unwrap(),
+    }
+}
+
+/// Store the inputs of the selected grammars with the given seed and options
+/// as golden files.
+fn save_golden(dir: &Path, names: &[&str], seed: u64, n: usize, len: Option<usize>) {
+    for name in names {
+        let key = golden::Key {
+            grammar: name.to_string(),
+            seed,
+            n,
+            len,
+        };
+        let grammar = GrammarCost::try_from(grammar_by_name(name).unwrap()).unwrap();
+        golden::save(dir, &key, &grammar).unwrap();
+        println!("[+] Saved {}", dir.join(key.file_name()).display());
+    }
+}
+
+/// Regenerate the inputs of all golden files and compare them; exits with
+/// status 1 if any differ.
+fn check_golden(dir: &Path) {
+    let results = golden::check_all(dir, |name| {
+        grammar_by_name(name).map(|grammar| GrammarCost::try_from(grammar).unwrap())
+    })
+    .unwrap_or_else(|err| panic!("Can't read golden files in {}: {}", dir.display(), err));
+
+    let mut failures = 0;
+    for (name, outcome) in results.iter() {
+        match outcome {
+            golden::Outcome::Same => println!("    - {:32} same", name),
+            golden::Outcome::Differs(i, golden, regenerated) => {
+                let show = |s: &Option<String>| s.clone().unwrap_or("(none)".to_string());
+                println!("[!] {}: input {} differs", name, i);
+                println!("        golden:      {}", show(golden));
+                println!("        regenerated: {}", show(regenerated));
+                failures += 1;
+            }
+            golden::Outcome::Invalid(err) => {
+                println!("[!] {}: {}", name, err);
+                failures += 1;
+            }
+        }
+    }
+    if failures > 0 {
+        println!();
+        println!(
+            "[!] {} of {} golden files differ (update them with --golden save if the change is intended)",
+            failures,
+            results.len()
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Write the string of the tree to `<out>/<grammar>/<index>`.
+fn write_sample(
+    out: &Path,
+    grammar: &'static str,
+    index: usize,
+    thread: usize,
+    rng: String,
+    tree: &Tree,
+) -> Sample {
+    let input = tree.all_leafs();
+    let file = format!("{}/{:06}", grammar, index);
+    fs::write(out.join(&file), &input).unwrap();
+    Sample {
+        grammar,
+        index,
+        thread,
+        rng,
+        file,
+        size: input.len(),
+        depth: tree.depth(),
+        nodes: tree.node_count(),
+        sha256: sha256::sha256_hex(input.as_bytes()),
+    }
+}
+
+fn mean(values: &[usize]) -> f64 {
+    values.iter().sum::<usize>() as f64 / values.len().max(1) as f64
+}
@@ -0,0 +1,320 @@
+// SPDX-FileCopyrightText: 2018 xoshiro256** and splitmix64 implementation: David Blackman and Sebastiano Vigna
+// SPDX-License-Identifier: LicenseRef-PublicDomainRng
+//
+// SPDX-FileCopyrightText: 2025 Choices and bisect implementation translated from Python; original code: Python Software Foundation
+// SPDX-License-Identifier: PSF-2.0
+//
+// SPDX-FileCopyrightText: 2025 Rest of implementation and scaffolding: stfnw
+// SPDX-License-Identifier: MIT
+
+/// Pseudo-random generator xoshiro256** seeded with splitmix64.
+/// From https://prng.di.unimi.it/xoshiro256starstar.c
+/// and https://prng.di.unimi.it/splitmix64.c
+/// by David Blackman and Sebastiano Vigna.
+#[allow(dead_code)]
+pub struct Rng {
+    pub initialseed: u64,
+    state: [u64; 4],
+}
+
+#[allow(dead_code)]
+impl Rng {
+    /// Create a new PRNG with a seed based on current time.
+    pub fn new() -> Self {
+        // The system clock (and not the timestamp counter of x86, `_rdtsc`),
+        // so that this works on every platform. The process id separates
+        // processes started in the same clock tick.
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Self::seeded(nanos ^ ((std::process::id() as u64) << 32))
+    }
+
+    /// Create a new PRNG from a seed value.
+    pub fn seeded(initialseed: u64) -> Self {
+        let mut seed = initialseed;
+        let mut state = [0, 0, 0, 0];
+        (state[0], seed) = Self::splitmix64(seed);
+        (state[1], seed) = Self::splitmix64(seed);
+        (state[2], seed) = Self::splitmix64(seed);
+        (state[3], _) = Self::splitmix64(seed);
+        Self { initialseed, state }
+    }
+
+    fn splitmix64(seed: u64) -> (u64, u64) {
+        let seed_ = seed.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = seed_;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        (z ^ (z >> 31), seed_)
+    }
+
+    /// Create new random number and advance the internal state.
+    pub fn next(&mut self) -> u64 {
+        let result = (self.state[1].wrapping_mul(5))
+            .rotate_left(7)
+            .wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+
+        self.state[2] ^= t;
+
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    /// Advance the state by 2^128 calls to `next`. This can be used to
+    /// generate 2^128 non-overlapping subsequences.
+    /// From https://prng.di.unimi.it/xoshiro256starstar.c
+    pub fn jump(&mut self) {
+        const JUMP: [u64; 4] = [
+            0x180ec6d33cfd0aba,
+            0xd5a61266f0c9392c,
+            0xa9582618e03fc9aa,
+            0x39abdc4529b1661c,
+        ];
+
+        let mut s = [0, 0, 0, 0];
+        for jump in JUMP {
+            for b in 0..64 {
+                if jump & (1 << b) != 0 {
+                    s[0] ^= self.state[0];
+                    s[1] ^= self.state[1];
+                    s[2] ^= self.state[2];
+                    s[3] ^= self.state[3];
+                }
+                self.next();
+            }
+        }
+        self.state = s;
+    }
+
+    /// Split off a new independent PRNG (e.g. for a worker thread). The new
+    /// PRNG continues with the current state, while this one jumps ahead by
+    /// 2^128 steps. This way all streams derived from the same initial seed
+    /// are deterministic and don't overlap.
+    pub fn split(&mut self) -> Self {
+        let res = Self {
+            initialseed: self.initialseed,
+            state: self.state,
+        };
+        self.jump();
+        res
+    }
+
+    /// The current internal state as 64 hex digits, from which
+    /// `from_state_hex` continues with the same random numbers.
+    pub fn state_hex(&self) -> String {
+        self.state
+            .iter()
+            .map(|word| format!("{:016x}", word))
+            .collect()
+    }
+
+    /// Recreate a PRNG from a state saved with `state_hex`.
+    pub fn from_state_hex(initialseed: u64, hex: &str) -> Option<Self> {
+        if hex.len() != 64 || !hex.is_ascii() {
+            return None;
+        }
+        let mut state = [0; 4];
+        for (i, word) in state.iter_mut().enumerate() {
+            *word = u64::from_str_radix(&hex[i * 16..(i + 1) * 16], 16).ok()?;
+        }
+        Some(Self { initialseed, state })
+    }
+
+    /// Create random u64.
+    pub fn u64(&mut self) -> u64 {
+        self.next()
+    }
+
+    /// Create random float in [0,1.0).
+    pub fn f64(&mut self) -> f64 {
+        (self.u64() as f64) / (u64::MAX as f64)
+    }
+
+    /// Create random number in given range [min,max) (`max` exclusive, like
+    /// Python's `randrange`).
+    /// Uniformly distributed: the modulo alone would prefer the small results
+    /// when the size of the range doesn't divide 2^64, so the (2^64 mod range)
+    /// smallest random numbers are rejected. This happens with probability
+    /// below range/2^64, so in practice the results are the same as those of
+    /// the plain modulo.
+    pub fn range(&mut self, min: u64, max: u64) -> u64 {
+        assert!(min < max, "{} >= {}", min, max);
+        let range = max - min;
+        let threshold = range.wrapping_neg() % range; // 2^64 mod range
+        loop {
+            let x = self.next();
+            if x >= threshold {
+                return min + (x % range);
+            }
+        }
+    }
+
+    /// Create random number in range [0,max).
+    pub fn int(&mut self, max: u64) -> u64 {
+        self.range(0, max)
+    }
+
+    /// Create random index in range [0,max), e.g. a position in a slice.
+    pub fn usize_below(&mut self, max: usize) -> usize {
+        self.int(max as u64) as usize
+    }
+
+    /// Create random byte in the given range, with both bounds inclusive (so
+    /// that all bytes up to 0xff can be given): `rng.u8_range(0x20..=0x7e)`.
+    pub fn u8_range(&mut self, range: std::ops::RangeInclusive<u8>) -> u8 {
+        let (min, max) = range.into_inner();
+        self.range(min as u64, max as u64 + 1) as u8
+    }
+
+    /// Fill a buffer with random bytes (eight bytes per random number).
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            let bytes = self.next().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// Create a random sequence of bytes.
+    pub fn bytes(&mut self, len: u64) -> Vec<u8> {
+        let mut res = vec![0; len as usize];
+        self.fill_bytes(&mut res);
+        res
+    }
+
+    /// Create a random sequence of bytes where each byte lies in the given
+    /// range (both bounds inclusive).
+    pub fn bytes_range(&mut self, len: u64, range: std::ops::RangeInclusive<u8>) -> Vec<u8> {
+        (0..len).map(|_| self.u8_range(range.clone())).collect()
+    }
+
+    /// Create a random ascii string.
+    pub fn ascii(&mut self, len: u64) -> String {
+        let res = self.bytes_range(len, 0..=0x7f);
+        String::from_utf8(res).unwrap()
+    }
+
+    /// Create a random printable ascii string.
+    pub fn ascii_printable(&mut self, len: u64) -> String {
+        let res = self.bytes_range(len, 0x20..=0x7e);
+        String::from_utf8(res).unwrap()
+    }
+
+    /// Randomly choose an element of a slice.
+    pub fn choice<'a, T>(&mut self, v: &'a [T]) -> &'a T {
+        let pos = self.usize_below(v.len());
+        &v[pos]
+    }
+
+    /// Randomly choose one element from a slice given weights.
+    /// Translated from https://github.com/python/cpython/blob/9634085af3670b1eb654e3c7820aca66f358f39f/Lib/random.py#L460
+    /// and https://github.com/python/cpython/blob/9634085af3670b1eb654e3c7820aca66f358f39f/Lib/bisect.py#L21
+    pub fn choice_w<'a, T>(&mut self, v: &'a [T], weights: &[f64]) -> &'a T {
+        assert!(v.len() == weights.len(), "{} != {}", v.len(), weights.len());
+        let mut cumuluative_weights = Vec::new();
+        let mut tmp = 0.0;
+        for w in weights {
+            assert!(*w >= 0.0, "Weight must be non-negative {}", w);
+            tmp += w;
+            cumuluative_weights.push(tmp);
+        }
+        self.choice_cw(v, &cumuluative_weights)
+    }
+
+    pub fn choice_cw<'a, T>(&mut self, v: &'a [T], cumulative_weights: &[f64]) -> &'a T {
+        assert!(
+            v.len() == cumulative_weights.len(),
+            "{} != {}",
+            v.len(),
+            cumulative_weights.len()
+        );
+
+        let total = *cumulative_weights.last().unwrap();
+        assert!(total > 0.0, "Total weight must be non-zero: {}", total);
+
+        let pos = bisect(
+            cumulative_weights,
+            self.f64() * total,
+            0,
+            cumulative_weights.len() - 1,
+        );
+
+        &v[pos]
+    }
+
+    /// Shuffle a slice in place (Fisher-Yates shuffle).
+    pub fn shuffle<T>(&mut self, v: &mut [T]) {
+        for i in (1..v.len()).rev() {
+            let j = self.usize_below(i + 1);
+            v.swap(i, j);
+        }
+    }
+
+    /// Randomly choose `k` distinct elements of a slice (sampling without
+    /// replacement). The order of the returned elements is random as well.
+    pub fn sample<'a, T>(&mut self, v: &'a [T], k: usize) -> Vec<&'a T> {
+        assert!(
+            k <= v.len(),
+            "Sample larger than population {} > {}",
+            k,
+            v.len()
+        );
+        // Partial Fisher-Yates shuffle over the indices: only the first k
+        // positions need to be determined.
+        let mut indices: Vec<usize> = (0..v.len()).collect();
+        for i in 0..k {
+            let j = self.range(i as u64, v.len() as u64) as usize;
+            indices.swap(i, j);
+        }
+        indices[..k].iter().map(|&i| &v[i]).collect()
+    }
+
+    /// Create random float from a normal distribution with mean `mu` and
+    /// standard deviation `sigma` (Box-Muller transform).
+    pub fn gaussian(&mut self, mu: f64, sigma: f64) -> f64 {
+        let u1 = self.f64_nonzero();
+        let u2 = self.f64();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        mu + sigma * z
+    }
+
+    /// Create random number from a geometric distribution: the number of
+    /// failed Bernoulli trials with success probability `p` before the first
+    /// success (support 0, 1, 2, ...). Uses inversion of the CDF.
+    pub fn geometric(&mut self, p: f64) -> u64 {
+        assert!(0.0 < p && p <= 1.0, "Probability must be in (0,1]: {}", p);
+        if p == 1.0 {
+            return 0;
+        }
+        (self.f64_nonzero().ln() / (1.0 - p).ln()).floor() as u64
+    }
+
+    /// Create random float in (0,1.0) (for taking logarithms).
+    fn f64_nonzero(&mut self) -> f64 {
+        loop {
+            let u = self.f64();
+            if 0.0 < u && u < 1.0 {
+                return u;
+            }
+        }
+    }
+}
+
+fn bisect(v: &[f64], x: f64, mut lo: usize, mut hi: usize) -> usize {
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if x < v[mid] {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    lo
+}
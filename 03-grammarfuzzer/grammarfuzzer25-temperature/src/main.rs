@@ -0,0 +1,148 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod examplegrammars;
+mod grammarfuzzer;
+mod greybox;
+mod rng;
+mod runner;
+mod temperature;
+
+use std::io::Write;
+use std::time::Instant;
+
+use grammarfuzzer::GrammarCost;
+use greybox::Mutations;
+use rng::Rng;
+use temperature::TemperatureControl;
+
+fn main() {
+    let seed = cli::seed().unwrap_or(42);
+    println!("[+] Running with random seed {}", seed);
+
+    let n = cli::iterations(2000);
+    let quiet = cli::quiet();
+
+    let runner = runner::GcovRunner::new(&cli::target("expr"));
+    let grammar: GrammarCost = examplegrammars::expr_grammar().into();
+
+    // Initial temperature and the knobs of the temperature control.
+    let mut control = TemperatureControl::adaptive(cli::parsed("--temperature", 0.5));
+    control.step = cli::parsed("--step", control.step);
+    control.window = cli::parsed("--window", control.window);
+    control.max_finds = cli::parsed("--max-finds", control.max_finds);
+    println!(
+        "[+] Temperature {}, step {} per window of {} fuzz cases, at most {} finds per window",
+        control.temperature, control.step, control.window, control.max_finds
+    );
+
+    // Tree mutations of the greybox fuzzers (all by default).
+    let mutations = match cli::option("--mutations").as_deref() {
+        None | Some("all") => Mutations::All,
+        Some("structural") => Mutations::Structural,
+        Some("terminal") => Mutations::Terminal,
+        Some(other) => panic!("Unknown mutations {}", other),
+    };
+
+    let mut coverage_cumuls = Vec::new();
+    let mut temperature_log = Vec::new();
+
+    // Generating all inputs from the grammar (without any feedback) is just
+    // the special case where all fuzz cases are seeds. The fixed phases are
+    // those of `expand_tree` (MaxCost, then Random, then MinCost).
+    for (name, n_seeds, temperature) in [
+        ("Blackbox grammar fuzzer", n, None),
+        ("Greybox grammar fuzzer, fixed phases", 10, None),
+        (
+            "Greybox grammar fuzzer, fixed temperature",
+            10,
+            Some(TemperatureControl::fixed(control.temperature)),
+        ),
+        (
+            "Greybox grammar fuzzer, temperature control",
+            10,
+            Some(control.clone()),
+        ),
+    ] {
+        // Same seed for each fuzzer.
+        let mut rng = Rng::seeded(seed);
+        let mut stats = greybox::Statistics {
+            temperature,
+            ..Default::default()
+        };
+
+        let start = Instant::now();
+        greybox::run(
+            &mut rng, &grammar, &runner, &mut stats, n, n_seeds, mutations,
+        );
+        let end = Instant::now();
+
+        println!();
+        println!("[+] {}:", name);
+        println!(
+            "    - Runtime:                   {:0.4}s",
+            (end - start).as_secs_f64()
+        );
+        println!("    - Trees in corpus:           {}", stats.corpus.len());
+        if quiet {
+            println!(
+                "    - All coverage:              {}",
+                stats.coverage_all.len()
+            );
+        } else {
+            println!(
+                "    - All coverage:              {} {:?}",
+                stats.coverage_all.len(),
+                stats.coverage_all
+            );
+        }
+
+        if let Some(temperature) = &stats.temperature {
+            let (lo, hi) = temperature.range();
+            println!(
+                "    - Temperature:               {:.1} at the end (between {:.1} and {:.1})",
+                temperature.temperature, lo, hi
+            );
+            if temperature.step > 0.0 {
+                temperature_log = temperature.log.clone();
+            }
+        }
+
+        let mut inputs: Vec<_> = stats
+            .corpus
+            .iter()
+            .map(|(tree, _)| tree.all_leafs())
+            .collect();
+        inputs.sort_by_key(|input| input.len());
+        println!(
+            "    - Mean input length:         {:.1}",
+            inputs.iter().map(|input| input.len()).sum::<usize>() as f64
+                / inputs.len().max(1) as f64
+        );
+        if !quiet {
+            println!("    - Shortest inputs in corpus:");
+            for input in inputs.iter().take(5) {
+                println!("        {}", input);
+            }
+        }
+
+        coverage_cumuls.push(stats.coverage_cumul);
+    }
+
+    let mut logfile = std::fs::File::create(cli::out_file("plot.data")).unwrap();
+    for i in 0..n {
+        let columns: Vec<_> = coverage_cumuls.iter().map(|c| c[i].to_string()).collect();
+        writeln!(logfile, "{} {}", i, columns.join(" ")).unwrap();
+    }
+    logfile.flush().unwrap();
+
+    // Temperature of the temperature control over time.
+    let mut logfile = std::fs::File::create(cli::out_file("temperature.data")).unwrap();
+    for (fuzz_cases, temperature) in temperature_log {
+        writeln!(logfile, "{} {}", fuzz_cases, temperature).unwrap();
+    }
+    logfile.flush().unwrap();
+}
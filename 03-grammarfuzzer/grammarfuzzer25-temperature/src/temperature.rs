@@ -0,0 +1,93 @@
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Temperature control for the coverage-guided grammar fuzzer: the temperature
+// decides how the fuzzer expands nonterminals (see `expand_tree_tempered`),
+// from cold (mostly `MinCost`: small trees) to hot (mostly `MaxCost` and
+// `Random`: large trees). Instead of a fixed choice, the temperature follows
+// the rate of coverage discoveries: after each window of fuzz cases without
+// a new entry in the corpus (finds stall), it is raised to explore larger
+// inputs; after a window with many new entries (the corpus explodes, mostly
+// with large inputs that differ in details), it is lowered to produce
+// smaller inputs again.
+
+/// Temperature of the expansions, fixed or adapted to the discovery rate.
+#[derive(Clone, Debug)]
+pub struct TemperatureControl {
+    /// Current temperature, between `min` and `max`.
+    pub temperature: f64,
+    pub min: f64,
+    pub max: f64,
+
+    /// Change of the temperature per adjustment (0 for a fixed temperature).
+    pub step: f64,
+
+    /// Number of fuzz cases between adjustments.
+    pub window: usize,
+
+    /// More new corpus entries than this in a window are an explosion.
+    pub max_finds: usize,
+
+    /// Temperature after each window, with the number of fuzz cases so far.
+    pub log: Vec<(usize, f64)>,
+
+    finds: usize,
+    fuzz_cases: usize,
+}
+
+impl TemperatureControl {
+    /// Constant temperature.
+    pub fn fixed(temperature: f64) -> Self {
+        Self {
+            step: 0.0,
+            ..Self::adaptive(temperature)
+        }
+    }
+
+    /// Temperature adapted to the discovery rate, starting at `temperature`,
+    /// with default knobs (see the fields to change them).
+    pub fn adaptive(temperature: f64) -> Self {
+        assert!((0.0..=1.0).contains(&temperature));
+        Self {
+            temperature,
+            min: 0.0,
+            max: 1.0,
+            step: 0.1,
+            window: 100,
+            max_finds: 10,
+            log: vec![(0, temperature)],
+            finds: 0,
+            fuzz_cases: 0,
+        }
+    }
+
+    /// Record the result of a fuzz case (whether it added to the corpus),
+    /// and adjust the temperature at the end of a window.
+    pub fn record(&mut self, found: bool) {
+        self.fuzz_cases += 1;
+        self.finds += found as usize;
+        if !self.fuzz_cases.is_multiple_of(self.window) {
+            return;
+        }
+
+        if self.finds == 0 {
+            self.temperature += self.step;
+        } else if self.finds > self.max_finds {
+            self.temperature -= self.step;
+        }
+        // Rounded, so that repeated steps don't accumulate rounding errors.
+        self.temperature = ((self.temperature * 1e6).round() / 1e6).clamp(self.min, self.max);
+        self.log.push((self.fuzz_cases, self.temperature));
+        self.finds = 0;
+    }
+
+    /// Lowest and highest temperature so far.
+    pub fn range(&self) -> (f64, f64) {
+        self.log
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &(_, t)| {
+                (lo.min(t), hi.max(t))
+            })
+    }
+}
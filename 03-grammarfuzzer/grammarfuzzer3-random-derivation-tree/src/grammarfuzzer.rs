@@ -3,6 +3,9 @@
 //
 // SPDX-License-Identifier: MIT
 
+// Not all parts of the grammar API are used by the examples.
+#![allow(dead_code)]
+
 use std::collections::VecDeque;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -123,10 +126,7 @@ impl Grammar {
     /// Helper function for adding lots of productions which each have only one
     /// alternative to the grammar.
     fn add_productions(&mut self, nonterminal: &str, expansions: &[&str]) {
-        let expansions: Vec<_> = expansions
-            .into_iter()
-            .map(|x| vec![x.to_string()])
-            .collect();
+        let expansions: Vec<_> = expansions.iter().map(|x| vec![x.to_string()]).collect();
         match self.0.get_mut(nonterminal) {
             Some(exps) => exps.extend(expansions),
             None => {
@@ -516,11 +516,12 @@ impl Derivation {
     /// (Does iterative pre-order traversal of the tree).
     /// It can be rendered e.g. as follows: dot -Tpdf tree.dot -o tree.pdf
     pub fn to_dot(&self) -> String {
-        let mut lines = Vec::new();
-        lines.push("digraph Derivation {".to_string());
-        lines.push("".to_string());
-        lines.push("    node [shape=plain];".to_string());
-        lines.push("".to_string());
+        let mut lines = vec![
+            "digraph Derivation {".to_string(),
+            "".to_string(),
+            "    node [shape=plain];".to_string(),
+            "".to_string(),
+        ];
 
         let mut node_count = 0;
         let mut queue: VecDeque<(&Derivation, Option<usize>)> = VecDeque::new();
@@ -583,27 +584,25 @@ impl Derivation {
     /// unexpanded nonterminals) into one string.
     pub fn all_leafs(&self) -> String {
         let mut res: Vec<String> = Vec::new();
+        self.all_leafs_(&mut res);
+        res.join("")
+    }
 
-        let mut queue: VecDeque<&Derivation> = VecDeque::new();
-        queue.push_back(self);
-
-        while let Some(cur) = queue.pop_front() {
-            match cur {
-                Derivation::NT(name, children) => {
-                    if children.is_empty() {
-                        res.push(format!(" <{}> ", name));
-                    }
-
-                    for child in children.iter() {
-                        queue.push_back(child);
-                    }
+    /// Collect the leafs in order from left to right (depth-first, a
+    /// breadth-first traversal would mix up leafs of different depths).
+    fn all_leafs_(&self, res: &mut Vec<String>) {
+        match self {
+            Derivation::NT(name, children) => {
+                if children.is_empty() {
+                    res.push(format!(" <{}> ", name));
+                }
+                for child in children.iter() {
+                    child.all_leafs_(res);
                 }
-
-                Derivation::T(name) => res.push(name.clone()),
             }
-        }
 
-        res.join("")
+            Derivation::T(name) => res.push(name.clone()),
+        }
     }
 
     /// Count the number of nodes that can be expanded (nonterminals that do
@@ -660,22 +659,38 @@ pub fn fuzz(rng: &mut Rng, grammar: &Grammar) -> String {
     fuzz_tree(rng, grammar).all_leafs()
 }
 
+/// Depth of the derivation tree from which on nonterminals are no longer
+/// expanded randomly, but with minimum cost (so that the tree is finished
+/// quickly).
+const MAX_DEPTH: usize = 10;
+
 /// Create a random derivation tree from a context-free grammar.
 pub fn fuzz_tree(rng: &mut Rng, grammar: &Grammar) -> Derivation {
     let mut tree = Derivation::NT("start".to_string(), Vec::new());
-    let possible_expansions = grammar
-        .0
-        .get("expr")
-        .expect(&format!("Couldn't get key {}", "exprTODO"));
-
-    let random_expansion = rng.choice(possible_expansions);
-
-    fuzz_tree_(rng, grammar, &mut tree);
+    fuzz_tree_(rng, grammar, &mut tree, 0);
     tree
 }
 
-pub fn fuzz_tree_(rng: &mut Rng, grammar: &Grammar, derivation: &mut Derivation) {
-    todo!()
+/// Naive recursive expansion: expand each not-yet-expanded nonterminal with a
+/// random expansion, then recurse into the children. Purely random expansion
+/// of recursive grammars can grow without bounds, therefore from `MAX_DEPTH`
+/// on the expansion with minimum cost is chosen instead.
+pub fn fuzz_tree_(rng: &mut Rng, grammar: &Grammar, derivation: &mut Derivation, depth: usize) {
+    if let Derivation::NT(_, children) = derivation {
+        if children.is_empty() {
+            *derivation = if depth < MAX_DEPTH {
+                expand_node_randomly(rng, grammar, derivation)
+            } else {
+                expand_node_min_cost(grammar, derivation)
+            };
+        }
+    }
+
+    if let Derivation::NT(_, children) = derivation {
+        for child in children.iter_mut() {
+            fuzz_tree_(rng, grammar, child, depth + 1);
+        }
+    }
 }
 
 pub fn expand_tree_once(rng: &mut Rng, grammar: &Grammar, tree: Derivation) -> Derivation {
@@ -716,8 +731,8 @@ pub fn expand_tree_once(rng: &mut Rng, grammar: &Grammar, tree: Derivation) -> D
     }
 }
 
-pub fn expand_node(rng: &mut Rng, grammar: &Grammar, tree: &Derivation) -> Derivation {
-    // expand_node_randomly(rng, grammar, tree)
+pub fn expand_node(_rng: &mut Rng, grammar: &Grammar, tree: &Derivation) -> Derivation {
+    // expand_node_randomly(_rng, grammar, tree)
     expand_node_min_cost(grammar, tree)
 }
 
@@ -726,13 +741,10 @@ fn expand_node_randomly(rng: &mut Rng, grammar: &Grammar, tree: &Derivation) ->
     let expansions = grammar
         .0
         .get(&name)
-        .expect(&format!("Couldn't get expansion for symbol {}", name));
+        .unwrap_or_else(|| panic!("Couldn't get expansion for symbol {}", name));
 
     let random_expansion = rng.choice(expansions);
-    let random_expansion = random_expansion
-        .into_iter()
-        .map(|s| ts(s))
-        .collect::<Vec<_>>();
+    let random_expansion = random_expansion.iter().map(|s| ts(s)).collect::<Vec<_>>();
 
     Derivation::NT(
         name.trim_start_matches("<")
@@ -818,7 +830,7 @@ fn expand_node_by_cost(
     let expansions = grammar
         .0
         .get(&name)
-        .expect(&format!("Couldn't get expansion for symbol {}", name));
+        .unwrap_or_else(|| panic!("Couldn't get expansion for symbol {}", name));
 
     let mut seen = BTreeSet::new();
     seen.insert(name.clone());
@@ -832,7 +844,7 @@ fn expand_node_by_cost(
             .max_by_key(|expansion| expansion_cost(grammar, expansion, &seen)),
     }
     .unwrap();
-    let expansion = expansion.into_iter().map(|s| ts(s)).collect::<Vec<_>>();
+    let expansion = expansion.iter().map(|s| ts(s)).collect::<Vec<_>>();
 
     Derivation::NT(
         name.trim_start_matches("<")
@@ -3,43 +3,50 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod cli;
 mod grammarfuzzer;
 mod rng;
 
-use grammarfuzzer::{
-    expand_node, expand_tree_once, expr_grammar_ebnf, fuzz, fuzz_tree, symbol_cost, tnt, ts, tt,
-    SymbolCost,
-};
+use grammarfuzzer::{expand_tree_once, expr_grammar_ebnf, fuzz, fuzz_tree, tnt, tt};
 use rng::Rng;
 
 fn main() {
+    let quiet = cli::quiet();
+
     let grammar = expr_grammar_ebnf().to_bnf();
-    println!("[+] Expression grammar");
-    println!("{:?}", grammar);
-    println!();
+    if !quiet {
+        println!("[+] Expression grammar");
+        println!("{:?}", grammar);
+        println!();
+    }
 
     let derivation = tnt(
         "start",
         &[tnt("expr", &[tnt("expr", &[]), tt("+"), tnt("term", &[])])],
     );
-    println!("[+] Graphviz / dot format:");
-    println!("{}", derivation.to_dot());
-    println!();
+    if !quiet {
+        println!("[+] Graphviz / dot format:");
+        println!("{}", derivation.to_dot());
+        println!();
+    }
 
-    let mut rng = Rng::new();
+    let mut rng = cli::seed().map_or_else(Rng::new, Rng::seeded);
     println!("[+] Running with random seed {}", rng.initialseed);
 
     let derivation = expand_tree_once(&mut rng, &grammar, derivation);
     let derivation = expand_tree_once(&mut rng, &grammar, derivation);
-    println!("{}", derivation.to_dot());
+    if !quiet {
+        println!("{}", derivation.to_dot());
+        println!();
+    }
 
-    /*
     let randomtree = fuzz_tree(&mut rng, &grammar);
-    println!("{:?}", randomtree);
+    if !quiet {
+        println!("{:?}", randomtree);
+    }
     println!("{}", randomtree.all_leafs());
     println!();
 
     println!("{}", fuzz(&mut rng, &grammar));
     println!();
-    */
 }
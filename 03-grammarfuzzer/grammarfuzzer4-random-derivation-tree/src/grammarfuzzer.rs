@@ -3,6 +3,9 @@
 //
 // SPDX-License-Identifier: MIT
 
+// Not all parts of the grammar API are used by the example.
+#![allow(dead_code)]
+
 use std::collections::VecDeque;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -110,10 +113,7 @@ impl Grammar {
     /// Helper function for adding lots of productions which each have only one
     /// alternative to the grammar.
     pub fn add_productions(&mut self, nonterminal: &str, expansions: &[&str]) {
-        let expansions: Vec<_> = expansions
-            .into_iter()
-            .map(|x| vec![x.to_string()])
-            .collect();
+        let expansions: Vec<_> = expansions.iter().map(|x| vec![x.to_string()]).collect();
         match self.0.get_mut(nonterminal) {
             Some(exps) => exps.extend(expansions),
             None => {
@@ -186,8 +186,12 @@ impl Grammar {
 #[derive(Clone, Debug)]
 pub enum Derivation {
     /// Nonterminal symbol (inner node in the tree) consisting of a symbol name
-    /// and a list of child nodes / children.
-    NT(String, Vec<Derivation>),
+    /// and a list of child nodes / children, and the number of nodes in the
+    /// subtree that can still be expanded (see `possible_expansions`).
+    /// The number is cached so that it doesn't have to be computed by walking
+    /// the whole subtree, which made `expand_tree` quadratic in the size of the
+    /// tree.
+    NT(String, Vec<Derivation>, usize),
     /// Terminal symbol (leaf of the tree) consisting only of a symbol name
     /// (= final text for this tree part); it has no children.
     T(String),
@@ -196,7 +200,7 @@ pub enum Derivation {
 // Shorthand functions for easier construction of derivation trees.
 // Similar to grammar shorthand functions. Prefix `t` stands for `tree`.
 #[rustfmt::skip]
-fn tnt(name: &str, children: &[Derivation]) -> Derivation { Derivation::NT(name.to_string(), children.to_vec()) }
+fn tnt(name: &str, children: &[Derivation]) -> Derivation { Derivation::nt(name.to_string(), children.to_vec()) }
 #[rustfmt::skip]
 fn tt(name: &str)                           -> Derivation { Derivation::T(name.to_string()) }
 fn ts(s: &str) -> Derivation {
@@ -208,16 +212,27 @@ fn ts(s: &str) -> Derivation {
 }
 
 impl Derivation {
+    /// Create a nonterminal node and compute the number of possible expansions
+    /// from its children.
+    pub fn nt(name: String, children: Vec<Derivation>) -> Self {
+        let expansions = if children.is_empty() {
+            1
+        } else {
+            children.iter().map(|c| c.possible_expansions()).sum()
+        };
+        Derivation::NT(name, children, expansions)
+    }
+
     /// Returns a dot / graphviz definition of the derivation tree / graph.
     /// (Does iterative pre-order traversal of the tree).
     /// It can be rendered e.g. as follows: dot -Tpdf tree.dot -o tree.pdf
     pub fn to_dot(&self) -> String {
-        let mut lines = Vec::new();
-
-        lines.push("digraph Derivation {".to_string());
-        lines.push("".to_string());
-        lines.push("    node [shape=plain];".to_string());
-        lines.push("".to_string());
+        let mut lines = vec![
+            "digraph Derivation {".to_string(),
+            "".to_string(),
+            "    node [shape=plain];".to_string(),
+            "".to_string(),
+        ];
 
         let mut node_count = 0;
         let mut queue: VecDeque<(&Derivation, Option<usize>)> = VecDeque::new();
@@ -237,7 +252,7 @@ impl Derivation {
             }
 
             match cur {
-                Derivation::NT(_, children) => {
+                Derivation::NT(_, children, _) => {
                     for child in children.iter() {
                         queue.push_back((child, Some(node_count)));
                     }
@@ -257,7 +272,7 @@ impl Derivation {
 
     fn get_name(&self) -> String {
         match self {
-            Derivation::NT(name, _) => format!("<{}>", name),
+            Derivation::NT(name, _, _) => format!("<{}>", name),
             Derivation::T(name) => format!("\"{}\"", name),
         }
     }
@@ -286,7 +301,7 @@ impl Derivation {
 
     fn all_leafs_(&self, res: &mut Vec<String>) {
         match self {
-            Derivation::NT(name, children) => {
+            Derivation::NT(name, children, _) => {
                 if children.is_empty() {
                     res.push(format!(" <{}> ", name));
                 }
@@ -302,49 +317,22 @@ impl Derivation {
     /// Count the number of nodes that can be expanded (nonterminals that do
     /// not yet have any children assigned).
     fn possible_expansions(&self) -> usize {
-        let mut res = 0;
-
-        let mut queue: VecDeque<&Derivation> = VecDeque::new();
-        queue.push_back(self);
-
-        while let Some(cur) = queue.pop_front() {
-            match cur {
-                Derivation::NT(_, children) => {
-                    if children.is_empty() {
-                        res += 1;
-                    }
-                    for child in children.iter() {
-                        queue.push_back(child);
-                    }
-                }
-
-                Derivation::T(_) => (),
-            }
+        match self {
+            Derivation::NT(_, _, expansions) => *expansions,
+            Derivation::T(_) => 0,
         }
-
-        res
     }
 
     fn any_possible_expansions(&self) -> bool {
-        let mut queue: VecDeque<&Derivation> = VecDeque::new();
-        queue.push_back(self);
-
-        while let Some(cur) = queue.pop_front() {
-            match cur {
-                Derivation::NT(_, children) => {
-                    if children.is_empty() {
-                        return true;
-                    }
-                    for child in children.iter() {
-                        queue.push_back(child);
-                    }
-                }
+        self.possible_expansions() > 0
+    }
 
-                Derivation::T(_) => (),
-            }
+    /// Number of nodes in the tree.
+    pub fn size(&self) -> usize {
+        match self {
+            Derivation::NT(_, children, _) => 1 + children.iter().map(|c| c.size()).sum::<usize>(),
+            Derivation::T(_) => 1,
         }
-
-        false
     }
 }
 
@@ -355,12 +343,11 @@ pub fn fuzz(rng: &mut Rng, grammar: &Grammar) -> String {
 
 /// Create a random derivation tree from a context-free grammar.
 pub fn fuzz_tree(rng: &mut Rng, grammar: &Grammar) -> Derivation {
-    let empty_tree = Derivation::NT("start".to_string(), Vec::new());
-    let tree = expand_tree(rng, grammar, empty_tree, 300, 500);
-    tree
+    let empty_tree = Derivation::nt("start".to_string(), Vec::new());
+    expand_tree(rng, grammar, empty_tree, 300, 500)
 }
 
-fn expand_tree(
+pub fn expand_tree(
     rng: &mut Rng,
     grammar: &Grammar,
     mut tree: Derivation,
@@ -389,9 +376,14 @@ fn expand_tree_once(
     strategy: ExpandStrategy,
 ) -> Derivation {
     match tree {
-        Derivation::NT(name, children) => {
+        Derivation::NT(name, children, expansions) => {
             if children.is_empty() {
-                expand_node_by_strategy(rng, grammar, &Derivation::NT(name, children), strategy)
+                expand_node_by_strategy(
+                    rng,
+                    grammar,
+                    &Derivation::NT(name, children, expansions),
+                    strategy,
+                )
             } else {
                 let expandable_children: Vec<_> = children
                     .iter()
@@ -418,7 +410,8 @@ fn expand_tree_once(
                     })
                     .collect();
 
-                Derivation::NT(name, children)
+                // Only the counts on the path to the expanded node change.
+                Derivation::nt(name, children)
             }
         }
         Derivation::T(_) => tree,
@@ -488,7 +481,7 @@ fn expand_node_by_strategy(
     let expansions = grammar
         .0
         .get(&name)
-        .expect(&format!("Couldn't get expansion for symbol {}", name));
+        .unwrap_or_else(|| panic!("Couldn't get expansion for symbol {}", name));
 
     let mut seen = BTreeSet::new();
     seen.insert(name.clone());
@@ -497,7 +490,7 @@ fn expand_node_by_strategy(
         ExpandStrategy::Random => rng.choice(expansions),
         ExpandStrategy::MinCost | ExpandStrategy::MaxCost => {
             let costs: Vec<_> = expansions
-                .into_iter()
+                .iter()
                 .map(|expansion| (expansion, expansion_cost(grammar, expansion, &seen)))
                 .collect();
 
@@ -520,9 +513,9 @@ fn expand_node_by_strategy(
             *rng.choice(&choices)
         }
     };
-    let expansion = expansion.into_iter().map(|s| ts(s)).collect::<Vec<_>>();
+    let expansion = expansion.iter().map(|s| ts(s)).collect::<Vec<_>>();
 
-    Derivation::NT(
+    Derivation::nt(
         name.trim_start_matches("<")
             .trim_end_matches(">")
             .to_string(),
@@ -3,27 +3,58 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod cli;
 mod grammarfuzzer;
 mod rng;
 
-use grammarfuzzer::{expr_grammar, fuzz_tree};
+use grammarfuzzer::{expand_tree, expr_grammar, fuzz_tree, Derivation};
 use rng::Rng;
+use std::time::Instant;
 
 fn main() {
+    if cli::flag("--bench") {
+        bench();
+        return;
+    }
+
+    let quiet = cli::quiet();
+
     let grammar = expr_grammar();
-    println!("[+] Expression grammar");
-    println!("{}", grammar);
-    println!();
+    if !quiet {
+        println!("[+] Expression grammar");
+        println!("{}", grammar);
+        println!();
+    }
 
-    let mut rng = Rng::seeded(42);
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
     println!("[+] Running with random seed {}", rng.initialseed);
     println!();
 
     let tree = fuzz_tree(&mut rng, &grammar);
-    println!("{}", tree.to_dot());
+    if !quiet {
+        println!("{}", tree.to_dot());
+    }
     println!("{}", tree.all_leafs());
     // +((-7/0/6/4+4*5+4)*(9+6)-7*2.5-5+0/4-2)*(-4/3-6+5-5)/+6*-8*6*-4--++(7*9-8-8-6)/29/++7.33/9*7+-(4-8)/(8-3+5)*7*2+-(5)*2/0--8+9-8
 }
 
+/// Measure how long it takes to create large trees.
+fn bench() {
+    let grammar = expr_grammar();
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+
+    for nonterminals in [100, 1000, 10000, 30000] {
+        let start = Instant::now();
+        let empty_tree = Derivation::nt("start".to_string(), Vec::new());
+        let tree = expand_tree(&mut rng, &grammar, empty_tree, nonterminals, nonterminals);
+        println!(
+            "{:6} nonterminals: {:7} nodes in {:8.3}s",
+            nonterminals,
+            tree.size(),
+            start.elapsed().as_secs_f64()
+        );
+    }
+}
+
 // Debug trap: rust-lldb target
 // unsafe { core::arch::asm!("int3"); }
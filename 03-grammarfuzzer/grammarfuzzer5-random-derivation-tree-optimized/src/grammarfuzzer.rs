@@ -3,6 +3,9 @@
 //
 // SPDX-License-Identifier: MIT
 
+// Not all parts of the grammar API are used by the examples.
+#![allow(dead_code)]
+
 use std::collections::VecDeque;
 use std::collections::{HashMap, HashSet};
 
@@ -213,7 +216,13 @@ impl std::convert::From<Grammar> for GrammarCost {
 }
 
 /// Derivation tree in a given grammar.
-#[derive(Clone, Debug)]
+///
+/// All operations on trees are iterative (with an explicit stack) instead of
+/// recursive, since trees can get very deep (e.g. with a high number of
+/// expansions, or for degenerate grammars) and would otherwise overflow the
+/// call stack. This includes `Clone` and `Drop`, which are therefore
+/// implemented by hand below.
+#[derive(Debug)]
 pub enum Tree {
     /// Nonterminal symbol (inner node in the tree) consisting of a symbol name
     /// and a list of child nodes / children.
@@ -223,13 +232,52 @@ pub enum Tree {
     T(String),
 }
 
+impl Clone for Tree {
+    /// Iterative post-order traversal: the clones of finished subtrees are
+    /// collected on `done` until all children of their parent are cloned.
+    fn clone(&self) -> Self {
+        let mut done: Vec<Tree> = Vec::new();
+        let mut stack = vec![(self, false)];
+        while let Some((cur, visited)) = stack.pop() {
+            match cur {
+                Tree::NT(name, children) if visited => {
+                    let children = done.split_off(done.len() - children.len());
+                    done.push(Tree::NT(name.clone(), children));
+                }
+                Tree::NT(_, children) => {
+                    stack.push((cur, true));
+                    stack.extend(children.iter().rev().map(|child| (child, false)));
+                }
+                Tree::T(name) => done.push(Tree::T(name.clone())),
+            }
+        }
+        done.pop().unwrap()
+    }
+}
+
+impl Drop for Tree {
+    /// Move all descendants into one flat list before dropping them, so that
+    /// each one is dropped without children (the default drop glue would
+    /// recurse).
+    fn drop(&mut self) {
+        if let Tree::NT(_, children) = self {
+            let mut stack = std::mem::take(children);
+            while let Some(mut cur) = stack.pop() {
+                if let Tree::NT(_, children) = &mut cur {
+                    stack.append(children);
+                }
+            }
+        }
+    }
+}
+
 // Shorthand functions for easier construction of derivation trees.
 // Similar to grammar shorthand functions. Prefix `t` stands for `tree`.
 #[rustfmt::skip]
-fn tnt(name: &str, children: &[Tree]) -> Tree { Tree::NT(name.to_string(), children.to_vec()) }
+pub fn tnt(name: &str, children: &[Tree]) -> Tree { Tree::NT(name.to_string(), children.to_vec()) }
 #[rustfmt::skip]
-fn tt(name: &str)                     -> Tree { Tree::T(name.to_string()) }
-fn ts(s: &str) -> Tree {
+pub fn tt(name: &str)                     -> Tree { Tree::T(name.to_string()) }
+pub fn ts(s: &str) -> Tree {
     if Grammar::is_nonterminal(s) {
         tnt(Grammar::trim_angle_brackets(s), &[])
     } else {
@@ -242,12 +290,12 @@ impl Tree {
     /// (Does iterative pre-order traversal of the tree).
     /// It can be rendered e.g. as follows: dot -Tpdf tree.dot -o tree.pdf
     pub fn to_dot(&self) -> String {
-        let mut lines = Vec::new();
-
-        lines.push("digraph DerivationTree {".to_string());
-        lines.push("".to_string());
-        lines.push("    node [shape=plain];".to_string());
-        lines.push("".to_string());
+        let mut lines = vec![
+            "digraph DerivationTree {".to_string(),
+            "".to_string(),
+            "    node [shape=plain];".to_string(),
+            "".to_string(),
+        ];
 
         let mut node_count = 0;
         let mut queue: VecDeque<(&Tree, Option<usize>)> = VecDeque::new();
@@ -288,6 +336,35 @@ impl Tree {
     /// Get the symbol name as a string. Depending on the kind of symbol, the
     /// symbol name is wrapped into either double quotes (terminal symbol), or
     /// angle brackets (nonterminal symbols).
+    /// All subtrees with the given nonterminal (with angle brackets) as root,
+    /// e.g. for re-using them in `Bindings`.
+    pub fn subtrees(&self, nonterminal: &str) -> Vec<&Tree> {
+        let mut res = Vec::new();
+        let mut stack = vec![self];
+        while let Some(cur) = stack.pop() {
+            if let Tree::NT(_, children) = cur {
+                if cur.get_name() == nonterminal {
+                    res.push(cur);
+                }
+                stack.extend(children.iter().rev());
+            }
+        }
+        res
+    }
+
+    /// Depth of the tree (a single node has depth 0).
+    pub fn depth(&self) -> usize {
+        let mut res = 0;
+        let mut stack = vec![(self, 0)];
+        while let Some((cur, depth)) = stack.pop() {
+            res = res.max(depth);
+            if let Tree::NT(_, children) = cur {
+                stack.extend(children.iter().map(|child| (child, depth + 1)));
+            }
+        }
+        res
+    }
+
     fn get_name(&self) -> String {
         match self {
             Tree::NT(name, _) => format!("<{}>", name),
@@ -313,35 +390,36 @@ impl Tree {
     /// Concatenate all leafs of the derivation tree (terminals, and yet
     /// unexpanded nonterminals) into one string.
     pub fn all_leafs(&self) -> String {
-        let mut res: Vec<String> = Vec::new();
-        self.all_leafs_(&mut res);
-        res.join("")
-    }
+        let mut res = String::new();
 
-    fn all_leafs_(&self, res: &mut Vec<String>) {
-        match self {
-            Tree::NT(name, children) => {
-                if children.is_empty() {
-                    res.push(format!(" <{}> ", name));
-                }
-                for child in children.iter() {
-                    child.all_leafs_(res);
+        // Iterative pre-order traversal; children are pushed in reverse order
+        // so that they are popped from left to right.
+        let mut stack = vec![self];
+        while let Some(cur) = stack.pop() {
+            match cur {
+                Tree::NT(name, children) => {
+                    if children.is_empty() {
+                        res.push_str(&format!(" <{}> ", name));
+                    }
+                    stack.extend(children.iter().rev());
                 }
-            }
 
-            Tree::T(name) => res.push(name.clone()),
+                Tree::T(name) => res.push_str(name),
+            }
         }
+
+        res
     }
 
     /// Collect pointers to nodes that can be expanded (nonterminals that do not
-    /// yet have any children assigned).
-    fn get_expandable_nonterminals(&mut self) -> Vec<&mut Tree> {
-        let mut res: Vec<&mut Tree> = Vec::new();
+    /// yet have any children assigned), together with their depth in the tree.
+    fn get_expandable_nonterminals(&mut self) -> Vec<(&mut Tree, usize)> {
+        let mut res: Vec<(&mut Tree, usize)> = Vec::new();
 
-        let mut queue: VecDeque<&mut Tree> = VecDeque::new();
-        queue.push_back(self);
+        let mut queue: VecDeque<(&mut Tree, usize)> = VecDeque::new();
+        queue.push_back((self, 0));
 
-        while let Some(cur) = queue.pop_front() {
+        while let Some((cur, depth)) = queue.pop_front() {
             // We first determine whether this node is a nonterminal with empty
             // / no children (then it is expandable).
             // As far as I know, we can't do what we want here in a single match
@@ -358,12 +436,12 @@ impl Tree {
             }
 
             if expandable {
-                res.push(cur);
+                res.push((cur, depth));
             } else {
                 // `if` is only there for destructuring.
                 if let Tree::NT(_, children) = cur {
                     for child in children.iter_mut() {
-                        queue.push_back(child);
+                        queue.push_back((child, depth + 1));
                     }
                 }
             }
@@ -373,16 +451,35 @@ impl Tree {
     }
 }
 
+/// Fixed subtrees for nonterminals (with angle brackets): instead of
+/// expanding a bound nonterminal from the grammar, it is replaced with a copy
+/// of the given tree (e.g. a fixed string, or a subtree of another derivation
+/// tree). Nonterminals in the given tree that are not expanded yet are then
+/// expanded as usual, so a binding can also be a partial pre-expansion.
+pub type Bindings = HashMap<Nonterminal, Tree>;
+
 /// Create a random string from a context-free grammar.
 pub fn fuzz(rng: &mut Rng, grammar: Grammar) -> String {
-    fuzz_tree(rng, grammar).all_leafs()
+    fuzz_tree(rng, grammar, &Bindings::new()).all_leafs()
 }
 
 /// Create a random derivation tree from a context-free grammar.
-pub fn fuzz_tree(rng: &mut Rng, grammar: Grammar) -> Tree {
+pub fn fuzz_tree(rng: &mut Rng, grammar: Grammar, bindings: &Bindings) -> Tree {
+    fuzz_tree_with(rng, grammar, bindings, &mut Random)
+}
+
+/// Create a derivation tree from a context-free grammar, where the given
+/// strategy chooses the expansions in the second phase of `expand_tree`
+/// (instead of choosing randomly).
+pub fn fuzz_tree_with(
+    rng: &mut Rng,
+    grammar: Grammar,
+    bindings: &Bindings,
+    strategy: &mut dyn ExpandStrategy,
+) -> Tree {
     let grammar_cost: GrammarCost = grammar.into();
     let mut tree = Tree::NT("start".to_string(), Vec::new());
-    expand_tree(rng, &grammar_cost, &mut tree, 10, 500);
+    expand_tree(rng, &grammar_cost, bindings, &mut tree, 10, 500, strategy);
     tree
 }
 
@@ -391,40 +488,79 @@ pub fn fuzz_tree(rng: &mut Rng, grammar: Grammar) -> Tree {
 ///   1. Increase as much as possible by choosing expansions that lead to largest
 ///      number of children.
 ///
-///   2. Randomly expand leaf-nonterminals.
+///   2. Expand leaf-nonterminals with the given strategy (e.g. randomly).
 ///
 ///   3. Shrink as much as possible by choosing expansions that lead to smallest
 ///      number of children.
-fn expand_tree(
+pub fn expand_tree(
     rng: &mut Rng,
     grammar: &GrammarCost,
+    bindings: &Bindings,
     tree: &mut Tree,
     min_nonterminals: usize,
     max_nonterminals: usize,
+    strategy: &mut dyn ExpandStrategy,
 ) {
+    check_bindings(bindings);
+
     // Traverse down the tree to find non-expanded leaf-nonterminals.
     let mut expandable = tree.get_expandable_nonterminals();
 
     // Max expansion (increase size as much as possible).
     while !expandable.is_empty() && expandable.len() < min_nonterminals {
-        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::MaxCost);
+        expand_node_by_strategy(rng, grammar, bindings, &mut expandable, &mut MaxCost);
     }
 
-    // Random expansion.
+    // Random (or user-defined) expansion.
     while !expandable.is_empty() && expandable.len() < max_nonterminals {
-        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::Random);
+        expand_node_by_strategy(rng, grammar, bindings, &mut expandable, strategy);
     }
 
     // Min expansion (increase size as little as possible / shrink).
     while !expandable.is_empty() {
-        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::MinCost);
+        expand_node_by_strategy(rng, grammar, bindings, &mut expandable, &mut MinCost);
+    }
+}
+
+/// Create a derivation tree from a context-free grammar, where the given
+/// strategy chooses all expansions (no phases; the strategy itself has to
+/// make sure that the expansion terminates, e.g. `GrowShrink`).
+pub fn fuzz_tree_by(
+    rng: &mut Rng,
+    grammar: Grammar,
+    bindings: &Bindings,
+    strategy: &mut dyn ExpandStrategy,
+) -> Tree {
+    check_bindings(bindings);
+    let grammar_cost: GrammarCost = grammar.into();
+    let mut tree = Tree::NT("start".to_string(), Vec::new());
+    let mut expandable = tree.get_expandable_nonterminals();
+    while !expandable.is_empty() {
+        expand_node_by_strategy(rng, &grammar_cost, bindings, &mut expandable, strategy);
+    }
+    tree
+}
+
+/// Bound trees must not contain not-yet-expanded bound nonterminals, since
+/// these would be replaced again and again.
+fn check_bindings(bindings: &Bindings) {
+    for (nonterminal, tree) in bindings.iter() {
+        for (node, _) in tree.clone().get_expandable_nonterminals() {
+            let name = node.get_name();
+            if bindings.contains_key(&name) {
+                panic!(
+                    "Binding for {} contains unexpanded bound nonterminal {}",
+                    nonterminal, name
+                );
+            }
+        }
     }
 }
 
 /// Minimum cost of all expansions of a symbol. Infinite recursion is mapped
 /// to the value `Infinite`.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
-enum SymbolCost {
+pub enum SymbolCost {
     Finite(usize),
     Infinite,
 }
@@ -471,11 +607,131 @@ fn expansion_cost(grammar: &Grammar, expansion: &Expansion, seen: &HashSet<Strin
     }
 }
 
-#[derive(Clone, Debug)]
-enum ExpandStrategy {
-    MinCost,
-    Random,
-    MaxCost,
+/// Nonterminal node that is about to be expanded.
+pub struct ExpandNode<'a> {
+    /// Name of the nonterminal (with angle brackets).
+    pub name: &'a str,
+
+    /// Depth of the node in the derivation tree (the root has depth 0).
+    pub depth: usize,
+}
+
+/// Policy for choosing the expansion of a nonterminal node.
+/// Implement this for custom policies (e.g. coverage-guided, probabilistic or
+/// depth-dependent) and pass it to `fuzz_tree_with` / `expand_tree`.
+pub trait ExpandStrategy {
+    /// Choose one of the expansions of the node. `costs` holds the
+    /// pre-computed cost of each expansion (same order as `expansions`).
+    fn choose<'a>(
+        &mut self,
+        rng: &mut Rng,
+        node: &ExpandNode,
+        expansions: &'a [Expansion],
+        costs: &[SymbolCost],
+    ) -> &'a Expansion;
+}
+
+/// Choose a random expansion.
+pub struct Random;
+
+/// Choose a random expansion among those with minimum cost (smallest tree).
+pub struct MinCost;
+
+/// Choose a random expansion among those with maximum cost (largest tree).
+pub struct MaxCost;
+
+impl ExpandStrategy for Random {
+    fn choose<'a>(
+        &mut self,
+        rng: &mut Rng,
+        _node: &ExpandNode,
+        expansions: &'a [Expansion],
+        _costs: &[SymbolCost],
+    ) -> &'a Expansion {
+        rng.choice(expansions)
+    }
+}
+
+impl ExpandStrategy for MinCost {
+    fn choose<'a>(
+        &mut self,
+        rng: &mut Rng,
+        _node: &ExpandNode,
+        expansions: &'a [Expansion],
+        costs: &[SymbolCost],
+    ) -> &'a Expansion {
+        let cost = costs.iter().min().unwrap();
+        choose_with_cost(rng, expansions, costs, cost)
+    }
+}
+
+impl ExpandStrategy for MaxCost {
+    fn choose<'a>(
+        &mut self,
+        rng: &mut Rng,
+        _node: &ExpandNode,
+        expansions: &'a [Expansion],
+        costs: &[SymbolCost],
+    ) -> &'a Expansion {
+        let cost = costs.iter().max().unwrap();
+        choose_with_cost(rng, expansions, costs, cost)
+    }
+}
+
+/// Decide per node instead of globally: grow (randomly choose among the
+/// expansions without minimum cost) until the given depth, then shrink
+/// (minimum cost). Unlike the phases of `expand_tree`,
+/// which switch by the total number of expandable nodes, this treats all
+/// subtrees the same, so that none of them stays tiny while others explode.
+pub struct GrowShrink {
+    pub depth: usize,
+}
+
+impl ExpandStrategy for GrowShrink {
+    fn choose<'a>(
+        &mut self,
+        rng: &mut Rng,
+        node: &ExpandNode,
+        expansions: &'a [Expansion],
+        costs: &[SymbolCost],
+    ) -> &'a Expansion {
+        if node.depth < self.depth {
+            // Randomly among the expansions that grow the tree (if there
+            // are any).
+            let min = costs.iter().min().unwrap();
+            let choices: Vec<_> = expansions
+                .iter()
+                .zip(costs.iter())
+                .filter(|(_, c)| *c > min)
+                .map(|(exp, _)| exp)
+                .collect();
+            if choices.is_empty() {
+                rng.choice(expansions)
+            } else {
+                let choice: &&Expansion = rng.choice(&choices);
+                choice
+            }
+        } else {
+            MinCost.choose(rng, node, expansions, costs)
+        }
+    }
+}
+
+/// Randomly choose an expansion from all expansions with the given cost.
+fn choose_with_cost<'a>(
+    rng: &mut Rng,
+    expansions: &'a [Expansion],
+    costs: &[SymbolCost],
+    cost: &SymbolCost,
+) -> &'a Expansion {
+    let choices: Vec<_> = expansions
+        .iter()
+        .zip(costs.iter())
+        .filter(|(_, c)| *c == cost)
+        .map(|(exp, _)| exp)
+        .collect();
+    let choice: &&Expansion = rng.choice(&choices);
+    choice
 }
 
 /// Expand a leaf-non-terminal symbol with rules from a specific grammar
@@ -483,12 +739,13 @@ enum ExpandStrategy {
 fn expand_node_by_strategy(
     rng: &mut Rng,
     grammar: &GrammarCost,
-    expandable: &mut Vec<&mut Tree>,
-    strategy: ExpandStrategy,
+    bindings: &Bindings,
+    expandable: &mut Vec<(&mut Tree, usize)>,
+    strategy: &mut dyn ExpandStrategy,
 ) {
     // Choose random not-yet-expanded nonterminal symbol / node.
-    let treeidx = rng.int(expandable.len() as u64) as usize;
-    let tree: &mut Tree = expandable.remove(treeidx);
+    let treeidx = rng.usize_below(expandable.len());
+    let (tree, depth) = expandable.remove(treeidx);
 
     // I don't know how to assert destructured enum values concisely...
     // All these conditions should have been checked before calling this function.
@@ -501,40 +758,28 @@ fn expand_node_by_strategy(
     }
 
     let name = tree.get_name();
+
+    // Bound nonterminals are replaced with (a copy of) their tree instead.
+    if let Some(bound) = bindings.get(&name) {
+        *tree = bound.clone();
+        for (node, node_depth) in tree.get_expandable_nonterminals() {
+            expandable.push((node, depth + node_depth));
+        }
+        return;
+    }
+
     let expansions = grammar
         .grammar
         .0
         .get(&name)
         .unwrap_or_else(|| panic!("Couldn't get expansion for symbol {}", name));
+    let costs: Vec<_> = expansions
+        .iter()
+        .map(|expansion| grammar.cost_by_expansion.get(expansion).unwrap().clone())
+        .collect();
 
-    let expansion = match strategy {
-        ExpandStrategy::Random => rng.choice(expansions),
-        ExpandStrategy::MinCost | ExpandStrategy::MaxCost => {
-            let costs: Vec<_> = expansions
-                .iter()
-                .map(|expansion| (expansion, grammar.cost_by_expansion.get(expansion).unwrap()))
-                .collect();
-
-            let cost = match strategy {
-                ExpandStrategy::MinCost => *costs.iter().map(|(_, c)| c).min().unwrap(),
-                ExpandStrategy::MaxCost => *costs.iter().map(|(_, c)| c).max().unwrap(),
-                _ => panic!("Can't happen"),
-            };
-
-            let choices: Vec<_> = costs
-                .into_iter()
-                .filter(|(_, c)| match strategy {
-                    ExpandStrategy::MinCost => *c <= cost,
-                    ExpandStrategy::MaxCost => *c >= cost,
-                    _ => panic!("Can't happen"),
-                })
-                .map(|(exp, _)| exp)
-                .collect();
-
-            // Randomly choose expansion from all valid expansions.
-            *rng.choice(&choices)
-        }
-    };
+    let node = ExpandNode { name: &name, depth };
+    let expansion = strategy.choose(rng, &node, expansions, &costs);
     let expansion = expansion.iter().map(|s| ts(s)).collect::<Vec<_>>();
 
     // Modify derivation tree with expanded children.
@@ -542,18 +787,15 @@ fn expand_node_by_strategy(
 
     // Update expandable nonterminals: Add newly created not-yet expanded
     // nonterminals / tree leafs to the list.
-    match tree {
-        Tree::NT(_, children) => {
-            for symbol in children.iter_mut() {
-                match symbol {
-                    Tree::NT(_, children2) => {
-                        assert!(children2.is_empty());
-                        expandable.push(symbol);
-                    }
-                    _ => (), // Ignore terminal symbols.
-                }
+    if let Tree::NT(_, children) = tree {
+        for symbol in children.iter_mut() {
+            if let Tree::NT(_, children2) = symbol {
+                assert!(children2.is_empty());
+                expandable.push((symbol, depth + 1));
             }
+            // else: Ignore terminal symbols.
         }
-        _ => panic!("Can't happen"),
+    } else {
+        panic!("Can't happen");
     }
 }
@@ -3,26 +3,180 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod cli;
 mod grammarfuzzer;
 mod rng;
 
-use grammarfuzzer::{expr_grammar, fuzz_tree};
+use grammarfuzzer::{
+    expr_grammar, fuzz_tree, fuzz_tree_by, fuzz_tree_with, tnt, tt, Bindings, ExpandNode,
+    ExpandStrategy, Expansion, GrowShrink, MinCost, Random, SymbolCost, Tree,
+};
 use rng::Rng;
 
 fn main() {
+    let quiet = cli::quiet();
+    let n = cli::iterations(1000);
+
     let grammar = expr_grammar();
-    println!("[+] Expression grammar");
-    println!("{}", grammar);
-    println!();
+    if !quiet {
+        println!("[+] Expression grammar");
+        println!("{}", grammar);
+        println!();
+    }
 
-    let mut rng = Rng::seeded(42);
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
     println!("[+] Running with random seed {}", rng.initialseed);
     println!();
 
-    let tree = fuzz_tree(&mut rng, grammar);
-    println!("{}", tree.to_dot());
+    let no_bindings = Bindings::new();
+
+    let tree = fuzz_tree(&mut rng, grammar, &no_bindings);
+    if !quiet {
+        println!("{}", tree.to_dot());
+    }
     println!("{}", tree.all_leafs());
     // +((-7/0/6/4+4*5+4)*(9+6)-7*2.5-5+0/4-2)*(-4/3-6+5-5)/+6*-8*6*-4--++(7*9-8-8-6)/29/++7.33/9*7+-(4-8)/(8-3+5)*7*2+-(5)*2/0--8+9-8
+    println!();
+
+    // User-defined expansion strategy.
+    println!("[+] Expanding randomly only up to depth 8:");
+    for _ in 0..5 {
+        let mut strategy = DepthLimited { max_depth: 8 };
+        let tree = fuzz_tree_with(&mut rng, expr_grammar(), &no_bindings, &mut strategy);
+        println!("{}", tree.all_leafs());
+    }
+    println!();
+
+    // Compare the three phases of `fuzz_tree` (switching globally) with
+    // switching per node based on the depth.
+    println!(
+        "[+] Output length / tree depth over {} trees (min, median, max):",
+        n
+    );
+    let trees: Vec<_> = (0..n)
+        .map(|_| fuzz_tree(&mut rng, expr_grammar(), &no_bindings))
+        .collect();
+    print_stats("three phases", &trees);
+    for depth in [4, 6, 8, 10] {
+        let trees: Vec<_> = (0..n)
+            .map(|_| {
+                let mut strategy = GrowShrink { depth };
+                fuzz_tree_by(&mut rng, expr_grammar(), &no_bindings, &mut strategy)
+            })
+            .collect();
+        print_stats(&format!("grow/shrink, depth {}", depth), &trees);
+    }
+    println!();
+
+    // Pin nonterminals to fixed values / subtrees (small trees, so that the
+    // effect is easy to see).
+    let mut generate = |bindings: &Bindings| {
+        fuzz_tree_by(
+            &mut rng,
+            expr_grammar(),
+            bindings,
+            &mut GrowShrink { depth: 5 },
+        )
+    };
+
+    println!("[+] All integers fixed to 1337:");
+    let bindings = Bindings::from([("<integer>".to_string(), tnt("integer", &[tt("1337")]))]);
+    for _ in 0..3 {
+        println!("{}", generate(&bindings).all_leafs());
+    }
+
+    // Partial pre-expansion: only the part before the division is generated.
+    println!("[+] Division by zero (<term>/0):");
+    let zero = tnt("factor", &[tnt("integer", &[tnt("digit", &[tt("0")])])]);
+    let start = tnt(
+        "start",
+        &[tnt(
+            "expr",
+            &[tnt(
+                "term",
+                &[tnt("factor", &[]), tt("/"), tnt("term", &[zero])],
+            )],
+        )],
+    );
+    let bindings = Bindings::from([("<start>".to_string(), start)]);
+    for _ in 0..3 {
+        println!("{}", generate(&bindings).all_leafs());
+    }
+
+    // Re-use a fragment of another tree.
+    let tree = generate(&no_bindings);
+    let fragment = tree
+        .subtrees("<factor>")
+        .into_iter()
+        .max_by_key(|factor| factor.all_leafs().len())
+        .unwrap()
+        .clone();
+    println!(
+        "[+] All factors replaced by {} (from {}):",
+        fragment.all_leafs(),
+        tree.all_leafs()
+    );
+    let bindings = Bindings::from([("<factor>".to_string(), fragment)]);
+    for _ in 0..3 {
+        println!("{}", generate(&bindings).all_leafs());
+    }
+    println!();
+
+    // Degenerate tree (a chain of 1M nodes), which would overflow the stack
+    // with recursive tree operations.
+    println!("[+] Stress test with a degenerate tree of 1M nodes:");
+    let mut chain = tt("x");
+    for _ in 1..1_000_000 {
+        chain = Tree::NT("chain".to_string(), vec![chain]);
+    }
+    let copy = chain.clone();
+    println!(
+        "    depth {}, {} <chain> subtrees, leafs {:?}",
+        copy.depth(),
+        copy.subtrees("<chain>").len(),
+        copy.all_leafs()
+    );
+    drop(copy);
+    drop(chain);
+}
+
+fn print_stats(name: &str, trees: &[Tree]) {
+    let stats = |mut values: Vec<usize>| {
+        values.sort();
+        (
+            values[0],
+            values[values.len() / 2],
+            values[values.len() - 1],
+        )
+    };
+    let lengths = stats(trees.iter().map(|tree| tree.all_leafs().len()).collect());
+    let depths = stats(trees.iter().map(|tree| tree.depth()).collect());
+    println!(
+        "    {:22} length {:5} {:5} {:5}   depth {:3} {:3} {:3}",
+        name, lengths.0, lengths.1, lengths.2, depths.0, depths.1, depths.2
+    );
+}
+
+/// Example for a user-defined expansion strategy: expand randomly near the
+/// root, and with minimum cost from `max_depth` on (this gives flatter trees).
+struct DepthLimited {
+    max_depth: usize,
+}
+
+impl ExpandStrategy for DepthLimited {
+    fn choose<'a>(
+        &mut self,
+        rng: &mut Rng,
+        node: &ExpandNode,
+        expansions: &'a [Expansion],
+        costs: &[SymbolCost],
+    ) -> &'a Expansion {
+        if node.depth < self.max_depth {
+            Random.choose(rng, node, expansions, costs)
+        } else {
+            MinCost.choose(rng, node, expansions, costs)
+        }
+    }
 }
 
 // Debug trap: rust-lldb target
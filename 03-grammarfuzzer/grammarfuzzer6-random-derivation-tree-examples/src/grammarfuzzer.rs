@@ -3,8 +3,12 @@
 //
 // SPDX-License-Identifier: MIT
 
+// Not all parts of the grammar API are used by the examples.
+#![allow(dead_code)]
+
 use std::collections::VecDeque;
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 use crate::rng::Rng;
 
@@ -22,12 +26,35 @@ pub struct Grammar(HashMap<Nonterminal, Vec<Expansion>>);
 pub type Nonterminal = String;
 pub type Expansion = Vec<String>; // Right-hand-side of a production rule.
 
-/// Context-free grammar annotated with pre-computed cost values for symbols /
-/// expansions.
+/// Context-free grammar annotated with pre-computed cost values for
+/// expansions, for creating derivation trees.
+/// All symbols are interned (mapped to numbers), so that creating trees
+/// doesn't need to compare, hash or clone strings; strings are only used when
+/// constructing the grammar and when converting trees to text.
 pub struct GrammarCost {
-    grammar: Grammar,
-    cost_by_symbol: HashMap<String, SymbolCost>,
-    cost_by_expansion: HashMap<Expansion, SymbolCost>,
+    /// Names of all symbols, indexed by their id: nonterminals without angle
+    /// brackets, and terminals as they are. Shared with the derivation trees
+    /// (see `Tree`).
+    names: Rc<Vec<String>>,
+
+    /// Id of each symbol as written in the grammar (nonterminals with angle
+    /// brackets).
+    symbol_ids: HashMap<String, SymbolId>,
+
+    /// Whether the symbol with the given id is a nonterminal.
+    nonterminal: Vec<bool>,
+
+    /// Expansions of the symbol with the given id (empty for terminals).
+    expansions: Vec<Vec<ExpansionCost>>,
+}
+
+/// Interned symbol.
+pub type SymbolId = u32;
+
+/// Expansion with interned symbols and pre-computed cost.
+struct ExpansionCost {
+    symbols: Vec<SymbolId>,
+    cost: SymbolCost,
 }
 
 impl std::fmt::Display for Grammar {
@@ -161,30 +188,54 @@ impl Grammar {
     }
 }
 
-/// Pre-compute expansion costs.
+/// Intern the symbols and pre-compute expansion costs.
 impl std::convert::From<Grammar> for GrammarCost {
     fn from(grammar: Grammar) -> Self {
-        let mut cost_by_symbol = HashMap::new();
-        let mut cost_by_expansion = HashMap::new();
-
+        let mut names = Vec::new();
+        let mut symbol_ids = HashMap::new();
+        let mut nonterminal = Vec::new();
         for (symbol, expansions) in grammar.0.iter() {
-            cost_by_symbol.insert(
-                symbol.clone(),
-                symbol_cost(&grammar, symbol, &HashSet::new()),
-            );
+            for symbol in std::iter::once(symbol).chain(expansions.iter().flatten()) {
+                if !symbol_ids.contains_key(symbol) {
+                    symbol_ids.insert(symbol.clone(), names.len() as SymbolId);
+                    names.push(Grammar::trim_angle_brackets(symbol).to_string());
+                    nonterminal.push(Grammar::is_nonterminal(symbol));
+                }
+            }
+        }
 
-            for expansion in expansions.iter() {
-                cost_by_expansion.insert(
-                    expansion.clone(),
-                    expansion_cost(&grammar, expansion, &HashSet::new()),
-                );
+        let mut rules: Vec<Vec<Vec<SymbolId>>> = vec![Vec::new(); names.len()];
+        for (symbol, expansions) in grammar.0.iter() {
+            rules[symbol_ids[symbol] as usize] = expansions
+                .iter()
+                .map(|expansion| expansion.iter().map(|s| symbol_ids[s]).collect())
+                .collect();
+        }
+        for (symbol, id) in symbol_ids.iter() {
+            if nonterminal[*id as usize] && rules[*id as usize].is_empty() {
+                panic!("Couldn't get expansion for symbol {}", symbol);
             }
         }
 
+        let mut seen = vec![false; names.len()];
+        let expansions = rules
+            .iter()
+            .map(|expansions| {
+                expansions
+                    .iter()
+                    .map(|expansion| ExpansionCost {
+                        symbols: expansion.clone(),
+                        cost: expansion_cost(&rules, &nonterminal, expansion, &mut seen),
+                    })
+                    .collect()
+            })
+            .collect();
+
         Self {
-            grammar,
-            cost_by_symbol,
-            cost_by_expansion,
+            names: Rc::new(names),
+            symbol_ids,
+            nonterminal,
+            expansions,
         }
     }
 }
@@ -205,6 +256,31 @@ pub enum Expr {
     T(String),       // Terminal symbol.
 }
 
+impl Drop for Expr {
+    /// Move all nested expressions into one flat list before dropping them, so
+    /// that deeply nested expressions don't overflow the call stack (the
+    /// default drop glue would recurse).
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        Expr::take_children(self, &mut stack);
+        while let Some(mut cur) = stack.pop() {
+            Expr::take_children(&mut cur, &mut stack);
+        }
+    }
+}
+
+impl Expr {
+    fn take_children(expr: &mut Expr, stack: &mut Vec<Expr>) {
+        match expr {
+            Expr::Alt(exprs) | Expr::Seq(exprs) => stack.append(exprs),
+            Expr::Opt(expr) | Expr::Plus(expr) | Expr::Star(expr) => {
+                stack.push(std::mem::replace(&mut **expr, Expr::T(String::new())))
+            }
+            Expr::NT(_) | Expr::T(_) => {}
+        }
+    }
+}
+
 // Shorthand functions for easier construction of Expr variants.
 // (Handle cloning/boxing/slicing).
 #[rustfmt::skip]
@@ -331,94 +407,125 @@ impl Ebnf {
     /// Convert an EBNF expression into our BNF CFG grammar representation.
     /// This requires translating regular constructs like `?`/`+`/"`*`,
     /// as well as fully flattening nested groupings (alternatives and sequences).
+    ///
+    /// Uses an explicit stack instead of recursion, since deeply nested
+    /// expressions would otherwise overflow the call stack. Each stack entry
+    /// is an expression, the index of its next child expression to convert,
+    /// and the expansions collected from its children so far; `res` holds the
+    /// expansions of the last finished expression.
     fn to_bnf_expr(bnf: &mut Grammar, expression: &Expr, i: &mut usize) -> Vec<Expansion> {
-        match expression {
-            // Alternatives are represented as top-level Vecs.
-            Expr::Alt(exprs) => {
-                let mut res = Vec::new();
-                for expr in exprs {
-                    res.extend(Ebnf::to_bnf_expr(bnf, expr, i));
-                }
-                res
-            }
-
-            // Sequences are represented as inner Vecs.
-            // Therefore we need to expand each nested expression.
-            // If an expression expands to multiple alternatives or to one
-            // alternative with multiple elements in the sequence, we need
-            // to introduce a new nonterminal symbol and insert one level of
-            // indirection, in order to be able to fully flatten the grammar
-            // representation.
-            Expr::Seq(exprs) => {
-                let mut res = Vec::new();
-                for expr in exprs {
-                    let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
-                    if expr_expansions.len() == 1 && expr_expansions[0].len() == 1 {
-                        // We can shortcut and don't need to add a useless new
-                        // intermediate nonterminal symbol that would only expand
-                        // to *one single* other symbol anyway.
-                        res.push(expr_expansions[0][0].clone());
-                    } else {
-                        let s = Ebnf::new_nonterminal(bnf, i);
-                        for expr_expansion in expr_expansions.into_iter() {
-                            bnf.add_production_(s.clone(), expr_expansion);
+        let new_acc = |expr: &Expr| match expr {
+            Expr::Seq(_) => vec![Vec::new()],
+            _ => Vec::new(),
+        };
+        let mut stack: Vec<(&Expr, usize, Vec<Expansion>)> =
+            vec![(expression, 0, new_acc(expression))];
+        let mut res: Option<Vec<Expansion>> = None;
+
+        while let Some((expr, next, acc)) = stack.last_mut() {
+            let expr: &Expr = expr;
+
+            // Add the expansions of the child that was just finished.
+            if let Some(expr_expansions) = res.take() {
+                match expr {
+                    // Alternatives are represented as top-level Vecs.
+                    Expr::Alt(_) => acc.extend(expr_expansions),
+
+                    // Sequences are represented as inner Vecs.
+                    // Therefore we need to expand each nested expression.
+                    // If an expression expands to multiple alternatives or to one
+                    // alternative with multiple elements in the sequence, we need
+                    // to introduce a new nonterminal symbol and insert one level of
+                    // indirection, in order to be able to fully flatten the grammar
+                    // representation.
+                    Expr::Seq(_) => {
+                        if expr_expansions.len() == 1 && expr_expansions[0].len() == 1 {
+                            // We can shortcut and don't need to add a useless new
+                            // intermediate nonterminal symbol that would only expand
+                            // to *one single* other symbol anyway.
+                            acc[0].push(expr_expansions[0][0].clone());
+                        } else {
+                            let s = Ebnf::new_nonterminal(bnf, i);
+                            for expr_expansion in expr_expansions.into_iter() {
+                                bnf.add_production_(s.clone(), expr_expansion);
+                            }
+                            acc[0].push(s);
                         }
-                        res.push(s);
                     }
+
+                    // Regular constructs have exactly one child.
+                    _ => *acc = expr_expansions,
                 }
-                vec![res]
             }
 
-            // > An expression <symbol>? becomes <new-symbol>, where <new-symbol> ::= <empty>  | <symbol>.
-            // Since an expression can expand to multiple alternatives/sequences,
-            // we need to perform this substitution for all possible candidates.
-            Expr::Opt(expr) => {
-                let s = Ebnf::new_nonterminal(bnf, i);
-                let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
-                for expr_expansion in expr_expansions.into_iter() {
-                    bnf.add_production_(s.clone(), expr_expansion);
-                }
-                // Since the empty string / epsilon does not depend on the expansion,
-                // we can avoid duplicates and insert it once at the end (and not
-                // over and over inside the loop).
-                bnf.add_production_(s.clone(), vec!["".to_string()]);
-                vec![vec![s]]
+            let children: &[Expr] = match expr {
+                Expr::Alt(exprs) | Expr::Seq(exprs) => exprs,
+                Expr::Opt(expr) | Expr::Plus(expr) | Expr::Star(expr) => std::slice::from_ref(expr),
+                Expr::NT(_) | Expr::T(_) => &[],
+            };
+            if let Some(child) = children.get(*next) {
+                *next += 1;
+                stack.push((child, 0, new_acc(child)));
+                continue;
             }
 
-            // > An expression <symbol>+ becomes <new-symbol>, where <new-symbol> ::= <symbol> | <symbol><new-symbol>.
-            // Since an expression can expand to multiple alternatives/sequences,
-            // we need to perform this substitution for all possible candidates.
-            Expr::Plus(expr) => {
-                let s = Ebnf::new_nonterminal(bnf, i);
-                let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
-                for mut expr_expansion in expr_expansions.into_iter() {
-                    bnf.add_production_(s.clone(), expr_expansion.clone());
-                    expr_expansion.push(s.clone());
-                    bnf.add_production_(s.clone(), expr_expansion);
+            // All children are converted, finish the expression.
+            let (expr, _, acc) = stack.pop().unwrap();
+            res = Some(match expr {
+                Expr::Alt(_) | Expr::Seq(_) => acc,
+
+                // > An expression <symbol>? becomes <new-symbol>, where <new-symbol> ::= <empty>  | <symbol>.
+                // Since an expression can expand to multiple alternatives/sequences,
+                // we need to perform this substitution for all possible candidates.
+                // (The new symbol is only created after converting the inner
+                // expression, so that it can't clash with symbols created there.)
+                Expr::Opt(_) => {
+                    let s = Ebnf::new_nonterminal(bnf, i);
+                    for expr_expansion in acc.into_iter() {
+                        bnf.add_production_(s.clone(), expr_expansion);
+                    }
+                    // Since the empty string / epsilon does not depend on the expansion,
+                    // we can avoid duplicates and insert it once at the end (and not
+                    // over and over inside the loop).
+                    bnf.add_production_(s.clone(), vec!["".to_string()]);
+                    vec![vec![s]]
                 }
-                vec![vec![s]]
-            }
 
-            // > An expression <symbol>* becomes <new-symbol>, where <new-symbol> ::= <empty>  | <symbol><new-symbol>.
-            // Since an expression can expand to multiple alternatives/sequences,
-            // we need to perform this substitution for all possible candidates.
-            Expr::Star(expr) => {
-                let s = Ebnf::new_nonterminal(bnf, i);
-                let expr_expansions = Ebnf::to_bnf_expr(bnf, expr, i);
-                for mut expr_expansion in expr_expansions.into_iter() {
-                    expr_expansion.push(s.clone());
-                    bnf.add_production_(s.clone(), expr_expansion);
+                // > An expression <symbol>+ becomes <new-symbol>, where <new-symbol> ::= <symbol> | <symbol><new-symbol>.
+                // Since an expression can expand to multiple alternatives/sequences,
+                // we need to perform this substitution for all possible candidates.
+                Expr::Plus(_) => {
+                    let s = Ebnf::new_nonterminal(bnf, i);
+                    for mut expr_expansion in acc.into_iter() {
+                        bnf.add_production_(s.clone(), expr_expansion.clone());
+                        expr_expansion.push(s.clone());
+                        bnf.add_production_(s.clone(), expr_expansion);
+                    }
+                    vec![vec![s]]
                 }
-                // Since the empty string / epsilon does not depend on the expansion,
-                // we can avoid duplicates and insert it once at the end (and not
-                // over and over inside the loop).
-                bnf.add_production_(s.clone(), vec!["".to_string()]);
-                vec![vec![s]]
-            }
 
-            Expr::NT(s) => vec![vec![format!("<{}>", s)]],
-            Expr::T(s) => vec![vec![s.clone()]],
+                // > An expression <symbol>* becomes <new-symbol>, where <new-symbol> ::= <empty>  | <symbol><new-symbol>.
+                // Since an expression can expand to multiple alternatives/sequences,
+                // we need to perform this substitution for all possible candidates.
+                Expr::Star(_) => {
+                    let s = Ebnf::new_nonterminal(bnf, i);
+                    for mut expr_expansion in acc.into_iter() {
+                        expr_expansion.push(s.clone());
+                        bnf.add_production_(s.clone(), expr_expansion);
+                    }
+                    // Since the empty string / epsilon does not depend on the expansion,
+                    // we can avoid duplicates and insert it once at the end (and not
+                    // over and over inside the loop).
+                    bnf.add_production_(s.clone(), vec!["".to_string()]);
+                    vec![vec![s]]
+                }
+
+                Expr::NT(s) => vec![vec![format!("<{}>", s)]],
+                Expr::T(s) => vec![vec![s.clone()]],
+            });
         }
+
+        res.unwrap()
     }
 
     /// Create a copy of the grammar with only the actually used/reachable
@@ -481,67 +588,205 @@ impl Ebnf {
 }
 
 /// Derivation tree in a given grammar.
+///
+/// The nodes are stored in an arena (one `Vec` for the whole tree) and refer
+/// to their children by index, instead of each node owning its children. The
+/// symbol names are interned: they are only stored once for all trees created
+/// from the same grammar, and the nodes only store their index. This avoids
+/// lots of small allocations and string clones when creating and cloning
+/// trees.
 #[derive(Clone, Debug)]
-pub enum Tree {
+pub struct Tree {
+    /// All nodes of the tree; the root is the first one.
+    nodes: Vec<Node>,
+
+    /// Interned symbol names (shared with the grammar).
+    names: Rc<Vec<String>>,
+}
+
+/// Options for the dot / graphviz export of derivation trees (`to_dot_with`).
+/// The defaults give the same output as `to_dot`.
+#[derive(Clone, Copy, Default)]
+pub struct DotOptions<'a> {
+    /// Only draw nodes down to this depth (the root has depth 0); deeper
+    /// subtrees are collapsed into one node with the string they derive.
+    pub max_depth: Option<usize>,
+
+    /// Draw subtrees that occur repeatedly (e.g. the same whitespace or
+    /// digits) only once, with dashed edges pointing to the first one.
+    pub collapse_repeated: bool,
+
+    /// Color nodes by kind: expanded nonterminals blue, unexpanded
+    /// nonterminals red, terminals black (and collapsed subtrees gray).
+    pub colors: bool,
+
+    /// Label the edges to the children of a node with the index of the chosen
+    /// expansion (needs the grammar the tree was created from).
+    pub expansions: Option<&'a GrammarCost>,
+}
+
+/// Index of a node in the arena of its tree.
+pub type NodeId = usize;
+
+#[derive(Clone, Copy, Debug)]
+enum Node {
     /// Nonterminal symbol (inner node in the tree) consisting of a symbol name
-    /// and a list of child nodes / children.
-    NT(String, Vec<Tree>),
+    /// and its child nodes / children. The children of a node are created
+    /// together and thus stored next to each other in the arena, so only the
+    /// index of the first child and the number of children are stored.
+    NT(SymbolId, NodeId, usize),
     /// Terminal symbol (leaf of the tree) consisting only of a symbol name
     /// (= final text for this tree part); it has no children.
-    T(String),
+    T(SymbolId),
 }
 
-// Shorthand functions for easier construction of derivation trees.
-// Similar to grammar shorthand functions. Prefix `t` stands for `tree`.
-#[rustfmt::skip]
-fn tnt(name: &str, children: &[Tree]) -> Tree { Tree::NT(name.to_string(), children.to_vec()) }
-#[rustfmt::skip]
-fn tt(name: &str)                     -> Tree { Tree::T(name.to_string()) }
-fn ts(s: &str) -> Tree {
-    if Grammar::is_nonterminal(s) {
-        tnt(Grammar::trim_angle_brackets(s), &[])
-    } else {
-        tt(s)
+impl Tree {
+    /// Create a tree consisting only of a not-yet expanded `<start>`
+    /// nonterminal.
+    pub fn new(grammar: &GrammarCost) -> Self {
+        let mut tree = Self {
+            nodes: Vec::new(),
+            names: Rc::clone(&grammar.names),
+        };
+        tree.add_symbol(grammar, grammar.symbol_ids["<start>"]);
+        tree
+    }
+
+    const ROOT: NodeId = 0;
+
+    /// Add a new node for a symbol of the grammar (nonterminals without
+    /// children, i.e. not yet expanded).
+    fn add_symbol(&mut self, grammar: &GrammarCost, symbol: SymbolId) -> NodeId {
+        let node = if grammar.nonterminal[symbol as usize] {
+            Node::NT(symbol, 0, 0)
+        } else {
+            Node::T(symbol)
+        };
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// Number of nodes in the tree.
+    pub fn size(&self) -> usize {
+        self.nodes.len()
     }
-}
 
-impl Tree {
     /// Returns a dot / graphviz definition of the derivation tree / graph.
     /// (Does iterative pre-order traversal of the tree).
     /// It can be rendered e.g. as follows: dot -Tpdf tree.dot -o tree.pdf
     pub fn to_dot(&self) -> String {
-        let mut lines = Vec::new();
+        self.to_dot_with(&DotOptions::default())
+    }
+
+    /// Returns a dot / graphviz definition of the derivation tree / graph,
+    /// with options for making large trees readable (see `DotOptions`).
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        if let Some(grammar) = options.expansions {
+            assert!(
+                Rc::ptr_eq(&self.names, &grammar.names),
+                "Tree was not created from this grammar"
+            );
+        }
 
-        lines.push("digraph DerivationTree {".to_string());
-        lines.push("".to_string());
-        lines.push("    node [shape=plain];".to_string());
-        lines.push("".to_string());
+        let mut lines = vec![
+            "digraph DerivationTree {".to_string(),
+            "".to_string(),
+            "    node [shape=plain];".to_string(),
+            "".to_string(),
+        ];
+
+        // For collapsing repeated subtrees: structure id of each node, and
+        // the dot node of the first drawn subtree with this structure.
+        let structure = if options.collapse_repeated {
+            self.structure_ids()
+        } else {
+            Vec::new()
+        };
+        let mut drawn: HashMap<usize, usize> = HashMap::new();
 
         let mut node_count = 0;
-        let mut queue: VecDeque<(&Tree, Option<usize>)> = VecDeque::new();
-        queue.push_back((self, None));
+        // Nodes with the dot node of their parent and the label of the edge
+        // from it, and their depth.
+        let mut queue = VecDeque::new();
+        queue.push_back((Tree::ROOT, None::<(usize, String)>, 0));
+
+        while let Some((cur, parent, depth)) = queue.pop_front() {
+            let expanded = matches!(self.nodes[cur], Node::NT(_, _, len) if len > 0);
+
+            // Edge attributes (expansion label) are computed by the parent.
+            let edge = |lines: &mut Vec<String>, to: usize, style: &str| {
+                if let Some((parent, label)) = &parent {
+                    let mut attrs = Vec::new();
+                    if !label.is_empty() {
+                        attrs.push(format!("label=\"{}\"", label));
+                    }
+                    if !style.is_empty() {
+                        attrs.push(style.to_string());
+                    }
+                    if attrs.is_empty() {
+                        lines.push(format!("    n{} -> n{};", parent, to));
+                    } else {
+                        lines.push(format!("    n{} -> n{} [{}];", parent, to, attrs.join(" ")));
+                    }
+                    lines.push("".to_string());
+                }
+            };
+
+            if options.collapse_repeated && expanded {
+                if let Some(&node) = drawn.get(&structure[cur]) {
+                    // Same subtree as an already drawn one: only add an edge.
+                    edge(&mut lines, node, "style=dashed");
+                    continue;
+                }
+            }
 
-        while let Some((cur, parent)) = queue.pop_front() {
             node_count += 1;
+            if options.collapse_repeated && expanded {
+                drawn.insert(structure[cur], node_count);
+            }
+
+            let truncated = expanded && options.max_depth.is_some_and(|max| depth >= max);
+            // Collapsed subtrees: name and derived string on two lines.
+            let label = if truncated {
+                format!(
+                    "{}\\n{}",
+                    Tree::to_dot_label(&self.get_name(cur)),
+                    Tree::to_dot_label(&format!("\"{}\"", self.subtree_leafs(cur)))
+                )
+            } else {
+                Tree::to_dot_label(&self.get_name(cur))
+            };
+            let color = match self.nodes[cur] {
+                _ if !options.colors => "",
+                _ if truncated => " fontcolor=gray40",
+                Node::NT(_, _, 0) => " fontcolor=red",
+                Node::NT(_, _, _) => " fontcolor=blue",
+                Node::T(_) => "",
+            };
             lines.push(format!(
-                "    n{} [label=\"{}\"];",
-                node_count,
-                Tree::to_dot_label(&cur.get_name())
+                "    n{} [label=\"{}\"{}];",
+                node_count, label, color
             ));
+            edge(&mut lines, node_count, "");
 
-            if let Some(parent) = parent {
-                lines.push(format!("    n{} -> n{};", parent, node_count));
-                lines.push("".to_string());
+            if truncated {
+                continue;
             }
 
-            match cur {
-                Tree::NT(_, children) => {
-                    for child in children.iter() {
-                        queue.push_back((child, Some(node_count)));
+            match &self.nodes[cur] {
+                Node::NT(symbol, first, len) => {
+                    let label = match options.expansions {
+                        Some(grammar) if *len > 0 => {
+                            self.expansion_label(grammar, *symbol, *first, *len)
+                        }
+                        _ => String::new(),
+                    };
+                    for child in *first..first + len {
+                        queue.push_back((child, Some((node_count, label.clone())), depth + 1));
                     }
                 }
 
-                Tree::T(_) => {
+                Node::T(_) => {
                     // Edge to this node was already added previously.
                     // Since there are no children for terminal symbols, there
                     // is nothing left to do.
@@ -553,13 +798,55 @@ impl Tree {
         lines.join("\n")
     }
 
+    /// Which of the expansions of a nonterminal was chosen, as
+    /// `index/number of expansions` (1-based).
+    fn expansion_label(
+        &self,
+        grammar: &GrammarCost,
+        symbol: SymbolId,
+        first: NodeId,
+        len: usize,
+    ) -> String {
+        let children: Vec<SymbolId> = (first..first + len)
+            .map(|child| match self.nodes[child] {
+                Node::NT(symbol, _, _) | Node::T(symbol) => symbol,
+            })
+            .collect();
+        let expansions = &grammar.expansions[symbol as usize];
+        match expansions
+            .iter()
+            .position(|expansion| expansion.symbols == children)
+        {
+            Some(i) => format!("{}/{}", i + 1, expansions.len()),
+            None => format!("?/{}", expansions.len()),
+        }
+    }
+
+    /// Number each node by the structure of its subtree: nodes get the same
+    /// number iff their subtrees are equal.
+    fn structure_ids(&self) -> Vec<usize> {
+        let mut ids = vec![0; self.nodes.len()];
+        let mut known: HashMap<(bool, SymbolId, Vec<usize>), usize> = HashMap::new();
+        // Children are always added after their parent, so they have larger
+        // indices and are numbered first when going backwards.
+        for node in (0..self.nodes.len()).rev() {
+            let key = match self.nodes[node] {
+                Node::NT(symbol, first, len) => (true, symbol, ids[first..first + len].to_vec()),
+                Node::T(symbol) => (false, symbol, Vec::new()),
+            };
+            let next = known.len();
+            ids[node] = *known.entry(key).or_insert(next);
+        }
+        ids
+    }
+
     /// Get the symbol name as a string. Depending on the kind of symbol, the
     /// symbol name is wrapped into either double quotes (terminal symbol), or
     /// angle brackets (nonterminal symbols).
-    fn get_name(&self) -> String {
-        match self {
-            Tree::NT(name, _) => format!("<{}>", name),
-            Tree::T(name) => format!("\"{}\"", name),
+    fn get_name(&self, node: NodeId) -> String {
+        match &self.nodes[node] {
+            Node::NT(name, _, _) => format!("<{}>", self.names[*name as usize]),
+            Node::T(name) => format!("\"{}\"", self.names[*name as usize]),
         }
     }
 
@@ -581,59 +868,46 @@ impl Tree {
     /// Concatenate all leafs of the derivation tree (terminals, and yet
     /// unexpanded nonterminals) into one string.
     pub fn all_leafs(&self) -> String {
-        let mut res: Vec<String> = Vec::new();
-        self.all_leafs_(&mut res);
-        res.join("")
+        self.subtree_leafs(Tree::ROOT)
     }
 
-    fn all_leafs_(&self, res: &mut Vec<String>) {
-        match self {
-            Tree::NT(name, children) => {
-                if children.is_empty() {
-                    res.push(format!(" <{}> ", name));
-                }
-                for child in children.iter() {
-                    child.all_leafs_(res);
+    /// Concatenate all leafs of the subtree of a node into one string.
+    fn subtree_leafs(&self, node: NodeId) -> String {
+        let mut res = String::new();
+
+        // Iterative pre-order traversal; children are pushed in reverse order
+        // so that they are popped from left to right.
+        let mut stack = vec![node];
+        while let Some(cur) = stack.pop() {
+            match &self.nodes[cur] {
+                Node::NT(name, first, len) => {
+                    if *len == 0 {
+                        res.push_str(&format!(" <{}> ", self.names[*name as usize]));
+                    }
+                    stack.extend((*first..first + len).rev());
                 }
-            }
 
-            Tree::T(name) => res.push(name.clone()),
+                Node::T(name) => res.push_str(&self.names[*name as usize]),
+            }
         }
+
+        res
     }
 
-    /// Collect pointers to nodes that can be expanded (nonterminals that do not
-    /// yet have any children assigned).
-    fn get_expandable_nonterminals(&mut self) -> Vec<&mut Tree> {
-        let mut res: Vec<&mut Tree> = Vec::new();
+    /// Collect the nodes that can be expanded (nonterminals that do not yet
+    /// have any children assigned).
+    fn get_expandable_nonterminals(&self) -> Vec<NodeId> {
+        let mut res = Vec::new();
 
-        let mut queue: VecDeque<&mut Tree> = VecDeque::new();
-        queue.push_back(self);
+        let mut queue: VecDeque<NodeId> = VecDeque::new();
+        queue.push_back(Tree::ROOT);
 
         while let Some(cur) = queue.pop_front() {
-            // We first determine whether this node is a nonterminal with empty
-            // / no children (then it is expandable).
-            // As far as I know, we can't do what we want here in a single match
-            // since we would then have to borrow children either as mutable
-            // (for iterating over them and pushing mutable refs to the queue)
-            // or as immutable (for pushing cur to the result list), depending
-            // on its inner/destructured value.
-
-            let mut expandable = false;
-            if let Tree::NT(_, children) = cur {
-                if children.is_empty() {
-                    expandable = true;
-                }
-            }
-
-            if expandable {
-                res.push(cur);
-            } else {
-                // `if` is only there for destructuring.
-                if let Tree::NT(_, children) = cur {
-                    for child in children.iter_mut() {
-                        queue.push_back(child);
-                    }
+            if let Node::NT(_, first, len) = &self.nodes[cur] {
+                if *len == 0 {
+                    res.push(cur);
                 }
+                queue.extend(*first..first + len);
             }
         }
 
@@ -649,8 +923,14 @@ pub fn fuzz(rng: &mut Rng, grammar: Grammar) -> String {
 /// Create a random derivation tree from a context-free grammar.
 pub fn fuzz_tree(rng: &mut Rng, grammar: Grammar) -> Tree {
     let grammar_cost: GrammarCost = grammar.into();
-    let mut tree = Tree::NT("start".to_string(), Vec::new());
-    expand_tree(rng, &grammar_cost, &mut tree, 80, 200);
+    fuzz_tree_(rng, &grammar_cost)
+}
+
+/// Create a random derivation tree from a context-free grammar with already
+/// pre-computed costs (for creating many trees from the same grammar).
+pub fn fuzz_tree_(rng: &mut Rng, grammar: &GrammarCost) -> Tree {
+    let mut tree = Tree::new(grammar);
+    expand_tree(rng, grammar, &mut tree, 80, 200);
     tree
 }
 
@@ -663,13 +943,15 @@ pub fn fuzz_tree(rng: &mut Rng, grammar: Grammar) -> Tree {
 ///
 ///   3. Shrink as much as possible by choosing expansions that lead to smallest
 ///      number of children.
+///
+/// Returns the number of expansions of all three phases.
 fn expand_tree(
     rng: &mut Rng,
     grammar: &GrammarCost,
     tree: &mut Tree,
     min_expansions: usize, // Perform this much expansions in the first phase.
     max_expansions: usize, // Perform this much expansions in the second phase.
-) {
+) -> usize {
     // Traverse down the tree to find non-expanded leaf-nonterminals.
     let mut expandable = tree.get_expandable_nonterminals();
 
@@ -678,21 +960,23 @@ fn expand_tree(
 
     // Max expansion (increase size as much as possible).
     while !expandable.is_empty() && num_expansions < min_expansions {
-        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::MaxCost);
+        expand_node_by_strategy(rng, grammar, tree, &mut expandable, ExpandStrategy::MaxCost);
         num_expansions += 1;
     }
 
     // Random expansion.
     while !expandable.is_empty() && num_expansions < max_expansions {
-        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::Random);
+        expand_node_by_strategy(rng, grammar, tree, &mut expandable, ExpandStrategy::Random);
         num_expansions += 1;
     }
 
     // Min expansion (increase size as little as possible / shrink).
     while !expandable.is_empty() {
-        expand_node_by_strategy(rng, grammar, &mut expandable, ExpandStrategy::MinCost);
+        expand_node_by_strategy(rng, grammar, tree, &mut expandable, ExpandStrategy::MinCost);
         num_expansions += 1;
     }
+
+    num_expansions
 }
 
 /// Minimum cost of all expansions of a symbol. Infinite recursion is mapped
@@ -714,32 +998,39 @@ impl std::ops::Add for SymbolCost {
     }
 }
 
-fn symbol_cost(grammar: &Grammar, symbol: &str, seen: &HashSet<String>) -> SymbolCost {
+fn symbol_cost(
+    rules: &[Vec<Vec<SymbolId>>],
+    nonterminal: &[bool],
+    symbol: SymbolId,
+    seen: &mut [bool],
+) -> SymbolCost {
+    // Mark the symbol as seen while computing the costs of its expansions.
+    let was_seen = std::mem::replace(&mut seen[symbol as usize], true);
     let mut min = SymbolCost::Infinite;
-    for expansion in grammar
-        .0
-        .get(symbol)
-        .unwrap_or_else(|| panic!("Couldn't get expansion for symbol {}", symbol))
-    {
-        let mut seen = seen.clone();
-        seen.insert(symbol.to_string());
-        let tmp = expansion_cost(grammar, expansion, &seen);
+    for expansion in rules[symbol as usize].iter() {
+        let tmp = expansion_cost(rules, nonterminal, expansion, seen);
         min = std::cmp::min(tmp, min);
     }
+    seen[symbol as usize] = was_seen;
     min
 }
 
-fn expansion_cost(grammar: &Grammar, expansion: &Expansion, seen: &HashSet<String>) -> SymbolCost {
+fn expansion_cost(
+    rules: &[Vec<Vec<SymbolId>>],
+    nonterminal: &[bool],
+    expansion: &[SymbolId],
+    seen: &mut [bool],
+) -> SymbolCost {
     let nonterminals: Vec<_> = expansion
         .iter()
-        .filter(|symbol| Grammar::is_nonterminal(symbol))
+        .filter(|symbol| nonterminal[**symbol as usize])
         .collect();
-    if nonterminals.iter().any(|symbol| seen.contains(*symbol)) {
+    if nonterminals.iter().any(|symbol| seen[**symbol as usize]) {
         SymbolCost::Infinite
     } else {
         nonterminals
             .iter()
-            .map(|symbol| symbol_cost(grammar, symbol, seen))
+            .map(|symbol| symbol_cost(rules, nonterminal, **symbol, seen))
             .fold(SymbolCost::Finite(0), |acc, x| acc + x)
             + SymbolCost::Finite(1)
     }
@@ -757,36 +1048,31 @@ enum ExpandStrategy {
 fn expand_node_by_strategy(
     rng: &mut Rng,
     grammar: &GrammarCost,
-    expandable: &mut Vec<&mut Tree>,
+    tree: &mut Tree,
+    expandable: &mut Vec<NodeId>,
     strategy: ExpandStrategy,
 ) {
     // Choose random not-yet-expanded nonterminal symbol / node.
-    let treeidx = rng.int(expandable.len() as u64) as usize;
-    let tree: &mut Tree = expandable.remove(treeidx);
+    let treeidx = rng.usize_below(expandable.len());
+    let node = expandable.remove(treeidx);
 
-    // I don't know how to assert destructured enum values concisely...
     // All these conditions should have been checked before calling this function.
-    if let Tree::NT(_, children) = tree {
-        if !children.is_empty() {
-            panic!("Can't happen");
+    let symbol = match tree.nodes[node] {
+        Node::NT(symbol, _, len) => {
+            assert!(len == 0);
+            symbol
         }
-    } else {
-        panic!("Can't happen");
-    }
+        Node::T(_) => panic!("Can't happen"),
+    };
 
-    let name = tree.get_name();
-    let expansions = grammar
-        .grammar
-        .0
-        .get(&name)
-        .unwrap_or_else(|| panic!("Couldn't get expansion for symbol {}", name));
+    let expansions = &grammar.expansions[symbol as usize];
 
     let expansion = match strategy {
-        ExpandStrategy::Random => rng.choice(expansions),
+        ExpandStrategy::Random => &rng.choice(expansions).symbols,
         ExpandStrategy::MinCost | ExpandStrategy::MaxCost => {
             let costs: Vec<_> = expansions
                 .iter()
-                .map(|expansion| (expansion, grammar.cost_by_expansion.get(expansion).unwrap()))
+                .map(|expansion| (&expansion.symbols, &expansion.cost))
                 .collect();
 
             let cost = match strategy {
@@ -809,23 +1095,22 @@ fn expand_node_by_strategy(
             *rng.choice(&choices)
         }
     };
-    let expansion = expansion.iter().map(|s| ts(s)).collect::<Vec<_>>();
 
     // Modify derivation tree with expanded children.
-    *tree = Tree::NT(Grammar::trim_angle_brackets(&name).to_string(), expansion);
+    let first = tree.nodes.len();
+    for symbol in expansion.iter() {
+        tree.add_symbol(grammar, *symbol);
+    }
+    if let Node::NT(_, f, len) = &mut tree.nodes[node] {
+        (*f, *len) = (first, expansion.len());
+    }
 
     // Update expandable nonterminals: Add newly created not-yet expanded
     // nonterminals / tree leafs to the list.
-    match tree {
-        Tree::NT(_, children) => {
-            for symbol in children.iter_mut() {
-                if let Tree::NT(_, children2) = symbol {
-                    assert!(children2.is_empty());
-                    expandable.push(symbol);
-                }
-                // else: Ignore terminal symbols.
-            }
+    for child in first..tree.nodes.len() {
+        if let Node::NT(_, _, _) = tree.nodes[child] {
+            expandable.push(child);
         }
-        _ => panic!("Can't happen"),
+        // else: Ignore terminal symbols.
     }
 }
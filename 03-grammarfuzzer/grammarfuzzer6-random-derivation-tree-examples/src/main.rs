@@ -3,76 +3,170 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod cli;
 mod examplegrammars;
 mod grammarfuzzer;
+mod render;
 mod rng;
 
-use grammarfuzzer::{fuzz_tree, Grammar};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use grammarfuzzer::{fuzz_tree_, t, DotOptions, Ebnf, Expr, GrammarCost};
+use render::{Format, Renderer};
 
 use rng::Rng;
 
 fn main() {
-    let mut rng = Rng::seeded(42);
+    if cli::flag("--bench") {
+        bench();
+        return;
+    }
+    if cli::flag("--stress") {
+        stress();
+        return;
+    }
+
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
     println!("[+] Running with random seed {}", rng.initialseed);
     println!();
 
-    // Number of example derivation trees / expressions to generate from each
-    // grammar.
-    let n_examples = 10;
-
-    for _ in 0..n_examples {
-        run_grammar(
-            &mut rng,
-            examplegrammars::expr_grammar(),
-            "expression-grammar",
-        );
-    }
+    let renderer = Renderer::new(render::format_from_cli().unwrap_or(Format::Pdf));
 
-    for _ in 0..n_examples {
-        run_grammar(&mut rng, examplegrammars::cgi_grammar(), "cgi-grammar");
-    }
+    // Make large trees readable: color nodes, label the chosen expansions,
+    // draw repeated subtrees only once and cut off deep subtrees.
+    let readable = cli::flag("--readable");
+    let mut dotfiles = Vec::new();
 
-    for _ in 0..n_examples {
-        run_grammar(&mut rng, examplegrammars::title_grammar(), "title-grammar");
+    // Number of example derivation trees / expressions to generate from each
+    // grammar.
+    let n_examples = cli::iterations(10);
+
+    // Only the grammar given with --target (e.g. `--target json-grammar`).
+    let selected = cli::target("all");
+    let outdir = cli::out_dir("output");
+
+    let grammars = [
+        ("expression-grammar", examplegrammars::expr_grammar()),
+        ("cgi-grammar", examplegrammars::cgi_grammar()),
+        ("title-grammar", examplegrammars::title_grammar()),
+        ("json-grammar", examplegrammars::json_grammar().to_bnf()),
+    ];
+
+    for (grammarname, grammar) in grammars {
+        if selected != "all" && selected != grammarname {
+            continue;
+        }
+        let grammar: GrammarCost = grammar.into();
+        for _ in 0..n_examples {
+            dotfiles.push(run_grammar(
+                &mut rng,
+                &grammar,
+                &outdir.join(grammarname),
+                readable,
+            ));
+        }
     }
 
-    for _ in 0..n_examples {
-        run_grammar(
-            &mut rng,
-            examplegrammars::json_grammar().to_bnf(),
-            "json-grammar",
-        );
+    // Render all trees at once (in parallel).
+    match renderer.render(&dotfiles) {
+        Ok(rendered) if !rendered.is_empty() => {
+            println!("[+] Rendered {} derivation trees", rendered.len())
+        }
+        Ok(_) => (),
+        Err(err) => println!("[!] {}", err),
     }
 }
 
-/// Create a random derivation tree from a grammar, write it out to dot/graphviz
-/// format, and render it as PDF-file.
-fn run_grammar(rng: &mut Rng, grammar: Grammar, grammarname: &str) {
-    let filebase = format!("output/{}-{}", grammarname, rng.next());
-    println!("[+] {}", filebase);
-
-    let _ = std::fs::create_dir("output");
+/// Create a random derivation tree from a grammar, and write it out to
+/// dot/graphviz format (returns the path of the .dot file for rendering).
+/// The file names start with `prefix` (output directory and grammar name).
+fn run_grammar(rng: &mut Rng, grammar: &GrammarCost, prefix: &Path, readable: bool) -> PathBuf {
+    let filebase = format!("{}-{}", prefix.display(), rng.next());
+    if !cli::quiet() {
+        println!("[+] {}", filebase);
+    }
 
-    let tree = fuzz_tree(rng, grammar);
+    let tree = fuzz_tree_(rng, grammar);
 
     let terminals = tree.all_leafs();
     std::fs::write(format!("{}.txt", filebase), terminals).unwrap();
 
-    let dot = tree.to_dot();
-    std::fs::write(format!("{}.dot", filebase), dot).unwrap();
-    std::process::Command::new("dot")
-        .args([
-            "-Tpdf",
-            format!("{}.dot", filebase).as_str(),
-            "-o",
-            format!("{}.pdf", filebase).as_str(),
-        ])
-        .spawn()
-        .unwrap()
-        .wait()
-        .unwrap();
+    let dotfile = PathBuf::from(format!("{}.dot", filebase));
+    let dot = if readable {
+        tree.to_dot_with(&DotOptions {
+            max_depth: Some(8),
+            collapse_repeated: true,
+            colors: true,
+            expansions: Some(grammar),
+        })
+    } else {
+        tree.to_dot()
+    };
+    std::fs::write(&dotfile, dot).unwrap();
+
+    if !cli::quiet() {
+        println!();
+    }
+    dotfile
+}
 
-    println!();
+/// Measure how long it takes to create many derivation trees from the JSON
+/// grammar.
+fn bench() {
+    let grammar: GrammarCost = examplegrammars::json_grammar().to_bnf().into();
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+
+    let n = cli::iterations(20000);
+
+    let start = Instant::now();
+    let trees: Vec<_> = (0..n).map(|_| fuzz_tree_(&mut rng, &grammar)).collect();
+    println!(
+        "[+] Created {} JSON derivation trees in {:.3}s",
+        n,
+        start.elapsed().as_secs_f64()
+    );
+
+    let start = Instant::now();
+    let clones = trees.clone();
+    println!("[+] Cloned them in {:.3}s", start.elapsed().as_secs_f64());
+
+    let start = Instant::now();
+    let len: usize = clones.iter().map(|tree| tree.all_leafs().len()).sum();
+    println!(
+        "[+] Converted them to strings ({} characters) in {:.3}s",
+        len,
+        start.elapsed().as_secs_f64()
+    );
+}
+
+/// Convert a degenerate EBNF grammar (one expression nested 1M levels deep) to
+/// BNF, which would overflow the stack with a recursive conversion.
+fn stress() {
+    let depth = 1_000_000;
+    let mut expr = t("x");
+    for i in 0..depth {
+        // Constructed directly instead of with the shorthand functions, which
+        // clone their arguments.
+        expr = match i % 4 {
+            0 => Expr::Opt(Box::new(expr)),
+            1 => Expr::Seq(vec![t("("), expr, t(")")]),
+            2 => Expr::Star(Box::new(expr)),
+            3 => Expr::Alt(vec![expr, t("y")]),
+            _ => panic!("Can't happen"),
+        };
+    }
+    let mut grammar = Ebnf::new();
+    grammar.add_production("start", expr);
+
+    let start = Instant::now();
+    let bnf = grammar.to_bnf();
+    println!(
+        "[+] Converted an expression nested {} levels deep to BNF ({} rules) in {:.3}s",
+        depth,
+        bnf.to_string().lines().count(),
+        start.elapsed().as_secs_f64()
+    );
 }
 
 // Debug trap: rust-lldb target
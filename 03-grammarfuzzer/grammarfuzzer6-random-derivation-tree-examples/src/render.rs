@@ -0,0 +1,134 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Rendering dot/graphviz files with the `dot` program (if it is installed).
+// Without graphviz, only the .dot files are written, and can be rendered
+// later/elsewhere.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+/// Output format of the rendered files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl Format {
+    /// File extension, which is also the name of the format for `dot -T`.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Pdf => "pdf",
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "svg" => Ok(Format::Svg),
+            "png" => Ok(Format::Png),
+            "pdf" => Ok(Format::Pdf),
+            _ => Err(format!("Invalid output format {} (svg, png, pdf)", s)),
+        }
+    }
+}
+
+/// Parse the output format from command line arguments (`--format svg` or
+/// `--format=svg`), if given.
+pub fn format_from_cli() -> Option<Format> {
+    let parse = |s: &str| s.parse::<Format>().unwrap_or_else(|err| panic!("{}", err));
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--format" {
+            let value = args.next().expect("Missing value for --format");
+            return Some(parse(&value));
+        }
+        if let Some(value) = arg.strip_prefix("--format=") {
+            return Some(parse(value));
+        }
+    }
+    None
+}
+
+pub struct Renderer {
+    format: Format,
+
+    /// Whether the `dot` program is available.
+    available: bool,
+}
+
+impl Renderer {
+    /// Create a renderer; checks whether graphviz is installed.
+    pub fn new(format: Format) -> Self {
+        let available = Command::new("dot")
+            .arg("-V")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        Self { format, available }
+    }
+
+    /// Render the given .dot files (next to them, with the extension of the
+    /// output format), running one `dot` process per CPU at a time. Returns
+    /// the rendered files, or an error listing the files that couldn't be
+    /// rendered. Without graphviz, nothing is rendered (and a message says
+    /// so).
+    pub fn render(&self, dotfiles: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+        if !self.available {
+            println!(
+                "[!] graphviz (dot) not found, only the .dot files were written \
+                (render them with e.g.: dot -T{} file.dot -o file.{})",
+                self.format.extension(),
+                self.format.extension()
+            );
+            return Ok(Vec::new());
+        }
+
+        let batchsize = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        let mut rendered = Vec::new();
+        let mut failed = Vec::new();
+        for batch in dotfiles.chunks(batchsize) {
+            let processes: Vec<(&PathBuf, PathBuf, std::io::Result<Child>)> = batch
+                .iter()
+                .map(|dotfile| {
+                    let output = dotfile.with_extension(self.format.extension());
+                    let process = self.spawn(dotfile, &output);
+                    (dotfile, output, process)
+                })
+                .collect();
+
+            for (dotfile, output, process) in processes {
+                match process.and_then(|mut process| process.wait()) {
+                    Ok(status) if status.success() => rendered.push(output),
+                    _ => failed.push(dotfile.display().to_string()),
+                }
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(rendered)
+        } else {
+            Err(format!("Rendering failed for {}", failed.join(", ")))
+        }
+    }
+
+    fn spawn(&self, dotfile: &Path, output: &Path) -> std::io::Result<Child> {
+        Command::new("dot")
+            .arg(format!("-T{}", self.format.extension()))
+            .arg(dotfile)
+            .arg("-o")
+            .arg(output)
+            .spawn()
+    }
+}
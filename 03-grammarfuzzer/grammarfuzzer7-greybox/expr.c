@@ -0,0 +1,133 @@
+// Example program: recursive-descent parser and evaluator for the arithmetic
+// expressions of the expression grammar (see examplegrammars.rs). It has some
+// branches that are only reached by specific structures (deep nesting,
+// division by zero, long numbers, ...), which makes it a target where
+// coverage guidance on the level of derivation trees can help.
+
+#include <stdio.h>
+#include <stdlib.h>
+
+static const char *s;
+static int depth = 0;
+static int max_depth = 0;
+static int divisions_by_zero = 0;
+
+static double expr(void);
+
+static double integer(int *digits) {
+    double v = 0;
+    *digits = 0;
+    while (*s >= '0' && *s <= '9') {
+        v = v * 10 + (*s - '0');
+        s++;
+        (*digits)++;
+    }
+    return v;
+}
+
+static double factor(void) {
+    if (*s == '+') {
+        s++;
+        return factor();
+    }
+    if (*s == '-') {
+        s++;
+        return -factor();
+    }
+    if (*s == '(') {
+        s++;
+        depth++;
+        if (depth > max_depth) {
+            max_depth = depth;
+        }
+        double v = expr();
+        if (*s != ')') {
+            exit(2); // Syntax error.
+        }
+        s++;
+        depth--;
+        return v;
+    }
+
+    int digits;
+    double v = integer(&digits);
+    if (digits == 0) {
+        exit(2); // Syntax error.
+    }
+    if (digits > 5) {
+        printf("long number\n");
+    }
+    if (*s == '.') {
+        s++;
+        double scale = 1;
+        double frac = integer(&digits);
+        if (digits == 0) {
+            exit(2); // Syntax error.
+        }
+        while (digits-- > 0) {
+            scale *= 10;
+        }
+        v += frac / scale;
+    }
+    return v;
+}
+
+static double term(void) {
+    double v = factor();
+    while (*s == '*' || *s == '/') {
+        char op = *s++;
+        double w = factor();
+        if (op == '*') {
+            v *= w;
+        } else if (w == 0) {
+            divisions_by_zero++;
+            v = 0;
+        } else {
+            v /= w;
+        }
+    }
+    return v;
+}
+
+static double expr(void) {
+    double v = term();
+    while (*s == '+' || *s == '-') {
+        char op = *s++;
+        double w = term();
+        v = op == '+' ? v + w : v - w;
+    }
+    return v;
+}
+
+int main(int argc, char *argv[]) {
+    if (argc != 2) {
+        return 1;
+    }
+
+    s = argv[1];
+    double v = expr();
+    if (*s != '\0') {
+        return 2; // Trailing garbage.
+    }
+
+    if (max_depth > 3) {
+        printf("deeply nested\n");
+        if (max_depth > 6) {
+            printf("very deeply nested\n");
+        }
+    }
+    if (divisions_by_zero > 0) {
+        printf("division by zero\n");
+        if (divisions_by_zero > 2) {
+            printf("many divisions by zero\n");
+        }
+    }
+    if (v > 1e6) {
+        printf("large result\n");
+    } else if (v < -1e6) {
+        printf("small result\n");
+    }
+
+    printf("%f\n", v);
+    return 0;
+}
@@ -0,0 +1,188 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Coverage-guided grammar fuzzing: the greybox loop of 02-greyboxfuzzer, but
+// operating on derivation trees instead of bytes.
+// From https://www.fuzzingbook.org/html/GreyboxGrammarFuzzer.html
+
+use std::collections::{BTreeMap, HashMap};
+
+use ::greybox::fuzzer::Input;
+use ::greybox::runner::{Coverage, CoverageBackend, CoverageH, GcovRunner, RunResult};
+
+use crate::grammarfuzzer::{expand_tree, fuzz_tree_, GrammarCost, Tree};
+use crate::rng::Rng;
+
+/// Statistics relevant during fuzzing.
+#[derive(Default)]
+pub struct Statistics {
+    /// Number of times an input was tested.
+    pub fuzz_cases: usize,
+
+    /// Corpus: all derivation trees with unique coverage, together with a hash
+    /// of the associated coverage information.
+    pub corpus: Vec<(Tree, CoverageH)>,
+
+    /// All subtrees of the trees in the corpus, grouped by the name of their
+    /// root nonterminal. These are the building blocks for mutations.
+    pub fragments: HashMap<String, Vec<Tree>>,
+
+    /// Hashes of all coverages seen during fuzzing, together with the count /
+    /// frequency they occurred.
+    pub coverage_db: BTreeMap<CoverageH, usize>,
+
+    /// Union of all coverages that were achieved during execution.
+    pub coverage_all: Coverage,
+
+    /// Trace/history of coverage_all sizes.
+    pub coverage_cumul: Vec<usize>,
+}
+
+impl Statistics {
+    /// Add a derivation tree to the corpus and remember all its fragments.
+    fn add_to_corpus(&mut self, tree: Tree, coverageh: CoverageH) {
+        add_fragments(&mut self.fragments, &tree);
+        self.corpus.push((tree, coverageh));
+    }
+}
+
+fn add_fragments(fragments: &mut HashMap<String, Vec<Tree>>, tree: &Tree) {
+    if let Tree::NT(name, children) = tree {
+        fragments
+            .entry(name.clone())
+            .or_default()
+            .push(tree.clone());
+        for child in children.iter() {
+            add_fragments(fragments, child);
+        }
+    }
+}
+
+/// Run `n` fuzz cases of coverage-guided grammar fuzzing.
+/// The first `n_seeds` fuzz cases are freshly generated from the grammar; after
+/// that derivation trees from the corpus are mutated. Crashes (and runs that
+/// fail with an error) give no coverage.
+pub fn run(
+    rng: &mut Rng,
+    grammar: &GrammarCost,
+    runner: &GcovRunner,
+    stats: &mut Statistics,
+    n: usize,
+    n_seeds: usize,
+) {
+    for _ in 0..n {
+        let tree = if stats.fuzz_cases < n_seeds || stats.corpus.is_empty() {
+            fuzz_tree_(rng, grammar)
+        } else {
+            let mut tree = power_schedule_choose(rng, stats).clone();
+            for _ in 0..rng.range(1, 4 + 1) {
+                tree = mutate(rng, grammar, &stats.fragments, tree);
+            }
+            tree
+        };
+
+        let result = runner.run(&Input::from(tree.all_leafs()));
+        if let Err(err) = &result {
+            eprintln!("[!] Fuzz case {}: {}", stats.fuzz_cases, err);
+        }
+        if let Ok(RunResult::Ok(coverage)) = result {
+            let coverageh = CoverageH::new(&coverage);
+            match stats.coverage_db.get_mut(&coverageh) {
+                None => {
+                    // We have some new coverage.
+                    stats.coverage_db.insert(coverageh.clone(), 1);
+                    stats.add_to_corpus(tree, coverageh);
+                }
+                Some(count) => *count += 1,
+            }
+            stats.coverage_all.extend(coverage);
+        }
+
+        stats.coverage_cumul.push(stats.coverage_all.len());
+        stats.fuzz_cases += 1;
+    }
+}
+
+/// Choose a tree from the corpus according to an exponential power schedule
+/// as implemented in AFL (see 02-greyboxfuzzer/greyboxfuzzer5).
+fn power_schedule_choose<'a>(rng: &mut Rng, stats: &'a Statistics) -> &'a Tree {
+    let exponent = 5.0;
+    let fitness: Vec<_> = stats
+        .corpus
+        .iter()
+        .map(|(_, coverageh)| (1.0 / (stats.coverage_db[coverageh] as f64)).powf(exponent))
+        .collect();
+    &rng.choice_w(&stats.corpus, &fitness).0
+}
+
+/// Choose a random tree mutation and apply it.
+pub fn mutate(
+    rng: &mut Rng,
+    grammar: &GrammarCost,
+    fragments: &HashMap<String, Vec<Tree>>,
+    tree: Tree,
+) -> Tree {
+    match rng.int(2) {
+        0 => swap_fragment(rng, fragments, tree),
+        1 => regenerate_subtree(rng, grammar, tree),
+        _ => panic!("Can't happen"),
+    }
+}
+
+/// Replace a random subtree with a fragment (from the corpus) that has the
+/// same root nonterminal. The result is again a valid derivation tree.
+fn swap_fragment(rng: &mut Rng, fragments: &HashMap<String, Vec<Tree>>, mut tree: Tree) -> Tree {
+    let paths = nonterminal_paths(&tree);
+    let path: &Vec<usize> = rng.choice(&paths);
+    let node = node_mut(&mut tree, path);
+    if let Tree::NT(name, _) = node {
+        if let Some(candidates) = fragments.get(name) {
+            *node = rng.choice(candidates).clone();
+        }
+    }
+    tree
+}
+
+/// Throw away a random subtree and expand its root nonterminal anew.
+fn regenerate_subtree(rng: &mut Rng, grammar: &GrammarCost, mut tree: Tree) -> Tree {
+    let paths = nonterminal_paths(&tree);
+    let path: &Vec<usize> = rng.choice(&paths);
+    let node = node_mut(&mut tree, path);
+    if let Tree::NT(_, children) = node {
+        children.clear();
+    }
+    // Keep the new subtrees small.
+    expand_tree(rng, grammar, node, 0, 10);
+    tree
+}
+
+/// Paths (lists of child indices, starting from the root) to all nonterminal
+/// nodes of a derivation tree.
+fn nonterminal_paths(tree: &Tree) -> Vec<Vec<usize>> {
+    let mut res = Vec::new();
+    let mut stack = vec![(tree, Vec::new())];
+    while let Some((cur, path)) = stack.pop() {
+        if let Tree::NT(_, children) = cur {
+            for (i, child) in children.iter().enumerate() {
+                let mut childpath = path.clone();
+                childpath.push(i);
+                stack.push((child, childpath));
+            }
+            res.push(path);
+        }
+    }
+    res
+}
+
+fn node_mut<'a>(tree: &'a mut Tree, path: &[usize]) -> &'a mut Tree {
+    let mut cur = tree;
+    for &i in path {
+        cur = match cur {
+            Tree::NT(_, children) => &mut children[i],
+            Tree::T(_) => panic!("Can't happen"),
+        };
+    }
+    cur
+}
@@ -0,0 +1,56 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod grammarfuzzer;
+mod miner;
+mod rng;
+
+use rng::Rng;
+
+fn main() {
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    let samples: &[(&str, &[&str])] = &[
+        (
+            "urls",
+            &[
+                "http://www.google.com/search?q=fuzzing",
+                "http://www.fuzzingbook.org/html/Grammars.html",
+                "http://localhost:8080/index.html",
+            ],
+        ),
+        ("lists", &["[1, 2, 3]", "[42]", "[7, 13, 21, 1000]"]),
+        (
+            "key-value",
+            &["name=fuzzer; seed=42", "name=miner; seed=7; runs=100"],
+        ),
+    ];
+
+    // Number of example strings to generate from each mined grammar.
+    let n_examples = cli::iterations(5);
+
+    // Only the samples given with --target (e.g. `--target urls`).
+    let selected = cli::target("all");
+
+    for (name, samples) in samples.iter() {
+        if selected != "all" && selected != *name {
+            continue;
+        }
+        let grammar = miner::mine_grammar(samples);
+
+        println!();
+        println!("[+] Grammar mined from {}:", name);
+        if !cli::quiet() {
+            print!("{}", grammar);
+        }
+
+        println!("[+] Examples:");
+        for _ in 0..n_examples {
+            println!("    {}", grammarfuzzer::fuzz(&mut rng, grammar.to_bnf()));
+        }
+    }
+}
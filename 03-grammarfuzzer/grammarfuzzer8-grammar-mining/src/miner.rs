@@ -0,0 +1,220 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Mine a crude grammar from a set of sample inputs.
+// This is a lightweight step towards https://www.fuzzingbook.org/html/GrammarMiner.html:
+// Instead of tracking how the program under test processes its input, the
+// samples are only looked at lexically.
+//
+//   1. Split each sample into tokens by character class (runs of digits,
+//      runs of letters, runs of whitespace, and single punctuation characters).
+//
+//   2. Determine the common prefix and suffix (in terms of tokens) of all
+//      samples. The differing middle parts become alternatives.
+//
+//   3. Detect repetitions of short token sequences in the middle parts and
+//      fold them into `(...)+` expressions.
+//
+//   4. The token classes become nonterminals, which can only expand to the
+//      characters that were actually observed in the samples.
+
+use std::collections::BTreeSet;
+
+use crate::grammarfuzzer::{alt, nt, plus, seq, t, Ebnf, Expr};
+
+/// Token (class) of the input.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Token {
+    /// Run of digits.
+    Int,
+    /// Run of letters.
+    Word,
+    /// Run of whitespace.
+    Space,
+    /// Any other single character (taken literally).
+    Lit(char),
+}
+
+impl Token {
+    fn class(c: char) -> Self {
+        if c.is_ascii_digit() {
+            Token::Int
+        } else if c.is_alphabetic() {
+            Token::Word
+        } else if c.is_whitespace() {
+            Token::Space
+        } else {
+            Token::Lit(c)
+        }
+    }
+
+    fn to_expr(&self) -> Expr {
+        match self {
+            Token::Int => nt("int"),
+            Token::Word => nt("word"),
+            Token::Space => nt("space"),
+            Token::Lit(c) => t(&c.to_string()),
+        }
+    }
+}
+
+/// Split an input into tokens. Consecutive characters of the same class are
+/// merged (except for literal characters).
+fn tokenize(s: &str) -> Vec<Token> {
+    let mut res: Vec<Token> = Vec::new();
+    for c in s.chars() {
+        let token = Token::class(c);
+        let merge = !matches!(token, Token::Lit(_)) && res.last() == Some(&token);
+        if !merge {
+            res.push(token);
+        }
+    }
+    res
+}
+
+/// Element of a token sequence after repetition detection.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Unit {
+    Single(Token),
+    /// Token sequence that is repeated one or more times.
+    Repeat(Vec<Token>),
+}
+
+/// Longest token sequence that is considered for repetition detection.
+const MAX_REPEAT_LENGTH: usize = 4;
+
+/// Fold directly repeated token sequences (e.g. `<int> "," <int> "," <int>`
+/// becomes `(<int> ",")+ <int>`). Always greedily takes the repetition that
+/// covers the most tokens.
+fn fold_repetitions(tokens: &[Token]) -> Vec<Unit> {
+    let mut res = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        // (length of repeated unit, number of repetitions)
+        let mut best = None;
+        for k in 1..=MAX_REPEAT_LENGTH {
+            if i + k > tokens.len() {
+                break;
+            }
+            let unit = &tokens[i..i + k];
+            let mut r = 1;
+            while i + (r + 1) * k <= tokens.len() && &tokens[i + r * k..i + (r + 1) * k] == unit {
+                r += 1;
+            }
+            if r >= 2 && best.is_none_or(|(bk, br)| k * r > bk * br) {
+                best = Some((k, r));
+            }
+        }
+
+        match best {
+            Some((k, r)) => {
+                res.push(Unit::Repeat(tokens[i..i + k].to_vec()));
+                i += k * r;
+            }
+            None => {
+                res.push(Unit::Single(tokens[i].clone()));
+                i += 1;
+            }
+        }
+    }
+    res
+}
+
+fn units_to_expr(units: &[Unit]) -> Expr {
+    if units.is_empty() {
+        return t("");
+    }
+    let exprs: Vec<_> = units
+        .iter()
+        .map(|unit| match unit {
+            Unit::Single(token) => token.to_expr(),
+            Unit::Repeat(tokens) => {
+                plus(seq(&tokens.iter().map(|t| t.to_expr()).collect::<Vec<_>>()))
+            }
+        })
+        .collect();
+    seq(&exprs)
+}
+
+/// Mine a grammar from sample inputs; the result can be used as starting point
+/// for the grammar fuzzer.
+pub fn mine_grammar(samples: &[&str]) -> Ebnf {
+    assert!(!samples.is_empty());
+
+    let tokenized: Vec<_> = samples.iter().map(|s| tokenize(s)).collect();
+
+    // Common prefix over all samples.
+    let mut prefix_len = 0;
+    while tokenized
+        .iter()
+        .all(|tokens| prefix_len < tokens.len() && tokens[prefix_len] == tokenized[0][prefix_len])
+    {
+        prefix_len += 1;
+    }
+
+    // Common suffix over all samples (not overlapping with the prefix).
+    let mut suffix_len = 0;
+    while tokenized.iter().all(|tokens| {
+        prefix_len + suffix_len < tokens.len()
+            && tokens[tokens.len() - 1 - suffix_len]
+                == tokenized[0][tokenized[0].len() - 1 - suffix_len]
+    }) {
+        suffix_len += 1;
+    }
+
+    // Differing middle parts, with repetitions folded. The set removes
+    // duplicates.
+    let middles: BTreeSet<_> = tokenized
+        .iter()
+        .map(|tokens| fold_repetitions(&tokens[prefix_len..tokens.len() - suffix_len]))
+        .collect();
+
+    let mut grammar = Ebnf::new();
+
+    let mut start: Vec<_> = tokenized[0][..prefix_len]
+        .iter()
+        .map(|token| token.to_expr())
+        .collect();
+    start.push(nt("body"));
+    start.extend(
+        tokenized[0][tokenized[0].len() - suffix_len..]
+            .iter()
+            .map(|token| token.to_expr()),
+    );
+    grammar.add_production("start", seq(&start));
+
+    grammar.add_production(
+        "body",
+        alt(&middles.iter().map(|m| units_to_expr(m)).collect::<Vec<_>>()),
+    );
+
+    // Only the characters that were actually observed are used for the token
+    // classes.
+    let chars = |class: Token| -> Vec<Expr> {
+        samples
+            .iter()
+            .flat_map(|s| s.chars())
+            .filter(|&c| Token::class(c) == class)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|c| t(&c.to_string()))
+            .collect()
+    };
+
+    let all_tokens: BTreeSet<_> = tokenized.iter().flatten().collect();
+    if all_tokens.contains(&Token::Int) {
+        grammar.add_production("int", plus(nt("digit")));
+        grammar.add_production("digit", alt(&chars(Token::Int)));
+    }
+    if all_tokens.contains(&Token::Word) {
+        grammar.add_production("word", plus(nt("letter")));
+        grammar.add_production("letter", alt(&chars(Token::Word)));
+    }
+    if all_tokens.contains(&Token::Space) {
+        grammar.add_production("space", plus(alt(&chars(Token::Space))));
+    }
+
+    grammar
+}
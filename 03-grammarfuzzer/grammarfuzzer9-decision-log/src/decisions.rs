@@ -0,0 +1,259 @@
+// SPDX-FileCopyrightText: 2025 Implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+// Record the random decisions that are made while creating a derivation tree
+// (which node is expanded next, and with which expansion), so that the tree
+// can be re-created from the log alone.
+//
+// The log is a "choice sequence" (as in e.g. Hypothesis): any sequence of
+// decisions can be replayed, since each value is taken modulo the number of
+// available options, and decisions missing at the end are taken as 0 (the
+// first option). This allows operating on the level of decisions instead of
+// strings or trees: mutating the log always gives a valid derivation tree, and
+// a log can be minimized by deleting decisions or making them smaller.
+
+use crate::grammarfuzzer::{Expansion, Tree};
+use crate::rng::Rng;
+
+/// One decision: expansion of one nonterminal node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decision {
+    /// Index of the expanded node among the not-yet-expanded nonterminals.
+    pub node: usize,
+
+    /// Index of the chosen expansion (among those allowed in the current
+    /// phase of tree expansion).
+    pub expansion: usize,
+}
+
+/// Where the decisions come from.
+enum Source<'a> {
+    Random(&'a mut Rng),
+    Replay(&'a [Decision]),
+}
+
+/// Makes the decisions during the creation of a derivation tree, and records
+/// them.
+pub struct Decider<'a> {
+    source: Source<'a>,
+
+    /// Node index of the decision that is currently made (the expansion is
+    /// only chosen afterwards, when the number of options is known).
+    node: usize,
+
+    /// Decisions that were made so far. When replaying, these are the
+    /// decisions that were actually taken (values wrapped around to the
+    /// number of options), and can thus differ from the replayed log.
+    pub log: Vec<Decision>,
+
+    /// Human-readable trace of the applied expansions.
+    pub trace: Vec<String>,
+}
+
+impl<'a> Decider<'a> {
+    /// Make random decisions.
+    pub fn random(rng: &'a mut Rng) -> Self {
+        Self {
+            source: Source::Random(rng),
+            node: 0,
+            log: Vec::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    /// Take the decisions from a log.
+    pub fn replay(log: &'a [Decision]) -> Self {
+        Self {
+            source: Source::Replay(log),
+            node: 0,
+            log: Vec::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    /// Choose the index of the node to expand next out of `n` nodes.
+    pub fn choose_node(&mut self, n: usize) -> usize {
+        self.node = match &mut self.source {
+            Source::Random(rng) => rng.usize_below(n),
+            Source::Replay(log) => log.get(self.log.len()).copied().unwrap_or_default().node % n,
+        };
+        self.node
+    }
+
+    /// Choose the index of the expansion out of `n` expansions for the node
+    /// chosen before. This completes the decision.
+    pub fn choose_expansion(&mut self, n: usize) -> usize {
+        let expansion = match &mut self.source {
+            Source::Random(rng) => rng.usize_below(n),
+            Source::Replay(log) => {
+                log.get(self.log.len())
+                    .copied()
+                    .unwrap_or_default()
+                    .expansion
+                    % n
+            }
+        };
+        self.log.push(Decision {
+            node: self.node,
+            expansion,
+        });
+        expansion
+    }
+
+    /// Record the expansion that was applied for the last decision.
+    pub fn trace(&mut self, nonterminal: &str, expansion: &Expansion) {
+        self.trace
+            .push(format!("{} -> {}", nonterminal, expansion.join(" ")));
+    }
+}
+
+/// Function that creates a derivation tree with the decisions of the given
+/// decider, e.g. `fuzz_tree_with` for a specific grammar. Replaying a log
+/// only gives the same tree with the same function.
+pub type Generator<'a> = dyn Fn(&mut Decider) -> Tree + 'a;
+
+/// Re-create a derivation tree from a log. Returns the tree and the
+/// decisions that were actually taken.
+pub fn replay(generate: &Generator, log: &[Decision]) -> (Tree, Vec<Decision>) {
+    let mut decider = Decider::replay(log);
+    let tree = generate(&mut decider);
+    (tree, decider.log)
+}
+
+/// Remove decisions at the end that are 0 anyway when missing.
+fn trim(mut log: Vec<Decision>) -> Vec<Decision> {
+    while log.last() == Some(&Decision::default()) {
+        log.pop();
+    }
+    log
+}
+
+/// Mutate a log: change, delete or insert a random decision. Since decisions
+/// later in the log refer to the nodes created by earlier ones, changes at the
+/// beginning have a larger effect than changes at the end.
+pub fn mutate(rng: &mut Rng, log: &[Decision]) -> Vec<Decision> {
+    let mut log = log.to_vec();
+    let pos = rng.int(log.len() as u64 + 1) as usize;
+    let random = Decision {
+        node: rng.int(u16::MAX as u64) as usize,
+        expansion: rng.int(u16::MAX as u64) as usize,
+    };
+    match rng.int(3) {
+        0 if pos < log.len() => log[pos].expansion = random.expansion,
+        1 if pos < log.len() => {
+            log.remove(pos);
+        }
+        _ => log.insert(pos, random),
+    }
+    log
+}
+
+/// Number of different expansions that are tried for each decision when
+/// minimizing (e.g. 10 digits).
+const MAX_EXPANSIONS: usize = 10;
+
+/// Minimize a log while the string of the resulting tree keeps satisfying the
+/// predicate (e.g. still triggers a crash). Tries deleting chunks of decisions
+/// and changing decisions, and keeps each change that results in a shorter
+/// string (or an equally long string and a smaller log). Trailing decisions
+/// that are 0 are removed from the result.
+pub fn minimize<F: Fn(&str) -> bool>(
+    generate: &Generator,
+    log: &[Decision],
+    predicate: F,
+) -> Vec<Decision> {
+    let (tree, best) = replay(generate, log);
+    let mut best = trim(best);
+    let mut best_len = tree.all_leafs().len();
+    assert!(
+        predicate(&tree.all_leafs()),
+        "Log doesn't satisfy predicate"
+    );
+
+    // Replay the candidate and keep it if it is better.
+    let try_candidate =
+        |candidate: Vec<Decision>, best: &mut Vec<Decision>, best_len: &mut usize| -> bool {
+            let (tree, taken) = replay(generate, &candidate);
+            let taken = trim(taken);
+            let s = tree.all_leafs();
+            if predicate(&s) && (s.len(), taken.len(), &taken) < (*best_len, best.len(), best) {
+                *best = taken;
+                *best_len = s.len();
+                true
+            } else {
+                false
+            }
+        };
+
+    loop {
+        let mut improved = false;
+
+        for chunk in [8, 4, 2, 1] {
+            let mut i = 0;
+            while i + chunk <= best.len() {
+                let mut candidate = best.clone();
+                candidate.drain(i..i + chunk);
+                if try_candidate(candidate, &mut best, &mut best_len) {
+                    improved = true;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+
+        // Other expansions for the same node (not necessarily smaller ones:
+        // the first expansion of a nonterminal isn't always the one that
+        // leads to the shortest string), or the first node.
+        let mut i = 0;
+        while i < best.len() {
+            let decision = best[i];
+            let candidates = (0..MAX_EXPANSIONS)
+                .map(|expansion| Decision {
+                    expansion,
+                    ..decision
+                })
+                .chain([Decision {
+                    node: 0,
+                    ..decision
+                }]);
+            for candidate in candidates {
+                if i < best.len() && candidate != best[i] {
+                    let mut candidate_log = best.clone();
+                    candidate_log[i] = candidate;
+                    improved |= try_candidate(candidate_log, &mut best, &mut best_len);
+                }
+            }
+            i += 1;
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    best
+}
+
+/// Compact textual form of a log, e.g. `0:1 3:0 2:2`.
+pub fn format_log(log: &[Decision]) -> String {
+    log.iter()
+        .map(|decision| format!("{}:{}", decision.node, decision.expansion))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse the textual form of a log (see `format_log`).
+pub fn parse_log(s: &str) -> Vec<Decision> {
+    s.split_whitespace()
+        .map(|decision| {
+            let (node, expansion) = decision
+                .split_once(':')
+                .unwrap_or_else(|| panic!("Invalid decision {}", decision));
+            Decision {
+                node: node.parse().unwrap(),
+                expansion: expansion.parse().unwrap(),
+            }
+        })
+        .collect()
+}
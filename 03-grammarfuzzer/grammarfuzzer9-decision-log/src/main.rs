@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2025 Original python code: fuzzingbook, https://www.fuzzingbook.org, Saarland University, CISPA, authors, and contributors
+// SPDX-FileCopyrightText: 2025 This implementation/refactoring/adaptation: stfnw
+//
+// SPDX-License-Identifier: MIT
+
+mod cli;
+mod decisions;
+mod examplegrammars;
+mod grammarfuzzer;
+mod rng;
+
+use decisions::Decider;
+use grammarfuzzer::{fuzz_tree_with, GrammarCost};
+use rng::Rng;
+
+fn main() {
+    let grammar: GrammarCost = examplegrammars::expr_grammar().into();
+
+    // Smaller limits for the expansion phases than the default of `fuzz_tree`,
+    // which always does at least 80 expansions (and so can't create small
+    // expressions even when minimizing).
+    let generate = |decider: &mut Decider| fuzz_tree_with(decider, &grammar, 0, 30);
+
+    // Only re-create the expression from the given log, e.g. `--replay "0:1 0:2"`.
+    if let Some(log) = cli::option("--replay") {
+        let log = decisions::parse_log(&log);
+        let (tree, _) = decisions::replay(&generate, &log);
+        println!("{}", tree.all_leafs());
+        return;
+    }
+
+    let mut rng = Rng::seeded(cli::seed().unwrap_or(42));
+    println!("[+] Running with random seed {}", rng.initialseed);
+
+    // Create an expression and trace its decisions.
+    let mut decider = Decider::random(&mut rng);
+    let tree = generate(&mut decider);
+    let log = decider.log;
+    println!();
+    println!("[+] Expression: {}", tree.all_leafs());
+    println!("[+] Made {} decisions", log.len());
+    if !cli::quiet() {
+        println!("[+] First ones:");
+        for (decision, trace) in log.iter().zip(decider.trace.iter()).take(8) {
+            println!("    {:>2}:{} {}", decision.node, decision.expansion, trace);
+        }
+    }
+
+    // The log alone is enough to re-create the expression.
+    let (replayed, _) = decisions::replay(&generate, &log);
+    assert_eq!(tree.all_leafs(), replayed.all_leafs());
+    println!("[+] Replaying the log gives the same expression");
+
+    // Mutations on the level of decisions always give valid expressions.
+    println!();
+    println!("[+] Mutants of the log:");
+    for _ in 0..cli::iterations(5) {
+        let mutant = decisions::mutate(&mut rng, &log);
+        let (tree, _) = decisions::replay(&generate, &mutant);
+        println!("    {}", tree.all_leafs());
+    }
+
+    // Minimize an expression that contains a division by zero (as if that had
+    // crashed the program under test).
+    let predicate = |s: &str| s.contains("/0");
+    let log = loop {
+        let mut decider = Decider::random(&mut rng);
+        let tree = generate(&mut decider);
+        if predicate(&tree.all_leafs()) {
+            println!();
+            println!("[+] Expression containing \"/0\": {}", tree.all_leafs());
+            println!(
+                "[+] ({} characters, {} decisions)",
+                tree.all_leafs().len(),
+                decider.log.len()
+            );
+            break decider.log;
+        }
+    };
+    let minimized = decisions::minimize(&generate, &log, predicate);
+    let (tree, _) = decisions::replay(&generate, &minimized);
+    println!("[+] Minimized: {}", tree.all_leafs());
+    println!(
+        "[+] ({} characters, {} decisions)",
+        tree.all_leafs().len(),
+        minimized.len()
+    );
+    println!("[+] Log: {}", decisions::format_log(&minimized));
+}